@@ -0,0 +1,212 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{settings, tr};
+use registry::Action;
+use rules::{Rule, RulesAction};
+use top_track_playlist::{TopTrackPlaylistParams, TopTracksAction, TopTracksConfig};
+
+pub mod action_report;
+pub mod diff;
+pub mod export;
+pub mod parameters;
+pub mod pipeline;
+pub mod playlist_actions;
+pub mod playlist_builder;
+pub mod recommendation_playlist;
+pub mod registry;
+pub mod restore;
+pub mod rules;
+pub mod search;
+pub mod top_track_playlist;
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// The defaults of every action, from the `"actions"` section of the config.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ActionsConfig {
+    /// The actions selected when the TUI starts, by their sections like `top_tracks`;
+    /// without it the selection of the last run is kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<Vec<String>>,
+    #[serde(default)]
+    pub top_tracks: TopTracksConfig,
+}
+
+/// One run of an action with its parameters, chosen in the TUI, given to `spautofy run` or
+/// from a pipeline.
+#[derive(Debug, Clone)]
+pub enum Task {
+    TopTracks(TopTrackPlaylistParams),
+    Rule(Rule),
+}
+
+impl Task {
+    /// The action running the task.
+    pub fn action(&self) -> &'static dyn Action {
+        match self {
+            Task::TopTracks(_) => &TopTracksAction,
+            Task::Rule(_) => &RulesAction,
+        }
+    }
+
+    /// Names the task in the metrics if it does not belong to a pipeline, like
+    /// `top-tracks:short_term` or the name of the rule.
+    pub fn name(&self) -> String {
+        match self {
+            Task::TopTracks(params) => format!("top-tracks:{}", params.time_range),
+            Task::Rule(rule) => rule.name.clone(),
+        }
+    }
+
+    /// Shown while the task runs and in its report.
+    pub fn label(&self) -> String {
+        match self {
+            Task::TopTracks(params) => tr!("{} top tracks playlist", params.time_range),
+            Task::Rule(rule) => tr!("rule {}", rule.name),
+        }
+    }
+
+    /// Fills in the parameters the task was given no values for from the config.
+    pub fn with_config(self, config: &ActionsConfig) -> Self {
+        match self {
+            Task::TopTracks(params) => Task::TopTracks(params.with_config(&config.top_tracks)),
+            task => task,
+        }
+    }
+
+    /// The playlist the action writes, by id if it updates a given one, else by name.
+    fn target(&self) -> String {
+        match self {
+            Task::TopTracks(params) => match &params.target {
+                Some(target) => target.id.clone(),
+                None => params.playlist_name(),
+            },
+            Task::Rule(rule) => rule.target().to_string(),
+        }
+    }
+
+    fn reads_playlist(&self) -> bool {
+        matches!(self, Task::Rule(rule) if rule.reads_playlist())
+    }
+
+    fn enriches(&self) -> bool {
+        matches!(self, Task::Rule(rule) if rule.enriches())
+    }
+
+    /// Whether the task has to wait for an earlier one of the run: both write the same
+    /// playlist, one reads a playlist, which the other may write, or both look tracks up
+    /// on MusicBrainz, whose rate limit counts them together.
+    pub fn depends_on(&self, earlier: &Task) -> bool {
+        self.target() == earlier.target()
+            || self.reads_playlist()
+            || earlier.reads_playlist()
+            || (self.enriches() && earlier.enriches())
+    }
+}
+
+/// Groups the tasks into stages, by their indices: the tasks of a stage do not depend on
+/// each other and can run together, each after the stages of the tasks it depends on.
+pub fn stages(tasks: &[Task]) -> Vec<Vec<usize>> {
+    let mut stage_of: Vec<usize> = Vec::with_capacity(tasks.len());
+    let mut stages: Vec<Vec<usize>> = Vec::new();
+    for (index, task) in tasks.iter().enumerate() {
+        let stage = tasks[..index]
+            .iter()
+            .zip(&stage_of)
+            .filter(|(earlier, _)| task.depends_on(earlier))
+            .map(|(_, stage)| stage + 1)
+            .max()
+            .unwrap_or(0);
+        stage_of.push(stage);
+        if stage == stages.len() {
+            stages.push(Vec::new());
+        }
+        stages[stage].push(index);
+    }
+    stages
+}
+
+/// Makes the actions only read from Spotify and report what they would write.
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.store(dry_run, Ordering::SeqCst);
+}
+
+/// Whether the client running, else the process, makes a dry run.
+pub fn is_dry_run() -> bool {
+    settings::with(|settings| settings.dry_run).unwrap_or_else(|| DRY_RUN.load(Ordering::SeqCst))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::rules::RuleConfig;
+    use crate::actions::top_track_playlist::TimeRange;
+    use crate::integrations::IntegrationsConfig;
+
+    fn top_tracks(time_range: TimeRange) -> Task {
+        Task::TopTracks(TopTrackPlaylistParams::new(time_range))
+    }
+
+    fn rule(source: &str, target: &str, enrich: bool) -> Task {
+        let config = RuleConfig {
+            source: source.to_string(),
+            filter: None,
+            sort: None,
+            limit: None,
+            enrich: Some(enrich),
+            dedup: None,
+            target: target.to_string(),
+            public: None,
+            collaborative: None,
+        };
+        Task::Rule(Rule::parse(target, &config, &IntegrationsConfig::default()).unwrap())
+    }
+
+    #[test]
+    fn independent_tasks_run_together() {
+        let tasks = [
+            top_tracks(TimeRange::ShortTerm),
+            top_tracks(TimeRange::LongTerm),
+            rule("liked", "Modern Metal", false),
+        ];
+        assert_eq!(stages(&tasks), vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn tasks_writing_the_same_playlist_run_one_after_the_other() {
+        let tasks = [
+            rule("liked", "Mix", false),
+            top_tracks(TimeRange::ShortTerm),
+            rule("top:long", "Mix", false),
+        ];
+        assert_eq!(stages(&tasks), vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn reading_a_playlist_waits_for_everything_before() {
+        let tasks = [
+            top_tracks(TimeRange::ShortTerm),
+            top_tracks(TimeRange::MediumTerm),
+            rule("playlist:abc", "Copy", false),
+            top_tracks(TimeRange::LongTerm),
+        ];
+        assert_eq!(stages(&tasks), vec![vec![0, 1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn enriching_tasks_share_the_rate_limit() {
+        let tasks = [
+            rule("liked", "First", true),
+            rule("liked", "Second", true),
+            rule("liked", "Third", false),
+        ];
+        assert_eq!(stages(&tasks), vec![vec![0, 2], vec![1]]);
+    }
+
+    #[test]
+    fn no_tasks_no_stages() {
+        assert!(stages(&[]).is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::diff::TrackDiff;
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct CreatedPlaylist {
+    pub name: String,
+    pub url: String,
+    /// `None` in a dry run creating a new playlist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub track_count: usize,
+    /// The album cover of the first track; Spotify makes the playlist's own cover from the
+    /// first albums only a while later.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_url: Option<String>,
+    /// Whether the playlist existed and got new tracks.
+    #[serde(default)]
+    pub updated: bool,
+    /// How the tracks of an updated top tracks playlist changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub changes: Option<TrackDiff>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct ActionReport {
+    pub action: String,
+    pub result: Result<CreatedPlaylist, String>,
+}
+
+/// Sent by the background task running the actions, with the index of the action in the
+/// run; the actions running together may finish in any order.
+#[derive(Debug)]
+pub enum RunEvent {
+    /// The action with this label started.
+    Started(usize, String),
+    Finished(usize, ActionReport),
+}
+
+/// The reports of the finished actions in the order of the actions.
+pub fn in_run_order(mut finished: Vec<(usize, ActionReport)>) -> Vec<ActionReport> {
+    finished.sort_by_key(|(index, _)| *index);
+    finished.into_iter().map(|(_, report)| report).collect()
+}
@@ -0,0 +1,422 @@
+use std::collections::{HashMap, VecDeque};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::export::{Backup, PlaylistExport};
+use crate::models::track::Track;
+
+/// A track only in one of the two snapshots, with its position there counted from 1.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TrackChange {
+    pub position: usize,
+    pub id: String,
+    pub name: String,
+    pub artists: String,
+}
+
+/// A track in both snapshots that changed its place relative to the other tracks.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct TrackMove {
+    pub from: usize,
+    pub to: usize,
+    pub id: String,
+    pub name: String,
+    pub artists: String,
+}
+
+/// How the tracks of a playlist changed from the old snapshot to the new one.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct TrackDiff {
+    pub added: Vec<TrackChange>,
+    pub removed: Vec<TrackChange>,
+    pub moved: Vec<TrackMove>,
+}
+
+/// A playlist compared by `spautofy diff`.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlaylistName {
+    pub id: String,
+    pub name: String,
+}
+
+/// How the tracks of a playlist changed, printed by `spautofy diff --json` for playlists.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SnapshotDiff {
+    pub old: PlaylistName,
+    pub new: PlaylistName,
+    pub tracks: TrackDiff,
+}
+
+/// A playlist only in one of the two backups.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlaylistChange {
+    pub id: String,
+    pub name: String,
+    pub tracks: usize,
+}
+
+/// A playlist in both backups whose tracks changed.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PlaylistDiff {
+    pub id: String,
+    pub old_name: String,
+    pub new_name: String,
+    pub tracks: TrackDiff,
+}
+
+/// How the playlists changed from the old backup to the new one, matched by their ids.
+#[derive(Debug, Default, Serialize, JsonSchema)]
+pub struct BackupDiff {
+    pub added: Vec<PlaylistChange>,
+    pub removed: Vec<PlaylistChange>,
+    pub changed: Vec<PlaylistDiff>,
+}
+
+fn artist_names(track: &Track) -> String {
+    let names: Vec<&str> = track
+        .artists
+        .iter()
+        .map(|artist| artist.name.as_str())
+        .collect();
+    names.join(", ")
+}
+
+impl TrackChange {
+    fn new(index: usize, track: &Track) -> Self {
+        TrackChange {
+            position: index + 1,
+            id: track.id.clone(),
+            name: track.name.clone(),
+            artists: artist_names(track),
+        }
+    }
+}
+
+impl TrackDiff {
+    /// The number of added, removed and moved tracks.
+    pub fn count(&self) -> usize {
+        self.added.len() + self.removed.len() + self.moved.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+}
+
+impl PlaylistChange {
+    fn new(export: &PlaylistExport) -> Self {
+        PlaylistChange {
+            id: export.id.clone(),
+            name: export.name.clone(),
+            tracks: export.tracks.len(),
+        }
+    }
+}
+
+impl BackupDiff {
+    /// The number of added and removed playlists and of changed tracks.
+    pub fn count(&self) -> usize {
+        self.added.len()
+            + self.removed.len()
+            + self
+                .changed
+                .iter()
+                .map(|playlist| playlist.tracks.count())
+                .sum::<usize>()
+    }
+}
+
+/// Marks the longest run of pairs whose new positions keep their old order, the tracks
+/// that stayed in place; marking as few tracks as possible as moved.
+fn staying_in_place(pairs: &[(usize, usize)]) -> Vec<bool> {
+    // `tails[k]` is the pair ending the run of length `k + 1` with the smallest new position
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; pairs.len()];
+    for (index, (_, to)) in pairs.iter().enumerate() {
+        let length = tails.partition_point(|tail| pairs[*tail].1 < *to);
+        if length > 0 {
+            previous[index] = Some(tails[length - 1]);
+        }
+        if length == tails.len() {
+            tails.push(index);
+        } else {
+            tails[length] = index;
+        }
+    }
+    let mut staying = vec![false; pairs.len()];
+    let mut current = tails.last().copied();
+    while let Some(index) = current {
+        staying[index] = true;
+        current = previous[index];
+    }
+    staying
+}
+
+/// Compares the tracks by their URIs; a track in a playlist several times is matched
+/// copy by copy in the order they appear.
+pub fn diff_tracks(old: &[Track], new: &[impl AsRef<Track>]) -> TrackDiff {
+    let mut new_positions: HashMap<&str, VecDeque<usize>> = HashMap::new();
+    for (index, track) in new.iter().map(AsRef::as_ref).enumerate() {
+        new_positions
+            .entry(track.uri.as_str())
+            .or_default()
+            .push_back(index);
+    }
+    let mut diff = TrackDiff::default();
+    let mut matched = vec![false; new.len()];
+    let mut pairs = Vec::new();
+    for (index, track) in old.iter().enumerate() {
+        match new_positions
+            .get_mut(track.uri.as_str())
+            .and_then(|positions| positions.pop_front())
+        {
+            Some(new_index) => {
+                matched[new_index] = true;
+                pairs.push((index, new_index));
+            }
+            None => diff.removed.push(TrackChange::new(index, track)),
+        }
+    }
+    for (index, track) in new.iter().map(AsRef::as_ref).enumerate() {
+        if !matched[index] {
+            diff.added.push(TrackChange::new(index, track));
+        }
+    }
+    let staying = staying_in_place(&pairs);
+    for ((from, to), staying) in pairs.into_iter().zip(staying) {
+        if !staying {
+            let track = new[to].as_ref();
+            diff.moved.push(TrackMove {
+                from: from + 1,
+                to: to + 1,
+                id: track.id.clone(),
+                name: track.name.clone(),
+                artists: artist_names(track),
+            });
+        }
+    }
+    diff.moved.sort_by_key(|track| track.to);
+    diff
+}
+
+pub fn diff_backups(old: &Backup, new: &Backup) -> BackupDiff {
+    let mut diff = BackupDiff::default();
+    for old_export in &old.playlists {
+        match new
+            .playlists
+            .iter()
+            .find(|export| export.id == old_export.id)
+        {
+            Some(new_export) => {
+                let tracks = diff_tracks(&old_export.tracks, &new_export.tracks);
+                if !tracks.is_empty() {
+                    diff.changed.push(PlaylistDiff {
+                        id: new_export.id.clone(),
+                        old_name: old_export.name.clone(),
+                        new_name: new_export.name.clone(),
+                        tracks,
+                    });
+                }
+            }
+            None => diff.removed.push(PlaylistChange::new(old_export)),
+        }
+    }
+    for new_export in &new.playlists {
+        if !old
+            .playlists
+            .iter()
+            .any(|export| export.id == new_export.id)
+        {
+            diff.added.push(PlaylistChange::new(new_export));
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn track(id: &str) -> Track {
+        serde_json::from_value(json!({
+            "id": id,
+            "uri": format!("spotify:track:{}", id),
+            "name": id.to_uppercase(),
+            "album": {
+                "id": "album",
+                "name": "Album",
+                "album_type": "album",
+                "artists": [],
+                "total_tracks": 1,
+                "release_date": "2020",
+            },
+            "artists": [{"id": "queen", "name": "Queen"}, {"id": "bowie", "name": "David Bowie"}],
+            "duration_ms": 1000,
+        }))
+        .unwrap()
+    }
+
+    fn tracks(ids: &[&str]) -> Vec<Track> {
+        ids.iter().map(|id| track(id)).collect()
+    }
+
+    fn positions(changes: &[TrackChange]) -> Vec<(usize, &str)> {
+        changes
+            .iter()
+            .map(|change| (change.position, change.id.as_str()))
+            .collect()
+    }
+
+    fn moves(moves: &[TrackMove]) -> Vec<(usize, usize, &str)> {
+        moves
+            .iter()
+            .map(|track| (track.from, track.to, track.id.as_str()))
+            .collect()
+    }
+
+    fn playlist(id: &str, name: &str, ids: &[&str]) -> PlaylistExport {
+        PlaylistExport {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: None,
+            public: Some(false),
+            collaborative: false,
+            tracks: tracks(ids),
+        }
+    }
+
+    fn backup(playlists: Vec<PlaylistExport>) -> Backup {
+        Backup {
+            playlists,
+            liked_songs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn pairs_in_order_all_stay_in_place() {
+        assert_eq!(
+            staying_in_place(&[(0, 0), (1, 1), (2, 3)]),
+            vec![true, true, true]
+        );
+        assert_eq!(staying_in_place(&[]), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn as_few_pairs_as_possible_are_moved() {
+        // the last track moved to the front
+        assert_eq!(
+            staying_in_place(&[(0, 1), (1, 2), (2, 3), (3, 0)]),
+            vec![true, true, true, false]
+        );
+        // the first track moved to the end
+        assert_eq!(
+            staying_in_place(&[(0, 3), (1, 0), (2, 1), (3, 2)]),
+            vec![false, true, true, true]
+        );
+    }
+
+    #[test]
+    fn the_same_tracks_have_no_differences() {
+        let diff = diff_tracks(&tracks(&["a", "b", "c"]), &tracks(&["a", "b", "c"]));
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn tells_the_added_and_removed_tracks_with_their_positions() {
+        let diff = diff_tracks(&tracks(&["a", "b", "c"]), &tracks(&["a", "c", "d", "e"]));
+        assert_eq!(positions(&diff.removed), vec![(2, "b")]);
+        assert_eq!(positions(&diff.added), vec![(3, "d"), (4, "e")]);
+        assert!(diff.moved.is_empty());
+        assert_eq!(diff.count(), 3);
+        assert_eq!(diff.added[0].name, "D");
+        assert_eq!(diff.added[0].artists, "Queen, David Bowie");
+    }
+
+    #[test]
+    fn tells_only_the_track_that_moved() {
+        let diff = diff_tracks(
+            &tracks(&["a", "b", "c", "d"]),
+            &tracks(&["d", "a", "b", "c"]),
+        );
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(moves(&diff.moved), vec![(4, 1, "d")]);
+    }
+
+    #[test]
+    fn tells_the_moved_tracks_in_their_new_order() {
+        let diff = diff_tracks(
+            &tracks(&["a", "b", "c", "d"]),
+            &tracks(&["d", "c", "a", "b"]),
+        );
+        assert_eq!(moves(&diff.moved), vec![(4, 1, "d"), (3, 2, "c")]);
+    }
+
+    #[test]
+    fn matches_duplicate_tracks_copy_by_copy() {
+        let diff = diff_tracks(&tracks(&["a", "b", "a"]), &tracks(&["a", "b"]));
+        assert_eq!(positions(&diff.removed), vec![(3, "a")]);
+        assert!(diff.added.is_empty());
+        assert!(diff.moved.is_empty());
+
+        let diff = diff_tracks(&tracks(&["a", "b"]), &tracks(&["a", "a", "b"]));
+        assert_eq!(positions(&diff.added), vec![(2, "a")]);
+        assert!(diff.removed.is_empty());
+        assert!(diff.moved.is_empty());
+    }
+
+    #[test]
+    fn an_empty_playlist_adds_or_removes_every_track() {
+        let diff = diff_tracks(&[], &tracks(&["a", "b"]));
+        assert_eq!(positions(&diff.added), vec![(1, "a"), (2, "b")]);
+        assert!(diff.removed.is_empty());
+
+        let diff = diff_tracks(&tracks(&["a", "b"]), &Vec::<Track>::new());
+        assert_eq!(positions(&diff.removed), vec![(1, "a"), (2, "b")]);
+        assert!(diff.added.is_empty());
+
+        assert!(diff_tracks(&[], &Vec::<Track>::new()).is_empty());
+    }
+
+    #[test]
+    fn matches_the_playlists_of_backups_by_their_ids() {
+        let old = backup(vec![
+            playlist("kept", "Kept", &["a", "b"]),
+            playlist("renamed", "Old Name", &["a"]),
+            playlist("removed", "Removed", &["a", "b", "c"]),
+        ]);
+        let new = backup(vec![
+            playlist("added", "Added", &["d"]),
+            playlist("renamed", "New Name", &["a", "c"]),
+            playlist("kept", "Kept", &["a", "b"]),
+        ]);
+        let diff = diff_backups(&old, &new);
+        let ids = |changes: &[PlaylistChange]| -> Vec<String> {
+            changes.iter().map(|change| change.id.clone()).collect()
+        };
+        assert_eq!(ids(&diff.added), vec!["added"]);
+        assert_eq!(ids(&diff.removed), vec!["removed"]);
+        assert_eq!(diff.removed[0].tracks, 3);
+        assert_eq!(diff.changed.len(), 1);
+        let changed = &diff.changed[0];
+        assert_eq!(
+            (
+                changed.id.as_str(),
+                changed.old_name.as_str(),
+                changed.new_name.as_str()
+            ),
+            ("renamed", "Old Name", "New Name")
+        );
+        assert_eq!(positions(&changed.tracks.added), vec![(2, "c")]);
+        assert_eq!(diff.count(), 3);
+    }
+
+    #[test]
+    fn empty_backups_have_no_differences() {
+        let diff = diff_backups(&backup(Vec::new()), &backup(Vec::new()));
+        assert_eq!(diff.count(), 0);
+    }
+}
@@ -0,0 +1,269 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use futures::stream::{self, Stream, StreamExt};
+use reqwest::Client;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::playlist_actions::{get_playlist, get_playlist_tracks};
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute_api;
+use crate::models::album::SavedAlbum;
+use crate::models::artist::Artist;
+use crate::models::paging::{fetch_all_pages, pages, report_page};
+use crate::models::playlist::SimplifiedPlaylist;
+use crate::models::track::{SavedTrack, Track};
+use crate::progress::Progress;
+use crate::schemas::{validate, SchemaKind};
+use crate::{api_endpoint, tr, UserAccess};
+
+/// The files `spautofy backup --all` writes into the backup directory.
+pub const PLAYLISTS_FILE: &str = "playlists.json";
+pub const LIKED_SONGS_FILE: &str = "liked-songs.json";
+pub const FOLLOWED_ARTISTS_FILE: &str = "followed-artists.json";
+pub const SAVED_ALBUMS_FILE: &str = "saved-albums.json";
+
+/// A backup read back for restoring: a directory written by `backup --all`, or a file
+/// written by `backup` with only the playlists.
+#[derive(Debug, Default)]
+pub struct Backup {
+    pub playlists: Vec<PlaylistExport>,
+    pub liked_songs: Vec<SavedTrack>,
+}
+
+/// Checks the file against the schema of its kind before reading it, which tells better
+/// where a mistake is.
+fn read_backup_file<T: DeserializeOwned>(path: &Path, kind: SchemaKind) -> io::Result<T> {
+    let content = fs::read_to_string(path)?;
+    let file: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    validate(kind, &file).map_err(|err| {
+        let name = path
+            .file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy();
+        io::Error::new(
+            ErrorKind::InvalidData,
+            tr!("{} does not match its schema: {}", name, err),
+        )
+    })?;
+    serde_json::from_value(file).map_err(|err| io::Error::new(ErrorKind::InvalidData, err))
+}
+
+pub fn load_backup(path: &str) -> io::Result<Backup> {
+    let path = Path::new(path);
+    if !path.is_dir() {
+        return Ok(Backup {
+            playlists: read_backup_file(path, SchemaKind::Backup)?,
+            liked_songs: Vec::new(),
+        });
+    }
+    let liked_songs = path.join(LIKED_SONGS_FILE);
+    Ok(Backup {
+        playlists: read_backup_file(&path.join(PLAYLISTS_FILE), SchemaKind::Backup)?,
+        // older backups may have only some of the files
+        liked_songs: if liked_songs.exists() {
+            read_backup_file(&liked_songs, SchemaKind::LikedSongs)?
+        } else {
+            Vec::new()
+        },
+    })
+}
+
+/// A playlist with all its tracks, as written by `spautofy export` and `spautofy backup`.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct PlaylistExport {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub public: Option<bool>,
+    pub collaborative: bool,
+    pub tracks: Vec<Track>,
+}
+
+pub async fn export_playlist(
+    user_access: &UserAccess,
+    playlist_id: &str,
+    on_progress: impl FnMut(Progress),
+) -> Result<PlaylistExport, AuthorizeError> {
+    let playlist = get_playlist(user_access, playlist_id).await?;
+    let tracks = get_playlist_tracks(user_access, playlist_id, on_progress).await?;
+    Ok(PlaylistExport {
+        id: playlist.id,
+        name: playlist.name,
+        description: playlist.description,
+        public: Some(playlist.public),
+        collaborative: playlist.collaborative,
+        tracks,
+    })
+}
+
+/// Exports the playlists one after the other as the stream is polled, so only the tracks
+/// of one playlist are held at a time.
+pub fn backup_playlists(
+    user_access: &UserAccess,
+    playlists: Vec<SimplifiedPlaylist>,
+) -> impl Stream<Item = Result<PlaylistExport, AuthorizeError>> + '_ {
+    stream::iter(playlists).then(move |playlist| async move {
+        let tracks = get_playlist_tracks(user_access, &playlist.id, |_| {}).await?;
+        Ok(PlaylistExport {
+            id: playlist.id,
+            name: playlist.name,
+            description: playlist.description,
+            public: playlist.public,
+            collaborative: playlist.collaborative,
+            tracks,
+        })
+    })
+}
+
+pub async fn export_liked_songs(
+    user_access: &UserAccess,
+    on_progress: impl FnMut(Progress),
+) -> Result<Vec<SavedTrack>, AuthorizeError> {
+    fetch_all_pages(
+        user_access,
+        api_endpoint!("/me/tracks?limit=50"),
+        on_progress,
+    )
+    .await
+}
+
+/// The liked songs page by page, for libraries too large to hold at once.
+pub fn liked_song_pages<'a>(
+    user_access: &'a UserAccess,
+    on_progress: impl FnMut(Progress) + 'a,
+) -> impl Stream<Item = Result<Vec<SavedTrack>, AuthorizeError>> + 'a {
+    pages(
+        user_access,
+        api_endpoint!("/me/tracks?limit=50"),
+        on_progress,
+    )
+}
+
+pub fn saved_album_pages<'a>(
+    user_access: &'a UserAccess,
+    on_progress: impl FnMut(Progress) + 'a,
+) -> impl Stream<Item = Result<Vec<SavedAlbum>, AuthorizeError>> + 'a {
+    pages(
+        user_access,
+        api_endpoint!("/me/albums?limit=50"),
+        on_progress,
+    )
+}
+
+/// The followed artists come in pages with cursors instead of offsets.
+#[derive(Debug, Deserialize)]
+struct FollowedArtists {
+    artists: CursorPage<Artist>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CursorPage<T> {
+    items: Vec<T>,
+    next: Option<String>,
+    total: Option<i32>,
+}
+
+/// Like `pages`, following the cursors of the followed artists.
+pub fn followed_artist_pages<'a>(
+    user_access: &'a UserAccess,
+    on_progress: impl FnMut(Progress) + 'a,
+) -> impl Stream<Item = Result<Vec<Artist>, AuthorizeError>> + 'a {
+    let client = Client::new();
+    let url = api_endpoint!("/me/following?type=artist&limit=50");
+    stream::try_unfold(
+        (Some(url), 0, on_progress),
+        move |(next, done, mut on_progress)| {
+            let client = client.clone();
+            async move {
+                let Some(url) = next else {
+                    return Ok(None);
+                };
+                let request_builder = client.get(url);
+                let request_builder = user_access.authorize(request_builder);
+                let request = request_builder.build()?;
+                let resp = execute_api(&client, request).await?;
+                let page = resp.json::<FollowedArtists>().await?.artists;
+                let done = done + page.items.len();
+                let total = page.total.unwrap_or_default().max(0) as usize;
+                report_page(done, total, &mut on_progress);
+                Ok(Some((page.items, (page.next, done, on_progress))))
+            }
+        },
+    )
+}
+
+/// A file written under a temporary name next to `path`, which only replaces `path` once
+/// it is complete; an export failing halfway leaves the last one as it was.
+pub struct PartialFile {
+    file: BufWriter<File>,
+    partial: PathBuf,
+    path: PathBuf,
+}
+
+impl PartialFile {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".part");
+        let partial = PathBuf::from(partial);
+        Ok(PartialFile {
+            file: BufWriter::new(File::create(&partial)?),
+            partial,
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn persist(mut self) -> io::Result<()> {
+        self.file.flush()?;
+        fs::rename(&self.partial, &self.path)
+    }
+}
+
+impl Write for PartialFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Writes a JSON array one element at a time, formatted like `serde_json::to_string_pretty`
+/// would format the whole array.
+pub struct JsonArrayWriter<W: Write> {
+    out: W,
+    len: usize,
+}
+
+impl<W: Write> JsonArrayWriter<W> {
+    pub fn new(out: W) -> Self {
+        JsonArrayWriter { out, len: 0 }
+    }
+
+    pub fn push(&mut self, value: &impl Serialize) -> io::Result<()> {
+        let element = serde_json::to_string_pretty(value).map_err(io::Error::other)?;
+        self.out
+            .write_all(if self.len == 0 { b"[\n" } else { b",\n" })?;
+        // strings in JSON escape their line breaks, so every line is part of the layout
+        for (index, line) in element.lines().enumerate() {
+            if index > 0 {
+                self.out.write_all(b"\n")?;
+            }
+            write!(self.out, "  {}", line)?;
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Closes the array, returning the number of elements and the writer.
+    pub fn finish(mut self) -> io::Result<(usize, W)> {
+        self.out
+            .write_all(if self.len == 0 { b"[]" } else { b"\n]" })?;
+        Ok((self.len, self.out))
+    }
+}
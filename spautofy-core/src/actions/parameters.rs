@@ -0,0 +1,253 @@
+use crate::actions::playlist_actions::PlaylistTarget;
+use crate::actions::top_track_playlist::{
+    TimeRange, TopTrackPlaylistParams, DEFAULT_NAME_TEMPLATE, MAX_TOP_TRACKS_LIMIT,
+};
+use crate::i18n::t;
+use crate::tr;
+
+#[derive(Debug, Clone)]
+pub enum ParameterValue {
+    Text {
+        value: String,
+        placeholder: String,
+    },
+    Number {
+        value: u32,
+        min: u32,
+        max: u32,
+    },
+    Toggle(bool),
+    Choice {
+        options: Vec<String>,
+        index: usize,
+    },
+    /// Any number of the options, `cursor` is the option toggled next.
+    MultiChoice {
+        options: Vec<String>,
+        selected: Vec<bool>,
+        cursor: usize,
+    },
+    /// An existing playlist to update, or `None` to create a new one.
+    Playlist(Option<PlaylistTarget>),
+}
+
+impl ParameterValue {
+    pub fn is_editable_text(&self) -> bool {
+        matches!(
+            self,
+            ParameterValue::Text { .. } | ParameterValue::Number { .. }
+        )
+    }
+
+    pub fn step(&mut self, forward: bool) {
+        match self {
+            ParameterValue::Text { .. } => {}
+            ParameterValue::Number { value, min, max } => {
+                *value = if forward {
+                    value.saturating_add(1).min(*max)
+                } else {
+                    value.saturating_sub(1).max(*min)
+                };
+            }
+            ParameterValue::Toggle(value) => *value = !*value,
+            ParameterValue::Choice { options, index } => {
+                *index = if forward {
+                    (*index + 1) % options.len()
+                } else {
+                    (*index + options.len() - 1) % options.len()
+                };
+            }
+            ParameterValue::MultiChoice {
+                options, cursor, ..
+            } => {
+                *cursor = if forward {
+                    (*cursor + 1) % options.len()
+                } else {
+                    (*cursor + options.len() - 1) % options.len()
+                };
+            }
+            ParameterValue::Playlist(target) => *target = None,
+        }
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        match self {
+            ParameterValue::Text { value, .. } => value.push(c),
+            ParameterValue::Number { value, max, .. } => {
+                if let Some(digit) = c.to_digit(10) {
+                    *value = value.saturating_mul(10).saturating_add(digit).min(*max);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn pop_char(&mut self) {
+        match self {
+            ParameterValue::Text { value, .. } => {
+                value.pop();
+            }
+            ParameterValue::Number { value, .. } => *value /= 10,
+            _ => {}
+        }
+    }
+
+    pub fn finish_editing(&mut self) {
+        if let ParameterValue::Number { value, min, max } = self {
+            *value = (*value).clamp(*min, *max);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub value: ParameterValue,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActionParameters {
+    pub title: String,
+    pub description: String,
+    pub parameters: Vec<Parameter>,
+}
+
+impl ActionParameters {
+    /// The top tracks action, creating one playlist for each of the `time_ranges`.
+    pub fn top_tracks(time_ranges: &[TimeRange], params: &TopTrackPlaylistParams) -> Self {
+        let ranges: Vec<String> = TimeRange::ALL
+            .iter()
+            .map(|time_range| format!("{} ({})", time_range, t(time_range.description())))
+            .collect();
+        ActionParameters {
+            title: tr!("Top Tracks Playlists"),
+            description: tr!(
+                "Creates a playlist with the tracks you listened to most for each selected time \
+                range: {}.",
+                ranges.join(", ")
+            ),
+            parameters: vec![
+                Parameter {
+                    key: "time_ranges",
+                    label: "Time ranges",
+                    value: ParameterValue::MultiChoice {
+                        options: TimeRange::ALL.iter().map(|t| t.to_string()).collect(),
+                        selected: TimeRange::ALL
+                            .iter()
+                            .map(|t| time_ranges.contains(t))
+                            .collect(),
+                        cursor: 0,
+                    },
+                },
+                Parameter {
+                    key: "limit",
+                    label: "Number of tracks",
+                    value: ParameterValue::Number {
+                        value: params.limit,
+                        min: 1,
+                        max: MAX_TOP_TRACKS_LIMIT,
+                    },
+                },
+                Parameter {
+                    key: "name",
+                    label: "Playlist name",
+                    value: ParameterValue::Text {
+                        value: params.name.clone().unwrap_or_default(),
+                        placeholder: DEFAULT_NAME_TEMPLATE.to_string(),
+                    },
+                },
+                Parameter {
+                    key: "public",
+                    label: "Public",
+                    value: ParameterValue::Toggle(params.public),
+                },
+                Parameter {
+                    key: "collaborative",
+                    label: "Collaborative",
+                    value: ParameterValue::Toggle(params.collaborative),
+                },
+                Parameter {
+                    key: "target",
+                    label: "Update playlist",
+                    value: ParameterValue::Playlist(params.target.clone()),
+                },
+            ],
+        }
+    }
+
+    pub fn text(&self, key: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find_map(|parameter| match &parameter.value {
+                ParameterValue::Text { value, .. } if parameter.key == key => Some(value.trim()),
+                _ => None,
+            })
+    }
+
+    pub fn number(&self, key: &str) -> Option<u32> {
+        self.parameters
+            .iter()
+            .find_map(|parameter| match &parameter.value {
+                ParameterValue::Number { value, .. } if parameter.key == key => Some(*value),
+                _ => None,
+            })
+    }
+
+    pub fn set_playlist(&mut self, key: &str, target: PlaylistTarget) {
+        for parameter in &mut self.parameters {
+            if let ParameterValue::Playlist(value) = &mut parameter.value {
+                if parameter.key == key {
+                    *value = Some(target.clone());
+                }
+            }
+        }
+    }
+
+    /// One set of top tracks parameters for each selected time range.
+    pub fn top_track_params(&self) -> Result<Vec<TopTrackPlaylistParams>, String> {
+        let mut params = TopTrackPlaylistParams::new(TimeRange::ShortTerm);
+        let mut time_ranges = Vec::new();
+        for parameter in &self.parameters {
+            match (parameter.key, &parameter.value) {
+                ("time_ranges", ParameterValue::MultiChoice { selected, .. }) => {
+                    time_ranges = TimeRange::ALL
+                        .iter()
+                        .zip(selected)
+                        .filter_map(|(time_range, selected)| selected.then_some(*time_range))
+                        .collect();
+                }
+                ("limit", ParameterValue::Number { value, .. }) => params.limit = *value,
+                ("name", ParameterValue::Text { value, .. }) => {
+                    params.name = Some(value.trim().to_string()).filter(|name| !name.is_empty())
+                }
+                ("public", ParameterValue::Toggle(value)) => params.public = *value,
+                ("collaborative", ParameterValue::Toggle(value)) => params.collaborative = *value,
+                ("target", ParameterValue::Playlist(target)) => params.target = target.clone(),
+                _ => {}
+            }
+        }
+        if time_ranges.is_empty() {
+            return Err(tr!("{}: select at least one time range.", self.title));
+        }
+        if params.public && params.collaborative {
+            return Err(tr!(
+                "{}: a collaborative playlist cannot be public.",
+                self.title
+            ));
+        }
+        if params.target.is_some() && time_ranges.len() > 1 {
+            return Err(tr!(
+                "{}: select a single time range to update an existing playlist.",
+                self.title
+            ));
+        }
+        Ok(time_ranges
+            .into_iter()
+            .map(|time_range| TopTrackPlaylistParams {
+                time_range,
+                ..params.clone()
+            })
+            .collect())
+    }
+}
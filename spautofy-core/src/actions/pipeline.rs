@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::actions::registry::parse_action;
+use crate::actions::top_track_playlist::{TopTrackPlaylistParams, MAX_TOP_TRACKS_LIMIT};
+use crate::actions::{ActionsConfig, Task};
+
+/// The pipelines of the config by name, run with `spautofy run <name>`.
+pub type Pipelines = BTreeMap<String, Vec<PipelineStep>>;
+
+/// One action of a pipeline with its parameters, e.g.
+/// `{"action": "top-tracks:short", "limit": 30}`; unset parameters keep the defaults from
+/// the `"actions"` section.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PipelineStep {
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collaborative: Option<bool>,
+}
+
+impl PipelineStep {
+    pub fn params(&self, config: &ActionsConfig) -> Result<TopTrackPlaylistParams, String> {
+        let Task::TopTracks(mut params) = parse_action(&self.action)?.with_config(config) else {
+            return Err(format!(
+                "\"{}\" cannot be a step of a pipeline",
+                self.action
+            ));
+        };
+        if let Some(limit) = self.limit {
+            if !(1..=MAX_TOP_TRACKS_LIMIT).contains(&limit) {
+                return Err(format!(
+                    "limit {} is not between 1 and {}",
+                    limit, MAX_TOP_TRACKS_LIMIT
+                ));
+            }
+            params.limit = limit;
+        }
+        if let Some(name) = &self.name {
+            params.name = Some(name.clone());
+        }
+        params.public = self.public.unwrap_or(params.public);
+        params.collaborative = self.collaborative.unwrap_or(params.collaborative);
+        if params.public && params.collaborative {
+            return Err("a collaborative playlist cannot be public".to_string());
+        }
+        Ok(params)
+    }
+}
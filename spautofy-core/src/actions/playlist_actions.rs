@@ -0,0 +1,186 @@
+use futures::stream::Stream;
+use reqwest::Client;
+use serde_json::json;
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute_api;
+use crate::models::paging::{fetch_all_pages, pages};
+use crate::models::playlist::{Playlist, PlaylistItem, SimplifiedPlaylist};
+use crate::models::track::Track;
+use crate::progress::Progress;
+use crate::{api_endpoint, UserAccess};
+
+const MAX_TRACKS_PER_REQUEST: usize = 100;
+
+/// An existing playlist an action updates instead of creating a new one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaylistTarget {
+    pub id: String,
+    pub name: String,
+}
+
+impl PlaylistTarget {
+    pub fn url(&self) -> String {
+        format!("https://open.spotify.com/playlist/{}", self.id)
+    }
+}
+
+/// Who can see and change the playlists an action creates; Spotify only allows
+/// collaborative playlists that are not public.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlaylistVisibility {
+    pub public: bool,
+    pub collaborative: bool,
+}
+
+pub async fn create_playlist(
+    user_access: &UserAccess,
+    name: &str,
+    public: bool,
+    description: Option<&str>,
+    collaborative: bool,
+) -> Result<Playlist, AuthorizeError> {
+    let client = Client::new();
+    let user_id = &user_access.user.id;
+    let request_builder = client.post(api_endpoint!("/users/{user_id}/playlists"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder
+        .body(
+            json!({
+                "name": name,
+                "public": public,
+                "description": description.unwrap_or_default(),
+                "collaborative": collaborative,
+            })
+            .to_string(),
+        )
+        .build()?;
+    let resp = execute_api(&client, request).await?;
+    let resp = resp.json::<Playlist>().await?;
+    Ok(resp)
+}
+
+/// Adds the tracks at the end of the playlist, at most 100 per request.
+pub async fn add_to_playlist(
+    user_access: &UserAccess,
+    playlist_id: &str,
+    track_uris: &[&str],
+) -> Result<(), AuthorizeError> {
+    let client = Client::new();
+    for chunk in track_uris.chunks(MAX_TRACKS_PER_REQUEST) {
+        let request_builder = client.post(api_endpoint!("/playlists/{playlist_id}/tracks"));
+        let request_builder = user_access.authorize(request_builder);
+        let request = request_builder
+            .body(json!({ "uris": chunk }).to_string())
+            .build()?;
+        let _resp = execute_api(&client, request).await?;
+    }
+    Ok(())
+}
+
+/// Replaces the tracks of the playlist; Spotify takes at most 100 tracks per request, so
+/// the rest are added afterwards.
+pub async fn update_playlist_tracks(
+    user_access: &UserAccess,
+    playlist_id: &str,
+    track_uris: &[&str],
+) -> Result<(), AuthorizeError> {
+    let client = Client::new();
+    let (first, rest) = track_uris.split_at(track_uris.len().min(MAX_TRACKS_PER_REQUEST));
+    let request_builder = client.put(api_endpoint!("/playlists/{playlist_id}/tracks"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder
+        .body(json!({ "uris": first }).to_string())
+        .build()?;
+    let _resp = execute_api(&client, request).await?;
+    add_to_playlist(user_access, playlist_id, rest).await
+}
+
+/// Deletes the playlist if it is owned by the user, otherwise just stops following it.
+pub async fn unfollow_playlist(
+    user_access: &UserAccess,
+    playlist_id: &str,
+) -> Result<(), AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.delete(api_endpoint!("/playlists/{playlist_id}/followers"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder.build()?;
+    let _resp = execute_api(&client, request).await?;
+    Ok(())
+}
+
+pub async fn get_playlist(
+    user_access: &UserAccess,
+    playlist_id: &str,
+) -> Result<Playlist, AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!("/playlists/{playlist_id}"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder.build()?;
+    let resp = execute_api(&client, request).await?;
+    let resp = resp.json::<Playlist>().await?;
+    Ok(resp)
+}
+
+/// All entries of the playlist, including the ones whose track is no longer available.
+pub async fn get_playlist_items(
+    user_access: &UserAccess,
+    playlist_id: &str,
+    on_progress: impl FnMut(Progress),
+) -> Result<Vec<PlaylistItem>, AuthorizeError> {
+    fetch_all_pages(
+        user_access,
+        api_endpoint!("/playlists/{playlist_id}/tracks?limit=100"),
+        on_progress,
+    )
+    .await
+}
+
+/// The entries of the playlist page by page, for playlists too large to hold at once.
+pub fn playlist_item_pages<'a>(
+    user_access: &'a UserAccess,
+    playlist_id: &str,
+    on_progress: impl FnMut(Progress) + 'a,
+) -> impl Stream<Item = Result<Vec<PlaylistItem>, AuthorizeError>> + 'a {
+    pages(
+        user_access,
+        api_endpoint!("/playlists/{playlist_id}/tracks?limit=100"),
+        on_progress,
+    )
+}
+
+/// All tracks of the playlist, leaving out the ones that are no longer available.
+pub async fn get_playlist_tracks(
+    user_access: &UserAccess,
+    playlist_id: &str,
+    on_progress: impl FnMut(Progress),
+) -> Result<Vec<Track>, AuthorizeError> {
+    let items = get_playlist_items(user_access, playlist_id, on_progress).await?;
+    Ok(items.into_iter().filter_map(|item| item.track).collect())
+}
+
+pub async fn get_current_user_playlists(
+    user_access: &UserAccess,
+    on_progress: impl FnMut(Progress),
+) -> Result<Vec<SimplifiedPlaylist>, AuthorizeError> {
+    fetch_all_pages(
+        user_access,
+        api_endpoint!("/me/playlists?limit=50"),
+        on_progress,
+    )
+    .await
+}
+
+/// The public playlists of another user, the ones on their profile.
+pub async fn get_user_playlists(
+    user_access: &UserAccess,
+    user_id: &str,
+    on_progress: impl FnMut(Progress),
+) -> Result<Vec<SimplifiedPlaylist>, AuthorizeError> {
+    fetch_all_pages(
+        user_access,
+        api_endpoint!("/users/{user_id}/playlists?limit=50"),
+        on_progress,
+    )
+    .await
+}
@@ -0,0 +1,269 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use serde_json::json;
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::actions::diff::diff_tracks;
+use crate::actions::is_dry_run;
+use crate::actions::playlist_actions::{
+    create_playlist, get_current_user_playlists, get_playlist, get_playlist_tracks,
+    update_playlist_tracks, PlaylistTarget, PlaylistVisibility,
+};
+use crate::authorize::AuthorizeError;
+use crate::dates::now;
+use crate::models::track::Track;
+use crate::progress;
+use crate::storage;
+use crate::{status, UserAccess};
+
+/// Which playlist gets the tracks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Target {
+    /// A new playlist, even if the user has one of the same name.
+    #[default]
+    New,
+    /// The tracks of this playlist are replaced.
+    Existing(PlaylistTarget),
+    /// The user's own playlist of the same name is updated, a new one created if there is
+    /// none.
+    Named,
+}
+
+/// Writes a playlist of tracks, the way every action creating or updating one does: the
+/// tracks are given, narrowed down in the order the transforms are called and written to
+/// the target on `build`, or only told about in a dry run.
+///
+/// The tracks can be anything holding a track, like the candidates of a rule, so the
+/// transforms can use what else they know about them.
+#[derive(Debug)]
+pub struct PlaylistBuilder<T = Track> {
+    name: String,
+    description: Option<String>,
+    visibility: PlaylistVisibility,
+    target: Target,
+    tracks: Vec<T>,
+    diff: bool,
+    snapshot: bool,
+}
+
+impl<T: AsRef<Track>> PlaylistBuilder<T> {
+    /// A new private playlist of no tracks without a description.
+    pub fn new(name: impl Into<String>) -> Self {
+        PlaylistBuilder {
+            name: name.into(),
+            description: None,
+            visibility: PlaylistVisibility::default(),
+            target: Target::New,
+            tracks: Vec::new(),
+            diff: false,
+            snapshot: false,
+        }
+    }
+
+    pub fn description(mut self, description: Option<impl Into<String>>) -> Self {
+        self.description = description.map(Into::into);
+        self
+    }
+
+    pub fn visibility(mut self, visibility: PlaylistVisibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    pub fn target(mut self, target: Target) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Replaces the tracks of the playlist if one is given, else creates a new one.
+    pub fn replacing(self, target: Option<PlaylistTarget>) -> Self {
+        self.target(target.map_or(Target::New, Target::Existing))
+    }
+
+    /// Adds the tracks after the ones given before.
+    pub fn tracks(mut self, tracks: impl IntoIterator<Item = T>) -> Self {
+        self.tracks.extend(tracks);
+        self
+    }
+
+    pub fn filter(mut self, keep: impl FnMut(&T) -> bool) -> Self {
+        self.tracks.retain(keep);
+        self
+    }
+
+    /// Leaves out the tracks with these ids.
+    pub fn exclude(self, track_ids: &[String]) -> Self {
+        self.filter(|track| !track_ids.contains(&track.as_ref().id))
+    }
+
+    /// Keeps only the first track of each recording, see `Track::recording_id`.
+    pub fn dedup(self) -> Self {
+        self.dedup_by_key(|track| track.as_ref().recording_id().to_string())
+    }
+
+    /// Keeps only the first track of each key.
+    pub fn dedup_by_key(mut self, mut key: impl FnMut(&T) -> String) -> Self {
+        let mut seen = HashSet::new();
+        self.tracks.retain(|track| seen.insert(key(track)));
+        self
+    }
+
+    /// Sorts the tracks stably, so equal ones keep their order.
+    pub fn sort_by(mut self, compare: impl FnMut(&T, &T) -> Ordering) -> Self {
+        self.tracks.sort_by(compare);
+        self
+    }
+
+    pub fn reverse(mut self) -> Self {
+        self.tracks.reverse();
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.tracks.truncate(limit);
+        self
+    }
+
+    /// Tells how the tracks of an existing target changed, in `CreatedPlaylist::changes`.
+    pub fn diff_with_target(mut self) -> Self {
+        self.diff = true;
+        self
+    }
+
+    /// Keeps the tracks written in the local storage, for `spautofy history`.
+    pub fn record_snapshot(mut self) -> Self {
+        self.snapshot = true;
+        self
+    }
+
+    pub async fn build(self, user_access: &UserAccess) -> Result<CreatedPlaylist, AuthorizeError> {
+        let target = match self.target {
+            Target::New => None,
+            // its name may have changed, or not be known at all for a target from the config
+            Target::Existing(target) => {
+                let playlist = get_playlist(user_access, &target.id).await?;
+                Some(PlaylistTarget {
+                    id: playlist.id,
+                    name: playlist.name,
+                })
+            }
+            Target::Named => find_target(user_access, &self.name).await?,
+        };
+        let track_uris: Vec<&str> = self
+            .tracks
+            .iter()
+            .map(|track| track.as_ref().uri.as_str())
+            .collect();
+        let cover_url = self
+            .tracks
+            .first()
+            .and_then(|track| track.as_ref().cover_url());
+        let changes = match &target {
+            Some(target) if self.diff => {
+                let old_tracks = get_playlist_tracks(user_access, &target.id, |_| {}).await?;
+                Some(diff_tracks(&old_tracks, &self.tracks))
+            }
+            _ => None,
+        };
+        let updated = target.is_some();
+
+        if is_dry_run() {
+            let (name, url, id) = match target {
+                Some(target) => {
+                    status!(
+                        "Would replace the tracks of playlist \"{}\" with {} tracks.",
+                        target.name,
+                        track_uris.len()
+                    );
+                    (target.name.clone(), target.url(), Some(target.id))
+                }
+                None => {
+                    status!(
+                        "Would create playlist \"{}\" with {} tracks.",
+                        self.name,
+                        track_uris.len()
+                    );
+                    (self.name, String::new(), None)
+                }
+            };
+            return Ok(CreatedPlaylist {
+                name,
+                url,
+                id,
+                track_count: track_uris.len(),
+                cover_url,
+                updated,
+                changes,
+            });
+        }
+
+        let playlist = match target {
+            Some(target) => target,
+            None => {
+                let playlist = create_playlist(
+                    user_access,
+                    &self.name,
+                    self.visibility.public,
+                    self.description.as_deref(),
+                    self.visibility.collaborative,
+                )
+                .await?;
+                PlaylistTarget {
+                    id: playlist.id,
+                    name: playlist.name,
+                }
+            }
+        };
+        update_playlist_tracks(user_access, &playlist.id, &track_uris).await?;
+        if self.snapshot {
+            storage::record(|storage| {
+                let tracks = self.tracks.iter().map(AsRef::as_ref);
+                storage.add_playlist_snapshot(now(), &playlist.id, &playlist.name, tracks)
+            });
+        }
+        Ok(CreatedPlaylist {
+            url: playlist.url(),
+            name: playlist.name,
+            id: Some(playlist.id),
+            track_count: track_uris.len(),
+            cover_url,
+            updated,
+            changes,
+        })
+    }
+}
+
+/// Tells that the playlist was written, unless it was a dry run.
+pub fn announce(playlist: &CreatedPlaylist) {
+    if is_dry_run() {
+        return;
+    }
+    let event = if playlist.updated {
+        status!("Updated playlist \"{}\", enjoy!", playlist.name);
+        "playlist_updated"
+    } else {
+        status!("Created playlist \"{}\", enjoy!", playlist.name);
+        "playlist_created"
+    };
+    progress::emit(
+        event,
+        json!({ "name": playlist.name, "url": playlist.url, "tracks": playlist.track_count }),
+    );
+}
+
+/// The user's own playlist of the name, if there is one.
+async fn find_target(
+    user_access: &UserAccess,
+    name: &str,
+) -> Result<Option<PlaylistTarget>, AuthorizeError> {
+    let target = get_current_user_playlists(user_access, |_| {})
+        .await?
+        .into_iter()
+        .find(|playlist| playlist.owner.id == user_access.user.id && playlist.name == name)
+        .map(|playlist| PlaylistTarget {
+            id: playlist.id,
+            name: playlist.name,
+        });
+    Ok(target)
+}
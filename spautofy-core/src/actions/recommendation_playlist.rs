@@ -0,0 +1,46 @@
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::actions::playlist_actions::PlaylistVisibility;
+use crate::actions::playlist_builder::PlaylistBuilder;
+use crate::authorize::AuthorizeError;
+use crate::dates::today;
+use crate::endpoints::execute_api;
+use crate::models::track::Track;
+use crate::{api_endpoint, UserAccess};
+
+/// The recommendations endpoint accepts at most five seeds in total.
+pub const MAX_SEEDS: usize = 5;
+
+#[derive(Debug, Deserialize)]
+struct RecommendationsResponse {
+    tracks: Vec<Track>,
+}
+
+pub async fn create_recommendation_playlist(
+    user_access: &UserAccess,
+    seed_track_ids: &[String],
+    seed_artist_ids: &[String],
+    limit: u32,
+    visibility: PlaylistVisibility,
+) -> Result<CreatedPlaylist, AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!("/recommendations"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder
+        .query(&[
+            ("seed_tracks", seed_track_ids.join(",").as_str()),
+            ("seed_artists", seed_artist_ids.join(",").as_str()),
+            ("limit", limit.to_string().as_str()),
+        ])
+        .build()?;
+    let resp = execute_api(&client, request).await?;
+    let resp = resp.json::<RecommendationsResponse>().await?;
+
+    PlaylistBuilder::new(format!("Spautofy Recommendations {}", today()))
+        .visibility(visibility)
+        .tracks(resp.tracks)
+        .build(user_access)
+        .await
+}
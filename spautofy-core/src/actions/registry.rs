@@ -0,0 +1,125 @@
+use std::fmt::Debug;
+
+use futures::future::BoxFuture;
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::actions::parameters::ActionParameters;
+use crate::actions::playlist_actions::PlaylistVisibility;
+use crate::actions::rules::RulesAction;
+use crate::actions::top_track_playlist::TopTracksAction;
+use crate::actions::{ActionsConfig, Task};
+use crate::authorize::AuthorizeError;
+use crate::UserAccess;
+
+/// Every action, in the order the TUI lists them. Adding an action here offers it in the
+/// TUI and to `spautofy run`, and asks for its scopes when logging in.
+pub static ACTIONS: &[&dyn Action] = &[&TopTracksAction, &RulesAction];
+
+/// A kind of action Spautofy runs, like creating the top tracks playlists.
+pub trait Action: Debug + Sync {
+    /// The section of the action in the `"actions"` config, naming it in the selection.
+    fn key(&self) -> &'static str;
+
+    /// Shown in the list of actions in the TUI.
+    fn name(&self) -> &'static str;
+
+    /// Shown next to its names in the help of `spautofy run`.
+    fn description(&self) -> &'static str;
+
+    /// The Spotify scopes running the action needs.
+    fn scopes(&self) -> &'static [&'static str];
+
+    /// Whether the TUI lists the action; the rules are set up in the config instead.
+    fn selectable(&self) -> bool {
+        true
+    }
+
+    /// The names `spautofy run` and the pipelines take for the action, like
+    /// `top-tracks:short`.
+    fn cli_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Parses a name like one of the `cli_names`; `None` if it does not name this action.
+    fn parse(&self, _name: &str) -> Option<Result<Task, String>> {
+        None
+    }
+
+    /// The parameters edited in the TUI, starting with the defaults of the config.
+    fn params(
+        &self,
+        _config: &ActionsConfig,
+        _visibility: Option<PlaylistVisibility>,
+    ) -> ActionParameters {
+        ActionParameters {
+            title: self.name().to_string(),
+            description: self.description().to_string(),
+            parameters: Vec::new(),
+        }
+    }
+
+    /// The tasks the parameters edited in the TUI run, or why they are invalid.
+    fn tasks(
+        &self,
+        _params: &ActionParameters,
+        _config: &ActionsConfig,
+    ) -> Result<Vec<Task>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Runs one of the tasks of the action, see `Task::action`.
+    fn run<'a>(
+        &'a self,
+        user_access: &'a UserAccess,
+        task: &'a Task,
+    ) -> BoxFuture<'a, Result<CreatedPlaylist, AuthorizeError>>;
+}
+
+/// The actions listed in the TUI, in the order of the selection.
+pub fn selectable() -> Vec<&'static dyn Action> {
+    ACTIONS
+        .iter()
+        .copied()
+        .filter(|action| action.selectable())
+        .collect()
+}
+
+/// The keys of the actions listed in the TUI, naming them in the selection.
+pub fn selectable_keys() -> Vec<&'static str> {
+    selectable().iter().map(|action| action.key()).collect()
+}
+
+/// The actions `spautofy run` accepts, each with its action.
+pub fn cli_names() -> impl Iterator<Item = (&'static str, &'static dyn Action)> {
+    ACTIONS
+        .iter()
+        .flat_map(|action| action.cli_names().iter().map(move |name| (*name, *action)))
+}
+
+/// Parses an action like `top-tracks:short`, given on the command line or in a pipeline;
+/// the errors stay English like the rest of clap's, the locale may not be loaded yet.
+pub fn parse_action(name: &str) -> Result<Task, String> {
+    ACTIONS
+        .iter()
+        .find_map(|action| action.parse(name))
+        .unwrap_or_else(|| {
+            let names: Vec<&str> = cli_names().map(|(name, _)| name).collect();
+            Err(format!(
+                "unknown action \"{}\", expected one of: {}",
+                name,
+                names.join(", ")
+            ))
+        })
+}
+
+/// The `scopes` followed by the ones of every action, each once, as the login asks for
+/// them.
+pub fn scopes(scopes: &[&'static str]) -> String {
+    let mut all = scopes.to_vec();
+    for scope in ACTIONS.iter().flat_map(|action| action.scopes()) {
+        if !all.contains(scope) {
+            all.push(scope);
+        }
+    }
+    all.join(" ")
+}
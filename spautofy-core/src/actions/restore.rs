@@ -0,0 +1,59 @@
+use reqwest::Client;
+use serde_json::json;
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::actions::export::PlaylistExport;
+use crate::actions::is_dry_run;
+use crate::actions::playlist_actions::PlaylistVisibility;
+use crate::actions::playlist_builder::PlaylistBuilder;
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute_api;
+use crate::{api_endpoint, status, UserAccess};
+
+const MAX_SAVED_TRACKS_PER_REQUEST: usize = 50;
+
+/// Creates a new playlist like the exported one.
+pub async fn restore_playlist(
+    user_access: &UserAccess,
+    export: &PlaylistExport,
+) -> Result<CreatedPlaylist, AuthorizeError> {
+    let playlist = PlaylistBuilder::new(export.name.as_str())
+        .description(export.description.as_deref())
+        .visibility(PlaylistVisibility {
+            public: export.public.unwrap_or_default() && !export.collaborative,
+            collaborative: export.collaborative,
+        })
+        .tracks(&export.tracks)
+        .build(user_access)
+        .await?;
+    if !is_dry_run() {
+        status!(
+            "Restored playlist \"{}\" with {} tracks.",
+            playlist.name,
+            playlist.track_count
+        );
+    }
+    Ok(playlist)
+}
+
+/// Adds the tracks to the liked songs; liking a track twice changes nothing.
+pub async fn like_tracks(
+    user_access: &UserAccess,
+    track_ids: &[&str],
+) -> Result<(), AuthorizeError> {
+    if is_dry_run() {
+        status!("Would like {} tracks.", track_ids.len());
+        return Ok(());
+    }
+    let client = Client::new();
+    for chunk in track_ids.chunks(MAX_SAVED_TRACKS_PER_REQUEST) {
+        let request_builder = client.put(api_endpoint!("/me/tracks"));
+        let request_builder = user_access.authorize(request_builder);
+        let request = request_builder
+            .body(json!({ "ids": chunk }).to_string())
+            .build()?;
+        let _resp = execute_api(&client, request).await?;
+    }
+    status!("Liked {} tracks.", track_ids.len());
+    Ok(())
+}
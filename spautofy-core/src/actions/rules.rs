@@ -0,0 +1,895 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::pin::pin;
+
+use futures::future::BoxFuture;
+use futures::stream::TryStreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::actions::export::liked_song_pages;
+use crate::actions::playlist_actions::{playlist_item_pages, PlaylistVisibility};
+use crate::actions::playlist_builder::{announce, PlaylistBuilder, Target};
+use crate::actions::registry::Action;
+use crate::actions::search::search_track;
+use crate::actions::top_track_playlist::{get_top_tracks, TimeRange, MAX_TOP_TRACKS_LIMIT};
+use crate::actions::Task;
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute_api;
+use crate::integrations::lastfm::{
+    get_top_tracks as get_top_tracks_of_lastfm, LastfmConfig, Period,
+};
+use crate::integrations::listenbrainz::{get_top_recordings, ListenbrainzConfig, StatsRange};
+use crate::integrations::musicbrainz::{Enrichment, MusicBrainz};
+use crate::integrations::{IntegrationsConfig, ListenedTrack};
+use crate::models::artist::Artist;
+use crate::models::playlist::SPAUTOFY_DESCRIPTION;
+use crate::models::track::Track;
+use crate::{api_endpoint, status, status_error, status_verbose, UserAccess};
+
+/// Spotify returns at most this many artists per request.
+const ARTISTS_PER_REQUEST: usize = 50;
+
+/// How many tracks of Last.fm or ListenBrainz are looked up on Spotify for a rule without
+/// a limit.
+const DEFAULT_LISTENED_TRACKS: u32 = 50;
+
+/// The custom actions of the config by name, also run with `spautofy run <name>`.
+pub type Rules = BTreeMap<String, RuleConfig>;
+
+/// A custom action, e.g. `{"source": "liked", "filter": "genre:metal & year>=2010",
+/// "sort": "-year", "target": "Modern Metal"}`: the tracks of the source matching every
+/// condition of the filter replace the tracks of the user's playlist named like the
+/// target, which is created if there is none.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RuleConfig {
+    /// `liked`, `top:<short|medium|long>`, `playlist:<id>`, `lastfm:<period>`, see
+    /// `lastfm::Period`, or `listenbrainz:<range>`, see `listenbrainz::StatsRange`.
+    pub source: String,
+    /// Conditions joined by `&`: `genre:`, `artist:`, `album:`, `name:` and, with `enrich`,
+    /// `work:` look for a text, `year` and `duration` in seconds are compared with `=`,
+    /// `!=`, `<`, `<=`, `>` or `>=`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    /// `added`, `year`, `name`, `artist` or `duration`, descending with a leading `-`;
+    /// without it the order of the source is kept.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Looks the tracks up on MusicBrainz by their ISRCs, about one per second: `year` is
+    /// then the year of the first release, not that of a remaster, `artist:` also matches
+    /// the credited artists and `work:` the works recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enrich: Option<bool>,
+    /// Keeps a recording only once, also when it is on several albums: by its MusicBrainz
+    /// recording with `enrich`, else by its ISRC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dedup: Option<bool>,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collaborative: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    LikedSongs,
+    TopTracks(TimeRange),
+    Playlist(String),
+    Lastfm(LastfmConfig, Period),
+    Listenbrainz(ListenbrainzConfig, StatsRange),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextField {
+    Genre,
+    Artist,
+    Album,
+    Name,
+    Work,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberField {
+    Year,
+    Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Condition {
+    Contains {
+        field: TextField,
+        text: String,
+    },
+    Compare {
+        field: NumberField,
+        comparison: Comparison,
+        value: i64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortField {
+    Added,
+    Year,
+    Name,
+    Artist,
+    Duration,
+}
+
+/// A rule of the config, checked and ready to run.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    source: Source,
+    conditions: Vec<Condition>,
+    sort: Option<(SortField, bool)>,
+    pub limit: Option<u32>,
+    enrich: bool,
+    dedup: bool,
+    target: String,
+    pub public: bool,
+    pub collaborative: bool,
+}
+
+/// A track of the source, with when it was liked for the liked songs and what
+/// MusicBrainz knows about it if the rule enriches the tracks.
+struct Candidate {
+    track: Track,
+    added_at: Option<String>,
+    enrichment: Option<Enrichment>,
+}
+
+impl AsRef<Track> for Candidate {
+    fn as_ref(&self) -> &Track {
+        &self.track
+    }
+}
+
+impl Candidate {
+    fn new(track: Track) -> Self {
+        Candidate {
+            track,
+            added_at: None,
+            enrichment: None,
+        }
+    }
+
+    /// The values of the enrichment, none without one.
+    fn enriched(&self, values: fn(&Enrichment) -> &Vec<String>) -> impl Iterator<Item = &String> {
+        self.enrichment.iter().flat_map(values)
+    }
+}
+
+fn parse_source(source: &str, integrations: &IntegrationsConfig) -> Result<Source, String> {
+    match source.split_once(':') {
+        None if source == "liked" => Ok(Source::LikedSongs),
+        Some(("top", time_range)) => Ok(Source::TopTracks(time_range.parse()?)),
+        Some(("playlist", id)) if !id.is_empty() => Ok(Source::Playlist(id.to_string())),
+        Some(("lastfm", period)) => {
+            let Some(lastfm) = &integrations.lastfm else {
+                return Err(
+                    "the source lastfm needs an api_key and user in integrations.lastfm"
+                        .to_string(),
+                );
+            };
+            Ok(Source::Lastfm(lastfm.clone(), period.parse()?))
+        }
+        Some(("listenbrainz", range)) => {
+            let Some(listenbrainz) = &integrations.listenbrainz else {
+                return Err(
+                    "the source listenbrainz needs a token in integrations.listenbrainz"
+                        .to_string(),
+                );
+            };
+            Ok(Source::Listenbrainz(listenbrainz.clone(), range.parse()?))
+        }
+        _ => Err(format!(
+            "unknown source \"{}\", expected liked, top:<short|medium|long>, playlist:<id>, \
+            lastfm:<period> or listenbrainz:<range>",
+            source
+        )),
+    }
+}
+
+fn parse_condition(condition: &str) -> Result<Condition, String> {
+    if let Some((field, text)) = condition.split_once(':') {
+        let field = match field.trim() {
+            "genre" => TextField::Genre,
+            "artist" => TextField::Artist,
+            "album" => TextField::Album,
+            "name" => TextField::Name,
+            "work" => TextField::Work,
+            field => {
+                return Err(format!(
+                    "unknown field \"{}\", expected genre, artist, album, name or work before \
+                    ':'",
+                    field
+                ))
+            }
+        };
+        return Ok(Condition::Contains {
+            field,
+            text: text.trim().to_lowercase(),
+        });
+    }
+    // the two character operators first, `<=` also contains `<`
+    const OPERATORS: [(&str, Comparison); 6] = [
+        ("!=", Comparison::NotEqual),
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("=", Comparison::Equal),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+    ];
+    let Some((field, value, comparison)) = OPERATORS.iter().find_map(|(operator, comparison)| {
+        condition
+            .split_once(operator)
+            .map(|(field, value)| (field, value, *comparison))
+    }) else {
+        return Err(format!(
+            "condition \"{}\" has no ':' or comparison like year>=2010",
+            condition
+        ));
+    };
+    let field = match field.trim() {
+        "year" => NumberField::Year,
+        "duration" => NumberField::Duration,
+        field => {
+            return Err(format!(
+                "unknown field \"{}\", expected year or duration before a comparison",
+                field
+            ))
+        }
+    };
+    let value = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("\"{}\" in \"{}\" is no number", value.trim(), condition))?;
+    Ok(Condition::Compare {
+        field,
+        comparison,
+        value,
+    })
+}
+
+fn parse_sort(sort: &str) -> Result<(SortField, bool), String> {
+    let (field, descending) = match sort.strip_prefix('-') {
+        Some(field) => (field, true),
+        None => (sort, false),
+    };
+    let field = match field {
+        "added" => SortField::Added,
+        "year" => SortField::Year,
+        "name" => SortField::Name,
+        "artist" => SortField::Artist,
+        "duration" => SortField::Duration,
+        _ => {
+            return Err(format!(
+                "unknown sort \"{}\", expected added, year, name, artist or duration",
+                sort
+            ))
+        }
+    };
+    Ok((field, descending))
+}
+
+impl Rule {
+    /// The errors stay English like the ones of the pipeline steps.
+    pub fn parse(
+        name: &str,
+        config: &RuleConfig,
+        integrations: &IntegrationsConfig,
+    ) -> Result<Self, String> {
+        let conditions = config
+            .filter
+            .iter()
+            .flat_map(|filter| filter.split('&'))
+            .map(str::trim)
+            .filter(|condition| !condition.is_empty())
+            .map(parse_condition)
+            .collect::<Result<Vec<_>, _>>()?;
+        let public = config.public.unwrap_or(false);
+        let collaborative = config.collaborative.unwrap_or(false);
+        if public && collaborative {
+            return Err("a collaborative playlist cannot be public".to_string());
+        }
+        if let Some(limit) = config.limit {
+            if limit == 0 {
+                return Err("the limit must be at least 1".to_string());
+            }
+        }
+        if config.target.trim().is_empty() {
+            return Err("the target playlist needs a name".to_string());
+        }
+        let enrich = config.enrich.unwrap_or(false);
+        if !enrich
+            && conditions
+                .iter()
+                .any(|condition| condition.field_is(TextField::Work))
+        {
+            return Err("the field work needs enrich set to true".to_string());
+        }
+        Ok(Rule {
+            name: name.to_string(),
+            source: parse_source(&config.source, integrations)?,
+            conditions,
+            sort: config.sort.as_deref().map(parse_sort).transpose()?,
+            limit: config.limit,
+            enrich,
+            dedup: config.dedup.unwrap_or(false),
+            target: config.target.clone(),
+            public,
+            collaborative,
+        })
+    }
+
+    pub fn with_visibility(mut self, visibility: PlaylistVisibility) -> Self {
+        self.public = visibility.public;
+        self.collaborative = visibility.collaborative;
+        self
+    }
+
+    /// The name of the playlist the rule replaces the tracks of.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Whether the source is a playlist, which another action may write.
+    pub fn reads_playlist(&self) -> bool {
+        matches!(self.source, Source::Playlist(_))
+    }
+
+    pub fn enriches(&self) -> bool {
+        self.enrich
+    }
+
+    fn needs_genres(&self) -> bool {
+        self.conditions
+            .iter()
+            .any(|condition| condition.field_is(TextField::Genre))
+    }
+
+    fn needs_works(&self) -> bool {
+        self.conditions
+            .iter()
+            .any(|condition| condition.field_is(TextField::Work))
+    }
+}
+
+impl Condition {
+    fn field_is(&self, text_field: TextField) -> bool {
+        matches!(self, Condition::Contains { field, .. } if *field == text_field)
+    }
+
+    /// Whether the enrichment can change the outcome, the others are checked before, so
+    /// fewer tracks are looked up.
+    fn uses_enrichment(&self) -> bool {
+        matches!(
+            self,
+            Condition::Contains {
+                field: TextField::Artist | TextField::Work,
+                ..
+            } | Condition::Compare {
+                field: NumberField::Year,
+                ..
+            }
+        )
+    }
+}
+
+/// The year of the first release if MusicBrainz knows it, else that of the album.
+fn release_year(candidate: &Candidate) -> Option<i64> {
+    candidate
+        .enrichment
+        .as_ref()
+        .and_then(|enrichment| enrichment.first_release_year)
+        .or_else(|| candidate.track.album.release_date.get(..4)?.parse().ok())
+}
+
+fn matches(
+    condition: &Condition,
+    candidate: &Candidate,
+    genres: &HashMap<String, Vec<String>>,
+) -> bool {
+    let track = &candidate.track;
+    match condition {
+        Condition::Contains { field, text } => match field {
+            TextField::Genre => track.artists.iter().any(|artist| {
+                genres
+                    .get(&artist.id)
+                    .is_some_and(|genres| genres.iter().any(|genre| genre.contains(text)))
+            }),
+            TextField::Artist => track
+                .artists
+                .iter()
+                .map(|artist| &artist.name)
+                .chain(candidate.enriched(|enrichment| &enrichment.artist_credits))
+                .any(|artist| artist.to_lowercase().contains(text)),
+            TextField::Album => track.album.name.to_lowercase().contains(text),
+            TextField::Name => track.name.to_lowercase().contains(text),
+            TextField::Work => candidate
+                .enriched(|enrichment| &enrichment.works)
+                .any(|work| work.to_lowercase().contains(text)),
+        },
+        Condition::Compare {
+            field,
+            comparison,
+            value,
+        } => {
+            let actual = match field {
+                NumberField::Year => match release_year(candidate) {
+                    Some(year) => year,
+                    None => return false,
+                },
+                NumberField::Duration => (track.duration_ms / 1000) as i64,
+            };
+            match comparison {
+                Comparison::Equal => actual == *value,
+                Comparison::NotEqual => actual != *value,
+                Comparison::Less => actual < *value,
+                Comparison::LessOrEqual => actual <= *value,
+                Comparison::Greater => actual > *value,
+                Comparison::GreaterOrEqual => actual >= *value,
+            }
+        }
+    }
+}
+
+fn compare(a: &Candidate, b: &Candidate, field: SortField) -> Ordering {
+    let first_artist = |candidate: &Candidate| {
+        candidate
+            .track
+            .artists
+            .first()
+            .map(|artist| artist.name.to_lowercase())
+    };
+    match field {
+        SortField::Added => a.added_at.cmp(&b.added_at),
+        SortField::Year => release_year(a).cmp(&release_year(b)),
+        SortField::Name => a
+            .track
+            .name
+            .to_lowercase()
+            .cmp(&b.track.name.to_lowercase()),
+        SortField::Artist => first_artist(a).cmp(&first_artist(b)),
+        SortField::Duration => a.track.duration_ms.cmp(&b.track.duration_ms),
+    }
+}
+
+/// The Spotify tracks of the listened tracks, once each, leaving out the ones not found.
+async fn match_listened_tracks(
+    user_access: &UserAccess,
+    listened: Vec<ListenedTrack>,
+    service: &str,
+) -> Result<Vec<Candidate>, AuthorizeError> {
+    let mut uris = HashSet::new();
+    let mut candidates = Vec::with_capacity(listened.len());
+    for listened_track in &listened {
+        let artist = &listened_track.artist;
+        match search_track(user_access, &listened_track.name, artist).await? {
+            Some(track) if uris.insert(track.uri.clone()) => candidates.push(Candidate::new(track)),
+            Some(_) => {}
+            None => status_verbose!(
+                "Found no Spotify track for \"{}\" by {}",
+                listened_track.name,
+                artist
+            ),
+        }
+    }
+    status_verbose!(
+        "Found {} of {} {} tracks on Spotify",
+        candidates.len(),
+        listened.len(),
+        service
+    );
+    Ok(candidates)
+}
+
+/// The candidates of the source kept by `keep`, and how many tracks the source had. The
+/// liked songs and playlists are filtered page by page, so a large library is never held
+/// whole.
+async fn fetch_source(
+    user_access: &UserAccess,
+    source: &Source,
+    limit: Option<u32>,
+    keep: impl Fn(&Candidate) -> bool,
+) -> Result<(Vec<Candidate>, usize), AuthorizeError> {
+    let mut total = 0;
+    let mut kept = Vec::new();
+    let candidates: Vec<Candidate> = match source {
+        Source::LikedSongs => {
+            let mut pages = pin!(liked_song_pages(user_access, |_| {}));
+            while let Some(page) = pages.try_next().await? {
+                total += page.len();
+                let candidates = page.into_iter().map(|saved| Candidate {
+                    track: saved.track,
+                    added_at: Some(saved.added_at),
+                    enrichment: None,
+                });
+                kept.extend(candidates.filter(|candidate| keep(candidate)));
+            }
+            return Ok((kept, total));
+        }
+        Source::Playlist(id) => {
+            let mut pages = pin!(playlist_item_pages(user_access, id, |_| {}));
+            while let Some(page) = pages.try_next().await? {
+                total += page.len();
+                let candidates = page
+                    .into_iter()
+                    .filter_map(|item| item.track)
+                    .map(Candidate::new);
+                kept.extend(candidates.filter(|candidate| keep(candidate)));
+            }
+            return Ok((kept, total));
+        }
+        Source::TopTracks(time_range) => {
+            get_top_tracks(user_access, *time_range, MAX_TOP_TRACKS_LIMIT)
+                .await?
+                .into_iter()
+                .map(Candidate::new)
+                .collect()
+        }
+        Source::Lastfm(config, period) => {
+            let limit = limit.unwrap_or(DEFAULT_LISTENED_TRACKS) as usize;
+            let listened = get_top_tracks_of_lastfm(config, *period, limit).await?;
+            match_listened_tracks(user_access, listened, "Last.fm").await?
+        }
+        Source::Listenbrainz(config, range) => {
+            let limit = limit.unwrap_or(DEFAULT_LISTENED_TRACKS) as usize;
+            let listened = get_top_recordings(config, *range, limit).await?;
+            match_listened_tracks(user_access, listened, "ListenBrainz").await?
+        }
+    };
+    let total = candidates.len();
+    kept.extend(candidates.into_iter().filter(|candidate| keep(candidate)));
+    Ok((kept, total))
+}
+
+#[derive(Debug, Deserialize)]
+struct Artists {
+    artists: Vec<Artist>,
+}
+
+/// The genres of the artists by their ids; the tracks only name the artists.
+async fn get_genres(
+    user_access: &UserAccess,
+    candidates: &[Candidate],
+) -> Result<HashMap<String, Vec<String>>, AuthorizeError> {
+    let ids: HashSet<&str> = candidates
+        .iter()
+        .flat_map(|candidate| candidate.track.artists.iter())
+        .map(|artist| artist.id.as_str())
+        .collect();
+    let ids: Vec<&str> = ids.into_iter().collect();
+    let client = Client::new();
+    let mut genres = HashMap::with_capacity(ids.len());
+    for chunk in ids.chunks(ARTISTS_PER_REQUEST) {
+        let ids = chunk.join(",");
+        let request_builder = client.get(api_endpoint!("/artists?ids={ids}"));
+        let request_builder = user_access.authorize(request_builder);
+        let request = request_builder.build()?;
+        let resp = execute_api(&client, request).await?;
+        for artist in resp.json::<Artists>().await?.artists {
+            genres.insert(artist.id, artist.genres.unwrap_or_default());
+        }
+    }
+    Ok(genres)
+}
+
+/// Looks the candidates up on MusicBrainz, the ones without an ISRC stay as they are.
+async fn enrich(candidates: &mut [Candidate], with_works: bool) -> Result<(), AuthorizeError> {
+    let total = candidates
+        .iter()
+        .filter(|candidate| candidate.track.external_ids.isrc.is_some())
+        .count();
+    status!(
+        "Looking up {} tracks on MusicBrainz, about one per second",
+        total
+    );
+    let mut musicbrainz = MusicBrainz::new();
+    let mut enrichments: HashMap<String, Option<Enrichment>> = HashMap::new();
+    for candidate in candidates.iter_mut() {
+        let Some(isrc) = &candidate.track.external_ids.isrc else {
+            continue;
+        };
+        if !enrichments.contains_key(isrc) {
+            let enrichment = musicbrainz.lookup_isrc(isrc, with_works).await?;
+            enrichments.insert(isrc.clone(), enrichment);
+        }
+        candidate.enrichment = enrichments[isrc].clone();
+    }
+    let found = enrichments.values().filter(|found| found.is_some()).count();
+    status_verbose!(
+        "MusicBrainz knows {} of {} recordings",
+        found,
+        enrichments.len()
+    );
+    Ok(())
+}
+
+/// The recording MusicBrainz matched the candidate to, else the one of the track.
+fn recording(candidate: &Candidate) -> String {
+    match &candidate.enrichment {
+        Some(enrichment) => enrichment.recording_id.clone(),
+        None => candidate.track.recording_id().to_string(),
+    }
+}
+
+/// Runs the rules of the config, each by its name.
+#[derive(Debug)]
+pub struct RulesAction;
+
+impl Action for RulesAction {
+    fn key(&self) -> &'static str {
+        "rules"
+    }
+
+    fn name(&self) -> &'static str {
+        "Run rules"
+    }
+
+    fn description(&self) -> &'static str {
+        "A playlist of the tracks of a source matching the conditions of the rule"
+    }
+
+    fn scopes(&self) -> &'static [&'static str] {
+        &[
+            "user-library-read",
+            "user-top-read",
+            "playlist-read-private",
+            "playlist-modify-private",
+            "playlist-modify-public",
+        ]
+    }
+
+    fn selectable(&self) -> bool {
+        false
+    }
+
+    fn run<'a>(
+        &'a self,
+        user_access: &'a UserAccess,
+        task: &'a Task,
+    ) -> BoxFuture<'a, Result<CreatedPlaylist, AuthorizeError>> {
+        let Task::Rule(rule) = task else {
+            unreachable!("the task of another action: {:?}", task.name());
+        };
+        Box::pin(async move {
+            status!("Running rule {}", rule.name);
+            let result = run_rule(user_access, rule).await;
+            if let Err(err) = &result {
+                status_error!("Rule {} failed: {}", rule.name, err);
+            }
+            result
+        })
+    }
+}
+
+pub async fn run_rule(
+    user_access: &UserAccess,
+    rule: &Rule,
+) -> Result<CreatedPlaylist, AuthorizeError> {
+    // the genres are only known for the kept tracks and the enrichment later on
+    let checked_early = |condition: &&Condition| {
+        !(condition.field_is(TextField::Genre) || rule.enrich && condition.uses_enrichment())
+    };
+    let no_genres = HashMap::new();
+    let keep = |candidate: &Candidate| {
+        rule.conditions
+            .iter()
+            .filter(checked_early)
+            .all(|condition| matches(condition, candidate, &no_genres))
+    };
+    let (mut candidates, total) = fetch_source(user_access, &rule.source, rule.limit, keep).await?;
+    let genres = if rule.needs_genres() {
+        get_genres(user_access, &candidates).await?
+    } else {
+        HashMap::new()
+    };
+    let matches_all = |candidate: &Candidate, enriched: bool| {
+        rule.conditions
+            .iter()
+            .filter(|condition| !rule.enrich || condition.uses_enrichment() == enriched)
+            .all(|condition| matches(condition, candidate, &genres))
+    };
+    candidates.retain(|candidate| matches_all(candidate, false));
+    if rule.enrich {
+        enrich(&mut candidates, rule.needs_works()).await?;
+        candidates.retain(|candidate| matches_all(candidate, true));
+    }
+    status_verbose!(
+        "{} of {} tracks match the filter of rule {}",
+        candidates.len(),
+        total,
+        rule.name
+    );
+    let mut builder = PlaylistBuilder::new(rule.target.as_str())
+        .description(Some(SPAUTOFY_DESCRIPTION))
+        .visibility(PlaylistVisibility {
+            public: rule.public,
+            collaborative: rule.collaborative,
+        })
+        .target(Target::Named)
+        .tracks(candidates);
+    if rule.dedup {
+        builder = builder.dedup_by_key(recording);
+    }
+    if let Some((field, descending)) = rule.sort {
+        builder = builder.sort_by(|a, b| compare(a, b, field));
+        if descending {
+            builder = builder.reverse();
+        }
+    }
+    if let Some(limit) = rule.limit {
+        builder = builder.limit(limit as usize);
+    }
+    let playlist = builder.record_snapshot().build(user_access).await?;
+    announce(&playlist);
+    Ok(playlist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_config(source: &str, filter: Option<&str>) -> RuleConfig {
+        RuleConfig {
+            source: source.to_string(),
+            filter: filter.map(str::to_string),
+            sort: None,
+            limit: None,
+            enrich: None,
+            dedup: None,
+            target: "Modern Metal".to_string(),
+            public: None,
+            collaborative: None,
+        }
+    }
+
+    fn parse(config: &RuleConfig) -> Result<Rule, String> {
+        Rule::parse("metal", config, &IntegrationsConfig::default())
+    }
+
+    #[test]
+    fn parses_a_full_rule() {
+        let config = RuleConfig {
+            sort: Some("-year".to_string()),
+            limit: Some(20),
+            ..rule_config("liked", Some("genre: Metal & year>=2010 & duration < 600"))
+        };
+        let rule = parse(&config).unwrap();
+        assert_eq!(rule.source, Source::LikedSongs);
+        assert_eq!(
+            rule.conditions,
+            vec![
+                Condition::Contains {
+                    field: TextField::Genre,
+                    text: "metal".to_string(),
+                },
+                Condition::Compare {
+                    field: NumberField::Year,
+                    comparison: Comparison::GreaterOrEqual,
+                    value: 2010,
+                },
+                Condition::Compare {
+                    field: NumberField::Duration,
+                    comparison: Comparison::Less,
+                    value: 600,
+                },
+            ]
+        );
+        assert_eq!(rule.sort, Some((SortField::Year, true)));
+        assert_eq!(rule.limit, Some(20));
+        assert_eq!(rule.target, "Modern Metal");
+    }
+
+    #[test]
+    fn parses_the_sources() {
+        let source = |source: &str| parse(&rule_config(source, None)).map(|rule| rule.source);
+        assert_eq!(
+            source("top:short"),
+            Ok(Source::TopTracks(TimeRange::ShortTerm))
+        );
+        assert_eq!(
+            source("playlist:abc"),
+            Ok(Source::Playlist("abc".to_string()))
+        );
+        assert!(source("playlist:").is_err());
+        assert!(source("liked:all").is_err());
+        assert!(source("albums").is_err());
+        // the services need their settings in the integrations
+        assert!(source("lastfm:7day").is_err());
+        assert!(source("listenbrainz:week").is_err());
+    }
+
+    #[test]
+    fn parses_every_comparison() {
+        let comparisons = [
+            ("year=2000", Comparison::Equal),
+            ("year!=2000", Comparison::NotEqual),
+            ("year<2000", Comparison::Less),
+            ("year<=2000", Comparison::LessOrEqual),
+            ("year>2000", Comparison::Greater),
+            ("year >= 2000", Comparison::GreaterOrEqual),
+        ];
+        for (condition, comparison) in comparisons {
+            assert_eq!(
+                parse_condition(condition),
+                Ok(Condition::Compare {
+                    field: NumberField::Year,
+                    comparison,
+                    value: 2000,
+                }),
+                "{}",
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_conditions() {
+        assert!(parse_condition("label:nuclear blast").is_err());
+        assert!(parse_condition("popularity>50").is_err());
+        assert!(parse_condition("year>=twenty").is_err());
+        assert!(parse_condition("metal").is_err());
+    }
+
+    #[test]
+    fn an_empty_filter_has_no_conditions() {
+        let rule = parse(&rule_config("liked", Some(" & "))).unwrap();
+        assert!(rule.conditions.is_empty());
+    }
+
+    #[test]
+    fn parses_the_sorts() {
+        assert_eq!(parse_sort("added"), Ok((SortField::Added, false)));
+        assert_eq!(parse_sort("-duration"), Ok((SortField::Duration, true)));
+        assert!(parse_sort("--name").is_err());
+        assert!(parse_sort("popularity").is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_rules() {
+        let public_and_collaborative = RuleConfig {
+            public: Some(true),
+            collaborative: Some(true),
+            ..rule_config("liked", None)
+        };
+        assert!(parse(&public_and_collaborative).is_err());
+        let no_tracks = RuleConfig {
+            limit: Some(0),
+            ..rule_config("liked", None)
+        };
+        assert!(parse(&no_tracks).is_err());
+        let no_target = RuleConfig {
+            target: " ".to_string(),
+            ..rule_config("liked", None)
+        };
+        assert!(parse(&no_target).is_err());
+    }
+
+    #[test]
+    fn works_need_the_enrichment() {
+        let config = rule_config("liked", Some("work:requiem"));
+        assert!(parse(&config).is_err());
+        let enriched = RuleConfig {
+            enrich: Some(true),
+            ..config
+        };
+        assert!(parse(&enriched).unwrap().enrich);
+    }
+}
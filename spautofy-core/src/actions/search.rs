@@ -0,0 +1,94 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute_api;
+use crate::models::artist::Artist;
+use crate::models::paging::Page;
+use crate::models::track::Track;
+use crate::{api_endpoint, UserAccess};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SearchResults {
+    pub tracks: Page<Track>,
+    pub artists: Page<Artist>,
+}
+
+pub async fn search_tracks_and_artists(
+    user_access: &UserAccess,
+    query: &str,
+    limit: u32,
+) -> Result<SearchResults, AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!("/search"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder
+        .query(&[
+            ("q", query),
+            ("type", "track,artist"),
+            ("limit", limit.to_string().as_str()),
+        ])
+        .build()?;
+    let resp = execute_api(&client, request).await?;
+    let resp = resp.json::<SearchResults>().await?;
+    Ok(resp)
+}
+
+#[derive(Debug, Deserialize)]
+struct TrackResults {
+    tracks: Page<Track>,
+}
+
+/// The track best matching the name and artist: the first result named exactly like it,
+/// else the first result at all.
+pub async fn search_track(
+    user_access: &UserAccess,
+    name: &str,
+    artist: &str,
+) -> Result<Option<Track>, AuthorizeError> {
+    // quotes would end the field filters early
+    let query = format!(
+        "track:\"{}\" artist:\"{}\"",
+        name.replace('"', ""),
+        artist.replace('"', "")
+    );
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!("/search"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder
+        .query(&[("q", query.as_str()), ("type", "track"), ("limit", "5")])
+        .build()?;
+    let resp = execute_api(&client, request).await?;
+    let mut tracks = resp.json::<TrackResults>().await?.tracks.items;
+    let exact = tracks.iter().position(|track| {
+        track.name.eq_ignore_ascii_case(name)
+            && track
+                .artists
+                .iter()
+                .any(|found| found.name.eq_ignore_ascii_case(artist))
+    });
+    match exact {
+        Some(index) => Ok(Some(tracks.swap_remove(index))),
+        None => Ok(tracks.into_iter().next()),
+    }
+}
+
+/// The tracks Spotify finds for a free text query, the most relevant first.
+pub async fn search_tracks(
+    user_access: &UserAccess,
+    query: &str,
+    limit: u32,
+) -> Result<Vec<Track>, AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!("/search"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder
+        .query(&[
+            ("q", query),
+            ("type", "track"),
+            ("limit", limit.to_string().as_str()),
+        ])
+        .build()?;
+    let resp = execute_api(&client, request).await?;
+    Ok(resp.json::<TrackResults>().await?.tracks.items)
+}
@@ -0,0 +1,355 @@
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::actions::parameters::ActionParameters;
+use crate::actions::playlist_actions::{PlaylistTarget, PlaylistVisibility};
+use crate::actions::playlist_builder::{announce, PlaylistBuilder};
+use crate::actions::registry::Action;
+use crate::actions::{ActionsConfig, Task};
+use crate::authorize::AuthorizeError;
+use crate::dates::{now, today};
+use crate::endpoints::execute_api;
+use crate::i18n::t;
+use crate::models::paging::Page;
+use crate::models::playlist::SPAUTOFY_DESCRIPTION;
+use crate::models::track::Track;
+use crate::progress;
+use crate::storage;
+use crate::{api_endpoint, status, status_error, status_verbose, UserAccess};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+#[allow(clippy::enum_variant_names)]
+pub enum TimeRange {
+    ShortTerm,
+    MediumTerm,
+    LongTerm,
+}
+
+impl TimeRange {
+    pub const ALL: [TimeRange; 3] = [
+        TimeRange::ShortTerm,
+        TimeRange::MediumTerm,
+        TimeRange::LongTerm,
+    ];
+
+    /// The listening period Spotify computes the top tracks over.
+    pub fn description(&self) -> &'static str {
+        match self {
+            TimeRange::ShortTerm => "the last 4 weeks",
+            TimeRange::MediumTerm => "the last 6 months",
+            TimeRange::LongTerm => "the last year",
+        }
+    }
+}
+
+impl Display for TimeRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeRange::ShortTerm => write!(f, "short_term"),
+            TimeRange::MediumTerm => write!(f, "medium_term"),
+            TimeRange::LongTerm => write!(f, "long_term"),
+        }
+    }
+}
+
+impl TryFrom<String> for TimeRange {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<TimeRange> for String {
+    fn from(time_range: TimeRange) -> Self {
+        time_range.to_string()
+    }
+}
+
+/// Accepts Spotify's names as well as the shorter `short`, `medium` and `long`.
+impl FromStr for TimeRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "short" | "short_term" => Ok(TimeRange::ShortTerm),
+            "medium" | "medium_term" => Ok(TimeRange::MediumTerm),
+            "long" | "long_term" => Ok(TimeRange::LongTerm),
+            _ => Err(format!(
+                "unknown time range \"{}\", expected short, medium or long",
+                s
+            )),
+        }
+    }
+}
+
+pub const DEFAULT_TOP_TRACKS_LIMIT: u32 = 50;
+/// Spotify may run out of top tracks before that, the playlist then just has fewer.
+pub const MAX_TOP_TRACKS_LIMIT: u32 = 1000;
+/// Spotify returns at most this many top tracks per request.
+const TOP_TRACKS_PAGE_SIZE: u32 = 50;
+
+/// Placeholders: `{range}` for the time range, e.g. `short_term`, and `{date}` for today,
+/// see `dates`.
+pub const DEFAULT_NAME_TEMPLATE: &str = "Spautofy {range} Top Tracks {date}";
+
+/// Defaults of the top tracks action, set with `"actions": {"top_tracks": ...}` in the config.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct TopTracksConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// See `DEFAULT_NAME_TEMPLATE` for the placeholders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name_template: Option<String>,
+    /// Selected in the TUI at the start; `spautofy run` is given its time ranges.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_ranges: Option<Vec<TimeRange>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collaborative: Option<bool>,
+    /// Ids of the playlists to update instead of creating new ones, for each time range,
+    /// e.g. `{"short": "<playlist id>"}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub targets: BTreeMap<TimeRange, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TopTrackPlaylistParams {
+    pub time_range: TimeRange,
+    pub limit: u32,
+    /// Overrides `DEFAULT_NAME_TEMPLATE` when set.
+    pub name: Option<String>,
+    pub public: bool,
+    pub collaborative: bool,
+    /// Replaces the tracks of this playlist instead of creating a new one.
+    pub target: Option<PlaylistTarget>,
+    /// Tracks deselected in the preview, left out of the playlist.
+    pub excluded_track_ids: Vec<String>,
+}
+
+impl TopTrackPlaylistParams {
+    pub fn new(time_range: TimeRange) -> Self {
+        TopTrackPlaylistParams {
+            time_range,
+            limit: DEFAULT_TOP_TRACKS_LIMIT,
+            name: None,
+            public: false,
+            collaborative: false,
+            target: None,
+            excluded_track_ids: Vec::new(),
+        }
+    }
+
+    pub fn with_config(mut self, config: &TopTracksConfig) -> Self {
+        if let Some(limit) = config.limit {
+            self.limit = limit.clamp(1, MAX_TOP_TRACKS_LIMIT);
+        }
+        if let Some(name_template) = &config.name_template {
+            self.name = Some(name_template.clone());
+        }
+        self.public = config.public.unwrap_or(self.public);
+        self.collaborative = config.collaborative.unwrap_or(self.collaborative);
+        self.with_target_from(config)
+    }
+
+    /// Updates the playlist the config names for the time range, if there is one.
+    pub fn with_target_from(mut self, config: &TopTracksConfig) -> Self {
+        if let Some(id) = config.targets.get(&self.time_range) {
+            // `PlaylistBuilder::build` fetches the name
+            self.target = Some(PlaylistTarget {
+                id: id.clone(),
+                name: id.clone(),
+            });
+        }
+        self
+    }
+
+    pub fn with_visibility(mut self, visibility: PlaylistVisibility) -> Self {
+        self.public = visibility.public;
+        self.collaborative = visibility.collaborative;
+        self
+    }
+
+    /// The name template with its placeholders filled in.
+    pub fn playlist_name(&self) -> String {
+        let template = self.name.as_deref().unwrap_or(DEFAULT_NAME_TEMPLATE);
+        template
+            .replace("{range}", &self.time_range.to_string())
+            .replace("{date}", &today())
+    }
+}
+
+/// Creates a playlist of the top tracks for each time range.
+#[derive(Debug)]
+pub struct TopTracksAction;
+
+impl Action for TopTracksAction {
+    fn key(&self) -> &'static str {
+        "top_tracks"
+    }
+
+    fn name(&self) -> &'static str {
+        "Create top tracks playlists"
+    }
+
+    fn description(&self) -> &'static str {
+        "A playlist of the tracks you listened to most in the time range"
+    }
+
+    fn scopes(&self) -> &'static [&'static str] {
+        &[
+            "user-top-read",
+            "playlist-read-private",
+            "playlist-modify-private",
+            "playlist-modify-public",
+        ]
+    }
+
+    fn cli_names(&self) -> &'static [&'static str] {
+        &["top-tracks:short", "top-tracks:medium", "top-tracks:long"]
+    }
+
+    fn parse(&self, name: &str) -> Option<Result<Task, String>> {
+        let time_range = name.strip_prefix("top-tracks:")?;
+        Some(
+            time_range
+                .parse()
+                .map(|time_range| Task::TopTracks(TopTrackPlaylistParams::new(time_range))),
+        )
+    }
+
+    fn params(
+        &self,
+        config: &ActionsConfig,
+        visibility: Option<PlaylistVisibility>,
+    ) -> ActionParameters {
+        let config = &config.top_tracks;
+        let mut params = TopTrackPlaylistParams::new(TimeRange::ShortTerm).with_config(config);
+        // the targets of the config belong to single time ranges, see `tasks`
+        params.target = None;
+        if let Some(visibility) = visibility {
+            params = params.with_visibility(visibility);
+        }
+        ActionParameters::top_tracks(
+            config.time_ranges.as_deref().unwrap_or(&TimeRange::ALL),
+            &params,
+        )
+    }
+
+    fn tasks(
+        &self,
+        params: &ActionParameters,
+        config: &ActionsConfig,
+    ) -> Result<Vec<Task>, String> {
+        Ok(params
+            .top_track_params()?
+            .into_iter()
+            .map(|params| {
+                // a playlist picked in the TUI wins over the one of the config
+                if params.target.is_none() {
+                    Task::TopTracks(params.with_target_from(&config.top_tracks))
+                } else {
+                    Task::TopTracks(params)
+                }
+            })
+            .collect())
+    }
+
+    fn run<'a>(
+        &'a self,
+        user_access: &'a UserAccess,
+        task: &'a Task,
+    ) -> BoxFuture<'a, Result<CreatedPlaylist, AuthorizeError>> {
+        let Task::TopTracks(params) = task else {
+            unreachable!("the task of another action: {:?}", task.name());
+        };
+        Box::pin(async move {
+            status!("Creating {} top track playlist", params.time_range);
+            let result = create_top_track_playlist(user_access, params).await;
+            if let Err(err) = &result {
+                status_error!(
+                    "Failed to create {} top track playlist: {}",
+                    params.time_range,
+                    err
+                );
+            }
+            result
+        })
+    }
+}
+
+/// Fetches the top tracks page by page until there are `limit` or Spotify has no more.
+pub async fn get_top_tracks(
+    user_access: &UserAccess,
+    time_range: TimeRange,
+    limit: u32,
+) -> Result<Vec<Track>, AuthorizeError> {
+    let client = Client::new();
+    let mut tracks = Vec::new();
+    while (tracks.len() as u32) < limit {
+        let offset = tracks.len() as u32;
+        let request_builder = client.get(api_endpoint!("/me/top/tracks"));
+        let request_builder = user_access.access.authorize(request_builder);
+        let request = request_builder
+            .query(&[
+                ("time_range", time_range.to_string().as_str()),
+                (
+                    "limit",
+                    (limit - offset)
+                        .min(TOP_TRACKS_PAGE_SIZE)
+                        .to_string()
+                        .as_str(),
+                ),
+                ("offset", offset.to_string().as_str()),
+            ])
+            .build()?;
+        let resp = execute_api(&client, request).await?;
+        let page = resp.json::<Page<Track>>().await?;
+        let last_page = page.next.is_none() || page.items.is_empty();
+        tracks.extend(page.items);
+        progress::emit(
+            "page_fetched",
+            json!({ "done": tracks.len(), "total": limit.min(page.total.max(0) as u32) }),
+        );
+        if last_page {
+            break;
+        }
+    }
+    Ok(tracks)
+}
+
+pub async fn create_top_track_playlist(
+    user_access: &UserAccess,
+    params: &TopTrackPlaylistParams,
+) -> Result<CreatedPlaylist, AuthorizeError> {
+    let tracks = get_top_tracks(user_access, params.time_range, params.limit).await?;
+    status_verbose!(
+        "Fetched {} top tracks of {}",
+        tracks.len(),
+        t(params.time_range.description())
+    );
+    storage::record(|storage| storage.add_top_tracks(now(), params.time_range, &tracks));
+    let playlist = PlaylistBuilder::new(params.playlist_name())
+        .description(Some(SPAUTOFY_DESCRIPTION))
+        .visibility(PlaylistVisibility {
+            public: params.public,
+            collaborative: params.collaborative,
+        })
+        .replacing(params.target.clone())
+        .tracks(tracks)
+        .exclude(&params.excluded_track_ids)
+        .diff_with_target()
+        .record_snapshot()
+        .build(user_access)
+        .await?;
+    announce(&playlist);
+    Ok(playlist)
+}
@@ -0,0 +1,297 @@
+use std::net::IpAddr;
+use std::time::Instant;
+
+use rand::distributions::Alphanumeric;
+use rand::{thread_rng, Rng};
+use reqwest::{Client, Request, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::actions::registry;
+use crate::endpoints::execute;
+use crate::{authorization_endpoint, tr};
+
+/// The scopes of the commands besides the actions, like browsing the playlists, backing up
+/// and restoring the library or showing what is playing; the actions add their own.
+const COMMAND_SCOPES: [&str; 9] = [
+    "playlist-read-private",
+    "playlist-modify-private",
+    "playlist-modify-public",
+    "user-read-currently-playing",
+    "user-read-recently-played",
+    "user-read-private",
+    "user-library-read",
+    "user-library-modify",
+    "user-follow-read",
+];
+
+/// What logging in to Spotify needs: the credentials of the app, the address Spotify
+/// redirects the browser to, and what the login got so far.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AuthConfig {
+    pub address: IpAddr,
+    pub port: u16,
+    client_id: String,
+    client_secret: String,
+    pub user_auth_code: Option<String>,
+    random_state: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// Set by the callback if Spotify did not authorize the app.
+    #[serde(skip)]
+    pub auth_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Access {
+    access_token: String,
+    scope: String,
+    expires_in: i32,
+    refresh_token: String,
+    #[serde(skip, default = "Instant::now")]
+    received_at: Instant,
+}
+
+/// Spotify only sometimes hands out a new refresh token when refreshing.
+#[derive(Debug, Deserialize)]
+struct RefreshedAccess {
+    access_token: String,
+    scope: String,
+    expires_in: i32,
+    refresh_token: Option<String>,
+}
+
+impl Access {
+    pub fn is_expired(&self) -> bool {
+        self.received_at.elapsed().as_secs() > self.expires_in as u64
+    }
+    pub fn remaining_secs(&self) -> u64 {
+        (self.expires_in as u64).saturating_sub(self.received_at.elapsed().as_secs())
+    }
+    pub fn authorize(&self, request_builder: RequestBuilder) -> RequestBuilder {
+        request_builder.bearer_auth(self.access_token.as_str())
+    }
+    pub fn refresh_token(&self) -> &str {
+        self.refresh_token.as_str()
+    }
+    pub fn access_token(&self) -> &str {
+        self.access_token.as_str()
+    }
+    /// The scopes the user granted, e.g. `playlist-modify-private`.
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scope.split_whitespace()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AuthorizeError {
+    #[error("Have not received user authorization yet.")]
+    NoUserAuthCode,
+    #[error("User code has expired.")]
+    ExpiredUserCode,
+    #[error("Request error: {0}")]
+    RequestError(reqwest::Error),
+    #[error("Authorization failed: {0}")]
+    Denied(String),
+    /// Another service than Spotify, named first, answered with an error.
+    #[error("{0}: {1}")]
+    Service(&'static str, String),
+    #[error("Unknown error.")]
+    Unknown,
+}
+
+impl From<reqwest::Error> for AuthorizeError {
+    fn from(err: reqwest::Error) -> Self {
+        AuthorizeError::RequestError(err)
+    }
+}
+
+fn random_state() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(30)
+        .map(char::from)
+        .collect()
+}
+
+impl AuthConfig {
+    pub fn new(
+        address: IpAddr,
+        port: u16,
+        client_id: String,
+        client_secret: String,
+        refresh_token: Option<String>,
+    ) -> Self {
+        AuthConfig {
+            address,
+            port,
+            client_id,
+            client_secret,
+            user_auth_code: None,
+            random_state: random_state(),
+            refresh_token,
+            auth_error: None,
+        }
+    }
+
+    pub fn credentials(&self) -> (&str, &str) {
+        (self.client_id.as_str(), self.client_secret.as_str())
+    }
+
+    /// Takes what Spotify redirected the browser to the callback with: the code to get the
+    /// access with, or why the app was not authorized.
+    pub fn receive_callback(&mut self, state: &str, code: Option<String>, error: Option<String>) {
+        if state != self.random_state {
+            self.auth_error = Some(tr!("invalid state {}", state));
+        } else if let Some(error) = error {
+            self.auth_error = Some(tr!("Spotify returned the error \"{}\"", error));
+        } else if code.is_some() {
+            self.user_auth_code = code;
+        } else {
+            self.auth_error = Some(tr!("Spotify returned neither a code nor an error"));
+        }
+    }
+
+    pub fn needs_auth(&self) -> bool {
+        self.user_auth_code.is_none()
+    }
+
+    pub fn redirect_url(&self) -> String {
+        format!("http://{}:{}/callback", self.address, self.port)
+    }
+
+    /// The address of the page of `auth_request`, to send the user to.
+    pub fn authorization_url(&self) -> Result<String, AuthorizeError> {
+        Ok(self.auth_request()?.url().to_string())
+    }
+
+    /// The Spotify page asking the user to authorize the app.
+    pub fn auth_request(&self) -> Result<Request, AuthorizeError> {
+        Ok(Client::new()
+            .get(authorization_endpoint!("/authorize"))
+            .query(&[
+                ("client_id", self.client_id.as_str()),
+                ("response_type", "code"),
+                ("redirect_uri", self.redirect_url().as_str()),
+                ("scope", registry::scopes(&COMMAND_SCOPES).as_str()),
+                ("show_dialog", "true"),
+                ("state", self.random_state.as_str()),
+            ])
+            .build()?)
+    }
+
+    fn access_token_request(&self) -> Result<Request, AuthorizeError> {
+        Ok(Client::new()
+            .post(authorization_endpoint!("/api/token"))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                (
+                    "code",
+                    self.user_auth_code
+                        .as_ref()
+                        .ok_or(AuthorizeError::NoUserAuthCode)?
+                        .as_str(),
+                ),
+                ("redirect_uri", self.redirect_url().as_str()),
+            ])
+            .basic_auth(self.client_id.as_str(), Some(self.client_secret.as_str()))
+            .build()?)
+    }
+
+    fn refresh_token_request(&self, refresh_token: &str) -> Result<Request, AuthorizeError> {
+        Ok(Client::new()
+            .post(authorization_endpoint!("/api/token"))
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", refresh_token),
+            ])
+            .basic_auth(self.client_id.as_str(), Some(self.client_secret.as_str()))
+            .build()?)
+    }
+}
+
+/// Checks the app credentials by requesting a client credentials token with them.
+pub async fn validate_client_credentials(
+    client_id: &str,
+    client_secret: &str,
+) -> Result<bool, AuthorizeError> {
+    let resp = Client::new()
+        .post(authorization_endpoint!("/api/token"))
+        .form(&[("grant_type", "client_credentials")])
+        .basic_auth(client_id, Some(client_secret))
+        .send()
+        .await?;
+    Ok(resp.status().is_success())
+}
+
+pub async fn refresh_access_token(
+    config: &AuthConfig,
+    access: &Access,
+) -> Result<Access, AuthorizeError> {
+    access_from_refresh_token(config, &access.refresh_token).await
+}
+
+/// Gets a new access without a browser, from a refresh token kept in the config.
+pub async fn access_from_refresh_token(
+    config: &AuthConfig,
+    refresh_token: &str,
+) -> Result<Access, AuthorizeError> {
+    let request = config.refresh_token_request(refresh_token)?;
+    let resp = execute(&Client::new(), request).await?;
+    let refreshed = token_response::<RefreshedAccess>(resp).await?;
+    Ok(Access {
+        access_token: refreshed.access_token,
+        scope: refreshed.scope,
+        expires_in: refreshed.expires_in,
+        refresh_token: refreshed
+            .refresh_token
+            .unwrap_or_else(|| refresh_token.to_string()),
+        received_at: Instant::now(),
+    })
+}
+
+pub async fn get_access_token(config: &AuthConfig) -> Result<Access, AuthorizeError> {
+    try_get_access_token(config, None).await
+}
+
+pub async fn try_get_access_token(
+    config: &AuthConfig,
+    old_access: Option<Access>,
+) -> Result<Access, AuthorizeError> {
+    if config.user_auth_code.is_none() {
+        return Err(AuthorizeError::NoUserAuthCode);
+    }
+    let try_refresh = match &old_access {
+        Some(access) => access.is_expired(),
+        None => true,
+    };
+    if !try_refresh {
+        return old_access.ok_or(AuthorizeError::Unknown);
+    }
+    let request = config.access_token_request()?;
+    let resp = execute(&Client::new(), request).await?;
+    token_response::<Access>(resp).await
+}
+
+/// Why Spotify did not hand out an access.
+#[derive(Debug, Deserialize)]
+struct TokenError {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// The access Spotify answered with. Only a code or refresh token Spotify no longer accepts
+/// counts as expired; an outage or a network error is no reason to log in again.
+async fn token_response<T: DeserializeOwned>(resp: Response) -> Result<T, AuthorizeError> {
+    if resp.status() == StatusCode::BAD_REQUEST {
+        let error = resp.json::<TokenError>().await?;
+        if error.error == "invalid_grant" {
+            return Err(AuthorizeError::ExpiredUserCode);
+        }
+        return Err(AuthorizeError::Denied(
+            error.error_description.unwrap_or(error.error),
+        ));
+    }
+    Ok(resp.error_for_status()?.json::<T>().await?)
+}
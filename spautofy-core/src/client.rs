@@ -0,0 +1,197 @@
+//! A logged in Spotify user with the actions of Spautofy as methods returning their results,
+//! for programs embedding the playlist generation.
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::actions::export::{export_liked_songs, export_playlist, PlaylistExport};
+use crate::actions::playlist_actions::{get_current_user_playlists, get_user_playlists};
+use crate::actions::rules::{run_rule, Rule};
+use crate::actions::top_track_playlist::{
+    create_top_track_playlist, get_top_tracks, TimeRange, TopTrackPlaylistParams,
+};
+use crate::actions::Task;
+use crate::authorize::{
+    access_from_refresh_token, get_access_token, refresh_access_token, Access, AuthConfig,
+    AuthorizeError,
+};
+use crate::models::playlist::SimplifiedPlaylist;
+use crate::models::track::{SavedTrack, Track};
+use crate::player::{get_currently_playing, get_recently_played, CurrentlyPlaying, PlayedTrack};
+use crate::settings::{self, Settings};
+use crate::user_info::{get_user_access, get_user_profile, PublicUser, User, UserAccess};
+
+/// The access to Spotify of one user, renewed before each request once it has expired.
+///
+/// Log in with a refresh token kept from an earlier login, or through the browser: send the
+/// user to [`AuthConfig::authorization_url`], hand what Spotify redirects to the redirect url
+/// with to [`AuthConfig::receive_callback`] and call [`SpotifyClient::from_authorization`].
+///
+/// The methods run with the [`Settings`] of the client, the default ones unless
+/// [`SpotifyClient::with_settings`] gives others.
+#[derive(Debug, Clone)]
+pub struct SpotifyClient {
+    auth: AuthConfig,
+    user_access: UserAccess,
+    settings: Settings,
+}
+
+impl SpotifyClient {
+    /// Logs in without a browser, with the refresh token of an earlier login.
+    pub async fn from_refresh_token(
+        auth: AuthConfig,
+        refresh_token: &str,
+    ) -> Result<Self, AuthorizeError> {
+        let access = settings::scope(
+            Settings::default(),
+            access_from_refresh_token(&auth, refresh_token),
+        )
+        .await?;
+        Self::from_access(auth, access).await
+    }
+
+    /// Logs in with the code the callback received after the user authorized the app.
+    pub async fn from_authorization(auth: AuthConfig) -> Result<Self, AuthorizeError> {
+        if let Some(error) = &auth.auth_error {
+            return Err(AuthorizeError::Denied(error.clone()));
+        }
+        let access = settings::scope(Settings::default(), get_access_token(&auth)).await?;
+        Self::from_access(auth, access).await
+    }
+
+    pub async fn from_access(auth: AuthConfig, access: Access) -> Result<Self, AuthorizeError> {
+        let user_access = settings::scope(Settings::default(), get_user_access(access)).await?;
+        Ok(SpotifyClient {
+            auth,
+            user_access,
+            settings: Settings::default(),
+        })
+    }
+
+    pub fn with_settings(mut self, settings: Settings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn user(&self) -> &User {
+        &self.user_access.user
+    }
+
+    /// Keep it to log in again with [`SpotifyClient::from_refresh_token`]; Spotify may hand
+    /// out a new one when the access is renewed.
+    pub fn refresh_token(&self) -> &str {
+        self.user_access.access.refresh_token()
+    }
+
+    /// The access for the functions of the modules, which the client has no method for.
+    pub fn user_access(&self) -> &UserAccess {
+        &self.user_access
+    }
+
+    /// Renews the access if it has expired.
+    pub async fn refresh(&mut self) -> Result<&UserAccess, AuthorizeError> {
+        if self.user_access.access.is_expired() {
+            self.user_access.access =
+                refresh_access_token(&self.auth, &self.user_access.access).await?;
+        }
+        Ok(&self.user_access)
+    }
+
+    /// Up to `limit` of the user's most played tracks over the time range.
+    pub async fn top_tracks(
+        &mut self,
+        time_range: TimeRange,
+        limit: u32,
+    ) -> Result<Vec<Track>, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            get_top_tracks(self.refresh().await?, time_range, limit).await
+        })
+        .await
+    }
+
+    pub async fn create_top_tracks_playlist(
+        &mut self,
+        params: &TopTrackPlaylistParams,
+    ) -> Result<CreatedPlaylist, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            create_top_track_playlist(self.refresh().await?, params).await
+        })
+        .await
+    }
+
+    pub async fn run_rule(&mut self, rule: &Rule) -> Result<CreatedPlaylist, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            run_rule(self.refresh().await?, rule).await
+        })
+        .await
+    }
+
+    /// Runs a task like `spautofy run` does, without printing its outcome.
+    pub async fn run(&mut self, task: &Task) -> Result<CreatedPlaylist, AuthorizeError> {
+        match task {
+            Task::TopTracks(params) => self.create_top_tracks_playlist(params).await,
+            Task::Rule(rule) => self.run_rule(rule).await,
+        }
+    }
+
+    pub async fn playlists(&mut self) -> Result<Vec<SimplifiedPlaylist>, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            get_current_user_playlists(self.refresh().await?, |_| {}).await
+        })
+        .await
+    }
+
+    /// The public profile of another user, by their id.
+    pub async fn user_profile(&mut self, user_id: &str) -> Result<PublicUser, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            get_user_profile(self.refresh().await?, user_id).await
+        })
+        .await
+    }
+
+    /// The public playlists of another user, e.g. to export or diff a friend's playlist.
+    pub async fn user_playlists(
+        &mut self,
+        user_id: &str,
+    ) -> Result<Vec<SimplifiedPlaylist>, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            get_user_playlists(self.refresh().await?, user_id, |_| {}).await
+        })
+        .await
+    }
+
+    pub async fn export_playlist(
+        &mut self,
+        playlist_id: &str,
+    ) -> Result<PlaylistExport, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            export_playlist(self.refresh().await?, playlist_id, |_| {}).await
+        })
+        .await
+    }
+
+    pub async fn liked_songs(&mut self) -> Result<Vec<SavedTrack>, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            export_liked_songs(self.refresh().await?, |_| {}).await
+        })
+        .await
+    }
+
+    /// Returns `None` if nothing is playing right now.
+    pub async fn currently_playing(&mut self) -> Result<Option<CurrentlyPlaying>, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            get_currently_playing(&self.refresh().await?.access).await
+        })
+        .await
+    }
+
+    /// The last tracks played, the most recent first.
+    pub async fn recently_played(&mut self) -> Result<Vec<PlayedTrack>, AuthorizeError> {
+        settings::scope(self.settings.clone(), async {
+            get_recently_played(&self.refresh().await?.access).await
+        })
+        .await
+    }
+}
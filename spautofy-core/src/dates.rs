@@ -0,0 +1,102 @@
+//! How `{date}` is written in the names of the created playlists: `"date_format"` in the
+//! config file is a strftime format like `%d-%m-%Y`, and the names of months and weekdays
+//! are in `"date_locale"`, e.g. `de_DE`, or else in the language of `"locale"`.
+//!
+//! `"timezone"`, e.g. `Europe/Berlin`, decides which day it is, so a server running on UTC
+//! still dates the playlists like its user would; without it the local time is used.
+
+use std::sync::Mutex;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Local, Utc};
+use chrono_tz::Tz;
+
+use crate::i18n::Locale;
+use crate::{settings, tr};
+
+pub const DEFAULT_DATE_FORMAT: &str = "%d-%m-%Y";
+
+static DATE_SETTINGS: Mutex<Option<DateSettings>> = Mutex::new(None);
+
+#[derive(Debug, Clone)]
+pub struct DateSettings {
+    format: String,
+    locale: chrono::Locale,
+    timezone: Option<Tz>,
+}
+
+impl DateSettings {
+    pub fn new(
+        format: Option<&str>,
+        date_locale: Option<&str>,
+        locale: Locale,
+        timezone: Option<&str>,
+    ) -> Result<Self, String> {
+        let format = format.unwrap_or(DEFAULT_DATE_FORMAT);
+        // formatting with an invalid specifier panics
+        if StrftimeItems::new(format).any(|item| item == Item::Error) {
+            return Err(tr!(
+                "Invalid date format \"{}\", use strftime specifiers like %d-%m-%Y",
+                format
+            ));
+        }
+        let locale = match date_locale {
+            Some(date_locale) => chrono::Locale::try_from(date_locale).map_err(|_| {
+                tr!(
+                    "Unknown date locale \"{}\", expected e.g. en_US or de_DE",
+                    date_locale
+                )
+            })?,
+            None => match locale {
+                Locale::En => chrono::Locale::en_US,
+                Locale::De => chrono::Locale::de_DE,
+            },
+        };
+        let timezone = timezone
+            .map(|timezone| {
+                timezone.parse::<Tz>().map_err(|_| {
+                    tr!(
+                        "Unknown timezone \"{}\", expected e.g. Europe/Berlin or UTC",
+                        timezone
+                    )
+                })
+            })
+            .transpose()?;
+        Ok(DateSettings {
+            format: format.to_string(),
+            locale,
+            timezone,
+        })
+    }
+}
+
+/// Every profile sets its own date settings.
+pub fn set_date_settings(date_settings: DateSettings) {
+    *DATE_SETTINGS.lock().unwrap() = Some(date_settings);
+}
+
+/// The date settings of the client running, else the ones of the process.
+fn date_settings() -> Option<DateSettings> {
+    settings::with(|settings| settings.date_settings.clone())
+        .unwrap_or_else(|| DATE_SETTINGS.lock().unwrap().clone())
+}
+
+/// The current time in the timezone of the config.
+pub fn now() -> DateTime<FixedOffset> {
+    let timezone = date_settings().and_then(|date_settings| date_settings.timezone);
+    match timezone {
+        Some(timezone) => Utc::now().with_timezone(&timezone).fixed_offset(),
+        None => Local::now().fixed_offset(),
+    }
+}
+
+/// Today in the date format of the config.
+pub fn today() -> String {
+    let now = now();
+    match date_settings() {
+        Some(date_settings) => now
+            .format_localized(&date_settings.format, date_settings.locale)
+            .to_string(),
+        None => now.format(DEFAULT_DATE_FORMAT).to_string(),
+    }
+}
@@ -0,0 +1,37 @@
+use reqwest::{Client, Request, Response, StatusCode};
+
+use crate::metrics::count_request;
+use crate::status_trace;
+
+#[macro_export]
+macro_rules! authorization_endpoint {
+    ( $( $x: expr),+ ) => {{
+        format!("https://accounts.spotify.com{}", format_args!($($x),+))
+    }};
+}
+
+#[macro_export]
+macro_rules! api_endpoint {
+    ( $( $x: expr),+ ) => {{
+        format!("https://api.spotify.com/v1{}", format_args!($($x),+))
+    }};
+}
+
+/// Sends the request, logging it and the response status with `-vv` and counting it for
+/// the metrics.
+pub async fn execute(client: &Client, request: Request) -> reqwest::Result<Response> {
+    status_trace!("{} {}", request.method(), request.url());
+    let host = request.url().host_str().unwrap_or_default().to_string();
+    let resp = client.execute(request).await;
+    let rate_limited = matches!(&resp, Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS);
+    count_request(&host, rate_limited);
+    let resp = resp?;
+    status_trace!("{} {}", resp.status(), resp.url());
+    Ok(resp)
+}
+
+/// Sends a request to the Spotify API like `execute`, failing if Spotify rejects it, e.g.
+/// with 403 or 5xx, so a failed write does not go unnoticed.
+pub async fn execute_api(client: &Client, request: Request) -> reqwest::Result<Response> {
+    execute(client, request).await?.error_for_status()
+}
@@ -0,0 +1,47 @@
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::actions::action_report::ActionReport;
+use crate::dates::now;
+use crate::storage::{self, StorageError};
+use crate::tr;
+
+/// One run of the selected actions, as kept in the history database, see `storage`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
+pub struct RunRecord {
+    pub started_at: DateTime<FixedOffset>,
+    pub reports: Vec<ActionReport>,
+}
+
+impl RunRecord {
+    pub fn new(started_at: DateTime<FixedOffset>, reports: Vec<ActionReport>) -> Self {
+        RunRecord {
+            started_at,
+            reports,
+        }
+    }
+
+    /// How long ago the run started, e.g. "3 days ago".
+    pub fn age(&self) -> String {
+        match (now() - self.started_at).num_days() {
+            0 => tr!("today"),
+            1 => tr!("yesterday"),
+            days => tr!("{} days ago", days),
+        }
+    }
+}
+
+/// Reads all recorded runs, oldest first; a missing database means no runs yet.
+pub fn load(filepath: &str) -> Result<Vec<RunRecord>, StorageError> {
+    if !Path::new(filepath).exists() {
+        return Ok(Vec::new());
+    }
+    storage::with_storage_at(Path::new(filepath), |storage| storage.runs())
+}
+
+pub fn append(filepath: &str, record: RunRecord) -> Result<(), StorageError> {
+    storage::with_storage_at(Path::new(filepath), |storage| storage.add_run(&record))
+}
@@ -0,0 +1,510 @@
+//! Reads listening histories from elsewhere, `spautofy import-history`, into the played
+//! tracks of the database, which otherwise only go back to the first run archiving the
+//! recently played tracks:
+//! - the streaming history JSON of the Spotify account data, extended or not
+//! - scrobble CSV files, like the exports of Last.fm backup tools, with a header naming
+//!   the columns or as the header-less `artist,album,track,date` of lastfm-to-csv
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use serde::Deserialize;
+
+/// Spotify counts a stream from 30 seconds on, Last.fm scrobbles not much earlier.
+const MIN_MS_PLAYED: u64 = 30_000;
+
+/// The date formats of the scrobble exports, besides RFC 3339 and Unix timestamps; all
+/// in UTC like the exports.
+const DATE_FORMATS: [&str; 5] = [
+    "%d %b %Y %H:%M",
+    "%d %b %Y, %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+/// A play of a track, kept in the database like the recently played tracks.
+#[derive(Debug, Clone)]
+pub struct Play {
+    pub played_at: DateTime<Utc>,
+    pub name: String,
+    pub artists: String,
+    pub album: Option<String>,
+    /// Only known for the plays from Spotify.
+    pub track_id: Option<String>,
+}
+
+/// The plays read from a file, and how many of its entries were left out: skipped
+/// tracks, episodes, and lines missing the track or a readable date.
+#[derive(Debug, Default)]
+pub struct ImportedFile {
+    pub plays: Vec<Play>,
+    pub skipped: usize,
+}
+
+/// One entry of `Streaming_History_Audio_*.json` in the extended streaming history.
+#[derive(Debug, Deserialize)]
+struct ExtendedStream {
+    ts: String,
+    ms_played: u64,
+    master_metadata_track_name: Option<String>,
+    master_metadata_album_artist_name: Option<String>,
+    master_metadata_album_album_name: Option<String>,
+    spotify_track_uri: Option<String>,
+}
+
+/// One entry of `StreamingHistory*.json` in the account data, which has no album or id.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stream {
+    end_time: String,
+    artist_name: String,
+    track_name: String,
+    ms_played: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StreamingHistory {
+    Extended(Vec<ExtendedStream>),
+    Basic(Vec<Stream>),
+}
+
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    let date = date.trim();
+    if let Ok(timestamp) = date.parse::<i64>() {
+        return Utc.timestamp_opt(timestamp, 0).single();
+    }
+    if let Ok(date) = DateTime::parse_from_rfc3339(date) {
+        return Some(date.with_timezone(&Utc));
+    }
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+        .map(|date| date.and_utc())
+}
+
+fn invalid_data(err: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+fn read_streaming_history(content: &str) -> io::Result<ImportedFile> {
+    let history: StreamingHistory = serde_json::from_str(content).map_err(invalid_data)?;
+    let mut imported = ImportedFile::default();
+    match history {
+        StreamingHistory::Extended(streams) => {
+            for stream in streams {
+                let play = match stream {
+                    ExtendedStream {
+                        ts,
+                        ms_played,
+                        master_metadata_track_name: Some(name),
+                        master_metadata_album_artist_name: Some(artists),
+                        master_metadata_album_album_name: album,
+                        spotify_track_uri: uri,
+                    } if ms_played >= MIN_MS_PLAYED => parse_date(&ts).map(|played_at| Play {
+                        played_at,
+                        name,
+                        artists,
+                        album,
+                        track_id: uri
+                            .and_then(|uri| uri.strip_prefix("spotify:track:").map(String::from)),
+                    }),
+                    _ => None,
+                };
+                match play {
+                    Some(play) => imported.plays.push(play),
+                    None => imported.skipped += 1,
+                }
+            }
+        }
+        StreamingHistory::Basic(streams) => {
+            for stream in streams {
+                let played_at = parse_date(&stream.end_time);
+                match played_at {
+                    Some(played_at) if stream.ms_played >= MIN_MS_PLAYED => {
+                        imported.plays.push(Play {
+                            played_at,
+                            name: stream.track_name,
+                            artists: stream.artist_name,
+                            album: None,
+                            track_id: None,
+                        })
+                    }
+                    _ => imported.skipped += 1,
+                }
+            }
+        }
+    }
+    Ok(imported)
+}
+
+/// Where the columns of a scrobble CSV file are.
+struct Columns {
+    artist: usize,
+    album: Option<usize>,
+    track: usize,
+    date: usize,
+}
+
+impl Columns {
+    /// The columns named in the header, `None` if the first line names no artist and track.
+    fn from_header(header: &csv::StringRecord) -> Option<Self> {
+        let find = |names: &[&str]| {
+            header
+                .iter()
+                .position(|column| names.contains(&column.trim().to_lowercase().as_str()))
+        };
+        Some(Columns {
+            artist: find(&["artist", "artist_name", "artist name"])?,
+            album: find(&["album", "album_name", "album name"]),
+            track: find(&["track", "track_name", "track name", "title", "name"])?,
+            date: find(&[
+                "uts",
+                "timestamp",
+                "utc_time",
+                "date",
+                "time",
+                "played_at",
+                "scrobble time",
+            ])?,
+        })
+    }
+
+    /// The layout of lastfm-to-csv.
+    fn without_header() -> Self {
+        Columns {
+            artist: 0,
+            album: Some(1),
+            track: 2,
+            date: 3,
+        }
+    }
+}
+
+fn read_scrobbles(content: &str) -> io::Result<ImportedFile> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let mut records = reader.records();
+    let mut imported = ImportedFile::default();
+    let Some(first) = records.next().transpose().map_err(invalid_data)? else {
+        return Ok(imported);
+    };
+    let (columns, first) = match Columns::from_header(&first) {
+        Some(columns) => (columns, None),
+        None => (Columns::without_header(), Some(first)),
+    };
+    for record in first.into_iter().map(Ok).chain(records) {
+        let record = record.map_err(invalid_data)?;
+        let field = |index: usize| record.get(index).map(str::trim).filter(|f| !f.is_empty());
+        let play = match (
+            field(columns.artist),
+            field(columns.track),
+            field(columns.date).and_then(parse_date),
+        ) {
+            (Some(artists), Some(name), Some(played_at)) => Some(Play {
+                played_at,
+                name: name.to_string(),
+                artists: artists.to_string(),
+                album: columns.album.and_then(field).map(str::to_string),
+                track_id: None,
+            }),
+            _ => None,
+        };
+        match play {
+            Some(play) => imported.plays.push(play),
+            None => imported.skipped += 1,
+        }
+    }
+    Ok(imported)
+}
+
+/// Reads a streaming history if the file ends in `.json`, and scrobbles otherwise.
+pub fn read_file(path: &Path) -> io::Result<ImportedFile> {
+    let content = fs::read_to_string(path)?;
+    let is_json = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    if is_json {
+        read_streaming_history(&content)
+    } else {
+        read_scrobbles(&content)
+    }
+}
+
+/// Whether a file in a directory given is a history, the account data also holds the
+/// playlists and more as JSON.
+fn is_history(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let name = name.to_lowercase();
+    (name.starts_with("streaming_history") || name.starts_with("streaminghistory"))
+        && name.ends_with(".json")
+        || name.ends_with(".csv")
+}
+
+/// The files given, with a directory like the unpacked account data standing for the
+/// streaming histories and CSV files in it.
+pub fn expand_paths(paths: &[String]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths.iter().map(PathBuf::from) {
+        if !path.is_dir() {
+            files.push(path);
+            continue;
+        }
+        let mut entries: Vec<PathBuf> = fs::read_dir(&path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<_>>()?;
+        entries.retain(|entry| is_history(entry));
+        entries.sort();
+        files.extend(entries);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    /// The plays as `(played_at, artists, name, album)`.
+    fn plays(imported: &ImportedFile) -> Vec<(DateTime<Utc>, &str, &str, Option<&str>)> {
+        imported
+            .plays
+            .iter()
+            .map(|play| {
+                (
+                    play.played_at,
+                    play.artists.as_str(),
+                    play.name.as_str(),
+                    play.album.as_deref(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parses_the_dates_of_the_exports() {
+        let played_at = date("2021-01-31T20:15:00Z");
+        let cases = [
+            "1612124100",
+            "2021-01-31T20:15:00Z",
+            "2021-01-31T21:15:00+01:00",
+            "31 Jan 2021 20:15",
+            "31 Jan 2021, 20:15",
+            "2021-01-31 20:15:00",
+            "2021-01-31 20:15",
+            "2021-01-31T20:15:00",
+            " 2021-01-31 20:15 ",
+        ];
+        for text in cases {
+            assert_eq!(parse_date(text), Some(played_at), "{}", text);
+        }
+    }
+
+    #[test]
+    fn rejects_other_dates() {
+        for text in ["", "yesterday", "31/01/2021 20:15", "2021-01-31"] {
+            assert_eq!(parse_date(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn finds_the_columns_named_in_the_header() {
+        let header = csv::StringRecord::from(vec![" Date", "Track Name", "ARTIST", "album"]);
+        let columns = Columns::from_header(&header).unwrap();
+        assert_eq!(
+            (columns.artist, columns.album, columns.track, columns.date),
+            (2, Some(3), 1, 0)
+        );
+
+        let header = csv::StringRecord::from(vec!["uts", "utc_time", "artist", "track"]);
+        let columns = Columns::from_header(&header).unwrap();
+        assert_eq!(
+            (columns.artist, columns.album, columns.track, columns.date),
+            (2, None, 3, 0)
+        );
+    }
+
+    #[test]
+    fn a_first_line_naming_no_artist_and_track_is_no_header() {
+        let line = csv::StringRecord::from(vec![
+            "Queen",
+            "A Night at the Opera",
+            "Bohemian Rhapsody",
+            "31 Jan 2021 20:15",
+        ]);
+        assert!(Columns::from_header(&line).is_none());
+        let header = csv::StringRecord::from(vec!["artist", "track"]);
+        assert!(Columns::from_header(&header).is_none());
+    }
+
+    #[test]
+    fn reads_scrobbles_with_a_header() {
+        let content = "\
+uts,utc_time,artist,artist_mbid,album,album_mbid,track,track_mbid
+1612124100,\"31 Jan 2021, 20:15\",Queen,,A Night at the Opera,,Bohemian Rhapsody,
+1612124460,\"31 Jan 2021, 20:21\",\"Simon & Garfunkel\",,,,\"The Boxer, Live\",
+,,Queen,,,,Under Pressure,
+";
+        let imported = read_scrobbles(content).unwrap();
+        assert_eq!(
+            plays(&imported),
+            vec![
+                (
+                    date("2021-01-31T20:15:00Z"),
+                    "Queen",
+                    "Bohemian Rhapsody",
+                    Some("A Night at the Opera")
+                ),
+                (
+                    date("2021-01-31T20:21:00Z"),
+                    "Simon & Garfunkel",
+                    "The Boxer, Live",
+                    None
+                ),
+            ]
+        );
+        assert_eq!(imported.skipped, 1);
+    }
+
+    #[test]
+    fn reads_the_scrobbles_of_lastfm_to_csv_without_a_header() {
+        let content = "\
+Queen,A Night at the Opera,Bohemian Rhapsody,31 Jan 2021 20:15
+Radiohead,,Creep,31 Jan 2021 20:21
+Radiohead,Pablo Honey,,31 Jan 2021 20:25
+";
+        let imported = read_scrobbles(content).unwrap();
+        assert_eq!(
+            plays(&imported),
+            vec![
+                (
+                    date("2021-01-31T20:15:00Z"),
+                    "Queen",
+                    "Bohemian Rhapsody",
+                    Some("A Night at the Opera")
+                ),
+                (date("2021-01-31T20:21:00Z"), "Radiohead", "Creep", None),
+            ]
+        );
+        assert_eq!(imported.skipped, 1);
+        assert!(imported.plays.iter().all(|play| play.track_id.is_none()));
+    }
+
+    #[test]
+    fn an_empty_csv_file_has_no_plays() {
+        let imported = read_scrobbles("").unwrap();
+        assert!(imported.plays.is_empty());
+        assert_eq!(imported.skipped, 0);
+    }
+
+    #[test]
+    fn reads_the_extended_streaming_history() {
+        let content = r#"[
+            {
+                "ts": "2021-01-31T20:15:00Z",
+                "platform": "android",
+                "ms_played": 354000,
+                "master_metadata_track_name": "Bohemian Rhapsody",
+                "master_metadata_album_artist_name": "Queen",
+                "master_metadata_album_album_name": "A Night at the Opera",
+                "spotify_track_uri": "spotify:track:4u7EnebtmKWzUH433cf5Qv",
+                "episode_name": null
+            },
+            {
+                "ts": "2021-01-31T20:21:00Z",
+                "ms_played": 5000,
+                "master_metadata_track_name": "Creep",
+                "master_metadata_album_artist_name": "Radiohead",
+                "master_metadata_album_album_name": "Pablo Honey",
+                "spotify_track_uri": "spotify:track:70LcF31zb1H0PyJoS1Sx1r"
+            },
+            {
+                "ts": "2021-01-31T20:30:00Z",
+                "ms_played": 1800000,
+                "master_metadata_track_name": null,
+                "master_metadata_album_artist_name": null,
+                "master_metadata_album_album_name": null,
+                "spotify_track_uri": null,
+                "episode_name": "A podcast"
+            }
+        ]"#;
+        let imported = read_streaming_history(content).unwrap();
+        assert_eq!(
+            plays(&imported),
+            vec![(
+                date("2021-01-31T20:15:00Z"),
+                "Queen",
+                "Bohemian Rhapsody",
+                Some("A Night at the Opera")
+            )]
+        );
+        assert_eq!(
+            imported.plays[0].track_id.as_deref(),
+            Some("4u7EnebtmKWzUH433cf5Qv")
+        );
+        assert_eq!(imported.skipped, 2);
+    }
+
+    #[test]
+    fn reads_the_streaming_history_of_the_account_data() {
+        let content = r#"[
+            {
+                "endTime": "2021-01-31 20:15",
+                "artistName": "Queen",
+                "trackName": "Bohemian Rhapsody",
+                "msPlayed": 354000
+            },
+            {
+                "endTime": "2021-01-31 20:21",
+                "artistName": "Radiohead",
+                "trackName": "Creep",
+                "msPlayed": 29999
+            }
+        ]"#;
+        let imported = read_streaming_history(content).unwrap();
+        assert_eq!(
+            plays(&imported),
+            vec![(
+                date("2021-01-31T20:15:00Z"),
+                "Queen",
+                "Bohemian Rhapsody",
+                None
+            )]
+        );
+        assert!(imported.plays[0].track_id.is_none());
+        assert_eq!(imported.skipped, 1);
+    }
+
+    #[test]
+    fn other_json_is_no_streaming_history() {
+        assert!(read_streaming_history(r#"{"playlists": []}"#).is_err());
+        assert!(read_streaming_history(r#"[{"name": "My Playlist"}]"#).is_err());
+    }
+
+    #[test]
+    fn takes_only_the_histories_of_a_directory() {
+        let histories = [
+            "Streaming_History_Audio_2021.json",
+            "StreamingHistory0.json",
+            "scrobbles.CSV",
+        ];
+        for name in histories {
+            assert!(is_history(Path::new(name)), "{}", name);
+        }
+        for name in ["Playlist1.json", "Userdata.json", "StreamingHistory0.txt"] {
+            assert!(!is_history(Path::new(name)), "{}", name);
+        }
+    }
+}
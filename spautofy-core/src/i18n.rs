@@ -0,0 +1,69 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+mod de;
+
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+/// Language of the TUI and CLI messages, set with `"locale"` in the config file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+/// Translates the message, then fills its `{}` placeholders with the arguments.
+#[macro_export]
+macro_rules! tr {
+    ( $message: expr ) => {{
+        $crate::i18n::t($message).to_string()
+    }};
+    ( $message: expr, $( $x: expr),+ ) => {{
+        $crate::i18n::fill($crate::i18n::t($message), &[$(&$x),+])
+    }};
+}
+
+/// Messages before the config is read stay English, and every profile sets its own locale.
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::SeqCst);
+}
+
+pub fn current() -> Locale {
+    match LOCALE.load(Ordering::SeqCst) {
+        locale if locale == Locale::De as u8 => Locale::De,
+        _ => Locale::En,
+    }
+}
+
+/// The translation of an English message; messages missing from the catalog stay English.
+pub fn t(message: &str) -> &str {
+    let catalog = match current() {
+        Locale::En => return message,
+        Locale::De => de::MESSAGES,
+    };
+    catalog
+        .iter()
+        .find(|(english, _)| *english == message)
+        .map_or(message, |(_, translated)| translated)
+}
+
+/// Replaces the `{}` placeholders of `template` with `args`, in order.
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        filled.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
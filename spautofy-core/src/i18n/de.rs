@@ -0,0 +1,1121 @@
+//! German translations, keyed by the English messages.
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    // actions
+    ("the last 4 weeks", "die letzten 4 Wochen"),
+    ("the last 6 months", "die letzten 6 Monate"),
+    ("the last year", "das letzte Jahr"),
+    (
+        "Updated playlist \"{}\", enjoy!",
+        "Playlist \"{}\" aktualisiert, viel Spaß!",
+    ),
+    (
+        "Created playlist \"{}\", enjoy!",
+        "Playlist \"{}\" erstellt, viel Spaß!",
+    ),
+    ("{} top tracks playlist", "{} Top-Tracks-Playlist"),
+    ("Creating {} top track playlist", "Erstelle {} Top-Tracks-Playlist"),
+    (
+        "Failed to create {} top track playlist: {}",
+        "Konnte {} Top-Tracks-Playlist nicht erstellen: {}",
+    ),
+    ("Create top tracks playlists", "Top-Tracks-Playlists erstellen"),
+    (
+        "Add the ListenBrainz token as token in integrations.listenbrainz of the config file.",
+        "Trage den ListenBrainz-Token als token in integrations.listenbrainz der Config-Datei ein.",
+    ),
+    (
+        "Log in again with `spautofy auth` to allow reading the recently played tracks.",
+        "Melde dich mit `spautofy auth` neu an, um das Lesen der zuletzt gehörten Tracks zu erlauben.",
+    ),
+    (
+        "Would submit {} listens to ListenBrainz as {}.",
+        "Würde {} Listens als {} an ListenBrainz senden.",
+    ),
+    (
+        "ListenBrainz already has all recently played tracks.",
+        "ListenBrainz hat schon alle zuletzt gehörten Tracks.",
+    ),
+    (
+        "Submitted {} listens to ListenBrainz as {}.",
+        "{} Listens als {} an ListenBrainz gesendet.",
+    ),
+    // authorization
+    (
+        "The authorization failed: {}. The web server is going to stop. You can close this window now.",
+        "Die Autorisierung ist fehlgeschlagen: {}. Der Webserver wird beendet. Du kannst dieses Fenster jetzt schließen.",
+    ),
+    (
+        "You successfully authorized the app. The web server is going to stop. You can close this window now.",
+        "Du hast die App erfolgreich autorisiert. Der Webserver wird beendet. Du kannst dieses Fenster jetzt schließen.",
+    ),
+    (
+        "Error writing credentials file: {}",
+        "Fehler beim Schreiben der Zugangsdaten-Datei: {}",
+    ),
+    ("invalid state {}", "ungültiger Status {}"),
+    (
+        "Spotify returned the error \"{}\"",
+        "Spotify hat den Fehler \"{}\" gemeldet",
+    ),
+    (
+        "Spotify returned neither a code nor an error",
+        "Spotify hat weder einen Code noch einen Fehler gesendet",
+    ),
+    ("Stopped the web server.", "Webserver beendet."),
+    ("Getting access token...", "Hole Zugriffstoken..."),
+    (
+        "Successfully authenticated with Spotify as user {}.",
+        "Erfolgreich bei Spotify als {} angemeldet.",
+    ),
+    ("Authorization failed", "Autorisierung fehlgeschlagen"),
+    (
+        "Cancelled the authorization, still logged in as before.",
+        "Autorisierung abgebrochen, weiterhin wie zuvor angemeldet.",
+    ),
+    ("Cancelled the authorization.", "Autorisierung abgebrochen."),
+    (
+        "Access token expired, refreshing it...",
+        "Zugriffstoken abgelaufen, erneuere es...",
+    ),
+    (
+        "Could not refresh the access token",
+        "Zugriffstoken konnte nicht erneuert werden",
+    ),
+    (
+        "{} Waiting for you to authorize Spautofy with Spotify...",
+        "{} Warte darauf, dass du Spautofy bei Spotify autorisierst...",
+    ),
+    (
+        "Please visit the following URL in your browser:",
+        "Bitte öffne die folgende URL in deinem Browser:",
+    ),
+    ("Waiting for {}", "Wartet seit {}"),
+    (" Spautofy - Authorization ", " Spautofy - Autorisierung "),
+    (
+        " CANCEL WITH '{quit}' OR 'ESC' ",
+        " ABBRECHEN MIT '{quit}' ODER 'ESC' ",
+    ),
+    // config
+    (
+        "Config file \"{}\" does not exist.",
+        "Konfigurationsdatei \"{}\" existiert nicht.",
+    ),
+    (
+        "Not logged in yet, run `spautofy auth` first.",
+        "Noch nicht angemeldet, starte zuerst `spautofy auth`.",
+    ),
+    (
+        "Run `spautofy config init` to create it with the setup wizard.",
+        "Starte `spautofy config init`, um sie mit dem Einrichtungsassistenten zu erstellen.",
+    ),
+    (
+        "Config file \"{}\" already exists, pass --force to replace it.",
+        "Konfigurationsdatei \"{}\" existiert bereits, übergib --force, um sie zu ersetzen.",
+    ),
+    ("Wrote config file \"{}\".", "Konfigurationsdatei \"{}\" geschrieben."),
+    (
+        "Error reading config file {}: {}",
+        "Fehler beim Lesen der Konfigurationsdatei {}: {}",
+    ),
+    (
+        "Error parsing config file {}: {}",
+        "Fehler beim Einlesen der Konfigurationsdatei {}: {}",
+    ),
+    (
+        "Config file {} has version {}, this Spautofy only reads versions up to {}.",
+        "Die Konfigurationsdatei {} hat die Version {}, dieses Spautofy liest nur Versionen bis {}.",
+    ),
+    (
+        "Moved the credentials to \"{}\".",
+        "Zugangsdaten nach \"{}\" verschoben.",
+    ),
+    (
+        "Upgraded config file \"{}\" to version {}, the old one is kept as \"{}\".",
+        "Konfigurationsdatei \"{}\" auf Version {} aktualisiert, die alte bleibt als \"{}\" erhalten.",
+    ),
+    ("Error in the environment: {}", "Fehler in den Umgebungsvariablen: {}"),
+    ("found {}, e.g. {}", "gefunden: {}, z. B. {}"),
+    ("found {}", "gefunden: {}"),
+    ("e.g. {}", "z. B. {}"),
+    ("{} has {} errors.", "{} hat {} Fehler."),
+    (
+        "Warning: Other users can read \"{}\", which holds your client secret and refresh \
+        token.",
+        "Warnung: Andere Benutzer können \"{}\" lesen, die Datei enthält dein Client-Secret \
+        und dein Refresh-Token.",
+    ),
+    (
+        "Restrict it to your user with `chmod 600 {}`.",
+        "Beschränke sie mit `chmod 600 {}` auf deinen Benutzer.",
+    ),
+    (
+        "Restrict it to your user now? [Y/n]",
+        "Jetzt auf deinen Benutzer beschränken? [J/n]",
+    ),
+    ("Restricted \"{}\" to your user.", "\"{}\" ist jetzt auf deinen Benutzer beschränkt."),
+    (
+        "Invalid date format \"{}\", use strftime specifiers like %d-%m-%Y",
+        "Ungültiges Datumsformat \"{}\", verwende strftime-Platzhalter wie %d-%m-%Y",
+    ),
+    (
+        "Unknown date locale \"{}\", expected e.g. en_US or de_DE",
+        "Unbekannte Sprache für Datumsangaben \"{}\", erwartet z. B. en_US oder de_DE",
+    ),
+    (
+        "Unknown timezone \"{}\", expected e.g. Europe/Berlin or UTC",
+        "Unbekannte Zeitzone \"{}\", erwartet z. B. Europe/Berlin oder UTC",
+    ),
+    (
+        "Error in the date format of config file {}: {}",
+        "Fehler im Datumsformat der Konfigurationsdatei {}: {}",
+    ),
+    (
+        "There is no profile \"{}\" in the config file.",
+        "Die Konfigurationsdatei hat kein Profil \"{}\".",
+    ),
+    (
+        "Profile {} is a section of config file \"{}\", edit it there or replace the \
+        whole file with --profile {}.",
+        "Profil {} ist ein Abschnitt der Konfigurationsdatei \"{}\", bearbeite es dort oder \
+        ersetze die ganze Datei mit --profile {}.",
+    ),
+    (
+        "Error reading credentials file {}: {}",
+        "Fehler beim Lesen der Zugangsdaten-Datei {}: {}",
+    ),
+    (
+        "Error parsing credentials file {}: {}",
+        "Fehler beim Einlesen der Zugangsdaten-Datei {}: {}",
+    ),
+    (
+        "Wrote credentials file \"{}\".",
+        "Zugangsdaten-Datei \"{}\" geschrieben.",
+    ),
+    (
+        "Error in the keybindings of config file {}: {}",
+        "Fehler in den Tastenbelegungen der Konfigurationsdatei {}: {}",
+    ),
+    ("unknown key \"{}\"", "unbekannte Taste \"{}\""),
+    (
+        "every action needs at least one key",
+        "jede Aktion braucht mindestens eine Taste",
+    ),
+    (
+        "Spotify app settings (see https://developer.spotify.com/dashboard)",
+        "Einstellungen der Spotify-App (siehe https://developer.spotify.com/dashboard)",
+    ),
+    (
+        "The credentials of your Spotify app and where its redirect URL points to.",
+        "Die Zugangsdaten deiner Spotify-App und wohin ihre Weiterleitungs-URL zeigt.",
+    ),
+    ("Client secret", "Client-Secret"),
+    ("required", "erforderlich"),
+    ("Web app address", "Adresse der Web-App"),
+    ("Web app port", "Port der Web-App"),
+    (
+        "Client ID and client secret are required.",
+        "Client-ID und Client-Secret sind erforderlich.",
+    ),
+    (
+        "\"{}\" is not a valid IP address.",
+        "\"{}\" ist keine gültige IP-Adresse.",
+    ),
+    ("Spautofy - First-Run Setup", "Spautofy - Ersteinrichtung"),
+    (
+        "Spotify rejected the client ID or client secret.",
+        "Spotify hat die Client-ID oder das Client-Secret abgelehnt.",
+    ),
+    (
+        "The client ID should be 32 hexadecimal characters, copy it from your app in the Spotify developer dashboard.",
+        "Die Client-ID sollte aus 32 Hexadezimalzeichen bestehen, kopiere sie aus deiner App im Spotify Developer Dashboard.",
+    ),
+    (
+        "The client secret should be 32 hexadecimal characters, copy it from your app in the Spotify developer dashboard.",
+        "Das Client-Secret sollte aus 32 Hexadezimalzeichen bestehen, kopiere es aus deiner App im Spotify Developer Dashboard.",
+    ),
+    (
+        "The port must not be 0, Spotify redirects to a fixed port.",
+        "Der Port darf nicht 0 sein, Spotify leitet auf einen festen Port weiter.",
+    ),
+    (
+        "Spotify only accepts http redirect URLs on loopback addresses like 127.0.0.1, logging in with {} will fail.",
+        "Spotify akzeptiert http-Weiterleitungs-URLs nur für Loopback-Adressen wie 127.0.0.1, die Anmeldung mit {} wird fehlschlagen.",
+    ),
+    ("Error in the keybindings: {}", "Fehler in den Tastenbelegungen: {}"),
+    (
+        "There is no pipeline or rule \"{}\" in the config file, and it is no action.",
+        "Die Konfigurationsdatei enthält keine Pipeline oder Regel \"{}\", und es ist auch \
+        keine Aktion.",
+    ),
+    (
+        "The Last.fm integration needs an API key and a user.",
+        "Die Last.fm-Integration braucht einen API-Key und einen Nutzer.",
+    ),
+    (
+        "The ListenBrainz integration needs a token.",
+        "Die ListenBrainz-Integration braucht einen Token.",
+    ),
+    (
+        "The webhook URL \"{}\" is no http or https URL.",
+        "Die Webhook-URL \"{}\" ist keine http- oder https-URL.",
+    ),
+    (
+        "The Discord webhook URL \"{}\" is no https URL.",
+        "Die Discord-Webhook-URL \"{}\" ist keine https-URL.",
+    ),
+    (
+        "The Slack webhook URL \"{}\" is no https URL.",
+        "Die Slack-Webhook-URL \"{}\" ist keine https-URL.",
+    ),
+    (
+        "The Slack notifications name \"{}\", which is no pipeline, rule or action.",
+        "Die Slack-Benachrichtigungen nennen \"{}\", das ist keine Pipeline, Regel oder \
+        Aktion.",
+    ),
+    (
+        "Error in the email notifications: {}",
+        "Fehler in den E-Mail-Benachrichtigungen: {}",
+    ),
+    (
+        "The email notifications have a username but no password, set $SPAUTOFY_SMTP_PASSWORD.",
+        "Die E-Mail-Benachrichtigungen haben einen Nutzernamen, aber kein Passwort, setze $SPAUTOFY_SMTP_PASSWORD.",
+    ),
+    (
+        "The MQTT notifications need the host of the broker.",
+        "Die MQTT-Benachrichtigungen brauchen den Host des Brokers.",
+    ),
+    (
+        "The MQTT topic \"{}\" must not be empty or contain '+' or '#'.",
+        "Das MQTT-Topic \"{}\" darf nicht leer sein oder '+' oder '#' enthalten.",
+    ),
+    (
+        "The MQTT notifications have a username but no password, set $SPAUTOFY_MQTT_PASSWORD.",
+        "Die MQTT-Benachrichtigungen haben einen Benutzernamen, aber kein Passwort, setze $SPAUTOFY_MQTT_PASSWORD.",
+    ),
+    (
+        "The healthcheck URL \"{}\" is no http or https URL.",
+        "Die Healthcheck-URL \"{}\" ist keine http- oder https-URL.",
+    ),
+    (
+        "The ntfy notifications need a topic.",
+        "Die ntfy-Benachrichtigungen brauchen ein Topic.",
+    ),
+    (
+        "The ntfy server \"{}\" is no http or https URL.",
+        "Der ntfy-Server \"{}\" ist keine http- oder https-URL.",
+    ),
+    (
+        "The Pushover notifications need the API token of an application, e.g. in $SPAUTOFY_PUSHOVER_TOKEN, and a user key.",
+        "Die Pushover-Benachrichtigungen brauchen das API-Token einer Anwendung, z. B. in $SPAUTOFY_PUSHOVER_TOKEN, und einen Nutzerschlüssel.",
+    ),
+    ("Error in rule \"{}\": {}", "Fehler in Regel \"{}\": {}"),
+    (
+        "Rule \"{}\" has the name of a pipeline, `spautofy run {}` runs the pipeline.",
+        "Regel \"{}\" heißt wie eine Pipeline, `spautofy run {}` führt die Pipeline aus.",
+    ),
+    (
+        "Rule \"{}\" cannot be run, its name must not contain ':' or ','.",
+        "Regel \"{}\" kann nicht ausgeführt werden, ihr Name darf weder ':' noch ',' enthalten.",
+    ),
+    ("rule {}", "Regel {}"),
+    ("Running rule {}", "Führe Regel {} aus"),
+    ("Rule {} failed: {}", "Regel {} fehlgeschlagen: {}"),
+    (
+        "Error in step {} of pipeline \"{}\": {}",
+        "Fehler in Schritt {} der Pipeline \"{}\": {}",
+    ),
+    ("Pipeline \"{}\" has no steps.", "Pipeline \"{}\" hat keine Schritte."),
+    (
+        "The top tracks limit {} is not between 1 and {}, it will be clamped.",
+        "Das Top-Tracks-Limit {} liegt nicht zwischen 1 und {}, es wird begrenzt.",
+    ),
+    (
+        "The top tracks name template is empty, remove it to use the default.",
+        "Die Namensvorlage für Top-Tracks ist leer, entferne sie, um die Standardvorlage zu verwenden.",
+    ),
+    (
+        "Unknown action \"{}\" in the selected actions, expected one of: {}",
+        "Unbekannte Aktion \"{}\" in den ausgewählten Aktionen, erwartet wird eine von: {}",
+    ),
+    (
+        "The top tracks playlists cannot be public and collaborative at once.",
+        "Die Top-Tracks-Playlists können nicht gleichzeitig öffentlich und gemeinsam sein.",
+    ),
+    (
+        "The top tracks time ranges are empty, the TUI starts with none selected.",
+        "Die Zeiträume der Top-Tracks sind leer, die TUI startet ohne Auswahl.",
+    ),
+    (
+        "The top tracks target for {} is no playlist id: \"{}\"",
+        "Das Top-Tracks-Ziel für {} ist keine Playlist-ID: \"{}\"",
+    ),
+    (
+        "Not logged in yet, run `spautofy auth` before using the other commands.",
+        "Noch nicht angemeldet, führe `spautofy auth` aus, bevor du die anderen Befehle verwendest.",
+    ),
+    (
+        "Spotify rejected the client ID or client secret, check them in the Spotify developer dashboard.",
+        "Spotify hat die Client-ID oder das Client-Secret abgelehnt, prüfe sie im Spotify Developer Dashboard.",
+    ),
+    ("Warning: {}", "Warnung: {}"),
+    ("Error: {}", "Fehler: {}"),
+    (
+        "Add {} as a redirect URL of your app in the Spotify developer dashboard.",
+        "Füge {} als Weiterleitungs-URL deiner App im Spotify Developer Dashboard hinzu.",
+    ),
+    ("The config file has {} errors.", "Die Konfigurationsdatei hat {} Fehler."),
+    ("The config file is valid.", "Die Konfigurationsdatei ist gültig."),
+    // commands
+    ("Fetched {} of {} items", "{} von {} Einträgen geladen"),
+    ("Fetched {} top tracks of {}", "{} Top-Tracks für {} geladen"),
+    (
+        "{} of {} tracks match the filter of rule {}",
+        "{} von {} Tracks passen zum Filter von Regel {}",
+    ),
+    (
+        "Found no Spotify track for \"{}\" by {}",
+        "Kein Spotify-Track für \"{}\" von {} gefunden",
+    ),
+    (
+        "Found {} of {} {} tracks on Spotify",
+        "{} von {} Tracks von {} auf Spotify gefunden",
+    ),
+    (
+        "Could not call the webhook {}: {}",
+        "Konnte den Webhook {} nicht aufrufen: {}",
+    ),
+    (
+        "Could not post the playlists to Discord: {}",
+        "Konnte die Playlists nicht auf Discord posten: {}",
+    ),
+    (
+        "Could not post the run to Slack: {}",
+        "Konnte den Lauf nicht auf Slack posten: {}",
+    ),
+    ("Spautofy could not run {}", "Spautofy konnte {} nicht ausführen"),
+    ("Updated with {} tracks", "Mit {} Tracks aktualisiert"),
+    (
+        "Could not mail the summary of the run: {}",
+        "Konnte die Zusammenfassung des Laufs nicht mailen: {}",
+    ),
+    (
+        "Spautofy: {} of {} actions failed",
+        "Spautofy: {} von {} Aktionen fehlgeschlagen",
+    ),
+    (
+        "Spautofy: {} playlists created or updated",
+        "Spautofy: {} Playlists erstellt oder aktualisiert",
+    ),
+    ("Run from {} to {}.", "Lauf von {} bis {}."),
+    (
+        "This was a dry run, nothing was changed.",
+        "Das war ein Probelauf, nichts wurde geändert.",
+    ),
+    ("{} failed: {}", "{} fehlgeschlagen: {}"),
+    (
+        "Updated \"{}\" with {} tracks: {}",
+        "\"{}\" mit {} Tracks aktualisiert: {}",
+    ),
+    (
+        "Created \"{}\" with {} tracks: {}",
+        "\"{}\" mit {} Tracks erstellt: {}",
+    ),
+    ("  new: {} by {}", "  neu: {} von {}"),
+    ("  gone: {} by {}", "  raus: {} von {}"),
+    ("  {} tracks moved", "  {} Tracks verschoben"),
+    ("Created with {} tracks", "Mit {} Tracks erstellt"),
+    (
+        "Spautofy could not log in to Spotify",
+        "Spautofy konnte sich nicht bei Spotify anmelden",
+    ),
+    (
+        "Spotify did not accept the kept login, it may have been revoked; run `spautofy auth` again.",
+        "Spotify hat die gespeicherte Anmeldung nicht akzeptiert, vielleicht wurde sie widerrufen; führe `spautofy auth` erneut aus.",
+    ),
+    (
+        "Could not publish the run to MQTT: {}",
+        "Konnte den Lauf nicht über MQTT veröffentlichen: {}",
+    ),
+    (
+        "Could not ping the healthcheck: {}",
+        "Konnte den Healthcheck nicht anpingen: {}",
+    ),
+    (
+        "Could not publish to MQTT: {}",
+        "Konnte nicht über MQTT veröffentlichen: {}",
+    ),
+    (
+        "Could not subscribe to {}: {}",
+        "Konnte {} nicht abonnieren: {}",
+    ),
+    (
+        "Lost the connection to the MQTT broker: {}",
+        "Die Verbindung zum MQTT-Broker ist abgebrochen: {}",
+    ),
+    (
+        "Config file {} has no MQTT broker, set notifications.mqtt.",
+        "Die Konfigurationsdatei {} hat keinen MQTT-Broker, setze notifications.mqtt.",
+    ),
+    (
+        "Could not connect to the MQTT broker {}: {}",
+        "Konnte keine Verbindung zum MQTT-Broker {} herstellen: {}",
+    ),
+    ("Waiting for commands on {}.", "Warte auf Befehle auf {}."),
+    ("Waiting for commands", "Warte auf Befehle"),
+    (
+        "Disconnected from the MQTT broker.",
+        "Die Verbindung zum MQTT-Broker wurde getrennt.",
+    ),
+    ("Received command \"{}\".", "Befehl \"{}\" erhalten."),
+    (
+        "Could not push the notification to ntfy: {}",
+        "Konnte die Benachrichtigung nicht an ntfy senden: {}",
+    ),
+    (
+        "Could not push the notification with Pushover: {}",
+        "Konnte die Benachrichtigung nicht mit Pushover senden: {}",
+    ),
+    (
+        "Looking up {} tracks on MusicBrainz, about one per second",
+        "Suche {} Tracks auf MusicBrainz, etwa einen pro Sekunde",
+    ),
+    (
+        "Looking up {} tracks on MusicBrainz and Apple Music, a few seconds each",
+        "Suche {} Tracks auf MusicBrainz und Apple Music, jeden einige Sekunden lang",
+    ),
+    (
+        "Stopped looking up tracks on {}: {}",
+        "Suche auf {} abgebrochen: {}",
+    ),
+    ("{} of {} tracks looked up", "{} von {} Tracks gesucht"),
+    (
+        "Found {} of {} tracks on Apple Music and {} on YouTube Music.",
+        "{} von {} Tracks auf Apple Music und {} auf YouTube Music gefunden.",
+    ),
+    (
+        "MusicBrainz knows {} of {} recordings",
+        "MusicBrainz kennt {} von {} Aufnahmen",
+    ),
+    (
+        "Would replace the tracks of playlist \"{}\" with {} tracks.",
+        "Würde die Tracks der Playlist \"{}\" durch {} Tracks ersetzen.",
+    ),
+    (
+        "Would create playlist \"{}\" with {} tracks.",
+        "Würde die Playlist \"{}\" mit {} Tracks erstellen.",
+    ),
+    ("Would delete playlist \"{}\".", "Würde die Playlist \"{}\" löschen."),
+    (
+        "Would add {} tracks to playlist \"{}\".",
+        "Würde {} Tracks zur Playlist \"{}\" hinzufügen.",
+    ),
+    ("| DRY RUN ", "| TESTLAUF "),
+    ("{} of {} actions succeeded.", "{} von {} Aktionen erfolgreich."),
+    ("{} of {} actions failed.", "{} von {} Aktionen fehlgeschlagen."),
+    ("Exporting playlist {}...", "Exportiere Playlist {}..."),
+    (
+        "Exported playlist \"{}\" with {} tracks to \"{}\".",
+        "Playlist \"{}\" mit {} Tracks nach \"{}\" exportiert.",
+    ),
+    ("Exporting your liked songs...", "Exportiere deine Lieblingssongs..."),
+    (
+        "Exported {} liked songs to \"{}\".",
+        "{} Lieblingssongs nach \"{}\" exportiert.",
+    ),
+    (
+        "{} tracks have no ISRC, other services can only search for their titles.",
+        "{} Tracks haben keinen ISRC, andere Dienste können nur nach ihren Titeln suchen.",
+    ),
+    ("Liked Songs", "Lieblingssongs"),
+    ("Backing up your playlists...", "Sichere deine Playlists..."),
+    ("{} of {} playlists done", "{} von {} Playlists fertig"),
+    (
+        "Backed up {} playlists to \"{}\".",
+        "{} Playlists nach \"{}\" gesichert.",
+    ),
+    (
+        "Backing up your library to \"{}\"...",
+        "Sichere deine Bibliothek nach \"{}\"...",
+    ),
+    (
+        "{} of {} parts done: {} entries in {}",
+        "{} von {} Teilen fertig: {} Einträge in {}",
+    ),
+    (
+        "Backed up your library to \"{}\".",
+        "Deine Bibliothek wurde nach \"{}\" gesichert.",
+    ),
+    (
+        "Could not open the git repository {}: {}",
+        "Konnte das Git-Repository {} nicht öffnen: {}",
+    ),
+    (
+        "Could not commit the backup: {}",
+        "Konnte die Sicherung nicht committen: {}",
+    ),
+    (
+        "Committed the backup as {}.",
+        "Die Sicherung wurde als {} committet.",
+    ),
+    (
+        "Nothing changed since the last backup, made no commit.",
+        "Seit der letzten Sicherung hat sich nichts geändert, kein Commit.",
+    ),
+    ("Id: {}", "ID: {}"),
+    (
+        "The access token lacks the scopes {}, it was granted: {}",
+        "Dem Access-Token fehlen die Berechtigungen {}, erteilt wurden: {}",
+    ),
+    ("Country: {}", "Land: {}"),
+    ("Subscription: {}", "Abo: {}"),
+    (
+        "Log in again with `spautofy auth` to see the country and subscription.",
+        "Melde dich mit `spautofy auth` neu an, um Land und Abo zu sehen.",
+    ),
+    ("Error reading backup {}: {}", "Fehler beim Lesen der Sicherung {}: {}"),
+    (
+        "{} does not match its schema: {}",
+        "{} entspricht nicht seinem Schema: {}",
+    ),
+    ("Liked songs", "Lieblingssongs"),
+    ("Cancelled the restore.", "Wiederherstellung abgebrochen."),
+    (
+        "The backup has no playlist \"{}\".",
+        "Die Sicherung enthält keine Playlist \"{}\".",
+    ),
+    (
+        "Restored playlist \"{}\" with {} tracks.",
+        "Playlist \"{}\" mit {} Tracks wiederhergestellt.",
+    ),
+    (
+        "Could not restore playlist \"{}\": {}",
+        "Playlist \"{}\" konnte nicht wiederhergestellt werden: {}",
+    ),
+    (
+        "{} of {} playlists could not be restored.",
+        "{} von {} Playlists konnten nicht wiederhergestellt werden.",
+    ),
+    ("Would like {} tracks.", "Würde {} Tracks als Lieblingssongs speichern."),
+    ("Liked {} tracks.", "{} Tracks als Lieblingssongs gespeichert."),
+    ("was {}", "vorher {}"),
+    (
+        "Playlist \"{}\" ({} tracks)",
+        "Playlist \"{}\" ({} Tracks)",
+    ),
+    (
+        "Playlist \"{}\", renamed from \"{}\":",
+        "Playlist \"{}\", umbenannt von \"{}\":",
+    ),
+    ("No differences.", "Keine Unterschiede."),
+    ("Found {} differences.", "{} Unterschiede gefunden."),
+    (
+        "Restore or skip the highlighted entry",
+        "Markierten Eintrag wiederherstellen oder überspringen",
+    ),
+    ("Select all visible entries", "Alle sichtbaren Einträge auswählen"),
+    ("Deselect all visible entries", "Alle sichtbaren Einträge abwählen"),
+    (
+        "Filter the entries, Esc clears the filter",
+        "Einträge filtern, Esc löscht den Filter",
+    ),
+    (
+        "Restore the selected entries",
+        "Ausgewählte Einträge wiederherstellen",
+    ),
+    ("Cancel", "Abbrechen"),
+    (
+        " Spautofy - Restore Backup ",
+        " Spautofy - Sicherung wiederherstellen ",
+    ),
+    (" {} OF {} SELECTED", " {} VON {} AUSGEWÄHLT"),
+    (
+        " | TOGGLE WITH '{select}' | RESTORE WITH '{confirm}' | CANCEL WITH '{quit}' ",
+        " | WÄHLEN MIT '{select}' | WIEDERHERSTELLEN MIT '{confirm}' | ABBRECHEN MIT '{quit}' ",
+    ),
+    ("{} names no songs.", "{} nennt keine Songs."),
+    ("Searching for {} songs...", "Suche nach {} Songs..."),
+    ("\"{}\": {} by {} ({:.0}%)", "\"{}\": {} von {} ({:.0}%)"),
+    ("\"{}\": nothing found", "\"{}\": nichts gefunden"),
+    ("Left out line {}: \"{}\"", "Zeile {} ausgelassen: \"{}\""),
+    (
+        "Created playlist \"{}\" with {} of {} songs.",
+        "Playlist \"{}\" mit {} von {} Songs erstellt.",
+    ),
+    ("Cancelled the import.", "Import abgebrochen."),
+    ("Cancelled the scan.", "Suche abgebrochen."),
+    (
+        "Could not read the tags of {}: {}",
+        "Konnte die Tags von {} nicht lesen: {}",
+    ),
+    ("{} holds no music files.", "{} enthält keine Musikdateien."),
+    ("Read {} music files", "{} Musikdateien gelesen"),
+    (
+        "Created playlist \"{}\" with {} tracks.",
+        "Playlist \"{}\" mit {} Tracks erstellt.",
+    ),
+    ("{} of {} files are on Spotify.", "{} von {} Dateien sind auf Spotify."),
+    ("On Spotify", "Auf Spotify"),
+    ("Missing", "Fehlt"),
+    ("File", "Datei"),
+    (
+        "Could not read the history: {}",
+        "Konnte den Verlauf nicht lesen: {}",
+    ),
+    ("Running {}...", "{} läuft..."),
+    ("The last run failed: {}", "Der letzte Lauf ist fehlgeschlagen: {}"),
+    (
+        "There is no pipeline, rule or action \"{}\".",
+        "Es gibt keine Pipeline, Regel oder Aktion \"{}\".",
+    ),
+    ("{} is still running.", "{} läuft noch."),
+    (
+        "Running \"{}\" from the dashboard.",
+        "Starte \"{}\" vom Dashboard.",
+    ),
+    ("Started {}.", "{} gestartet."),
+    (
+        "Serving the dashboard on http://{}:{}/ until stopped.",
+        "Stelle das Dashboard auf http://{}:{}/ bereit, bis es beendet wird.",
+    ),
+    ("Dashboard", "Dashboard"),
+    ("Playlists", "Playlists"),
+    ("History", "Verlauf"),
+    ("Error", "Fehler"),
+    ("Run", "Starten"),
+    ("Kind", "Art"),
+    ("Pipeline", "Pipeline"),
+    ("Rule", "Regel"),
+    ("Last run", "Letzter Lauf"),
+    ("Started", "Gestartet"),
+    ("Actions", "Aktionen"),
+    ("No runs yet.", "Noch keine Läufe."),
+    (
+        "Running \"{}\" for a request to the API.",
+        "Starte \"{}\" für eine Anfrage an die API.",
+    ),
+    (
+        "The request needs the token of the API as `Authorization: Bearer <token>`.",
+        "Die Anfrage braucht das Token der API als `Authorization: Bearer <token>`.",
+    ),
+    (
+        "Config file {} has no token for the API, set api.token or $SPAUTOFY_API_TOKEN.",
+        "Die Konfigurationsdatei {} hat kein Token für die API, setze api.token oder \
+        $SPAUTOFY_API_TOKEN.",
+    ),
+    (
+        "Serving the API on http://{}:{}/api/ until stopped.",
+        "Stelle die API auf http://{}:{}/api/ bereit, bis sie beendet wird.",
+    ),
+    (
+        "The API needs a token, e.g. in $SPAUTOFY_API_TOKEN.",
+        "Die API braucht ein Token, z. B. in $SPAUTOFY_API_TOKEN.",
+    ),
+    (
+        "The CORS origin \"{}\" is neither * nor like http://localhost:5173.",
+        "Der CORS-Origin \"{}\" ist weder * noch wie http://localhost:5173.",
+    ),
+    (
+        "Show the other tracks found for the song",
+        "Die anderen für den Song gefundenen Tracks zeigen",
+    ),
+    (
+        "Add or leave out the highlighted song",
+        "Markierten Song hinzufügen oder auslassen",
+    ),
+    ("Create the playlist", "Playlist erstellen"),
+    ("{} by {}", "{} von {}"),
+    ("Nothing found", "Nichts gefunden"),
+    ("Line", "Zeile"),
+    ("Confidence", "Sicherheit"),
+    (" Spautofy - Review Matches ", " Spautofy - Treffer prüfen "),
+    (" {} OF {} UNSURE ADDED", " {} VON {} UNSICHEREN HINZUGEFÜGT"),
+    (
+        " | OTHER TRACK WITH LEFT/RIGHT | ADD WITH '{select}' | CREATE WITH '{confirm}' | \
+        CANCEL WITH '{quit}' ",
+        " | ANDERER TRACK MIT LINKS/RECHTS | HINZUFÜGEN MIT '{select}' | ERSTELLEN MIT \
+        '{confirm}' | ABBRECHEN MIT '{quit}' ",
+    ),
+    // history
+    ("today", "heute"),
+    ("yesterday", "gestern"),
+    ("{} days ago", "vor {} Tagen"),
+    ("Spautofy playlists", "Spautofy-Playlists"),
+    ("Error reading {}: {}", "Fehler beim Lesen von {}: {}"),
+    (
+        "Would import {} plays from {}.",
+        "Würde {} Wiedergaben aus {} importieren.",
+    ),
+    (
+        "Imported {} plays from {}.",
+        "{} Wiedergaben aus {} importiert.",
+    ),
+    (
+        "Imported {} of {} plays from {}, the others were imported before.",
+        "{} von {} Wiedergaben aus {} importiert, die anderen waren schon importiert.",
+    ),
+    (
+        "Left out {} entries of {}: short plays, episodes or lines without a track or date.",
+        "{} Einträge von {} ausgelassen: kurze Wiedergaben, Episoden oder Zeilen ohne Titel oder Datum.",
+    ),
+    (
+        "Could not write the feed to {}: {}",
+        "Der Feed konnte nicht nach {} geschrieben werden: {}",
+    ),
+    (
+        "Could not write the metrics to {}: {}",
+        "Die Metriken konnten nicht nach {} geschrieben werden: {}",
+    ),
+    (
+        "Could not save the run to the history: {}",
+        "Der Lauf konnte nicht im Verlauf gespeichert werden: {}",
+    ),
+    (
+        "Could not use the history database: {}",
+        "Die Verlaufsdatenbank konnte nicht verwendet werden: {}",
+    ),
+    (
+        "Could not archive the recently played tracks: {}",
+        "Die zuletzt gespielten Tracks konnten nicht archiviert werden: {}",
+    ),
+    (
+        "Archived {} newly played tracks",
+        "{} neu gespielte Tracks archiviert",
+    ),
+    (
+        "Could not read the last selection: {}",
+        "Die letzte Auswahl konnte nicht gelesen werden: {}",
+    ),
+    (
+        "Could not save the selection: {}",
+        "Die Auswahl konnte nicht gespeichert werden: {}",
+    ),
+    (
+        "Could not read the run history: {}",
+        "Der Verlauf konnte nicht gelesen werden: {}",
+    ),
+    (
+        "Filter by date or playlist name, Esc clears the filter",
+        "Nach Datum oder Playlist-Namen filtern, Esc löscht den Filter",
+    ),
+    (
+        "{} ({}) - {} of {} actions succeeded",
+        "{} ({}) - {} von {} Aktionen erfolgreich",
+    ),
+    (" Spautofy - Run History ", " Spautofy - Verlauf "),
+    (" BACK WITH '{quit}' ", " ZURÜCK MIT '{quit}' "),
+    ("No runs recorded yet.", "Noch keine Läufe aufgezeichnet."),
+    ("Run of {}", "Lauf vom {}"),
+    ("{} ({} tracks)", "{} ({} Tracks)"),
+    ("failed: {}", "fehlgeschlagen: {}"),
+    // keybindings and hints
+    ("Move the cursor", "Cursor bewegen"),
+    ("Move the cursor by pages", "Cursor seitenweise bewegen"),
+    ("Toggle the highlighted action", "Markierte Aktion an- oder abwählen"),
+    ("Select all visible actions", "Alle sichtbaren Aktionen auswählen"),
+    ("Deselect all visible actions", "Alle sichtbaren Aktionen abwählen"),
+    (
+        "Invert the selection of the visible actions",
+        "Auswahl der sichtbaren Aktionen umkehren",
+    ),
+    (
+        "Move the highlighted action in the run order",
+        "Markierte Aktion in der Ausführungsreihenfolge verschieben",
+    ),
+    (
+        "Filter the actions, Esc clears the filter",
+        "Aktionen filtern, Esc löscht den Filter",
+    ),
+    (
+        "Continue with the selected actions",
+        "Mit den ausgewählten Aktionen fortfahren",
+    ),
+    (
+        "Browse and delete your playlists",
+        "Deine Playlists ansehen und löschen",
+    ),
+    ("Search tracks and artists", "Tracks und Künstler suchen"),
+    ("Browse the previous runs", "Frühere Läufe ansehen"),
+    ("Log in to Spotify again", "Erneut bei Spotify anmelden"),
+    ("Quit", "Beenden"),
+    (" Spautofy - Select Actions ", " Spautofy - Aktionen auswählen "),
+    (
+        " TOGGLE WITH '{select}' | REORDER WITH 'SHIFT+↑'/'↓' | CONFIRM WITH '{confirm}' ",
+        " WÄHLEN MIT '{select}' | VERSCHIEBEN MIT 'SHIFT+↑'/'↓' | BESTÄTIGEN MIT '{confirm}' ",
+    ),
+    ("Run the actions", "Aktionen ausführen"),
+    ("Go back", "Zurück"),
+    (" Spautofy - Confirm ", " Spautofy - Bestätigen "),
+    (
+        " RUN WITH '{confirm}' | BACK WITH 'ESC' ",
+        " AUSFÜHREN MIT '{confirm}' | ZURÜCK MIT 'ESC' ",
+    ),
+    (
+        "No actions selected, nothing will be run.",
+        "Keine Aktionen ausgewählt, es wird nichts ausgeführt.",
+    ),
+    (
+        "The following actions will be run:",
+        "Die folgenden Aktionen werden ausgeführt:",
+    ),
+    ("Loading covers", "Lade Cover"),
+    (
+        "Could not load the cover from {}",
+        "Das Cover von {} konnte nicht geladen werden",
+    ),
+    (" Details ", " Details "),
+    (
+        " RETRY WITH 'R' OR '{select}' | DISMISS WITH 'ESC' ",
+        " ERNEUT VERSUCHEN MIT 'R' ODER '{select}' | SCHLIESSEN MIT 'ESC' ",
+    ),
+    (" HELP WITH '?' ", " HILFE MIT '?' "),
+    (
+        "Press any key to close this help.",
+        "Drücke eine beliebige Taste, um diese Hilfe zu schließen.",
+    ),
+    (" Keybindings ", " Tastenbelegung "),
+    (
+        "The following {} items will be removed:",
+        "Die folgenden {} Einträge werden entfernt:",
+    ),
+    (
+        " CONFIRM WITH 'Y' | ABORT WITH 'ESC' ",
+        " BESTÄTIGEN MIT 'Y' | ABBRECHEN MIT 'ESC' ",
+    ),
+    (" Log (scrolled up {} lines) ", " Log ({} Zeilen hochgescrollt) "),
+    // parameters
+    ("Move between parameters", "Zwischen Parametern wechseln"),
+    (
+        "Change the highlighted value, move between options",
+        "Markierten Wert ändern, zwischen Optionen wechseln",
+    ),
+    (
+        "Edit text and numbers, pick playlists, toggle options, change other values",
+        "Text und Zahlen bearbeiten, Playlists wählen, Optionen umschalten, andere Werte ändern",
+    ),
+    ("Enter, Esc (editing)", "Enter, Esc (beim Bearbeiten)"),
+    ("Finish editing", "Bearbeiten beenden"),
+    (
+        "Preview the tracks of the highlighted action",
+        "Tracks der markierten Aktion ansehen",
+    ),
+    ("Continue", "Weiter"),
+    ("yes", "ja"),
+    ("no", "nein"),
+    ("none", "keine"),
+    ("create a new playlist", "neue Playlist erstellen"),
+    ("Top Tracks Playlists", "Top-Tracks-Playlists"),
+    (
+        "Creates a playlist with the tracks you listened to most for each selected time range: {}.",
+        "Erstellt für jeden ausgewählten Zeitraum eine Playlist mit den Tracks, die du am meisten gehört hast: {}.",
+    ),
+    ("Time ranges", "Zeiträume"),
+    ("Number of tracks", "Anzahl der Tracks"),
+    ("Playlist name", "Name der Playlist"),
+    ("Public", "Öffentlich"),
+    ("Collaborative", "Gemeinsam bearbeitbar"),
+    ("Update playlist", "Playlist aktualisieren"),
+    (
+        "{}: select at least one time range.",
+        "{}: wähle mindestens einen Zeitraum.",
+    ),
+    (
+        "{}: a collaborative playlist cannot be public.",
+        "{}: eine gemeinsam bearbeitbare Playlist kann nicht öffentlich sein.",
+    ),
+    (
+        "{}: select a single time range to update an existing playlist.",
+        "{}: wähle genau einen Zeitraum, um eine bestehende Playlist zu aktualisieren.",
+    ),
+    (
+        " Spautofy - Action Parameters ",
+        " Spautofy - Parameter der Aktionen ",
+    ),
+    (
+        " TYPE TO EDIT | FINISH WITH 'ENTER' ",
+        " TIPPEN ZUM BEARBEITEN | FERTIG MIT 'ENTER' ",
+    ),
+    (
+        " CHANGE WITH '←'/'→' OR '{select}' | NEXT WITH '{confirm}' | BACK WITH 'ESC' ",
+        " ÄNDERN MIT '←'/'→' ODER '{select}' | WEITER MIT '{confirm}' | ZURÜCK MIT 'ESC' ",
+    ),
+    // playlists
+    ("Pick the highlighted playlist", "Markierte Playlist wählen"),
+    (
+        "Filter the playlists, Esc clears the filter",
+        "Playlists filtern, Esc löscht den Filter",
+    ),
+    (
+        "Show all playlists or only the ones created by Spautofy",
+        "Alle Playlists oder nur die von Spautofy erstellten anzeigen",
+    ),
+    (
+        "Mark the highlighted playlist for deletion",
+        "Markierte Playlist zum Löschen vormerken",
+    ),
+    (
+        "Delete the marked or the highlighted playlists",
+        "Vorgemerkte oder markierte Playlists löschen",
+    ),
+    ("Owner", "Besitzer"),
+    (
+        " MARK WITH 'X' | DELETE WITH 'D' | BACK WITH '{quit}' ",
+        " VORMERKEN MIT 'X' | LÖSCHEN MIT 'D' | ZURÜCK MIT '{quit}' ",
+    ),
+    (
+        " PICK WITH '{select}' | ONLY SPAUTOFY PLAYLISTS WITH 'O' | BACK WITH '{quit}' ",
+        " WÄHLEN MIT '{select}' | NUR SPAUTOFY-PLAYLISTS MIT 'O' | ZURÜCK MIT '{quit}' ",
+    ),
+    (
+        " PICK WITH '{select}' | ALL PLAYLISTS WITH 'O' | BACK WITH '{quit}' ",
+        " WÄHLEN MIT '{select}' | ALLE PLAYLISTS MIT 'O' | ZURÜCK MIT '{quit}' ",
+    ),
+    (
+        " PICK WITH '{select}' | BACK WITH '{quit}' ",
+        " WÄHLEN MIT '{select}' | ZURÜCK MIT '{quit}' ",
+    ),
+    ("collaborative", "gemeinsam"),
+    ("public", "öffentlich"),
+    ("private", "privat"),
+    ("unknown", "unbekannt"),
+    ("by {}", "von {}"),
+    ("Visibility: {}", "Sichtbarkeit: {}"),
+    ("Visibility", "Sichtbarkeit"),
+    ("Duration", "Dauer"),
+    ("Added", "Hinzugefügt"),
+    ("{} of {} tracks loaded", "{} von {} Tracks geladen"),
+    (
+        "None of your playlists matches \"{}\".",
+        "Keine deiner Playlists passt zu \"{}\".",
+    ),
+    (
+        "\"{}\" matches {} playlists, use one of their ids: {}",
+        "\"{}\" passt zu {} Playlists, verwende eine ihrer IDs: {}",
+    ),
+    ("There is no user \"{}\".", "Es gibt keinen Benutzer \"{}\"."),
+    ("Public playlists of {}:", "Öffentliche Playlists von {}:"),
+    ("Delete Playlists", "Playlists löschen"),
+    ("Loading playlists", "Lade Playlists"),
+    (
+        "Could not load your playlists",
+        "Deine Playlists konnten nicht geladen werden",
+    ),
+    ("Spautofy - My Playlists", "Spautofy - Meine Playlists"),
+    (
+        "Could not delete the playlist",
+        "Die Playlist konnte nicht gelöscht werden",
+    ),
+    ("Deleted playlist \"{}\".", "Playlist \"{}\" gelöscht."),
+    (
+        "Spautofy - Pick Playlist To Update",
+        "Spautofy - Zu aktualisierende Playlist wählen",
+    ),
+    ("Spautofy - Add To Playlist", "Spautofy - Zur Playlist hinzufügen"),
+    (
+        "Added {} tracks to playlist \"{}\".",
+        "{} Tracks zur Playlist \"{}\" hinzugefügt.",
+    ),
+    // running and results
+    ("Running profile {}...", "Führe Profil {} aus..."),
+    ("Profile {} failed: {}", "Profil {} fehlgeschlagen: {}"),
+    ("{} of {} profiles succeeded.", "{} von {} Profilen erfolgreich."),
+    ("Profiles failed: {}", "Fehlgeschlagene Profile: {}"),
+    ("{} {} ({} of {} done)", "{} {} ({} von {} fertig)"),
+    ("{} of {} done", "{} von {} fertig"),
+    (" Spautofy - Running Actions ", " Spautofy - Aktionen laufen "),
+    (
+        " RUNNING ACTIONS... | SCROLL THE LOG WITH 'PGUP'/'PGDN' ",
+        " AKTIONEN LAUFEN... | LOG SCROLLEN MIT 'PGUP'/'PGDN' ",
+    ),
+    (
+        "Copy the highlighted playlist URL",
+        "URL der markierten Playlist kopieren",
+    ),
+    ("Scroll the log", "Log scrollen"),
+    ("Back to the action selection", "Zurück zur Aktionsauswahl"),
+    (
+        "This action did not create a playlist.",
+        "Diese Aktion hat keine Playlist erstellt.",
+    ),
+    ("Copied {} to the clipboard.", "{} in die Zwischenablage kopiert."),
+    ("done", "fertig"),
+    ("failed", "fehlgeschlagen"),
+    ("Action", "Aktion"),
+    (" Spautofy - Results ", " Spautofy - Ergebnisse "),
+    (
+        " COPY URL WITH 'C' | BACK WITH '{select}' | QUIT WITH '{quit}' ",
+        " URL KOPIEREN MIT 'C' | ZURÜCK MIT '{select}' | BEENDEN MIT '{quit}' ",
+    ),
+    // search
+    ("Typing", "Tippen"),
+    ("Edit the search query", "Suchanfrage bearbeiten"),
+    (
+        "Switch between tracks and artists",
+        "Zwischen Tracks und Künstlern wechseln",
+    ),
+    (
+        "Pick or unpick the highlighted result",
+        "Markiertes Ergebnis wählen oder abwählen",
+    ),
+    (
+        "Create a recommendations playlist from the picks",
+        "Aus der Auswahl eine Empfehlungs-Playlist erstellen",
+    ),
+    (
+        "Add the picked tracks to a playlist",
+        "Ausgewählte Tracks zu einer Playlist hinzufügen",
+    ),
+    (" Spautofy - Search ", " Spautofy - Suche "),
+    (
+        " Tracks (switch with 'TAB') ",
+        " Tracks (wechseln mit 'TAB') ",
+    ),
+    (
+        " Artists (switch with 'TAB') ",
+        " Künstler (wechseln mit 'TAB') ",
+    ),
+    (
+        "{} tracks, {} artists picked",
+        "{} Tracks, {} Künstler ausgewählt",
+    ),
+    (
+        " PICK WITH 'ENTER' | BACK WITH 'ESC' ",
+        " WÄHLEN MIT 'ENTER' | ZURÜCK MIT 'ESC' ",
+    ),
+    (" HELP WITH 'F1' ", " HILFE MIT 'F1' "),
+    ("Search failed", "Suche fehlgeschlagen"),
+    (
+        "Created playlist \"{}\" from your recommendations.",
+        "Playlist \"{}\" aus deinen Empfehlungen erstellt.",
+    ),
+    // status bar
+    ("token expired", "Token abgelaufen"),
+    ("token expires in {} min", "Token läuft in {} Min. ab"),
+    (" Logged in as {} | ", " Angemeldet als {} | "),
+    (
+        " | RE-AUTHORIZE WITH 'R' ",
+        " | NEU AUTORISIEREN MIT 'R' ",
+    ),
+    // tracks
+    (
+        "Include or leave out the highlighted track",
+        "Markierten Track aufnehmen oder weglassen",
+    ),
+    (
+        "Filter by track, artist or album, Esc clears the filter",
+        "Nach Track, Künstler oder Album filtern, Esc löscht den Filter",
+    ),
+    ("Go back to the parameters", "Zurück zu den Parametern"),
+    ("Artists", "Künstler"),
+    (" {} OF {} TRACKS INCLUDED", " {} VON {} TRACKS AUSGEWÄHLT"),
+    (
+        " | TOGGLE WITH '{select}' | BACK WITH '{quit}' ",
+        " | UMSCHALTEN MIT '{select}' | ZURÜCK MIT '{quit}' ",
+    ),
+    ("Loading tracks", "Lade Tracks"),
+    (
+        "Spautofy - Preview {} Top Tracks",
+        "Spautofy - Vorschau der {} Top-Tracks",
+    ),
+    ("Could not load the tracks", "Die Tracks konnten nicht geladen werden"),
+];
@@ -0,0 +1,34 @@
+//! Other music services; the ones needing an account are set up in the `"integrations"`
+//! section of the config file.
+
+use serde::{Deserialize, Serialize};
+
+use lastfm::LastfmConfig;
+use listenbrainz::ListenbrainzConfig;
+
+pub mod itunes;
+pub mod lastfm;
+pub mod listenbrainz;
+pub mod musicbrainz;
+pub mod service_mapping;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct IntegrationsConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lastfm: Option<LastfmConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listenbrainz: Option<ListenbrainzConfig>,
+}
+
+impl IntegrationsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.lastfm.is_none() && self.listenbrainz.is_none()
+    }
+}
+
+/// A track listened to as another service names it, to be found on Spotify.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListenedTrack {
+    pub name: String,
+    pub artist: String,
+}
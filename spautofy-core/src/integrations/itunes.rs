@@ -0,0 +1,166 @@
+//! The iTunes Search API finds songs of the Apple Music catalogue without an account, but
+//! not by their ISRCs: the track is searched for by its title and artists, and the result
+//! most like it taken, with how sure that is.
+
+use std::time::Duration;
+
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Instant};
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::models::track::Track;
+use crate::song_list::{similarity, title_similarity};
+use crate::storage;
+
+const API_URL: &str = "https://itunes.apple.com/search";
+
+/// Apple allows about 20 searches a minute.
+const REQUEST_INTERVAL: Duration = Duration::from_secs(3);
+
+/// How long to wait once Apple turned a search away all the same, and how often to.
+const THROTTLE_WAIT: Duration = Duration::from_secs(60);
+const RETRIES: u32 = 2;
+
+const RESULTS: &str = "10";
+
+/// The catalogue of a song seldom changes.
+const CACHE_DAYS: i64 = 30;
+
+/// A result lasting longer or shorter than this is another version of the song.
+const MAX_DURATION_DIFFERENCE_MS: u64 = 5000;
+
+/// A song of the Apple Music catalogue.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AppleSong {
+    pub id: String,
+    pub url: String,
+    /// From 0 to 1, how much the song resembles the track searched for.
+    pub confidence: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResult {
+    track_id: u64,
+    track_name: String,
+    artist_name: String,
+    track_view_url: String,
+    track_time_millis: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResults {
+    results: Vec<SearchResult>,
+}
+
+/// Keeps to the rate limit of Apple over all searches.
+pub struct Itunes {
+    client: Client,
+    /// The storefront searched, the country of the Spotify account.
+    country: String,
+    last_request: Option<Instant>,
+}
+
+impl Itunes {
+    pub fn new(country: Option<&str>) -> Self {
+        Itunes {
+            client: Client::new(),
+            country: country.unwrap_or("US").to_lowercase(),
+            last_request: None,
+        }
+    }
+
+    async fn search(&mut self, term: &str) -> Result<SearchResults, AuthorizeError> {
+        for _ in 0..=RETRIES {
+            if let Some(last_request) = self.last_request {
+                sleep(REQUEST_INTERVAL.saturating_sub(last_request.elapsed())).await;
+            }
+            self.last_request = Some(Instant::now());
+            let request = self
+                .client
+                .get(API_URL)
+                .query(&[
+                    ("term", term),
+                    ("country", &self.country),
+                    ("media", "music"),
+                    ("entity", "song"),
+                    ("limit", RESULTS),
+                ])
+                .build()?;
+            let resp = execute(&self.client, request).await?;
+            match resp.status() {
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::FORBIDDEN => {
+                    sleep(THROTTLE_WAIT).await;
+                }
+                status if !status.is_success() => {
+                    return Err(AuthorizeError::Service("iTunes", status.to_string()))
+                }
+                // the results come as text/javascript
+                _ => {
+                    let body = resp.text().await?;
+                    return serde_json::from_str(&body)
+                        .map_err(|err| AuthorizeError::Service("iTunes", err.to_string()));
+                }
+            }
+        }
+        Err(AuthorizeError::Service(
+            "iTunes",
+            "too many requests, try again later".to_string(),
+        ))
+    }
+
+    /// Like `find_uncached`, if the search is not cached.
+    pub async fn find(
+        &mut self,
+        track: &Track,
+        min_confidence: f64,
+    ) -> Result<Option<AppleSong>, AuthorizeError> {
+        let key = format!("itunes/{}/{}", self.country, track.id);
+        let song =
+            match storage::cached::<Option<AppleSong>>(&key, chrono::Duration::days(CACHE_DAYS)) {
+                Some(song) => song,
+                None => {
+                    let song = self.find_uncached(track).await?;
+                    storage::cache(&key, &song);
+                    song
+                }
+            };
+        Ok(song.filter(|song| song.confidence >= min_confidence))
+    }
+
+    /// The song most like the track, by title and artists, of those about as long as it.
+    async fn find_uncached(&mut self, track: &Track) -> Result<Option<AppleSong>, AuthorizeError> {
+        // Apple finds less with what Spotify adds to the name, like `- Remastered 2011`
+        let title = track.name.split(" - ").next().unwrap_or(&track.name);
+        let first_artist = track
+            .artists
+            .first()
+            .map_or("", |artist| artist.name.as_str());
+        let found = self.search(&format!("{} {}", title, first_artist)).await?;
+        let artists = track.artist_names();
+        let best = found
+            .results
+            .into_iter()
+            .filter(|song| {
+                song.track_time_millis.is_none_or(|duration| {
+                    duration.abs_diff(track.duration_ms) <= MAX_DURATION_DIFFERENCE_MS
+                })
+            })
+            .map(|song| {
+                // both may add a version to the name
+                let title = title_similarity(&track.name, &song.track_name)
+                    .max(title_similarity(&song.track_name, &track.name));
+                let artist = similarity(&artists, &song.artist_name)
+                    .max(similarity(first_artist, &song.artist_name));
+                (song, 0.6 * title + 0.4 * artist)
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        Ok(best.map(|(song, confidence)| AppleSong {
+            id: song.track_id.to_string(),
+            url: song.track_view_url,
+            confidence,
+        }))
+    }
+}
@@ -0,0 +1,203 @@
+//! The listening history of a Last.fm user, the source `lastfm:<period>` of the rules:
+//! the most scrobbled tracks of the period, found on Spotify by their name and artist, as
+//! Last.fm knows no ISRCs.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use chrono::NaiveDate;
+use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::integrations::ListenedTrack;
+
+const API_URL: &str = "https://ws.audioscrobbler.com/2.0/";
+
+/// Last.fm returns at most this many top tracks per page.
+const TRACKS_PER_PAGE: usize = 1000;
+
+/// `"integrations": {"lastfm": {...}}`, the API key from
+/// https://www.last.fm/api/account/create.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct LastfmConfig {
+    pub api_key: String,
+    /// Whose scrobbles are read, they need not be public.
+    pub user: String,
+}
+
+/// The periods of Last.fm's own charts, or a calendar year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Overall,
+    Week,
+    Month,
+    ThreeMonths,
+    SixMonths,
+    TwelveMonths,
+    Year(i32),
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    fn from_str(period: &str) -> Result<Self, Self::Err> {
+        match period {
+            "overall" => Ok(Period::Overall),
+            "7day" => Ok(Period::Week),
+            "1month" => Ok(Period::Month),
+            "3month" => Ok(Period::ThreeMonths),
+            "6month" => Ok(Period::SixMonths),
+            "12month" => Ok(Period::TwelveMonths),
+            // Last.fm started in 2002
+            year => match year.parse() {
+                Ok(year) if (2002..=9999).contains(&year) => Ok(Period::Year(year)),
+                _ => Err(format!(
+                    "unknown Last.fm period \"{}\", expected overall, 7day, 1month, 3month, \
+                    6month, 12month or a year like 2019",
+                    period
+                )),
+            },
+        }
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Period::Overall => write!(f, "overall"),
+            Period::Week => write!(f, "7day"),
+            Period::Month => write!(f, "1month"),
+            Period::ThreeMonths => write!(f, "3month"),
+            Period::SixMonths => write!(f, "6month"),
+            Period::TwelveMonths => write!(f, "12month"),
+            Period::Year(year) => write!(f, "{}", year),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ScrobbledTrack {
+    name: String,
+    artist: ScrobbledArtist,
+}
+
+/// `user.getTopTracks` has the name in `name`, `user.getWeeklyTrackChart` in `#text`.
+#[derive(Debug, Deserialize)]
+struct ScrobbledArtist {
+    #[serde(alias = "#text")]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tracks {
+    #[serde(default)]
+    track: Vec<ScrobbledTrack>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopTracks {
+    toptracks: Tracks,
+}
+
+#[derive(Debug, Deserialize)]
+struct WeeklyTrackChart {
+    weeklytrackchart: Tracks,
+}
+
+/// Last.fm answers errors with a JSON body, mostly with an error status.
+#[derive(Debug, Deserialize)]
+struct LastfmError {
+    message: String,
+}
+
+async fn get(config: &LastfmConfig, params: &[(&str, &str)]) -> Result<Response, AuthorizeError> {
+    let client = Client::new();
+    let request = client
+        .get(API_URL)
+        .query(&[
+            ("user", config.user.as_str()),
+            ("api_key", config.api_key.as_str()),
+            ("format", "json"),
+        ])
+        .query(params)
+        .build()?;
+    Ok(execute(&client, request).await?)
+}
+
+async fn parse<T: serde::de::DeserializeOwned>(resp: Response) -> Result<T, AuthorizeError> {
+    let body = resp.text().await?;
+    match serde_json::from_str::<T>(&body) {
+        Ok(value) => Ok(value),
+        Err(err) => Err(AuthorizeError::Service(
+            "Last.fm",
+            match serde_json::from_str::<LastfmError>(&body) {
+                Ok(error) => error.message,
+                Err(_) => format!("unexpected response: {}", err),
+            },
+        )),
+    }
+}
+
+/// The first of January of the year and of the next as Unix timestamps, in UTC.
+fn year_range(year: i32) -> (String, String) {
+    let start = |year| {
+        NaiveDate::from_ymd_opt(year, 1, 1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|time| time.and_utc().timestamp())
+            .unwrap_or_default()
+            .to_string()
+    };
+    (start(year), start(year + 1))
+}
+
+/// The user's most scrobbled tracks of the period, the most played first.
+pub async fn get_top_tracks(
+    config: &LastfmConfig,
+    period: Period,
+    limit: usize,
+) -> Result<Vec<ListenedTrack>, AuthorizeError> {
+    let mut tracks = Vec::with_capacity(limit);
+    if let Period::Year(year) = period {
+        // the weekly charts cover any range, all of its tracks in one page
+        let (from, to) = year_range(year);
+        let params = [
+            ("method", "user.getweeklytrackchart"),
+            ("from", from.as_str()),
+            ("to", to.as_str()),
+        ];
+        let chart: WeeklyTrackChart = parse(get(config, &params).await?).await?;
+        tracks = chart.weeklytrackchart.track;
+    } else {
+        let period = period.to_string();
+        let page_size = limit.min(TRACKS_PER_PAGE);
+        let page_size_param = page_size.to_string();
+        let mut page = 1;
+        while tracks.len() < limit {
+            let page_number = page.to_string();
+            let params = [
+                ("method", "user.gettoptracks"),
+                ("period", period.as_str()),
+                ("limit", page_size_param.as_str()),
+                ("page", page_number.as_str()),
+            ];
+            let top_tracks: TopTracks = parse(get(config, &params).await?).await?;
+            let last_page = top_tracks.toptracks.track.len() < page_size;
+            tracks.extend(top_tracks.toptracks.track);
+            if last_page {
+                break;
+            }
+            page += 1;
+        }
+    }
+    tracks.truncate(limit);
+    let tracks = tracks
+        .into_iter()
+        .map(|track| ListenedTrack {
+            name: track.name,
+            artist: track.artist.name,
+        })
+        .collect();
+    Ok(tracks)
+}
@@ -0,0 +1,272 @@
+//! ListenBrainz, both ways: `spautofy listenbrainz submit` sends the recently played tracks
+//! as listens, and the rules with the source `listenbrainz:<range>` get the user's most
+//! listened tracks of the range from its statistics.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::integrations::ListenedTrack;
+use crate::models::track::Track;
+
+const API_URL: &str = "https://api.listenbrainz.org/1";
+
+/// ListenBrainz returns at most this many recordings of the statistics per request.
+const RECORDINGS_PER_PAGE: usize = 100;
+
+/// `"integrations": {"listenbrainz": {"token": "..."}}`, the token from
+/// https://listenbrainz.org/settings/.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ListenbrainzConfig {
+    pub token: String,
+}
+
+/// The ranges of the ListenBrainz statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsRange {
+    ThisWeek,
+    ThisMonth,
+    ThisYear,
+    Week,
+    Month,
+    Quarter,
+    HalfYear,
+    Year,
+    AllTime,
+}
+
+impl FromStr for StatsRange {
+    type Err = String;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        match range {
+            "this_week" => Ok(StatsRange::ThisWeek),
+            "this_month" => Ok(StatsRange::ThisMonth),
+            "this_year" => Ok(StatsRange::ThisYear),
+            "week" => Ok(StatsRange::Week),
+            "month" => Ok(StatsRange::Month),
+            "quarter" => Ok(StatsRange::Quarter),
+            "half_yearly" => Ok(StatsRange::HalfYear),
+            "year" => Ok(StatsRange::Year),
+            "all_time" => Ok(StatsRange::AllTime),
+            _ => Err(format!(
+                "unknown ListenBrainz range \"{}\", expected this_week, this_month, this_year, \
+                week, month, quarter, half_yearly, year or all_time",
+                range
+            )),
+        }
+    }
+}
+
+impl Display for StatsRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = match self {
+            StatsRange::ThisWeek => "this_week",
+            StatsRange::ThisMonth => "this_month",
+            StatsRange::ThisYear => "this_year",
+            StatsRange::Week => "week",
+            StatsRange::Month => "month",
+            StatsRange::Quarter => "quarter",
+            StatsRange::HalfYear => "half_yearly",
+            StatsRange::Year => "year",
+            StatsRange::AllTime => "all_time",
+        };
+        write!(f, "{}", range)
+    }
+}
+
+/// A track to submit, played at the Unix timestamp.
+pub struct Listen<'a> {
+    pub listened_at: i64,
+    pub track: &'a Track,
+}
+
+#[derive(Debug, Deserialize)]
+struct ValidToken {
+    valid: bool,
+    user_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenedAt {
+    listened_at: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Listens {
+    listens: Vec<ListenedAt>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Recording {
+    track_name: String,
+    artist_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Recordings {
+    recordings: Vec<Recording>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Payload<T> {
+    payload: T,
+}
+
+/// ListenBrainz answers errors with the message in `error`.
+#[derive(Debug, Deserialize)]
+struct ListenbrainzError {
+    error: String,
+}
+
+fn authorize(config: &ListenbrainzConfig, request_builder: RequestBuilder) -> RequestBuilder {
+    request_builder.header("Authorization", format!("Token {}", config.token))
+}
+
+async fn send(request_builder: RequestBuilder) -> Result<Response, AuthorizeError> {
+    let client = Client::new();
+    let request = request_builder.build()?;
+    Ok(execute(&client, request).await?)
+}
+
+async fn parse<T: DeserializeOwned>(resp: Response) -> Result<T, AuthorizeError> {
+    let status = resp.status();
+    let body = resp.text().await?;
+    let error = |message| AuthorizeError::Service("ListenBrainz", message);
+    if !status.is_success() {
+        return Err(error(
+            serde_json::from_str::<ListenbrainzError>(&body)
+                .map(|error| error.error)
+                .unwrap_or_else(|_| status.to_string()),
+        ));
+    }
+    serde_json::from_str(&body).map_err(|err| error(format!("unexpected response: {}", err)))
+}
+
+/// The name of the user the token belongs to.
+pub async fn get_user_name(config: &ListenbrainzConfig) -> Result<String, AuthorizeError> {
+    let request_builder = authorize(
+        config,
+        Client::new().get(format!("{}/validate-token", API_URL)),
+    );
+    // an invalid token is no error status, only `"valid": false`
+    let token: ValidToken = parse(send(request_builder).await?).await?;
+    match token.user_name {
+        Some(user_name) if token.valid => Ok(user_name),
+        _ => Err(AuthorizeError::Service(
+            "ListenBrainz",
+            "the token is invalid".to_string(),
+        )),
+    }
+}
+
+/// When the user's latest listen was, if there is any.
+pub async fn get_latest_listen(
+    config: &ListenbrainzConfig,
+    user_name: &str,
+) -> Result<Option<i64>, AuthorizeError> {
+    let request_builder = authorize(
+        config,
+        Client::new()
+            .get(format!("{}/user/{}/listens", API_URL, user_name))
+            .query(&[("count", "1")]),
+    );
+    let listens: Payload<Listens> = parse(send(request_builder).await?).await?;
+    Ok(listens
+        .payload
+        .listens
+        .first()
+        .map(|listen| listen.listened_at))
+}
+
+/// Imports the listens, with the Spotify URL of each track so ListenBrainz can link it.
+pub async fn submit_listens(
+    config: &ListenbrainzConfig,
+    listens: &[Listen<'_>],
+) -> Result<(), AuthorizeError> {
+    let payload: Vec<Value> = listens
+        .iter()
+        .map(|listen| {
+            let track = listen.track;
+            json!({
+                "listened_at": listen.listened_at,
+                "track_metadata": {
+                    "artist_name": track
+                        .artists
+                        .iter()
+                        .map(|artist| artist.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    "track_name": track.name,
+                    "release_name": track.album.name,
+                    "additional_info": {
+                        "duration_ms": track.duration_ms,
+                        "spotify_id": format!("https://open.spotify.com/track/{}", track.id),
+                        "submission_client": "spautofy",
+                        "submission_client_version": env!("CARGO_PKG_VERSION"),
+                        "music_service": "spotify.com",
+                    },
+                },
+            })
+        })
+        .collect();
+    let request_builder = authorize(
+        config,
+        Client::new()
+            .post(format!("{}/submit-listens", API_URL))
+            .json(&json!({ "listen_type": "import", "payload": payload })),
+    );
+    parse::<Value>(send(request_builder).await?).await?;
+    Ok(())
+}
+
+/// The user's most listened tracks of the range, the most listened first.
+pub async fn get_top_recordings(
+    config: &ListenbrainzConfig,
+    range: StatsRange,
+    limit: usize,
+) -> Result<Vec<ListenedTrack>, AuthorizeError> {
+    let user_name = get_user_name(config).await?;
+    let range = range.to_string();
+    let mut recordings = Vec::with_capacity(limit);
+    while recordings.len() < limit {
+        let count = (limit - recordings.len())
+            .min(RECORDINGS_PER_PAGE)
+            .to_string();
+        let offset = recordings.len().to_string();
+        let request_builder = authorize(
+            config,
+            Client::new()
+                .get(format!("{}/stats/user/{}/recordings", API_URL, user_name))
+                .query(&[
+                    ("range", range.as_str()),
+                    ("count", count.as_str()),
+                    ("offset", offset.as_str()),
+                ]),
+        );
+        let resp = send(request_builder).await?;
+        // the statistics are calculated once a day, new users have none yet
+        if resp.status() == StatusCode::NO_CONTENT {
+            break;
+        }
+        let page: Payload<Recordings> = parse(resp).await?;
+        if page.payload.recordings.is_empty() {
+            break;
+        }
+        recordings.extend(page.payload.recordings);
+    }
+    let tracks = recordings
+        .into_iter()
+        .map(|recording| ListenedTrack {
+            name: recording.track_name,
+            artist: recording.artist_name,
+        })
+        .collect();
+    Ok(tracks)
+}
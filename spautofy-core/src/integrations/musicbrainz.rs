@@ -0,0 +1,232 @@
+//! MusicBrainz knows more about a recording than Spotify: the year it was first released,
+//! rather than that of the remaster or compilation on Spotify, the works it records and the
+//! artists as credited. The tracks are found by their ISRCs, no account is needed.
+
+use std::time::Duration;
+
+use reqwest::{Client, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Instant};
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::storage;
+
+const API_URL: &str = "https://musicbrainz.org/ws/2";
+
+/// MusicBrainz answers every app with one request per second and turns away the ones
+/// without a user agent naming them.
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+const USER_AGENT: &str = concat!(
+    "Spautofy/",
+    env!("CARGO_PKG_VERSION"),
+    " ( https://github.com/ftschirpke/Spautofy )"
+);
+
+/// How often a request turned away for going too fast is tried again.
+const RETRIES: u32 = 3;
+
+/// The lookups are kept in the database, so the next run of a rule does not wait for them
+/// again; the recordings of the tracks change seldom.
+const CACHE_DAYS: i64 = 30;
+
+/// What MusicBrainz adds to a track.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Enrichment {
+    pub recording_id: String,
+    pub first_release_year: Option<i64>,
+    pub artist_credits: Vec<String>,
+    pub works: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArtistCredit {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Recording {
+    id: String,
+    #[serde(rename = "first-release-date", default)]
+    first_release_date: Option<String>,
+    #[serde(rename = "artist-credit", default)]
+    artist_credit: Vec<ArtistCredit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IsrcRecordings {
+    recordings: Vec<Recording>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Work {
+    title: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Url {
+    resource: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Relation {
+    work: Option<Work>,
+    url: Option<Url>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Relations {
+    #[serde(default)]
+    relations: Vec<Relation>,
+}
+
+/// The recording with an ISRC and the pages of it MusicBrainz links to, like the song on
+/// Apple Music or the video on YouTube.
+#[derive(Debug, Clone)]
+pub struct RecordingLinks {
+    pub recording_id: String,
+    pub urls: Vec<String>,
+}
+
+/// Keeps to the rate limit of MusicBrainz over all lookups.
+#[derive(Default)]
+pub struct MusicBrainz {
+    client: Client,
+    last_request: Option<Instant>,
+}
+
+impl MusicBrainz {
+    pub fn new() -> Self {
+        MusicBrainz {
+            client: Client::new(),
+            last_request: None,
+        }
+    }
+
+    /// `None` if MusicBrainz does not know the resource.
+    async fn get<T: DeserializeOwned>(
+        &mut self,
+        path: &str,
+        include: &str,
+    ) -> Result<Option<T>, AuthorizeError> {
+        for _ in 0..=RETRIES {
+            if let Some(last_request) = self.last_request {
+                sleep(REQUEST_INTERVAL.saturating_sub(last_request.elapsed())).await;
+            }
+            self.last_request = Some(Instant::now());
+            let request = self
+                .client
+                .get(format!("{}{}", API_URL, path))
+                .header("User-Agent", USER_AGENT)
+                .query(&[("inc", include), ("fmt", "json")])
+                .build()?;
+            let resp = execute(&self.client, request).await?;
+            match resp.status() {
+                StatusCode::NOT_FOUND => return Ok(None),
+                StatusCode::SERVICE_UNAVAILABLE => continue,
+                status if !status.is_success() => {
+                    return Err(AuthorizeError::Service("MusicBrainz", status.to_string()))
+                }
+                _ => return Ok(Some(resp.json().await?)),
+            }
+        }
+        Err(AuthorizeError::Service(
+            "MusicBrainz",
+            "too many requests, try again later".to_string(),
+        ))
+    }
+
+    /// Like `lookup_isrc_uncached`, if the lookup is not cached.
+    pub async fn lookup_isrc(
+        &mut self,
+        isrc: &str,
+        with_works: bool,
+    ) -> Result<Option<Enrichment>, AuthorizeError> {
+        let key = if with_works {
+            format!("musicbrainz/isrc/{}?works", isrc)
+        } else {
+            format!("musicbrainz/isrc/{}", isrc)
+        };
+        if let Some(enrichment) = storage::cached(&key, chrono::Duration::days(CACHE_DAYS)) {
+            return Ok(enrichment);
+        }
+        let enrichment = self.lookup_isrc_uncached(isrc, with_works).await?;
+        storage::cache(&key, &enrichment);
+        Ok(enrichment)
+    }
+
+    /// The recording with the ISRC; if several have it, the first released of them with
+    /// the credits of the first. The works need a second request.
+    async fn lookup_isrc_uncached(
+        &mut self,
+        isrc: &str,
+        with_works: bool,
+    ) -> Result<Option<Enrichment>, AuthorizeError> {
+        let Some(found) = self
+            .get::<IsrcRecordings>(&format!("/isrc/{}", isrc), "artist-credits")
+            .await?
+        else {
+            return Ok(None);
+        };
+        let Some(recording) = found.recordings.first() else {
+            return Ok(None);
+        };
+        let first_release_year = found
+            .recordings
+            .iter()
+            .filter_map(|recording| recording.first_release_date.as_deref()?.get(..4))
+            .filter_map(|year| year.parse().ok())
+            .min();
+        let mut enrichment = Enrichment {
+            recording_id: recording.id.clone(),
+            first_release_year,
+            artist_credits: recording
+                .artist_credit
+                .iter()
+                .map(|credit| credit.name.clone())
+                .collect(),
+            works: Vec::new(),
+        };
+        if with_works {
+            let path = format!("/recording/{}", enrichment.recording_id);
+            if let Some(relations) = self.get::<Relations>(&path, "work-rels").await? {
+                enrichment.works = relations
+                    .relations
+                    .into_iter()
+                    .filter_map(|relation| Some(relation.work?.title))
+                    .collect();
+            }
+        }
+        Ok(Some(enrichment))
+    }
+
+    /// The recording with the ISRC, found like by `lookup_isrc`, with the URLs it links to.
+    pub async fn lookup_links(
+        &mut self,
+        isrc: &str,
+    ) -> Result<Option<RecordingLinks>, AuthorizeError> {
+        let Some(enrichment) = self.lookup_isrc(isrc, false).await? else {
+            return Ok(None);
+        };
+        let recording_id = enrichment.recording_id;
+        let key = format!("musicbrainz/recording/{}?urls", recording_id);
+        let urls = match storage::cached(&key, chrono::Duration::days(CACHE_DAYS)) {
+            Some(urls) => urls,
+            None => {
+                let path = format!("/recording/{}", recording_id);
+                let urls: Vec<String> = match self.get::<Relations>(&path, "url-rels").await? {
+                    Some(relations) => relations
+                        .relations
+                        .into_iter()
+                        .filter_map(|relation| Some(relation.url?.resource))
+                        .collect(),
+                    None => Vec::new(),
+                };
+                storage::cache(&key, &urls);
+                urls
+            }
+        };
+        Ok(Some(RecordingLinks { recording_id, urls }))
+    }
+}
@@ -0,0 +1,210 @@
+//! The ids of the tracks on other services, written by `spautofy export --format mapping`
+//! for migration tools to add the tracks directly instead of searching for them.
+//!
+//! MusicBrainz knows the recording of an ISRC, and often links it to Apple Music and
+//! YouTube; these are sure. Songs it does not link to Apple Music are searched for on
+//! iTunes, taken if they resemble the track enough and marked with how much. YouTube Music
+//! has no API to search without an account, its videos come from MusicBrainz only.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::integrations::itunes::Itunes;
+use crate::integrations::musicbrainz::{MusicBrainz, RecordingLinks};
+use crate::models::track::Track;
+use crate::{status, status_error, status_verbose};
+
+/// Below this, a song found by searching on iTunes is more likely another one.
+const MIN_SEARCH_CONFIDENCE: f64 = 0.8;
+
+/// A playlist, or the liked songs, with the ids of its tracks on the other services.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ServiceMapping {
+    pub name: String,
+    /// `None` for the liked songs.
+    pub spotify_id: Option<String>,
+    pub tracks: Vec<TrackMapping>,
+}
+
+/// A track and where it is on the other services, `None` where it was not found.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct TrackMapping {
+    pub title: String,
+    pub artists: Vec<String>,
+    pub album: String,
+    pub duration_ms: u64,
+    pub isrc: Option<String>,
+    pub spotify_id: String,
+    pub spotify_uri: String,
+    pub musicbrainz: Option<ServiceTrack>,
+    pub apple_music: Option<ServiceTrack>,
+    pub youtube_music: Option<ServiceTrack>,
+}
+
+/// The track on another service.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ServiceTrack {
+    pub id: String,
+    pub url: String,
+    pub matched_by: MatchedBy,
+    /// From 0 to 1, how sure it is the same recording; 1 for a match by ISRC.
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedBy {
+    /// The recording of the ISRC on MusicBrainz, or a link of it.
+    Isrc,
+    /// A search for the title and artists, among the songs about as long as the track.
+    Search,
+}
+
+impl ServiceTrack {
+    fn by_isrc(id: String, url: String) -> Self {
+        ServiceTrack {
+            id,
+            url,
+            matched_by: MatchedBy::Isrc,
+            confidence: 1.0,
+        }
+    }
+}
+
+/// The value of a parameter of the query of a URL.
+fn query_param<'a>(url: &'a str, name: &str) -> Option<&'a str> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|param| param.strip_prefix(name)?.strip_prefix('='))
+}
+
+/// The id of the song of a link like `https://music.apple.com/us/album/x/123?i=456` or
+/// `https://music.apple.com/us/song/x/456`.
+fn apple_music_id(url: &str) -> Option<&str> {
+    if !url.contains("music.apple.com") && !url.contains("itunes.apple.com") {
+        return None;
+    }
+    if let Some(id) = query_param(url, "i") {
+        return Some(id);
+    }
+    let path = url.split('?').next().unwrap_or(url);
+    if !path.contains("/song/") {
+        return None;
+    }
+    let id = path.trim_end_matches('/').rsplit('/').next()?;
+    let id = id.trim_start_matches("id");
+    id.chars().all(|c| c.is_ascii_digit()).then_some(id)
+}
+
+/// The id of the video of a link like `https://www.youtube.com/watch?v=abc` or
+/// `https://youtu.be/abc`; YouTube Music plays the same videos.
+fn youtube_id(url: &str) -> Option<&str> {
+    if url.contains("youtube.com/watch") {
+        return query_param(url, "v");
+    }
+    let (_, id) = url.split_once("youtu.be/")?;
+    id.split(['?', '/']).next().filter(|id| !id.is_empty())
+}
+
+#[derive(Default)]
+struct TrackLinks {
+    musicbrainz: Option<ServiceTrack>,
+    apple_music: Option<ServiceTrack>,
+    youtube_music: Option<ServiceTrack>,
+}
+
+fn from_links(links: &RecordingLinks) -> TrackLinks {
+    let apple_music = links.urls.iter().find_map(|url| {
+        let id = apple_music_id(url)?;
+        Some(ServiceTrack::by_isrc(id.to_string(), url.clone()))
+    });
+    let youtube_music = links.urls.iter().find_map(|url| {
+        let id = youtube_id(url)?;
+        let url = format!("https://music.youtube.com/watch?v={}", id);
+        Some(ServiceTrack::by_isrc(id.to_string(), url))
+    });
+    TrackLinks {
+        musicbrainz: Some(ServiceTrack::by_isrc(
+            links.recording_id.clone(),
+            format!("https://musicbrainz.org/recording/{}", links.recording_id),
+        )),
+        apple_music,
+        youtube_music,
+    }
+}
+
+/// Looks the tracks up on MusicBrainz, about one per second, and the ones it does not link
+/// to Apple Music on iTunes, about one in three seconds; both remember their answers for a
+/// month. A service that fails is not asked again, the rest of the tracks go without it.
+pub async fn map_tracks(tracks: &[Track], country: Option<&str>) -> Vec<TrackMapping> {
+    status!(
+        "Looking up {} tracks on MusicBrainz and Apple Music, a few seconds each",
+        tracks.len()
+    );
+    let mut musicbrainz = Some(MusicBrainz::new());
+    let mut itunes = Some(Itunes::new(country));
+    let mut mappings = Vec::with_capacity(tracks.len());
+    for track in tracks {
+        let mut links = TrackLinks::default();
+        if let (Some(client), Some(isrc)) = (&mut musicbrainz, &track.external_ids.isrc) {
+            match client.lookup_links(isrc).await {
+                Ok(found) => links = found.as_ref().map(from_links).unwrap_or_default(),
+                Err(err) => {
+                    status_error!("Stopped looking up tracks on {}: {}", "MusicBrainz", err);
+                    musicbrainz = None;
+                }
+            }
+        }
+        if links.apple_music.is_none() {
+            if let Some(client) = &mut itunes {
+                match client.find(track, MIN_SEARCH_CONFIDENCE).await {
+                    Ok(song) => {
+                        links.apple_music = song.map(|song| ServiceTrack {
+                            id: song.id,
+                            url: song.url,
+                            matched_by: MatchedBy::Search,
+                            confidence: song.confidence,
+                        })
+                    }
+                    Err(err) => {
+                        status_error!("Stopped looking up tracks on {}: {}", "iTunes", err);
+                        itunes = None;
+                    }
+                }
+            }
+        }
+        mappings.push(TrackMapping {
+            title: track.name.clone(),
+            artists: track
+                .artists
+                .iter()
+                .map(|artist| artist.name.clone())
+                .collect(),
+            album: track.album.name.clone(),
+            duration_ms: track.duration_ms,
+            isrc: track.external_ids.isrc.clone(),
+            spotify_id: track.id.clone(),
+            spotify_uri: track.uri.clone(),
+            musicbrainz: links.musicbrainz,
+            apple_music: links.apple_music,
+            youtube_music: links.youtube_music,
+        });
+        status_verbose!("{} of {} tracks looked up", mappings.len(), tracks.len());
+    }
+    let apple_music = mappings
+        .iter()
+        .filter(|mapping| mapping.apple_music.is_some())
+        .count();
+    let youtube_music = mappings
+        .iter()
+        .filter(|mapping| mapping.youtube_music.is_some())
+        .count();
+    status!(
+        "Found {} of {} tracks on Apple Music and {} on YouTube Music.",
+        apple_music,
+        mappings.len(),
+        youtube_music
+    );
+    mappings
+}
@@ -0,0 +1,64 @@
+//! The Spotify automation of Spautofy: logging in, the actions creating and updating the
+//! playlists, the API models and the local storage, for the `spautofy` binary and other
+//! programs alike.
+//!
+//! Programs embedding Spautofy go through [`SpotifyClient`] and the types exported at the
+//! root of the crate, which keep their shape between versions. The modules are shared with
+//! the binary and may change with it. A client prints nothing and keeps no history; its
+//! [`Settings`] decide that instead of the settings of the process:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use spautofy_core::{AuthConfig, Settings, SpotifyClient, TimeRange, TopTrackPlaylistParams};
+//!
+//! # async fn bot(client_id: String, client_secret: String, refresh_token: &str) -> Result<(), spautofy_core::AuthorizeError> {
+//! let auth = AuthConfig::new([127, 0, 0, 1].into(), 3000, client_id, client_secret, None);
+//! let settings = Settings {
+//!     on_status: Some(Arc::new(|level, message| eprintln!("{:?}: {}", level, message))),
+//!     ..Settings::default()
+//! };
+//! let mut client = SpotifyClient::from_refresh_token(auth, refresh_token)
+//!     .await?
+//!     .with_settings(settings);
+//! let params = TopTrackPlaylistParams::new(TimeRange::ShortTerm);
+//! let playlist = client.create_top_tracks_playlist(&params).await?;
+//! println!("{} has {} tracks: {}", playlist.name, playlist.track_count, playlist.url);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod actions;
+pub mod authorize;
+pub mod client;
+pub mod dates;
+pub mod endpoints;
+pub mod history;
+pub mod history_import;
+pub mod i18n;
+pub mod integrations;
+pub mod metrics;
+pub mod models;
+pub mod player;
+pub mod progress;
+pub mod schemas;
+pub mod settings;
+pub mod song_list;
+pub mod status_log;
+pub mod storage;
+pub mod user_info;
+
+pub use actions::action_report::CreatedPlaylist;
+pub use actions::export::PlaylistExport;
+pub use actions::playlist_actions::PlaylistVisibility;
+pub use actions::playlist_builder::PlaylistBuilder;
+pub use actions::rules::{Rule, RuleConfig};
+pub use actions::top_track_playlist::{TimeRange, TopTrackPlaylistParams};
+pub use actions::Task;
+pub use authorize::{Access, AuthConfig, AuthorizeError};
+pub use client::SpotifyClient;
+pub use dates::DateSettings;
+pub use models::track::Track;
+pub use settings::Settings;
+pub use status_log::Level;
+pub use user_info::{User, UserAccess};
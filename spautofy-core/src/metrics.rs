@@ -0,0 +1,181 @@
+//! Counters of all runs for Prometheus, `--metrics-path <file>`: written after every
+//! command from the ones kept in the database, in the text format the textfile collector of
+//! the node exporter reads, so an alert can tell when a scheduled pipeline stops succeeding.
+//! `spautofy dashboard` and `spautofy api` serve them as `/metrics` too.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::Utc;
+
+use crate::actions::action_report::{ActionReport, CreatedPlaylist};
+use crate::status_error;
+use crate::storage::{self, StorageError};
+
+const RUNS: &str = "spautofy_runs_total";
+const API_REQUESTS: &str = "spautofy_api_requests_total";
+const RATE_LIMITED: &str = "spautofy_rate_limited_total";
+const TRACKS_ADDED: &str = "spautofy_tracks_added_total";
+const LAST_SUCCESS: &str = "spautofy_last_success_timestamp_seconds";
+
+/// The name, type and help of every metric, in the order they are written.
+const METRICS: [(&str, &str, &str); 5] = [
+    (
+        RUNS,
+        "counter",
+        "Runs of each pipeline, a success if all of its actions succeeded.",
+    ),
+    (API_REQUESTS, "counter", "Requests sent, by host."),
+    (
+        RATE_LIMITED,
+        "counter",
+        "Requests answered with 429 Too Many Requests, by host.",
+    ),
+    (
+        TRACKS_ADDED,
+        "counter",
+        "Tracks put into the playlists of each pipeline.",
+    ),
+    (
+        LAST_SUCCESS,
+        "gauge",
+        "When each pipeline last succeeded, in seconds since the epoch.",
+    ),
+];
+
+/// The requests of this command by host, the ones rate limited second; kept in the database
+/// once the command is done.
+static REQUESTS: Mutex<BTreeMap<String, (u64, u64)>> = Mutex::new(BTreeMap::new());
+
+/// Counts a request to the host; called for every request, whatever service it goes to.
+pub fn count_request(host: &str, rate_limited: bool) {
+    let mut requests = REQUESTS.lock().unwrap();
+    let (sent, limited) = requests.entry(host.to_string()).or_default();
+    *sent += 1;
+    if rate_limited {
+        *limited += 1;
+    }
+}
+
+/// The value of a label, with `\`, `"` and line breaks escaped.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn label(name: &str, value: &str) -> String {
+    format!("{}=\"{}\"", name, escape(value))
+}
+
+/// All of them for a created playlist or one updated without a diff, like the playlists of
+/// the rules.
+fn tracks_added(playlist: &CreatedPlaylist) -> usize {
+    match &playlist.changes {
+        Some(changes) => changes.added.len(),
+        None => playlist.track_count,
+    }
+}
+
+/// Counts a run; `pipelines` names the pipeline of every report, in the same order.
+pub fn record_run(pipelines: &[String], reports: &[ActionReport]) {
+    let mut by_pipeline: BTreeMap<&str, (bool, usize)> = BTreeMap::new();
+    for (pipeline, report) in pipelines.iter().zip(reports) {
+        let (succeeded, added) = by_pipeline.entry(pipeline).or_insert((true, 0));
+        match &report.result {
+            Ok(playlist) => *added += tracks_added(playlist),
+            Err(_) => *succeeded = false,
+        }
+    }
+    let now = Utc::now().timestamp() as f64;
+    storage::record(|storage| {
+        for (pipeline, (succeeded, added)) in by_pipeline {
+            let result = if succeeded { "success" } else { "failure" };
+            let labels = format!(
+                "{},{}",
+                label("pipeline", pipeline),
+                label("result", result)
+            );
+            storage.add_to_metric(RUNS, &labels, 1.0)?;
+            storage.add_to_metric(TRACKS_ADDED, &label("pipeline", pipeline), added as f64)?;
+            if succeeded {
+                storage.set_metric(LAST_SUCCESS, &label("pipeline", pipeline), now)?;
+            }
+        }
+        Ok(())
+    });
+}
+
+/// Keeps the requests counted so far in the database.
+fn keep_requests() {
+    let requests = std::mem::take(&mut *REQUESTS.lock().unwrap());
+    storage::record(|storage| {
+        for (host, (sent, limited)) in &requests {
+            let labels = label("host", host);
+            storage.add_to_metric(API_REQUESTS, &labels, *sent as f64)?;
+            storage.add_to_metric(RATE_LIMITED, &labels, *limited as f64)?;
+        }
+        Ok(())
+    });
+}
+
+/// All metrics in the text format of Prometheus, with the requests counted so far, for the
+/// `/metrics` of a server; without a database there are none.
+pub fn text(profile: Option<&str>) -> Result<String, StorageError> {
+    keep_requests();
+    let metrics = storage::with_current_storage(|storage| storage.metrics())?;
+    Ok(render(&metrics.unwrap_or_default(), profile))
+}
+
+/// Keeps the requests of the command in the database, then writes all metrics to `path` if
+/// given; a failure is only reported, like one of the history.
+pub fn save(path: Option<&str>, profile: Option<&str>) {
+    keep_requests();
+    let Some(path) = path else {
+        return;
+    };
+    let result = storage::with_current_storage(|storage| storage.metrics())
+        .map_err(|err| err.to_string())
+        .and_then(|metrics| {
+            let Some(metrics) = metrics else {
+                return Ok(());
+            };
+            write(Path::new(path), &render(&metrics, profile)).map_err(|err| err.to_string())
+        });
+    if let Err(err) = result {
+        status_error!("Could not write the metrics to {}: {}", path, err);
+    }
+}
+
+/// The metrics kept as `(name, labels, value)` in the text format of Prometheus; the
+/// profile is added as a label, so the files of several profiles can be collected together.
+fn render(metrics: &[(String, String, f64)], profile: Option<&str>) -> String {
+    let profile = profile.map(|profile| label("profile", profile));
+    let mut text = String::new();
+    for (name, kind, help) in METRICS {
+        text.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} {}\n",
+            name, help, name, kind
+        ));
+        for (_, labels, value) in metrics.iter().filter(|metric| metric.0 == name) {
+            let labels = match &profile {
+                Some(profile) => format!("{},{}", profile, labels),
+                None => labels.clone(),
+            };
+            text.push_str(&format!("{}{{{}}} {}\n", name, labels, value));
+        }
+    }
+    text
+}
+
+/// Written next to the file and renamed over it, so the collector never reads half of it.
+fn write(path: &Path, text: &str) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, text)?;
+    fs::rename(&temporary, path)
+}
@@ -1,4 +1,6 @@
 pub mod album;
 pub mod artist;
+pub mod image;
+pub mod paging;
 pub mod playlist;
 pub mod track;
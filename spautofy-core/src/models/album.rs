@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::artist::Artist;
+use crate::models::image::Image;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct Album {
+    pub id: String,
+    pub name: String,
+    pub album_type: String,
+    pub artists: Vec<Artist>,
+    pub total_tracks: i32,
+    pub release_date: String,
+    /// Cover images, widest first.
+    #[serde(default)]
+    pub images: Vec<Image>,
+}
+
+/// An album saved in the library, with when it was saved.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SavedAlbum {
+    pub added_at: String,
+    pub album: Album,
+}
@@ -1,17 +1,18 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SimplifiedArtist {
-    id: String,
-    name: String,
+    pub id: String,
+    pub name: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct Artist {
-    id: String,
-    name: String,
-    genres: Option<Vec<String>>,
-    popularity: Option<i32>,
+    pub id: String,
+    pub name: String,
+    pub genres: Option<Vec<String>>,
+    pub popularity: Option<i32>,
 }
 
 impl From<Artist> for SimplifiedArtist {
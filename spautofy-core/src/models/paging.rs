@@ -0,0 +1,68 @@
+use futures::stream::{self, Stream, TryStreamExt};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute_api;
+use crate::progress::{self, Progress};
+use crate::{status_verbose, UserAccess};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Page<T> {
+    pub href: String,
+    pub limit: i32,
+    pub offset: i32,
+    pub total: i32,
+    pub next: Option<String>,
+    pub previous: Option<String>,
+    pub items: Vec<T>,
+}
+
+/// Reports the number of fetched items after a page, for `pages` and the streams of
+/// pages with cursors.
+pub fn report_page(done: usize, total: usize, on_progress: &mut impl FnMut(Progress)) {
+    status_verbose!("Fetched {} of {} items", done, total);
+    progress::emit("page_fetched", json!({ "done": done, "total": total }));
+    on_progress(Progress { done, total });
+}
+
+/// Follows the `next` links starting at `url` and yields the items of every page. The next
+/// page is only fetched once the stream is polled again, so no more than one page is held
+/// at a time unless the caller keeps them.
+pub fn pages<'a, T: DeserializeOwned + 'a>(
+    user_access: &'a UserAccess,
+    url: String,
+    on_progress: impl FnMut(Progress) + 'a,
+) -> impl Stream<Item = Result<Vec<T>, AuthorizeError>> + 'a {
+    let client = Client::new();
+    stream::try_unfold(
+        (Some(url), 0, on_progress),
+        move |(next, done, mut on_progress)| {
+            let client = client.clone();
+            async move {
+                let Some(url) = next else {
+                    return Ok(None);
+                };
+                let request_builder = client.get(url);
+                let request_builder = user_access.authorize(request_builder);
+                let request = request_builder.build()?;
+                let resp = execute_api(&client, request).await?;
+                let page = resp.json::<Page<T>>().await?;
+                let done = done + page.items.len();
+                report_page(done, page.total.max(0) as usize, &mut on_progress);
+                Ok(Some((page.items, (page.next, done, on_progress))))
+            }
+        },
+    )
+}
+
+/// Collects the items of every page of `pages`; for the lists that are needed whole.
+pub async fn fetch_all_pages<T: DeserializeOwned>(
+    user_access: &UserAccess,
+    url: String,
+    on_progress: impl FnMut(Progress),
+) -> Result<Vec<T>, AuthorizeError> {
+    pages(user_access, url, on_progress).try_concat().await
+}
@@ -0,0 +1,81 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::image::Image;
+use crate::models::track::Track;
+use crate::user_info::PublicUser;
+
+/// Description of the playlists Spautofy creates, used to recognize them later.
+pub const SPAUTOFY_DESCRIPTION: &str = "Created by Spautofy.";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub collaborative: bool,
+    pub href: String,
+    pub public: bool,
+    pub tracks: PlaylistItems,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlaylistItems {
+    pub href: String,
+    pub total: i32,
+    pub offset: i32,
+    pub next: Option<String>,
+    pub previous: Option<String>,
+    pub items: Vec<PlaylistItem>,
+}
+
+/// An entry of a playlist; `track` is `null` if the track is no longer available.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlaylistItem {
+    pub added_at: Option<String>,
+    pub track: Option<Track>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SimplifiedPlaylist {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub collaborative: bool,
+    pub href: String,
+    pub public: Option<bool>,
+    pub owner: PublicUser,
+    pub tracks: PlaylistTracksRef,
+    /// Cover images, widest first; Spotify sends `null` for playlists without tracks.
+    #[serde(default)]
+    pub images: Option<Vec<Image>>,
+}
+
+impl SimplifiedPlaylist {
+    /// Whether the user owns the playlist and it was created by Spautofy.
+    pub fn is_spautofy_owned(&self, user_id: &str) -> bool {
+        let description = self.description.as_deref().unwrap_or_default();
+        self.owner.id == user_id
+            && (description.contains(SPAUTOFY_DESCRIPTION) || self.name.starts_with("Spautofy"))
+    }
+
+    pub fn owner_name(&self) -> &str {
+        self.owner.display_name.as_deref().unwrap_or(&self.owner.id)
+    }
+
+    /// One of "collaborative", "public", "private" or "unknown", untranslated.
+    pub fn visibility(&self) -> &'static str {
+        match (self.public, self.collaborative) {
+            (_, true) => "collaborative",
+            (Some(true), _) => "public",
+            (Some(false), _) => "private",
+            (None, _) => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct PlaylistTracksRef {
+    pub href: String,
+    pub total: i32,
+}
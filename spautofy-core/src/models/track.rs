@@ -0,0 +1,65 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::album::Album;
+use crate::models::artist::Artist;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct Track {
+    pub id: String,
+    pub uri: String,
+    pub name: String,
+    pub album: Album,
+    pub artists: Vec<Artist>,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub external_ids: ExternalIds,
+}
+
+impl AsRef<Track> for Track {
+    fn as_ref(&self) -> &Track {
+        self
+    }
+}
+
+impl Track {
+    pub fn cover_url(&self) -> Option<String> {
+        self.album.images.first().map(|image| image.url.clone())
+    }
+
+    /// The recording of the track, which the same song on several albums shares; the URI
+    /// if Spotify does not know it.
+    pub fn recording_id(&self) -> &str {
+        self.external_ids.isrc.as_deref().unwrap_or(&self.uri)
+    }
+
+    /// The names of the artists, separated by commas.
+    pub fn artist_names(&self) -> String {
+        let names: Vec<&str> = self
+            .artists
+            .iter()
+            .map(|artist| artist.name.as_str())
+            .collect();
+        names.join(", ")
+    }
+}
+
+/// Only the ISRC, which identifies the recording beyond Spotify.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema)]
+pub struct ExternalIds {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isrc: Option<String>,
+}
+
+/// A liked song, with when it was liked.
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct SavedTrack {
+    pub added_at: String,
+    pub track: Track,
+}
+
+/// Formats the duration as `m:ss`.
+pub fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
@@ -0,0 +1,60 @@
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::api_endpoint;
+use crate::authorize::{Access, AuthorizeError};
+use crate::endpoints::execute_api;
+use crate::models::track::Track;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CurrentlyPlaying {
+    pub progress_ms: Option<u64>,
+    pub is_playing: bool,
+    /// `None` while an ad or an episode is playing.
+    pub item: Option<Track>,
+}
+
+/// Returns `None` if nothing is playing right now.
+pub async fn get_currently_playing(
+    access: &Access,
+) -> Result<Option<CurrentlyPlaying>, AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!("/me/player/currently-playing"));
+    let request_builder = access.authorize(request_builder);
+    let request = request_builder.build()?;
+    let resp = execute_api(&client, request).await?;
+    if resp.status() == StatusCode::NO_CONTENT {
+        return Ok(None);
+    }
+    let resp = resp.json::<CurrentlyPlaying>().await?;
+    Ok(Some(resp))
+}
+
+/// Spotify keeps only this many of the recently played tracks.
+pub const MAX_RECENTLY_PLAYED: u32 = 50;
+
+/// A recently played track, with when it was played.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PlayedTrack {
+    pub played_at: String,
+    pub track: Track,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecentlyPlayed {
+    items: Vec<PlayedTrack>,
+}
+
+/// The last tracks played, the most recent first; needs the scope
+/// `user-read-recently-played`.
+pub async fn get_recently_played(access: &Access) -> Result<Vec<PlayedTrack>, AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!(
+        "/me/player/recently-played?limit={}",
+        MAX_RECENTLY_PLAYED
+    ));
+    let request_builder = access.authorize(request_builder);
+    let request = request_builder.build()?;
+    let resp = execute_api(&client, request).await?;
+    Ok(resp.json::<RecentlyPlayed>().await?.items)
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::{json, Value};
+
+use crate::settings;
+
+static NDJSON: AtomicBool = AtomicBool::new(false);
+
+/// Progress of a long-running fetch, reported after every page.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Progress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn ratio(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            (self.done as f64 / self.total as f64).min(1.0)
+        }
+    }
+}
+
+/// How the commands report their progress, set with `--progress`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressFormat {
+    #[default]
+    Text,
+    Ndjson,
+}
+
+impl ProgressFormat {
+    pub const ALL: [ProgressFormat; 2] = [ProgressFormat::Text, ProgressFormat::Ndjson];
+
+    /// The name in `--progress`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ProgressFormat::Text => "text",
+            ProgressFormat::Ndjson => "ndjson",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            ProgressFormat::Text => "Only the status messages",
+            ProgressFormat::Ndjson => {
+                "One JSON event per line on stdout, the status messages go to stderr"
+            }
+        }
+    }
+}
+
+pub fn set_progress_format(format: ProgressFormat) {
+    NDJSON.store(format == ProgressFormat::Ndjson, Ordering::SeqCst);
+}
+
+/// Never within a client, whose program reads the results instead.
+pub fn is_ndjson() -> bool {
+    settings::with(|_| ()).is_none() && NDJSON.load(Ordering::SeqCst)
+}
+
+/// Prints `{"event": <event>, ...fields}` as one line with `--progress=ndjson`.
+pub fn emit(event: &str, fields: Value) {
+    if !is_ndjson() {
+        return;
+    }
+    let mut line = json!({ "event": event });
+    if let (Some(line), Value::Object(fields)) = (line.as_object_mut(), fields) {
+        line.extend(fields);
+    }
+    println!("{}", line);
+}
@@ -0,0 +1,133 @@
+//! JSON Schemas of the files and outputs other tools may read, printed with `spautofy
+//! --schema <kind>`; made from the same types that write and read the files, so they
+//! cannot drift apart. The backups are checked against them when read back by `restore`
+//! and `diff`, naming the place of a mistake instead of failing somewhere in serde.
+//!
+//! Every schema has `SCHEMA_VERSION` in its `$id`. It only goes up when a file changes
+//! in a way older readers would misunderstand, like a field renamed or a type changed;
+//! new fields do not count, unknown fields are allowed.
+
+use schemars::{schema_for, Schema};
+use serde_json::Value;
+
+use crate::actions::diff::{BackupDiff, SnapshotDiff};
+use crate::actions::export::PlaylistExport;
+use crate::integrations::service_mapping::ServiceMapping;
+use crate::models::album::SavedAlbum;
+use crate::models::artist::Artist;
+use crate::models::track::SavedTrack;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Listing all errors of a broken backup would drown the first one.
+const MAX_ERRORS: usize = 5;
+
+/// The files and outputs with a schema, see `SchemaKind::description`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaKind {
+    Playlist,
+    Backup,
+    LikedSongs,
+    FollowedArtists,
+    SavedAlbums,
+    Mapping,
+    PlaylistDiff,
+    BackupDiff,
+}
+
+impl SchemaKind {
+    pub const ALL: [SchemaKind; 8] = [
+        SchemaKind::Playlist,
+        SchemaKind::Backup,
+        SchemaKind::LikedSongs,
+        SchemaKind::FollowedArtists,
+        SchemaKind::SavedAlbums,
+        SchemaKind::Mapping,
+        SchemaKind::PlaylistDiff,
+        SchemaKind::BackupDiff,
+    ];
+
+    /// The name in `--schema` and in the `$id` of the schema.
+    pub fn name(self) -> &'static str {
+        match self {
+            SchemaKind::Playlist => "playlist",
+            SchemaKind::Backup => "backup",
+            SchemaKind::LikedSongs => "liked-songs",
+            SchemaKind::FollowedArtists => "followed-artists",
+            SchemaKind::SavedAlbums => "saved-albums",
+            SchemaKind::Mapping => "mapping",
+            SchemaKind::PlaylistDiff => "playlist-diff",
+            SchemaKind::BackupDiff => "backup-diff",
+        }
+    }
+
+    /// Which file or output the schema describes, for the help of `--schema`.
+    pub fn description(self) -> &'static str {
+        match self {
+            SchemaKind::Playlist => "A playlist written by `export`",
+            SchemaKind::Backup => {
+                "The playlists written by `backup`, or `playlists.json` of `backup --all`"
+            }
+            SchemaKind::LikedSongs => {
+                "`liked-songs.json` of `backup --all`, or `export --liked-songs`"
+            }
+            SchemaKind::FollowedArtists => "`followed-artists.json` of `backup --all`",
+            SchemaKind::SavedAlbums => "`saved-albums.json` of `backup --all`",
+            SchemaKind::Mapping => {
+                "A playlist or the liked songs written by `export --format mapping`"
+            }
+            SchemaKind::PlaylistDiff => {
+                "`diff --json` of two playlists, or of a playlist in two backups"
+            }
+            SchemaKind::BackupDiff => "`diff --json` of two backups",
+        }
+    }
+
+    fn generate(self) -> Schema {
+        match self {
+            SchemaKind::Playlist => schema_for!(PlaylistExport),
+            SchemaKind::Backup => schema_for!(Vec<PlaylistExport>),
+            SchemaKind::LikedSongs => schema_for!(Vec<SavedTrack>),
+            SchemaKind::FollowedArtists => schema_for!(Vec<Artist>),
+            SchemaKind::SavedAlbums => schema_for!(Vec<SavedAlbum>),
+            SchemaKind::Mapping => schema_for!(ServiceMapping),
+            SchemaKind::PlaylistDiff => schema_for!(SnapshotDiff),
+            SchemaKind::BackupDiff => schema_for!(BackupDiff),
+        }
+    }
+}
+
+/// The schema with its versioned `$id`, e.g. `urn:spautofy:schema:backup:1`.
+pub fn schema(kind: SchemaKind) -> Value {
+    let mut schema = kind.generate();
+    schema.insert(
+        "title".to_string(),
+        Value::String(format!("Spautofy {}", kind.name())),
+    );
+    schema.insert(
+        "$id".to_string(),
+        Value::String(format!(
+            "urn:spautofy:schema:{}:{}",
+            kind.name(),
+            SCHEMA_VERSION
+        )),
+    );
+    schema.to_value()
+}
+
+/// Checks the file against its schema; the error names the places of the first mistakes,
+/// like `/3/tracks/0/id: null is not of type "string"`.
+pub fn validate(kind: SchemaKind, file: &Value) -> Result<(), String> {
+    let validator =
+        jsonschema::validator_for(&schema(kind)).expect("The generated schemas are valid");
+    let errors: Vec<String> = validator
+        .iter_errors(file)
+        .take(MAX_ERRORS)
+        .map(|err| format!("{}: {}", err.instance_path(), err))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
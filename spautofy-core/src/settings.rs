@@ -0,0 +1,55 @@
+//! What the actions take from their surroundings besides the login: whether they only tell
+//! what they would write, where the history is kept, how the playlists are dated and where
+//! the status messages go.
+//!
+//! The `spautofy` binary sets them for the whole process with `actions::set_dry_run`,
+//! `storage::set_path`, `dates::set_date_settings` and the output of `status_log`. A
+//! `SpotifyClient` brings its own, which hold only while its methods run, so programs can
+//! keep several clients with different settings.
+
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::dates::DateSettings;
+use crate::status_log::Level;
+
+/// Takes the status messages of the actions, e.g. to log them.
+pub type StatusHandler = Arc<dyn Fn(Level, &str) + Send + Sync>;
+
+tokio::task_local! {
+    static SETTINGS: Settings;
+}
+
+/// The settings of a `SpotifyClient`; by default it writes, keeps no history, dates the
+/// playlists in the local time and drops the status messages.
+#[derive(Clone, Default)]
+pub struct Settings {
+    pub dry_run: bool,
+    /// The history database, see `storage`.
+    pub storage_path: Option<PathBuf>,
+    pub date_settings: Option<DateSettings>,
+    pub on_status: Option<StatusHandler>,
+}
+
+impl Debug for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Settings")
+            .field("dry_run", &self.dry_run)
+            .field("storage_path", &self.storage_path)
+            .field("date_settings", &self.date_settings)
+            .field("on_status", &self.on_status.is_some())
+            .finish()
+    }
+}
+
+/// Runs the future with the settings in place of the ones of the process.
+pub async fn scope<F: Future>(settings: Settings, future: F) -> F::Output {
+    SETTINGS.scope(settings, future).await
+}
+
+/// Reads the settings of the client running the current task, `None` outside of one.
+pub fn with<T>(read: impl FnOnce(&Settings) -> T) -> Option<T> {
+    SETTINGS.try_with(read).ok()
+}
@@ -0,0 +1,607 @@
+//! Reads a plain list of songs, `spautofy import-songs`, one per line as copied from a
+//! forum post, a tracklist or a notes app: numbering, bullets, durations and noise like
+//! `(Official Video)` are left out, and the artist may come before or after the title, or
+//! not at all. Every track found for a line gets a confidence, how well its title and
+//! artists resemble the line, so only the unsure ones need a look.
+
+use crate::models::track::Track;
+
+/// Left out of the lines, mostly from the titles of videos.
+const NOISE: [&str; 12] = [
+    "official",
+    "video",
+    "audio",
+    "lyrics",
+    "lyric",
+    "visualizer",
+    "remaster",
+    "remastered",
+    "hd",
+    "hq",
+    "4k",
+    "explicit",
+];
+
+/// What separates artist and title, with whether the artist comes first.
+const SEPARATORS: [(&str, bool); 6] = [
+    (" - ", true),
+    (" – ", true),
+    (" — ", true),
+    ("\t", true),
+    (": ", true),
+    (" by ", false),
+];
+
+/// A song named by a line of the list.
+#[derive(Debug, Clone)]
+pub struct Song {
+    /// The number of the line, from 1.
+    pub line: usize,
+    /// The line as written.
+    pub text: String,
+    /// The line without numbering, durations and noise, searched for.
+    pub query: String,
+    /// The title and artist, if the line separates them; both ways round for a dash, the
+    /// lists do not agree on the order.
+    readings: Vec<(String, String)>,
+    /// Whether the whole line may be the title all the same, like `Stand by Me`.
+    may_be_title: bool,
+    /// The ISRC from the tags of a file, which finds the recording for sure.
+    pub isrc: Option<String>,
+}
+
+impl Song {
+    /// A song from the tags of a file of `spautofy scan-library`, numbered like a line.
+    pub fn tagged(
+        line: usize,
+        text: String,
+        title: &str,
+        artist: Option<&str>,
+        isrc: Option<String>,
+    ) -> Self {
+        let (query, readings) = match artist {
+            Some(artist) => (
+                format!("{} {}", title, artist),
+                vec![(title.to_string(), artist.to_string())],
+            ),
+            None => (title.to_string(), Vec::new()),
+        };
+        Song {
+            line,
+            text,
+            query,
+            readings,
+            may_be_title: artist.is_none(),
+            isrc,
+        }
+    }
+}
+
+/// A track found for a song, with the confidence from 0 to 1 that it is the one meant.
+#[derive(Debug)]
+pub struct Candidate {
+    pub track: Track,
+    pub confidence: f64,
+}
+
+/// The tracks found for a song, the most likely first, and which one to add; `None` if
+/// none is.
+#[derive(Debug)]
+pub struct SongMatch {
+    pub song: Song,
+    pub candidates: Vec<Candidate>,
+    pub choice: Option<usize>,
+}
+
+impl SongMatch {
+    /// Ranks the tracks found and chooses the most likely unless it is below
+    /// `min_confidence`.
+    pub fn new(song: Song, tracks: Vec<Track>, min_confidence: f64) -> Self {
+        let mut candidates: Vec<Candidate> = tracks
+            .into_iter()
+            .map(|track| Candidate {
+                confidence: confidence(&song, &track),
+                track,
+            })
+            .collect();
+        // stable, so equally likely tracks stay in the order of Spotify's relevance
+        candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        let choice = candidates
+            .first()
+            .is_some_and(|best| best.confidence >= min_confidence)
+            .then_some(0);
+        SongMatch {
+            song,
+            candidates,
+            choice,
+        }
+    }
+
+    /// The track found by the ISRC of the song, which is the one meant.
+    pub fn by_isrc(song: Song, track: Track) -> Self {
+        SongMatch {
+            song,
+            candidates: vec![Candidate {
+                track,
+                confidence: 1.0,
+            }],
+            choice: Some(0),
+        }
+    }
+
+    pub fn best_confidence(&self) -> f64 {
+        self.candidates
+            .first()
+            .map_or(0.0, |candidate| candidate.confidence)
+    }
+
+    pub fn chosen(&self) -> Option<&Candidate> {
+        self.choice.and_then(|choice| self.candidates.get(choice))
+    }
+}
+
+/// Lowercase words of letters and digits, for comparing names however they are written.
+fn normalize(text: &str) -> String {
+    let text = text.to_lowercase().replace('&', " and ");
+    let mut normalized = String::new();
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() {
+            continue;
+        }
+        if !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalized.push_str(word);
+    }
+    normalized
+}
+
+fn bigrams(text: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// The Sørensen–Dice coefficient of the letter pairs of the normalized texts, from 0 to 1;
+/// forgiving of typos and of words left out, unlike an exact comparison.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize(a), normalize(b));
+    if a == b {
+        return 1.0;
+    }
+    let (a, mut b) = (bigrams(&a), bigrams(&b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let total = a.len() + b.len();
+    let mut shared = 0;
+    for pair in a {
+        if let Some(position) = b.iter().position(|other| *other == pair) {
+            b.swap_remove(position);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total as f64
+}
+
+/// Whether all characters of `query` appear in `text` in the same order, ignoring case.
+pub fn fuzzy_matches(query: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|q| text.any(|t| t == q))
+}
+
+/// Whether a part in brackets only says what kind of upload it is.
+fn is_noise(part: &str) -> bool {
+    let words: Vec<String> = normalize(part).split(' ').map(String::from).collect();
+    words.iter().any(|word| NOISE.contains(&word.as_str()))
+        || words
+            .iter()
+            .all(|word| word.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Removes the parts in brackets that are noise, like `(Official Video)` or `[HD]`.
+fn strip_noise(text: &str) -> String {
+    let mut cleaned = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(['(', '[']) {
+        let close = if rest[start..].starts_with('(') {
+            ')'
+        } else {
+            ']'
+        };
+        let Some(length) = rest[start..].find(close) else {
+            break;
+        };
+        let end = start + length + 1;
+        cleaned.push_str(&rest[..start]);
+        if !is_noise(&rest[start + 1..end - 1]) {
+            cleaned.push_str(&rest[start..end]);
+        }
+        rest = &rest[end..];
+    }
+    cleaned.push_str(rest);
+    cleaned
+}
+
+/// Removes the numbering or bullet at the start, like `1.`, `02)`, `3 -`, `07 ` or `*`; a
+/// title starting with a number, like `99 Luftballons`, keeps it.
+fn strip_numbering(line: &str) -> &str {
+    let line = line.trim_start_matches(['-', '*', '•', '·', '>', ' ', '\t']);
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 || digits > 3 {
+        return line;
+    }
+    let rest = &line[digits..];
+    match rest.trim_start().strip_prefix(['.', ')', '-', ':', '–']) {
+        Some(rest) => rest.trim_start(),
+        None if line.starts_with('0') && rest.starts_with([' ', '\t']) => rest.trim_start(),
+        None => line,
+    }
+}
+
+/// Removes a duration at the end, like `3:45` or `(03:45)`.
+fn strip_duration(line: &str) -> &str {
+    let trimmed = line.trim_end().trim_end_matches([')', ']']);
+    let Some((start, duration)) = trimmed.rsplit_once([' ', '(', '[', '\t']) else {
+        return line;
+    };
+    let is_duration = duration.split(':').count() >= 2
+        && duration
+            .split(':')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    if is_duration {
+        start.trim_end_matches([' ', '(', '[', '\t', '-'])
+    } else {
+        line
+    }
+}
+
+fn unquote(text: &str) -> &str {
+    text.trim()
+        .trim_matches(['"', '\'', '“', '”', '„', '‘', '’'])
+        .trim()
+}
+
+/// The song on a line, `None` for empty lines and `#` comments.
+pub fn read_line(line: usize, text: &str) -> Option<Song> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    let cleaned = strip_noise(strip_duration(strip_numbering(trimmed)));
+    let cleaned = unquote(&cleaned);
+    if normalize(cleaned).is_empty() {
+        return None;
+    }
+    let mut readings = Vec::new();
+    let mut query = cleaned.to_string();
+    let mut may_be_title = true;
+    if let Some((separator, artist_first)) = SEPARATORS
+        .iter()
+        .find(|(separator, _)| cleaned.contains(separator))
+    {
+        let (first, second) = cleaned.split_once(separator)?;
+        let (first, second) = (unquote(first).to_string(), unquote(second).to_string());
+        if *artist_first {
+            query = format!("{} {}", first, second);
+            may_be_title = false;
+            readings.push((second.clone(), first.clone()));
+            if *separator != ": " {
+                readings.push((first, second));
+            }
+        } else {
+            // searched for as written, the "by" may belong to the title
+            readings.push((first, second));
+        }
+    }
+    Some(Song {
+        line,
+        text: trimmed.to_string(),
+        query,
+        readings,
+        may_be_title,
+        isrc: None,
+    })
+}
+
+/// The songs of the list, in their order.
+pub fn read_list(content: &str) -> Vec<Song> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, text)| read_line(index + 1, text))
+        .collect()
+}
+
+/// How well the title resembles the name of the track, also without what Spotify adds
+/// after a dash or in brackets, like `- Remastered 2011` or `(feat. ...)`.
+pub fn title_similarity(title: &str, name: &str) -> f64 {
+    let short = name.split(" - ").next().unwrap_or(name);
+    let short = short.split(['(', '[']).next().unwrap_or(short).trim();
+    similarity(title, name).max(similarity(title, short))
+}
+
+fn artist_similarity(artist: &str, track: &Track) -> f64 {
+    let all: Vec<&str> = track
+        .artists
+        .iter()
+        .map(|artist| artist.name.as_str())
+        .collect();
+    track
+        .artists
+        .iter()
+        .map(|found| similarity(artist, &found.name))
+        .fold(similarity(artist, &all.join(" ")), f64::max)
+}
+
+/// The confidence from 0 to 1 that the track is the song; the title counts more than the
+/// artist. A line that may be the title alone is also compared with the title, and with
+/// the title and artists together, the artist may just lack a separator.
+pub fn confidence(song: &Song, track: &Track) -> f64 {
+    let by_readings = song
+        .readings
+        .iter()
+        .map(|(title, artist)| {
+            0.6 * title_similarity(title, &track.name) + 0.4 * artist_similarity(artist, track)
+        })
+        .fold(0.0, f64::max);
+    if !song.may_be_title {
+        return by_readings;
+    }
+    let artists: Vec<&str> = track
+        .artists
+        .iter()
+        .map(|artist| artist.name.as_str())
+        .collect();
+    let artists = artists.join(" ");
+    [
+        title_similarity(&song.query, &track.name),
+        similarity(&song.query, &format!("{} {}", artists, track.name)),
+        similarity(&song.query, &format!("{} {}", track.name, artists)),
+    ]
+    .into_iter()
+    .fold(by_readings, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn track(name: &str, artists: &[&str]) -> Track {
+        let artists: Vec<_> = artists
+            .iter()
+            .map(|artist| json!({"id": artist.to_lowercase(), "name": artist}))
+            .collect();
+        serde_json::from_value(json!({
+            "id": name.to_lowercase(),
+            "uri": format!("spotify:track:{}", name.to_lowercase()),
+            "name": name,
+            "album": {
+                "id": "album",
+                "name": "Album",
+                "album_type": "album",
+                "artists": [],
+                "total_tracks": 1,
+                "release_date": "2020",
+            },
+            "artists": artists,
+            "duration_ms": 1000,
+        }))
+        .unwrap()
+    }
+
+    /// The query, the title and artist readings and whether the line may be the title.
+    fn read(text: &str) -> (String, Vec<(String, String)>, bool) {
+        let song = read_line(1, text).unwrap();
+        (song.query, song.readings, song.may_be_title)
+    }
+
+    fn readings(readings: &[(&str, &str)]) -> Vec<(String, String)> {
+        readings
+            .iter()
+            .map(|(title, artist)| (title.to_string(), artist.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn leaves_out_the_numbering() {
+        let cases = [
+            ("1. Bohemian Rhapsody", "Bohemian Rhapsody"),
+            ("02) Bohemian Rhapsody", "Bohemian Rhapsody"),
+            ("3 - Bohemian Rhapsody", "Bohemian Rhapsody"),
+            ("04: Bohemian Rhapsody", "Bohemian Rhapsody"),
+            ("07 Bohemian Rhapsody", "Bohemian Rhapsody"),
+            ("* Bohemian Rhapsody", "Bohemian Rhapsody"),
+            ("- Bohemian Rhapsody", "Bohemian Rhapsody"),
+            ("• 5. Bohemian Rhapsody", "Bohemian Rhapsody"),
+            ("99 Luftballons", "99 Luftballons"),
+            ("1999", "1999"),
+        ];
+        for (line, query) in cases {
+            assert_eq!(read(line).0, query, "{}", line);
+        }
+    }
+
+    #[test]
+    fn leaves_out_durations_and_noise() {
+        let cases = [
+            ("Bohemian Rhapsody 5:55", "Bohemian Rhapsody"),
+            ("Bohemian Rhapsody (05:55)", "Bohemian Rhapsody"),
+            ("Bohemian Rhapsody [1:05:55]", "Bohemian Rhapsody"),
+            ("Bohemian Rhapsody (Official Video)", "Bohemian Rhapsody"),
+            (
+                "Bohemian Rhapsody [HD] (Remastered 2011)",
+                "Bohemian Rhapsody",
+            ),
+            ("Bohemian Rhapsody (1975)", "Bohemian Rhapsody"),
+            ("\"Bohemian Rhapsody\"", "Bohemian Rhapsody"),
+            (
+                "Bohemian Rhapsody (Live Aid)",
+                "Bohemian Rhapsody (Live Aid)",
+            ),
+            ("Bohemian Rhapsody (Live", "Bohemian Rhapsody (Live"),
+        ];
+        for (line, query) in cases {
+            assert_eq!(read(line).0, query, "{}", line);
+        }
+    }
+
+    #[test]
+    fn reads_a_dash_both_ways_round() {
+        for separator in [" - ", " – ", " — ", "\t"] {
+            let line = format!("Queen{}Bohemian Rhapsody", separator);
+            assert_eq!(
+                read(&line),
+                (
+                    "Queen Bohemian Rhapsody".to_string(),
+                    readings(&[
+                        ("Bohemian Rhapsody", "Queen"),
+                        ("Queen", "Bohemian Rhapsody")
+                    ]),
+                    false
+                ),
+                "{:?}",
+                separator
+            );
+        }
+    }
+
+    #[test]
+    fn reads_the_artist_before_a_colon() {
+        assert_eq!(
+            read("Queen: Bohemian Rhapsody (Official Video)"),
+            (
+                "Queen Bohemian Rhapsody".to_string(),
+                readings(&[("Bohemian Rhapsody", "Queen")]),
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn reads_the_artist_after_by_but_keeps_the_line() {
+        assert_eq!(
+            read("Bohemian Rhapsody by Queen"),
+            (
+                "Bohemian Rhapsody by Queen".to_string(),
+                readings(&[("Bohemian Rhapsody", "Queen")]),
+                true
+            )
+        );
+        assert_eq!(
+            read("Stand by Me"),
+            (
+                "Stand by Me".to_string(),
+                readings(&[("Stand", "Me")]),
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn unquotes_title_and_artist() {
+        assert_eq!(
+            read("\"Creep\" - Radiohead (03:58)"),
+            (
+                "Creep Radiohead".to_string(),
+                readings(&[("Radiohead", "Creep"), ("Creep", "Radiohead")]),
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn skips_empty_lines_comments_and_noise() {
+        for line in ["", "   ", "# Road trip", "(Official Video)", "1.", "---"] {
+            assert!(read_line(1, line).is_none(), "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn numbers_the_songs_by_their_lines() {
+        let songs = read_list("# Road trip\n\n1. Queen - Bohemian Rhapsody\n  Creep  \n");
+        let lines: Vec<(usize, &str)> = songs
+            .iter()
+            .map(|song| (song.line, song.text.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![(3, "1. Queen - Bohemian Rhapsody"), (4, "Creep")]
+        );
+    }
+
+    #[test]
+    fn compares_names_however_they_are_written() {
+        assert_eq!(similarity("AC/DC", "ac dc"), 1.0);
+        assert_eq!(similarity("Simon & Garfunkel", "Simon and Garfunkel"), 1.0);
+        assert_eq!(similarity("", "Queen"), 0.0);
+        assert!(similarity("Bohemian Rapsody", "Bohemian Rhapsody") > 0.9);
+        assert!(similarity("Queen", "Pentatonix") < 0.2);
+        assert_eq!(
+            title_similarity("Bohemian Rhapsody", "Bohemian Rhapsody - Remastered 2011"),
+            1.0
+        );
+        assert_eq!(
+            title_similarity("Under Pressure", "Under Pressure (feat. David Bowie)"),
+            1.0
+        );
+    }
+
+    #[test]
+    fn ranks_the_most_likely_track_first() {
+        let song = read_line(1, "Queen - Bohemian Rhapsody").unwrap();
+        let tracks = vec![
+            track("Another One Bites the Dust", &["Queen"]),
+            track("Bohemian Rhapsody", &["Pentatonix"]),
+            track("Bohemian Rhapsody - Remastered 2011", &["Queen"]),
+        ];
+        let song_match = SongMatch::new(song, tracks, 0.8);
+        let names: Vec<&str> = song_match
+            .candidates
+            .iter()
+            .map(|candidate| candidate.track.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "Bohemian Rhapsody - Remastered 2011",
+                "Bohemian Rhapsody",
+                "Another One Bites the Dust"
+            ]
+        );
+        assert_eq!(song_match.best_confidence(), 1.0);
+        assert_eq!(song_match.choice, Some(0));
+    }
+
+    #[test]
+    fn chooses_nothing_below_the_minimum_confidence() {
+        let song = read_line(1, "Queen - Bohemian Rhapsody").unwrap();
+        let song_match = SongMatch::new(song.clone(), vec![track("Creep", &["Radiohead"])], 0.8);
+        assert!(song_match.best_confidence() < 0.8);
+        assert!(song_match.chosen().is_none());
+
+        let song_match = SongMatch::new(song, Vec::new(), 0.8);
+        assert_eq!(song_match.best_confidence(), 0.0);
+        assert!(song_match.chosen().is_none());
+    }
+
+    #[test]
+    fn a_line_with_by_may_be_the_whole_title() {
+        let song = read_line(1, "Stand by Me").unwrap();
+        let found = track("Stand by Me", &["Ben E. King"]);
+        assert_eq!(confidence(&song, &found), 1.0);
+    }
+
+    #[test]
+    fn a_line_without_separator_may_hold_the_artist() {
+        let song = read_line(1, "Queen Bohemian Rhapsody").unwrap();
+        let found = track("Bohemian Rhapsody", &["Queen"]);
+        let other = track("Bohemian Rhapsody", &["Pentatonix"]);
+        assert_eq!(confidence(&song, &found), 1.0);
+        assert!(confidence(&song, &other) < confidence(&song, &found));
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Mutex;
+
+use serde_json::json;
+
+use crate::{progress, settings};
+
+static MESSAGES: Mutex<Vec<(Level, String)>> = Mutex::new(Vec::new());
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+static STDERR: AtomicBool = AtomicBool::new(false);
+static JOURNAL: AtomicBool = AtomicBool::new(false);
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+/// How much detail a message adds; only messages up to the level set with `-q`/`-v` are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Shown even with `-q`.
+    Error,
+    Info,
+    /// Progress details, shown with `-v`.
+    Verbose,
+    /// Every request to Spotify, shown with `-vv`.
+    Trace,
+}
+
+impl Level {
+    /// The level for the number of `-v` flags, or `Error` with `-q`.
+    pub fn from_flags(verbose: u8, quiet: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Level::Error,
+            (false, 0) => Level::Info,
+            (false, 1) => Level::Verbose,
+            (false, _) => Level::Trace,
+        }
+    }
+
+    /// The syslog priority the journal files messages of this level under.
+    fn journal_priority(&self) -> u8 {
+        match self {
+            Level::Error => 3,
+            Level::Info => 6,
+            Level::Verbose | Level::Trace => 7,
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! status {
+    ( $( $x: expr),+ ) => {{
+        $crate::status_log::push_at($crate::status_log::Level::Info, $crate::tr!($($x),+))
+    }};
+}
+
+#[macro_export]
+macro_rules! status_error {
+    ( $( $x: expr),+ ) => {{
+        $crate::status_log::push_at($crate::status_log::Level::Error, $crate::tr!($($x),+))
+    }};
+}
+
+#[macro_export]
+macro_rules! status_verbose {
+    ( $( $x: expr),+ ) => {{
+        $crate::status_log::push_at($crate::status_log::Level::Verbose, $crate::tr!($($x),+))
+    }};
+}
+
+#[macro_export]
+macro_rules! status_trace {
+    ( $( $x: expr),+ ) => {{
+        $crate::status_log::push_at($crate::status_log::Level::Trace, $crate::tr!($($x),+))
+    }};
+}
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::SeqCst);
+}
+
+fn is_shown(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::SeqCst)
+}
+
+/// Prints the message, or keeps it for the log pane while the TUI owns the terminal.
+/// Errors always go to stderr.
+pub fn push_at(level: Level, message: String) {
+    // a client hands every message to its handler and prints none
+    if let Some(handler) = settings::with(|settings| settings.on_status.clone()) {
+        if let Some(handler) = handler {
+            handler(level, &message);
+        }
+        return;
+    }
+    if !is_shown(level) {
+        return;
+    }
+    if level == Level::Error {
+        progress::emit("error", json!({ "message": message }));
+    }
+    let message = if JOURNAL.load(Ordering::SeqCst) {
+        format!("<{}>{}", level.journal_priority(), message)
+    } else {
+        message
+    };
+    if CAPTURING.load(Ordering::SeqCst) {
+        MESSAGES.lock().unwrap().push((level, message));
+    } else if level == Level::Error || STDERR.load(Ordering::SeqCst) {
+        eprintln!("{}", message);
+    } else {
+        println!("{}", message);
+    }
+}
+
+/// Prints the messages to stderr from now on, keeping stdout for the `--json` output.
+pub fn use_stderr() {
+    STDERR.store(true, Ordering::SeqCst);
+}
+
+/// Prefixes the messages with their priority, which the journal strips and keeps.
+pub fn use_journal_priorities() {
+    JOURNAL.store(true, Ordering::SeqCst);
+}
+
+pub fn start_capturing() {
+    CAPTURING.store(true, Ordering::SeqCst);
+}
+
+/// Stops capturing and returns everything captured since the last call.
+pub fn stop_capturing() -> Vec<(Level, String)> {
+    CAPTURING.store(false, Ordering::SeqCst);
+    std::mem::take(&mut *MESSAGES.lock().unwrap())
+}
+
+pub fn messages() -> Vec<String> {
+    MESSAGES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, message)| message.clone())
+        .collect()
+}
@@ -0,0 +1,496 @@
+//! The local database of a profile, one SQLite file at `--history-path`: the runs, the top
+//! tracks and playlists of every run, the played tracks archived or imported, the counters
+//! of the metrics and the cached answers of slow services. It keeps what Spotify forgets,
+//! like the top tracks of last year or more than the last 50 played tracks.
+
+use std::fmt::{self, Display};
+use std::fs::{self, File};
+use std::io::{self, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::actions::is_dry_run;
+use crate::actions::top_track_playlist::TimeRange;
+use crate::history::RunRecord;
+use crate::history_import::Play;
+use crate::models::track::Track;
+use crate::player::PlayedTrack;
+use crate::{settings, status_error};
+
+/// Set for the profile in use, for the actions recording what they did.
+static PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// The databases opened so far with their paths.
+static OPENED: Mutex<Vec<(PathBuf, Storage)>> = Mutex::new(Vec::new());
+
+/// Several actions may write at once.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The files written before the database start with a JSON array.
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+/// Each step brings the schema from the version before, kept in `PRAGMA user_version`.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE runs (
+        id INTEGER PRIMARY KEY,
+        started_at TEXT NOT NULL,
+        reports TEXT NOT NULL
+    );
+    CREATE TABLE tracks (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        artists TEXT NOT NULL,
+        album TEXT NOT NULL,
+        isrc TEXT,
+        duration_ms INTEGER NOT NULL
+    );
+    CREATE TABLE top_tracks (
+        taken_at TEXT NOT NULL,
+        time_range TEXT NOT NULL,
+        rank INTEGER NOT NULL,
+        track_id TEXT NOT NULL REFERENCES tracks (id),
+        PRIMARY KEY (taken_at, time_range, rank)
+    );
+    CREATE TABLE playlist_snapshots (
+        id INTEGER PRIMARY KEY,
+        taken_at TEXT NOT NULL,
+        playlist_id TEXT NOT NULL,
+        name TEXT NOT NULL
+    );
+    CREATE INDEX playlist_snapshots_playlist ON playlist_snapshots (playlist_id, taken_at);
+    CREATE TABLE playlist_snapshot_tracks (
+        snapshot_id INTEGER NOT NULL REFERENCES playlist_snapshots (id),
+        position INTEGER NOT NULL,
+        track_id TEXT NOT NULL REFERENCES tracks (id),
+        PRIMARY KEY (snapshot_id, position)
+    );
+    -- without a Spotify id for plays imported from elsewhere
+    CREATE TABLE played_tracks (
+        played_at TEXT NOT NULL,
+        name TEXT NOT NULL,
+        artists TEXT NOT NULL,
+        album TEXT,
+        track_id TEXT,
+        PRIMARY KEY (played_at, name)
+    );
+    CREATE TABLE request_cache (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL,
+        fetched_at TEXT NOT NULL
+    );
+",
+    "
+    -- the labels in the text format of Prometheus, like `pipeline=\"weekly\"`
+    CREATE TABLE metrics (
+        name TEXT NOT NULL,
+        labels TEXT NOT NULL,
+        value REAL NOT NULL,
+        PRIMARY KEY (name, labels)
+    );
+",
+];
+
+#[derive(Debug)]
+pub enum StorageError {
+    Database(rusqlite::Error),
+    Io(io::Error),
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Database(err) => write!(f, "{}", err),
+            StorageError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(err: rusqlite::Error) -> Self {
+        StorageError::Database(err)
+    }
+}
+
+impl From<io::Error> for StorageError {
+    fn from(err: io::Error) -> Self {
+        StorageError::Io(err)
+    }
+}
+
+pub fn set_path(path: &str) {
+    *PATH.lock().unwrap() = Some(PathBuf::from(path));
+}
+
+/// Uses the database at `path`, opened on first use and then kept open until the process
+/// ends, so recording or caching something does not open it again each time.
+pub fn with_storage_at<T>(
+    path: &Path,
+    use_storage: impl FnOnce(&mut Storage) -> rusqlite::Result<T>,
+) -> Result<T, StorageError> {
+    let mut opened = OPENED.lock().unwrap();
+    let index = match opened
+        .iter()
+        .position(|(opened_path, _)| opened_path == path)
+    {
+        Some(index) => index,
+        None => {
+            opened.push((path.to_path_buf(), Storage::open_at(path)?));
+            opened.len() - 1
+        }
+    };
+    Ok(use_storage(&mut opened[index].1)?)
+}
+
+/// Uses the database of the client or profile in use; `None` without a path, e.g. before
+/// `set_path` for `config`.
+pub fn with_current_storage<T>(
+    use_storage: impl FnOnce(&mut Storage) -> rusqlite::Result<T>,
+) -> Result<Option<T>, StorageError> {
+    let path = settings::with(|settings| settings.storage_path.clone())
+        .unwrap_or_else(|| PATH.lock().unwrap().clone());
+    path.map(|path| with_storage_at(&path, use_storage))
+        .transpose()
+}
+
+/// Like `with_current_storage`, but like with the history, a failure is only reported.
+fn with_storage<T>(use_storage: impl FnOnce(&mut Storage) -> rusqlite::Result<T>) -> Option<T> {
+    with_current_storage(use_storage).unwrap_or_else(|err| {
+        status_error!("Could not use the history database: {}", err);
+        None
+    })
+}
+
+/// Records what a run did; a dry run did not do anything worth remembering.
+pub fn record(record: impl FnOnce(&mut Storage) -> rusqlite::Result<()>) {
+    if !is_dry_run() {
+        with_storage(record);
+    }
+}
+
+/// See `Storage::cached`.
+pub fn cached<T: DeserializeOwned>(key: &str, max_age: chrono::Duration) -> Option<T> {
+    with_storage(|storage| storage.cached(key, max_age)).flatten()
+}
+
+pub fn cache<T: Serialize>(key: &str, value: &T) {
+    with_storage(|storage| storage.cache(key, value));
+}
+
+fn artist_names(track: &Track) -> String {
+    track
+        .artists
+        .iter()
+        .map(|artist| artist.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Keeps the name of the track for the rows referring to it.
+fn upsert_track(transaction: &Transaction, track: &Track) -> rusqlite::Result<()> {
+    transaction.execute(
+        "INSERT INTO tracks (id, name, artists, album, isrc, duration_ms)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        ON CONFLICT (id) DO UPDATE SET name = ?2, artists = ?3, album = ?4, isrc = ?5,
+            duration_ms = ?6",
+        params![
+            track.id,
+            track.name,
+            artist_names(track),
+            track.album.name,
+            track.external_ids.isrc,
+            track.duration_ms as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+fn insert_run(connection: &Connection, run: &RunRecord) -> rusqlite::Result<()> {
+    let reports = serde_json::to_string(&run.reports).expect("Failed to serialize reports");
+    connection.execute(
+        "INSERT INTO runs (started_at, reports) VALUES (?1, ?2)",
+        params![run.started_at, reports],
+    )?;
+    Ok(())
+}
+
+/// The runs of a JSON history file written before the database; `None` for a database or
+/// no file. Only the start of a database is read to tell it apart.
+fn read_legacy_runs(path: &Path) -> Result<Option<Vec<RunRecord>>, StorageError> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut content = Vec::new();
+    (&mut file)
+        .take(SQLITE_HEADER.len() as u64)
+        .read_to_end(&mut content)?;
+    if content.is_empty() || content == SQLITE_HEADER {
+        return Ok(None);
+    }
+    file.read_to_end(&mut content)?;
+    let runs = serde_json::from_slice(&content)
+        .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    Ok(Some(runs))
+}
+
+/// Writes the runs into a new database next to the JSON file and only puts it in its place
+/// once they are all in, so a failed import leaves the file as it was.
+fn import_legacy_runs(path: &Path, runs: &[RunRecord]) -> Result<(), StorageError> {
+    let imported_path = with_suffix(path, ".import");
+    // left behind by an import that failed before
+    if imported_path.exists() {
+        fs::remove_file(&imported_path)?;
+    }
+    let mut storage = Storage::connect(&imported_path)?;
+    let transaction = storage.connection.transaction()?;
+    for run in runs {
+        insert_run(&transaction, run)?;
+    }
+    transaction.commit()?;
+    drop(storage);
+    fs::rename(path, with_suffix(path, ".json"))?;
+    fs::rename(imported_path, path)?;
+    Ok(())
+}
+
+/// The path with `suffix` added to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn from_json<T: DeserializeOwned>(column: usize, json: &str) -> rusqlite::Result<T> {
+    serde_json::from_str(json)
+        .map_err(|err| rusqlite::Error::FromSqlConversionFailure(column, Type::Text, Box::new(err)))
+}
+
+pub struct Storage {
+    connection: Connection,
+}
+
+impl Storage {
+    /// Creates the database or brings it to the current schema; a JSON history file
+    /// written before is imported and kept next to it as `<path>.json`.
+    pub fn open_at(path: &Path) -> Result<Self, StorageError> {
+        if let Some(runs) = read_legacy_runs(path)? {
+            import_legacy_runs(path, &runs)?;
+        }
+        Storage::connect(path)
+    }
+
+    fn connect(path: &Path) -> Result<Self, StorageError> {
+        let mut connection = Connection::open(path)?;
+        connection.busy_timeout(BUSY_TIMEOUT)?;
+        let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        let version = version as usize;
+        if version < MIGRATIONS.len() {
+            let transaction = connection.transaction()?;
+            for migration in &MIGRATIONS[version..] {
+                transaction.execute_batch(migration)?;
+            }
+            transaction.pragma_update(None, "user_version", MIGRATIONS.len() as i64)?;
+            transaction.commit()?;
+        }
+        Ok(Storage { connection })
+    }
+
+    pub fn add_run(&self, run: &RunRecord) -> rusqlite::Result<()> {
+        insert_run(&self.connection, run)
+    }
+
+    /// All recorded runs, oldest first.
+    pub fn runs(&self) -> rusqlite::Result<Vec<RunRecord>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT started_at, reports FROM runs ORDER BY id")?;
+        let runs = statement
+            .query_map([], |row| {
+                let reports: String = row.get(1)?;
+                Ok(RunRecord::new(row.get(0)?, from_json(1, &reports)?))
+            })?
+            .collect();
+        runs
+    }
+
+    /// The top tracks of the time range at the time of the run, the first ranked first.
+    pub fn add_top_tracks<'a>(
+        &mut self,
+        taken_at: DateTime<FixedOffset>,
+        time_range: TimeRange,
+        tracks: impl IntoIterator<Item = &'a Track>,
+    ) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        for (rank, track) in tracks.into_iter().enumerate() {
+            upsert_track(&transaction, track)?;
+            transaction.execute(
+                "INSERT OR REPLACE INTO top_tracks (taken_at, time_range, rank, track_id)
+                VALUES (?1, ?2, ?3, ?4)",
+                params![taken_at, time_range.to_string(), rank as i64 + 1, track.id],
+            )?;
+        }
+        transaction.commit()
+    }
+
+    /// The tracks of a playlist as a run left it.
+    pub fn add_playlist_snapshot<'a>(
+        &mut self,
+        taken_at: DateTime<FixedOffset>,
+        playlist_id: &str,
+        name: &str,
+        tracks: impl IntoIterator<Item = &'a Track>,
+    ) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO playlist_snapshots (taken_at, playlist_id, name) VALUES (?1, ?2, ?3)",
+            params![taken_at, playlist_id, name],
+        )?;
+        let snapshot_id = transaction.last_insert_rowid();
+        for (position, track) in tracks.into_iter().enumerate() {
+            upsert_track(&transaction, track)?;
+            transaction.execute(
+                "INSERT INTO playlist_snapshot_tracks (snapshot_id, position, track_id)
+                VALUES (?1, ?2, ?3)",
+                params![snapshot_id, position as i64 + 1, track.id],
+            )?;
+        }
+        transaction.commit()
+    }
+
+    /// Archives the played tracks; returns how many were not archived yet.
+    pub fn add_played_tracks(&mut self, played: &[PlayedTrack]) -> rusqlite::Result<usize> {
+        let transaction = self.connection.transaction()?;
+        let mut added = 0;
+        for played in played {
+            let track = &played.track;
+            upsert_track(&transaction, track)?;
+            added += transaction.execute(
+                "INSERT OR IGNORE INTO played_tracks (played_at, name, artists, album, track_id)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    played.played_at,
+                    track.name,
+                    artist_names(track),
+                    track.album.name,
+                    track.id,
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(added)
+    }
+
+    pub fn add_to_metric(&self, name: &str, labels: &str, delta: f64) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "INSERT INTO metrics (name, labels, value) VALUES (?1, ?2, ?3)
+            ON CONFLICT (name, labels) DO UPDATE SET value = value + ?3",
+            params![name, labels, delta],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_metric(&self, name: &str, labels: &str, value: f64) -> rusqlite::Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO metrics (name, labels, value) VALUES (?1, ?2, ?3)",
+            params![name, labels, value],
+        )?;
+        Ok(())
+    }
+
+    /// All metrics as `(name, labels, value)`, sorted.
+    pub fn metrics(&self) -> rusqlite::Result<Vec<(String, String, f64)>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT name, labels, value FROM metrics ORDER BY name, labels")?;
+        let metrics = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect();
+        metrics
+    }
+
+    /// Adds the plays imported from elsewhere; returns how many were not there yet.
+    pub fn add_plays(&mut self, plays: &[Play]) -> rusqlite::Result<usize> {
+        let transaction = self.connection.transaction()?;
+        let mut added = 0;
+        for play in plays {
+            // like the `played_at` of Spotify, so the rows sort by time
+            let played_at = play.played_at.to_rfc3339_opts(SecondsFormat::Millis, true);
+            added += transaction.execute(
+                "INSERT OR IGNORE INTO played_tracks (played_at, name, artists, album, track_id)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    played_at,
+                    play.name,
+                    play.artists,
+                    play.album,
+                    play.track_id
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(added)
+    }
+
+    /// The cached value of the key, unless it is older than `max_age`.
+    pub fn cached<T: DeserializeOwned>(
+        &self,
+        key: &str,
+        max_age: chrono::Duration,
+    ) -> rusqlite::Result<Option<T>> {
+        let oldest = Utc::now() - max_age;
+        let value: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT value FROM request_cache WHERE key = ?1 AND fetched_at >= ?2",
+                params![key, oldest],
+                |row| row.get(0),
+            )
+            .optional()?;
+        value.map(|value| from_json(0, &value)).transpose()
+    }
+
+    pub fn cache<T: Serialize>(&self, key: &str, value: &T) -> rusqlite::Result<()> {
+        let value = serde_json::to_string(value).expect("Failed to serialize cache value");
+        self.connection.execute(
+            "INSERT OR REPLACE INTO request_cache (key, value, fetched_at) VALUES (?1, ?2, ?3)",
+            params![key, value, Utc::now()],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_a_json_history_once() {
+        let dir = std::env::temp_dir().join(format!("spautofy-storage-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("spautofy.history");
+        fs::write(
+            &path,
+            r#"[{"started_at":"2024-01-02T03:04:05+01:00","reports":[]}]"#,
+        )
+        .unwrap();
+
+        let storage = Storage::open_at(&path).unwrap();
+        assert_eq!(storage.runs().unwrap().len(), 1);
+        assert!(with_suffix(&path, ".json").exists());
+        assert!(!with_suffix(&path, ".import").exists());
+        drop(storage);
+        let storage = Storage::open_at(&path).unwrap();
+        assert_eq!(storage.runs().unwrap().len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
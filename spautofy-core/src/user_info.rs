@@ -0,0 +1,67 @@
+use reqwest::Client;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api_endpoint;
+use crate::authorize::{Access, AuthorizeError};
+use crate::endpoints::execute_api;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct User {
+    pub display_name: String,
+    pub id: String,
+    /// Only sent with the `user-read-private` scope, missing for logins before it was asked for.
+    pub country: Option<String>,
+    /// "premium", "free" or "open"; also only sent with `user-read-private`.
+    pub product: Option<String>,
+}
+
+/// The access to Spotify with the user it belongs to, which every request on their behalf
+/// takes.
+#[derive(Debug, Clone)]
+pub struct UserAccess {
+    pub access: Access,
+    pub user: User,
+}
+
+impl UserAccess {
+    pub fn authorize(&self, request_builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        self.access.authorize(request_builder)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct PublicUser {
+    pub id: String,
+    pub display_name: Option<String>,
+    pub uri: String,
+}
+
+pub async fn get_user_access(access: Access) -> Result<UserAccess, AuthorizeError> {
+    let user = get_user_info(&access).await?;
+    Ok(UserAccess { access, user })
+}
+
+async fn get_user_info(access: &Access) -> Result<User, AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!("/me"));
+    let request_builder = access.authorize(request_builder);
+    let request = request_builder.build()?;
+    let resp = execute_api(&client, request).await?;
+    let resp = resp.json::<User>().await?;
+    Ok(resp)
+}
+
+/// The public profile of any user, by their id.
+pub async fn get_user_profile(
+    user_access: &UserAccess,
+    user_id: &str,
+) -> Result<PublicUser, AuthorizeError> {
+    let client = Client::new();
+    let request_builder = client.get(api_endpoint!("/users/{user_id}"));
+    let request_builder = user_access.authorize(request_builder);
+    let request = request_builder.build()?;
+    let resp = execute_api(&client, request).await?;
+    let resp = resp.json::<PublicUser>().await?;
+    Ok(resp)
+}
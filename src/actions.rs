@@ -1,2 +0,0 @@
-pub mod playlist_actions;
-pub mod top_track_playlist;
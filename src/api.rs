@@ -0,0 +1,409 @@
+//! A JSON API, `spautofy api`, for other programs on the machine or in the home network to
+//! run pipelines without starting spautofy themselves:
+//!
+//! - `POST /api/run/<target>` starts a pipeline, rule or action like `spautofy run <target>`
+//!   and answers 202 at once; 409 while another run goes on.
+//! - `GET /api/status` tells whether one does, why the last one could not start and how the
+//!   last one went.
+//! - `GET /api/playlists` lists the playlists like `spautofy playlists list --json`.
+//!
+//! Every request needs the token of the `"api"` section as `Authorization: Bearer <token>`,
+//! the errors come as `{"error": "..."}`. The runs are those of the dashboard, see
+//! `dashboard`, the two share how they run.
+//!
+//! `GET /api/openapi.json` describes all this for generating clients, without a token. Web
+//! pages may only call the API from the browser if their origins are listed in
+//! `cors_origins`.
+//!
+//! Like the dashboard, the server hands out the metrics for Prometheus as `GET /metrics` and
+//! the feed of the playlists as `GET /feed.atom`, without a token.
+
+use std::sync::Arc;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{Header, Method, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::status::Custom;
+use rocket::serde::json::Json;
+use rocket::{catch, catchers, get, options, post, routes, Responder, Response, State};
+use schemars::generate::{Contract, SchemaSettings};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::actions::playlist_actions::get_current_user_playlists;
+use crate::config::SpautofyConfigFile;
+use crate::dashboard::{self, Dashboard};
+use crate::history::RunRecord;
+use crate::models::playlist::SimplifiedPlaylist;
+use crate::{parse_run_target, status, tr, Args, MainError};
+
+/// How long browsers may remember the answer to a preflight request, in seconds.
+const PREFLIGHT_MAX_AGE: &str = "3600";
+
+/// `"api": {"token": "..."}`, the token better given as $SPAUTOFY_API_TOKEN.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ApiConfig {
+    /// Anyone knowing it may run everything, so better long and random.
+    #[serde(default)]
+    pub token: String,
+    /// The origins of the web pages that may call the API, like `http://localhost:5173`, or
+    /// `*` for all; none by default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cors_origins: Vec<String>,
+}
+
+impl ApiConfig {
+    fn allows_origin(&self, origin: &str) -> bool {
+        self.cors_origins.iter().any(|allowed| {
+            allowed == "*" || allowed.trim_end_matches('/').eq_ignore_ascii_case(origin)
+        })
+    }
+}
+
+/// Whether the origin is `*` or a scheme, host and maybe port, like `http://localhost:5173`;
+/// a trailing slash does no harm.
+pub fn is_valid_origin(origin: &str) -> bool {
+    if origin == "*" {
+        return true;
+    }
+    match reqwest::Url::parse(origin) {
+        Ok(url) => {
+            matches!(url.scheme(), "http" | "https")
+                && url.has_host()
+                && url.path() == "/"
+                && url.query().is_none()
+        }
+        Err(_) => false,
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiError {
+    error: String,
+}
+
+type Failure = Custom<Json<ApiError>>;
+
+fn failure(status: Status, error: String) -> Failure {
+    Custom(status, Json(ApiError { error }))
+}
+
+impl From<MainError> for Failure {
+    fn from(err: MainError) -> Self {
+        let status = match err {
+            // Spotify failed, not the API
+            MainError::Auth(_) | MainError::NotLoggedIn(_) => Status::BadGateway,
+            _ => Status::InternalServerError,
+        };
+        failure(status, err.to_string())
+    }
+}
+
+/// Compares every byte, so the time taken does not tell how much of a guess was right.
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// The `"api"` section of the current config, which may have changed since the start.
+fn api_config(dashboard: &Dashboard) -> ApiConfig {
+    dashboard
+        .read_config(|file_config| file_config.api.clone())
+        .unwrap_or_default()
+}
+
+/// The request came with the token of the config.
+pub struct Authorized;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for Authorized {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+        let Some(dashboard) = request.rocket().state::<Arc<Dashboard>>() else {
+            return Outcome::Error((Status::InternalServerError, ()));
+        };
+        let config = api_config(dashboard);
+        let given = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|value| value.strip_prefix("Bearer "));
+        match given {
+            // a config reloaded without a token lets nobody in
+            Some(given)
+                if !config.token.trim().is_empty()
+                    && same_token(given.trim(), config.token.trim()) =>
+            {
+                Outcome::Success(Authorized)
+            }
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+/// Adds the headers that let the browser hand the answers to the pages of the allowed
+/// origins, and the answers to their preflight requests too.
+pub struct Cors;
+
+#[rocket::async_trait]
+impl Fairing for Cors {
+    fn info(&self) -> Info {
+        Info {
+            name: "CORS",
+            kind: Kind::Response,
+        }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let Some(dashboard) = request.rocket().state::<Arc<Dashboard>>() else {
+            return;
+        };
+        let config = api_config(dashboard);
+        let Some(origin) = request.headers().get_one("Origin") else {
+            return;
+        };
+        if !config.allows_origin(origin) {
+            return;
+        }
+        response.set_raw_header("Access-Control-Allow-Origin", origin.to_string());
+        response.set_raw_header("Vary", "Origin");
+        if request.method() == Method::Options {
+            response.set_raw_header("Access-Control-Allow-Methods", "GET, POST");
+            response.set_raw_header(
+                "Access-Control-Allow-Headers",
+                "Authorization, Content-Type",
+            );
+            response.set_raw_header("Access-Control-Max-Age", PREFLIGHT_MAX_AGE);
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct RunStarted {
+    target: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiStatus {
+    /// The target of the run going on, if one is.
+    running: Option<String>,
+    /// Why the last run could not start; the failed actions are in `last_run`.
+    last_error: Option<String>,
+    last_run: Option<RunRecord>,
+}
+
+#[post("/run/<target>")]
+fn run(
+    _authorized: Authorized,
+    dashboard: &State<Arc<Dashboard>>,
+    target: &str,
+) -> Result<Custom<Json<RunStarted>>, Failure> {
+    let parsed = parse_run_target(target).map_err(|err| failure(Status::BadRequest, err))?;
+    dashboard
+        .actions(&parsed)
+        .map_err(|err| failure(Status::NotFound, err.to_string()))?;
+    dashboard
+        .start(target.to_string())
+        .map_err(|err| failure(Status::Conflict, err))?;
+    status!("Running \"{}\" for a request to the API.", target);
+    Ok(Custom(
+        Status::Accepted,
+        Json(RunStarted {
+            target: target.to_string(),
+        }),
+    ))
+}
+
+#[get("/status")]
+fn api_status(
+    _authorized: Authorized,
+    dashboard: &State<Arc<Dashboard>>,
+) -> Result<Json<ApiStatus>, Failure> {
+    let last_run = dashboard.history()?.pop();
+    Ok(Json(ApiStatus {
+        running: dashboard.running(),
+        last_error: dashboard.last_error(),
+        last_run,
+    }))
+}
+
+#[get("/playlists")]
+async fn playlists(
+    _authorized: Authorized,
+    dashboard: &State<Arc<Dashboard>>,
+) -> Result<Json<Vec<SimplifiedPlaylist>>, Failure> {
+    let user_access = dashboard.user_access().await?;
+    let playlists = get_current_user_playlists(&user_access, |_| {})
+        .await
+        .map_err(MainError::from)?;
+    Ok(Json(playlists))
+}
+
+#[derive(Responder)]
+#[response(status = 401)]
+pub struct Unauthorized {
+    error: Json<ApiError>,
+    challenge: Header<'static>,
+}
+
+#[catch(401)]
+fn unauthorized() -> Unauthorized {
+    Unauthorized {
+        error: Json(ApiError {
+            error: tr!(
+                "The request needs the token of the API as `Authorization: Bearer <token>`."
+            ),
+        }),
+        challenge: Header::new("WWW-Authenticate", "Bearer"),
+    }
+}
+
+/// Browsers ask before sending the token from another origin; `Cors` adds the answer.
+#[options("/<_..>")]
+fn preflight() -> Status {
+    Status::NoContent
+}
+
+#[get("/openapi.json")]
+fn openapi_json() -> Json<Value> {
+    Json(openapi())
+}
+
+/// The OpenAPI description of the API, with the schemas of the answers made from the types
+/// they are written from.
+pub fn openapi() -> Value {
+    let mut settings = SchemaSettings::draft2020_12();
+    settings.definitions_path = "/components/schemas".into();
+    settings.contract = Contract::Serialize;
+    let mut generator = settings.into_generator();
+    let error = generator.subschema_for::<ApiError>();
+    let failure = |description: &str| {
+        json!({
+            "description": description,
+            "content": {"application/json": {"schema": error}},
+        })
+    };
+    let success = |description: &str, schema: schemars::Schema| {
+        json!({
+            "description": description,
+            "content": {"application/json": {"schema": schema}},
+        })
+    };
+    let unauthorized = failure("The token is missing or wrong");
+    let paths = json!({
+        "/run/{target}": {
+            "post": {
+                "operationId": "run",
+                "summary": "Starts a pipeline, rule or action in the background, like `spautofy run <target>`",
+                "parameters": [{
+                    "name": "target",
+                    "in": "path",
+                    "required": true,
+                    "description": "The name of a pipeline or rule, or an action like `top-tracks:short`",
+                    "schema": {"type": "string"},
+                    "example": "weekly",
+                }],
+                "responses": {
+                    "202": success("The run started, `/status` tells how it went", generator.subschema_for::<RunStarted>()),
+                    "400": failure("The action is invalid"),
+                    "401": unauthorized,
+                    "404": failure("There is no such pipeline, rule or action"),
+                    "409": failure("Another run goes on"),
+                },
+            },
+        },
+        "/status": {
+            "get": {
+                "operationId": "status",
+                "summary": "Whether a run goes on, why the last one could not start and how the last one went",
+                "responses": {
+                    "200": success("The status", generator.subschema_for::<ApiStatus>()),
+                    "401": unauthorized,
+                    "500": failure("The history could not be read"),
+                },
+            },
+        },
+        "/playlists": {
+            "get": {
+                "operationId": "playlists",
+                "summary": "The playlists of the user, like `spautofy playlists list --json`",
+                "responses": {
+                    "200": success("The playlists", generator.subschema_for::<Vec<SimplifiedPlaylist>>()),
+                    "401": unauthorized,
+                    "502": failure("Spotify could not be reached or the login failed"),
+                },
+            },
+        },
+        "/openapi.json": {
+            "get": {
+                "operationId": "openapi",
+                "summary": "This description",
+                "security": [],
+                "responses": {"200": {"description": "The OpenAPI description"}},
+            },
+        },
+    });
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "Spautofy",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "Runs the pipelines, rules and actions of Spautofy, served by `spautofy api`.",
+        },
+        // relative to where the description is served
+        "servers": [{"url": "/api"}],
+        "security": [{"token": []}],
+        "paths": paths,
+        "components": {
+            "securitySchemes": {"token": {"type": "http", "scheme": "bearer"}},
+            "schemas": generator.take_definitions(true),
+        },
+    })
+}
+
+/// The other errors of Rocket, like an unknown route, as JSON too.
+#[catch(default)]
+fn other_error(status: Status, _request: &Request) -> Failure {
+    failure(status, status.reason_lossy().to_string())
+}
+
+/// Serves the API until stopped.
+pub async fn serve(args: &Args, file_config: SpautofyConfigFile) -> Result<(), MainError> {
+    if file_config
+        .api
+        .as_ref()
+        .is_none_or(|api| api.token.trim().is_empty())
+    {
+        return Err(MainError::Config(tr!(
+            "Config file {} has no token for the API, set api.token or $SPAUTOFY_API_TOKEN.",
+            args.config_path
+        )));
+    }
+    let dashboard = Dashboard::new(args, file_config).await?;
+    dashboard::launch(
+        args,
+        dashboard,
+        |rocket| {
+            rocket
+                .attach(Cors)
+                .mount(
+                    "/api",
+                    routes![run, api_status, playlists, preflight, openapi_json],
+                )
+                .register("/api", catchers![unauthorized, other_error])
+        },
+        |address, port| {
+            status!(
+                "Serving the API on http://{}:{}/api/ until stopped.",
+                address,
+                port
+            )
+        },
+    )
+    .await
+}
@@ -0,0 +1,83 @@
+//! The routes of the web server Spotify redirects the browser to when logging in.
+
+use std::ops::Deref;
+
+use rocket::http::Status;
+use rocket::response::Redirect;
+use rocket::{get, Shutdown, State};
+
+use crate::config::{Credentials, SharedConfig};
+use crate::secret_files::write_secret_file;
+use crate::{status, status_error, tr};
+
+#[get("/")]
+pub fn index(config: &State<SharedConfig>) -> Redirect {
+    let authorized = config.read().unwrap().auth.user_auth_code.is_some();
+    if authorized {
+        Redirect::to("/done")
+    } else {
+        Redirect::to("/auth")
+    }
+}
+
+#[get("/done")]
+#[allow(clippy::result_large_err)]
+pub fn done(
+    credentials_filepath: &State<String>,
+    config: &State<SharedConfig>,
+    shutdown: Shutdown,
+) -> Result<String, Redirect> {
+    // the file is written after the lock is given back
+    let (auth_error, credentials) = {
+        let config = config.read().unwrap();
+        let credentials = config
+            .auth
+            .user_auth_code
+            .is_some()
+            .then(|| Credentials::from(config.deref()));
+        (config.auth.auth_error.clone(), credentials)
+    };
+    if let Some(error) = auth_error {
+        shutdown.notify();
+        Ok(tr!(
+            "The authorization failed: {}. The web server is going to stop. You can close this window now.",
+            error
+        ))
+    } else if let Some(credentials) = credentials {
+        let write_result = write_secret_file(credentials_filepath, &credentials);
+        if let Err(err) = write_result {
+            status!("Error writing credentials file: {}", err);
+        }
+        shutdown.notify();
+        Ok(tr!("You successfully authorized the app. The web server is going to stop. You can close this window now."))
+    } else {
+        Err(Redirect::to("/auth"))
+    }
+}
+
+#[get("/auth")]
+pub fn auth(config: &State<SharedConfig>) -> Result<Redirect, Status> {
+    let url = config.read().unwrap().auth.authorization_url();
+    match url {
+        Ok(url) => Ok(Redirect::to(url)),
+        Err(err) => {
+            status_error!("Error building request: {}", err);
+            Err(Status::InternalServerError)
+        }
+    }
+}
+
+#[get("/callback?<state>&<code>&<error>")]
+pub fn callback(
+    config: &State<SharedConfig>,
+    state: String,
+    code: Option<String>,
+    error: Option<String>,
+) -> Redirect {
+    config
+        .write()
+        .unwrap()
+        .auth
+        .receive_callback(&state, code, error);
+    Redirect::to("/done")
+}
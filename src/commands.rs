@@ -0,0 +1,1828 @@
+//! The subcommands running without the TUI; their progress is printed as it happens.
+//! With `--json` their results go to stdout as JSON and the progress goes to stderr.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+
+use chrono::{DateTime, FixedOffset};
+use clap::ValueEnum;
+use futures::stream::{Stream, TryStreamExt};
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::actions::action_report::{in_run_order, CreatedPlaylist, RunEvent};
+use crate::actions::diff::{
+    diff_backups, diff_tracks, BackupDiff, PlaylistName, SnapshotDiff, TrackDiff,
+};
+use crate::actions::export::{
+    backup_playlists, export_liked_songs, export_playlist, followed_artist_pages, liked_song_pages,
+    load_backup, saved_album_pages, Backup, JsonArrayWriter, PartialFile, PlaylistExport,
+    FOLLOWED_ARTISTS_FILE, LIKED_SONGS_FILE, PLAYLISTS_FILE, SAVED_ALBUMS_FILE,
+};
+use crate::actions::playlist_actions::{
+    get_current_user_playlists, get_playlist, get_playlist_items, get_user_playlists,
+    PlaylistVisibility,
+};
+use crate::actions::playlist_builder::PlaylistBuilder;
+use crate::actions::registry::{self, parse_action};
+use crate::actions::restore::{like_tracks, restore_playlist};
+use crate::actions::rules::Rule;
+use crate::actions::search::search_tracks;
+use crate::actions::top_track_playlist::MAX_TOP_TRACKS_LIMIT;
+use crate::actions::{is_dry_run, Task};
+#[cfg(feature = "web-auth")]
+use crate::api::is_valid_origin;
+use crate::authorize::{validate_client_credentials, AuthorizeError};
+use crate::config::{SpautofyConfig, SpautofyConfigFile};
+use crate::dates;
+use crate::git_history::GitHistory;
+use crate::history::{self, RunRecord};
+use crate::history_import::{expand_paths, read_file};
+use crate::i18n::t;
+use crate::integrations::listenbrainz::{self, Listen, ListenbrainzConfig};
+use crate::integrations::service_mapping::{map_tracks, ServiceMapping};
+use crate::metrics;
+use crate::models::playlist::PlaylistItem;
+use crate::models::track::{format_duration, Track};
+use crate::notifications::Notifier;
+use crate::player::get_recently_played;
+use crate::progress;
+use crate::song_list::{fuzzy_matches, read_list, Song, SongMatch};
+use crate::storage;
+#[cfg(feature = "tui")]
+use crate::tui::keymap::Keymap;
+use crate::user_info::{get_user_profile, PublicUser};
+use crate::{
+    spawn_actions, status, status_error, status_log, status_verbose, systemd, tr, MainError,
+    UserAccess,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Everything Spotify knows about the tracks, as read back by `restore` and `diff`
+    Json,
+    /// The ISRC, title, artists and album of every track, for the importers of other
+    /// services like Apple Music, YouTube Music or Tidal
+    Csv,
+    /// The ids of the tracks on MusicBrainz, Apple Music and YouTube Music, found by their
+    /// ISRCs or by searching, for migration tools to add them without searching
+    Mapping,
+}
+
+/// Prints the value pretty, or on one line to keep the `--progress=ndjson` stream intact.
+fn print_json(value: &impl Serialize) {
+    let output = if progress::is_ndjson() {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    };
+    println!("{}", output.expect("Failed to serialize output"));
+}
+
+/// Prints the rows in columns as wide as their widest cell, below the header.
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = header.iter().map(|cell| cell.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let print_row = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+    print_row(header.to_vec());
+    for row in rows {
+        print_row(row.iter().map(String::as_str).collect());
+    }
+}
+
+fn can_read_recently_played(user_access: &UserAccess) -> bool {
+    user_access
+        .access
+        .scopes()
+        .any(|scope| scope == "user-read-recently-played")
+}
+
+/// Spotify only keeps the last 50 played tracks, so every run adds them to the database;
+/// logins from before the scope was asked for are skipped.
+pub async fn archive_recently_played(user_access: &UserAccess) {
+    if !can_read_recently_played(user_access) {
+        return;
+    }
+    match get_recently_played(&user_access.access).await {
+        Ok(played) => storage::record(|storage| {
+            let added = storage.add_played_tracks(&played)?;
+            status_verbose!("Archived {} newly played tracks", added);
+            Ok(())
+        }),
+        Err(err) => status_error!("Could not archive the recently played tracks: {}", err),
+    }
+}
+
+pub async fn run_actions(
+    user_access: UserAccess,
+    // with the name of the pipeline of each
+    actions: Vec<(String, Task)>,
+    history_path: &str,
+    json: bool,
+    // set when running several profiles, to tell their results apart
+    profile: Option<&str>,
+    notifier: &Notifier,
+) -> Result<(), MainError> {
+    let started_at = dates::now();
+    let (pipelines, actions): (Vec<String>, Vec<Task>) = actions.into_iter().unzip();
+    let mut receiver = spawn_actions(user_access.clone(), actions);
+    let mut finished = Vec::new();
+    while let Some(event) = receiver.recv().await {
+        match event {
+            RunEvent::Started(_, action) => {
+                systemd::notify(&format!("STATUS={}", action));
+                progress::emit("action_started", json!({ "action": action }));
+            }
+            RunEvent::Finished(index, report) => {
+                progress::emit(
+                    "action_finished",
+                    json!({ "action": report.action, "result": report.result }),
+                );
+                finished.push((index, report));
+            }
+        }
+    }
+    let reports = in_run_order(finished);
+    let succeeded = reports
+        .iter()
+        .filter(|report| report.result.is_ok())
+        .count();
+    let total = reports.len();
+    status!("{} of {} actions succeeded.", succeeded, total);
+    progress::emit(
+        "run_finished",
+        json!({ "succeeded": succeeded, "total": total }),
+    );
+    match (json, profile) {
+        (true, Some(profile)) => print_json(&json!({ "profile": profile, "reports": reports })),
+        (true, None) => print_json(&reports),
+        (false, _) => {}
+    }
+    notifier
+        .run_finished(started_at, is_dry_run(), &pipelines, &reports)
+        .await;
+    if !is_dry_run() {
+        for report in &reports {
+            if let Ok(playlist) = &report.result {
+                status!("{}: {}", report.action, playlist.url);
+            }
+        }
+        notifier.pipelines_finished(&pipelines, &reports).await;
+        metrics::record_run(&pipelines, &reports);
+        let record = RunRecord::new(started_at, reports);
+        if let Err(err) = history::append(history_path, record) {
+            status_error!("Could not save the run to the history: {}", err);
+        }
+        archive_recently_played(&user_access).await;
+    }
+    if succeeded < total {
+        return Err(MainError::ActionsFailed(tr!(
+            "{} of {} actions failed.",
+            total - succeeded,
+            total
+        )));
+    }
+    Ok(())
+}
+
+/// Lists the playlists whose name matches `filter`, like the '/' filter of the TUI; the
+/// public ones of `user` if given, else the user's own.
+pub async fn list_playlists(
+    user_access: &UserAccess,
+    filter: Option<&str>,
+    owned_only: bool,
+    user: Option<&str>,
+    json: bool,
+) -> Result<(), MainError> {
+    let (owner_id, playlists) = match user {
+        Some(user) => {
+            let profile = get_public_user(user_access, user).await?;
+            if !json {
+                status!(
+                    "{}",
+                    tr!(
+                        "Public playlists of {}:",
+                        profile.display_name.as_deref().unwrap_or(&profile.id)
+                    )
+                );
+            }
+            let playlists = get_user_playlists(user_access, &profile.id, |_| {}).await?;
+            (profile.id, playlists)
+        }
+        None => (
+            user_access.user.id.clone(),
+            get_current_user_playlists(user_access, |_| {}).await?,
+        ),
+    };
+    let playlists: Vec<_> = playlists
+        .into_iter()
+        .filter(|playlist| !owned_only || playlist.owner.id == owner_id)
+        .filter(|playlist| filter.is_none_or(|filter| fuzzy_matches(filter, &playlist.name)))
+        .collect();
+    if json {
+        print_json(&playlists);
+        return Ok(());
+    }
+    let rows: Vec<Vec<String>> = playlists
+        .iter()
+        .map(|playlist| {
+            vec![
+                playlist.id.clone(),
+                playlist.name.clone(),
+                playlist.tracks.total.to_string(),
+                playlist.owner_name().to_string(),
+                t(playlist.visibility()).to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        &[t("Id"), t("Name"), t("Tracks"), t("Owner"), t("Visibility")],
+        &rows,
+    );
+    Ok(())
+}
+
+/// Opens the repository of `--git` before anything is fetched, so a wrong path fails early.
+fn open_git_history(git: Option<&str>) -> Result<Option<GitHistory>, MainError> {
+    git.map(|path| {
+        GitHistory::open_or_init(path).map_err(|err| {
+            MainError::Io(io::Error::other(tr!(
+                "Could not open the git repository {}: {}",
+                path,
+                err
+            )))
+        })
+    })
+    .transpose()
+}
+
+/// Commits the files written into the repository, with the time of the backup as the
+/// message; returns the id of the commit, if anything changed.
+fn commit_backup(
+    git_history: &GitHistory,
+    files: &[&str],
+    time: DateTime<FixedOffset>,
+) -> Result<Option<String>, MainError> {
+    let message = time.format("%Y-%m-%d %H:%M:%S").to_string();
+    let commit = git_history.commit(files, &message).map_err(|err| {
+        MainError::Io(io::Error::other(tr!(
+            "Could not commit the backup: {}",
+            err
+        )))
+    })?;
+    match commit {
+        Some(commit) => status!("Committed the backup as {}.", commit),
+        None => status!("Nothing changed since the last backup, made no commit."),
+    }
+    Ok(commit.map(|commit| commit.to_string()))
+}
+
+/// The migration format: the ISRC, which identifies a recording on every service, then
+/// what to search for without one.
+const MIGRATION_CSV_HEADER: &str = "isrc,title,artist,album,playlist";
+
+fn migration_csv_row(playlist: &str, track: &Track) -> String {
+    let artists: Vec<&str> = track
+        .artists
+        .iter()
+        .map(|artist| artist.name.as_str())
+        .collect();
+    let fields = [
+        track.external_ids.isrc.as_deref().unwrap_or_default(),
+        &track.name,
+        &artists.join(", "),
+        &track.album.name,
+        playlist,
+    ];
+    let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+    fields.join(",")
+}
+
+fn migration_csv(playlist: &str, tracks: &[Track]) -> String {
+    let mut lines = vec![MIGRATION_CSV_HEADER.to_string()];
+    lines.extend(
+        tracks
+            .iter()
+            .map(|track| migration_csv_row(playlist, track)),
+    );
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn report_missing_isrcs(without_isrc: usize) {
+    if without_isrc > 0 {
+        status!(
+            "{} tracks have no ISRC, other services can only search for their titles.",
+            without_isrc
+        );
+    }
+}
+
+/// Writes the liked songs to the file page by page, since there can be tens of thousands.
+async fn export_liked_songs_to_file(
+    user_access: &UserAccess,
+    output: Option<String>,
+    git_history: Option<GitHistory>,
+    format: ExportFormat,
+    time: DateTime<FixedOffset>,
+) -> Result<(), MainError> {
+    let file = match format {
+        ExportFormat::Csv => "liked-songs.csv",
+        _ => "liked-songs.json",
+    };
+    let output = match (&git_history, output) {
+        (Some(git_history), _) => git_history.path(file),
+        (None, Some(output)) => PathBuf::from(output),
+        (None, None) => PathBuf::from(file),
+    };
+    let mut pages = pin!(liked_song_pages(user_access, |_| {}));
+    let (count, without_isrc) = if format == ExportFormat::Csv {
+        let playlist = t("Liked Songs");
+        let mut out = PartialFile::create(&output)?;
+        writeln!(out, "{}", MIGRATION_CSV_HEADER)?;
+        let (mut count, mut without_isrc) = (0, 0);
+        while let Some(page) = pages.try_next().await? {
+            for saved in &page {
+                writeln!(out, "{}", migration_csv_row(playlist, &saved.track))?;
+                if saved.track.external_ids.isrc.is_none() {
+                    without_isrc += 1;
+                }
+            }
+            count += page.len();
+        }
+        out.persist()?;
+        (count, without_isrc)
+    } else {
+        let mut writer = JsonArrayWriter::new(PartialFile::create(&output)?);
+        while let Some(page) = pages.try_next().await? {
+            for saved in &page {
+                writer.push(saved)?;
+            }
+        }
+        let (count, out) = writer.finish()?;
+        out.persist()?;
+        (count, 0)
+    };
+    status!(
+        "Exported {} liked songs to \"{}\".",
+        count,
+        output.display()
+    );
+    report_missing_isrcs(without_isrc);
+    if let Some(git_history) = &git_history {
+        commit_backup(git_history, &[file], time)?;
+    }
+    Ok(())
+}
+
+/// Exports the playlist, or the liked songs without one, to `output`, by default
+/// `<playlist id>.json`; with `json` it is printed instead, and only written if `output`
+/// is given.
+pub async fn export(
+    user_access: &UserAccess,
+    playlist_id: Option<&str>,
+    output: Option<String>,
+    git: Option<&str>,
+    format: ExportFormat,
+    json: bool,
+) -> Result<(), MainError> {
+    let time = dates::now();
+    let git_history = open_git_history(git)?;
+    if playlist_id.is_none() && !json && format != ExportFormat::Mapping {
+        status!("Exporting your liked songs...");
+        return export_liked_songs_to_file(user_access, output, git_history, format, time).await;
+    }
+    let (stem, name, export, tracks) = match playlist_id {
+        Some(playlist_id) => {
+            status!("Exporting playlist {}...", playlist_id);
+            let export = export_playlist(user_access, playlist_id, |_| {}).await?;
+            let value = serde_json::to_value(&export).expect("Failed to serialize playlist");
+            (playlist_id, export.name, value, export.tracks)
+        }
+        None => {
+            status!("Exporting your liked songs...");
+            let liked_songs = export_liked_songs(user_access, |_| {}).await?;
+            let value =
+                serde_json::to_value(&liked_songs).expect("Failed to serialize liked songs");
+            let tracks = liked_songs.into_iter().map(|saved| saved.track).collect();
+            ("liked-songs", t("Liked Songs").to_string(), value, tracks)
+        }
+    };
+    let (file, content, export) = match format {
+        ExportFormat::Json => (
+            format!("{}.json", stem),
+            serde_json::to_string_pretty(&export).expect("Failed to serialize export"),
+            export,
+        ),
+        ExportFormat::Csv => (
+            format!("{}.csv", stem),
+            migration_csv(&name, &tracks),
+            export,
+        ),
+        ExportFormat::Mapping => {
+            let mapping = ServiceMapping {
+                name: name.clone(),
+                spotify_id: playlist_id.map(String::from),
+                tracks: map_tracks(&tracks, user_access.user.country.as_deref()).await,
+            };
+            (
+                format!("{}.mapping.json", stem),
+                serde_json::to_string_pretty(&mapping).expect("Failed to serialize mapping"),
+                serde_json::to_value(&mapping).expect("Failed to serialize mapping"),
+            )
+        }
+    };
+    if json {
+        print_json(&export);
+    }
+    let output = match (&git_history, output) {
+        (Some(git_history), _) => git_history.path(&file),
+        (None, Some(output)) => PathBuf::from(output),
+        (None, None) if json => return Ok(()),
+        (None, None) => PathBuf::from(&file),
+    };
+    fs::write(&output, content)?;
+    if playlist_id.is_some() {
+        status!(
+            "Exported playlist \"{}\" with {} tracks to \"{}\".",
+            name,
+            tracks.len(),
+            output.display()
+        );
+    } else {
+        status!(
+            "Exported {} liked songs to \"{}\".",
+            tracks.len(),
+            output.display()
+        );
+    }
+    if format != ExportFormat::Json {
+        let without_isrc = tracks
+            .iter()
+            .filter(|track| track.external_ids.isrc.is_none())
+            .count();
+        report_missing_isrcs(without_isrc);
+    }
+    if let Some(git_history) = &git_history {
+        commit_backup(git_history, &[&file], time)?;
+    }
+    Ok(())
+}
+
+/// With `git`, the playlists are written as `PLAYLISTS_FILE` like by `backup_all`, so the
+/// repository can be restored and diffed like a backup directory.
+pub async fn backup(
+    user_access: &UserAccess,
+    output: &str,
+    git: Option<&str>,
+    json: bool,
+) -> Result<(), MainError> {
+    let time = dates::now();
+    let git_history = open_git_history(git)?;
+    status!("Backing up your playlists...");
+    let output = match &git_history {
+        Some(git_history) => git_history.path(PLAYLISTS_FILE),
+        None => PathBuf::from(output),
+    };
+    let playlists = write_playlists(user_access, &output).await?;
+    status!(
+        "Backed up {} playlists to \"{}\".",
+        playlists,
+        output.display()
+    );
+    let commit = match &git_history {
+        Some(git_history) => commit_backup(git_history, &[PLAYLISTS_FILE], time)?,
+        None => None,
+    };
+    if json {
+        print_json(&json!({
+            "output": output,
+            "playlists": playlists,
+            "commit": commit,
+        }));
+    }
+    Ok(())
+}
+
+/// Writes the playlists with their tracks to the file as they are exported, so only one
+/// playlist is held at a time; returns how many there were.
+async fn write_playlists(user_access: &UserAccess, path: &Path) -> Result<usize, MainError> {
+    let playlists = get_current_user_playlists(user_access, |_| {}).await?;
+    let total = playlists.len();
+    let mut writer = JsonArrayWriter::new(PartialFile::create(path)?);
+    let mut exports = pin!(backup_playlists(user_access, playlists));
+    let mut done = 0;
+    while let Some(export) = exports.try_next().await? {
+        writer.push(&export)?;
+        done += 1;
+        status_verbose!("{} of {} playlists done", done, total);
+    }
+    let (count, out) = writer.finish()?;
+    out.persist()?;
+    Ok(count)
+}
+
+/// Writes every item of every page to the file as the pages come in; returns how many
+/// there were.
+async fn write_pages<T: Serialize>(
+    path: &Path,
+    pages: impl Stream<Item = Result<Vec<T>, AuthorizeError>>,
+) -> Result<usize, MainError> {
+    let mut writer = JsonArrayWriter::new(PartialFile::create(path)?);
+    let mut pages = pin!(pages);
+    while let Some(page) = pages.try_next().await? {
+        for item in &page {
+            writer.push(item)?;
+        }
+    }
+    let (count, out) = writer.finish()?;
+    out.persist()?;
+    Ok(count)
+}
+
+/// Writes the playlists, liked songs, followed artists and saved albums to their own
+/// files in a new directory named after the current time, inside `parent`; with `git`
+/// into the repository instead, over the files of the last backup.
+pub async fn backup_all(
+    user_access: &UserAccess,
+    parent: &str,
+    git: Option<&str>,
+    json: bool,
+) -> Result<(), MainError> {
+    const PARTS: usize = 4;
+    let time = dates::now();
+    let git_history = open_git_history(git)?;
+    let directory = match &git_history {
+        Some(git_history) => git_history.directory().to_path_buf(),
+        None => {
+            Path::new(parent).join(time.format("spautofy-backup-%Y-%m-%d_%H-%M-%S").to_string())
+        }
+    };
+    fs::create_dir_all(&directory)?;
+    status!("Backing up your library to \"{}\"...", directory.display());
+    let part_done = |done: usize, file: &str, count: usize| {
+        status!(
+            "{} of {} parts done: {} entries in {}",
+            done,
+            PARTS,
+            count,
+            file
+        );
+        progress::emit(
+            "backup_part_done",
+            json!({ "done": done, "total": PARTS, "file": file, "entries": count }),
+        );
+    };
+
+    let playlists = write_playlists(user_access, &directory.join(PLAYLISTS_FILE)).await?;
+    part_done(1, PLAYLISTS_FILE, playlists);
+
+    let liked_songs = write_pages(
+        &directory.join(LIKED_SONGS_FILE),
+        liked_song_pages(user_access, |_| {}),
+    )
+    .await?;
+    part_done(2, LIKED_SONGS_FILE, liked_songs);
+
+    let followed_artists = write_pages(
+        &directory.join(FOLLOWED_ARTISTS_FILE),
+        followed_artist_pages(user_access, |_| {}),
+    )
+    .await?;
+    part_done(3, FOLLOWED_ARTISTS_FILE, followed_artists);
+
+    let saved_albums = write_pages(
+        &directory.join(SAVED_ALBUMS_FILE),
+        saved_album_pages(user_access, |_| {}),
+    )
+    .await?;
+    part_done(4, SAVED_ALBUMS_FILE, saved_albums);
+
+    status!("Backed up your library to \"{}\".", directory.display());
+    let files = [
+        PLAYLISTS_FILE,
+        LIKED_SONGS_FILE,
+        FOLLOWED_ARTISTS_FILE,
+        SAVED_ALBUMS_FILE,
+    ];
+    let commit = match &git_history {
+        Some(git_history) => commit_backup(git_history, &files, time)?,
+        None => None,
+    };
+    if json {
+        print_json(&json!({
+            "directory": directory,
+            "playlists": playlists,
+            "liked_songs": liked_songs,
+            "followed_artists": followed_artists,
+            "saved_albums": saved_albums,
+            "commit": commit,
+        }));
+    }
+    Ok(())
+}
+
+/// The profile of the user given by their id or a link like
+/// `https://open.spotify.com/user/<id>`.
+async fn get_public_user(user_access: &UserAccess, user: &str) -> Result<PublicUser, MainError> {
+    let id = user_id_from_link(user).unwrap_or(user);
+    match get_user_profile(user_access, id).await {
+        Err(AuthorizeError::RequestError(err)) if err.status() == Some(StatusCode::NOT_FOUND) => {
+            Err(MainError::NotFound(tr!("There is no user \"{}\".", id)))
+        }
+        profile => Ok(profile?),
+    }
+}
+
+/// The id in a link like `https://open.spotify.com/user/<id>?si=...` or
+/// `spotify:user:<id>`.
+fn user_id_from_link(link: &str) -> Option<&str> {
+    let rest = link.strip_prefix("spotify:user:").or_else(|| {
+        link.split_once("open.spotify.com/user/")
+            .map(|(_, rest)| rest)
+    })?;
+    rest.split(['?', '/']).next().filter(|id| !id.is_empty())
+}
+
+/// The id in a link like `https://open.spotify.com/playlist/<id>?si=...` or
+/// `spotify:playlist:<id>`.
+fn id_from_link(link: &str) -> Option<&str> {
+    let rest = link.strip_prefix("spotify:playlist:").or_else(|| {
+        link.split_once("open.spotify.com/playlist/")
+            .map(|(_, rest)| rest)
+    })?;
+    rest.split(['?', '/']).next().filter(|id| !id.is_empty())
+}
+
+fn looks_like_id(text: &str) -> bool {
+    text.len() == 22 && text.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// The id of the playlist given by its id or link, or by the name of one of the user's
+/// playlists; an exact name wins over names only matching like the '/' filter.
+async fn resolve_playlist(user_access: &UserAccess, query: &str) -> Result<String, MainError> {
+    if let Some(id) = id_from_link(query) {
+        return Ok(id.to_string());
+    }
+    let playlists = get_current_user_playlists(user_access, |_| {}).await?;
+    if let Some(playlist) = playlists.iter().find(|playlist| playlist.id == query) {
+        return Ok(playlist.id.clone());
+    }
+    let mut matches: Vec<_> = playlists
+        .iter()
+        .filter(|playlist| playlist.name.to_lowercase() == query.to_lowercase())
+        .collect();
+    if matches.is_empty() {
+        matches = playlists
+            .iter()
+            .filter(|playlist| fuzzy_matches(query, &playlist.name))
+            .collect();
+    }
+    match matches.as_slice() {
+        [playlist] => Ok(playlist.id.clone()),
+        // not one of the user's playlists, but it may still be someone else's
+        [] if looks_like_id(query) => Ok(query.to_string()),
+        [] => Err(MainError::NotFound(tr!(
+            "None of your playlists matches \"{}\".",
+            query
+        ))),
+        matches => {
+            let names: Vec<String> = matches
+                .iter()
+                .map(|playlist| format!("{} ({})", playlist.name, playlist.id))
+                .collect();
+            Err(MainError::NotFound(tr!(
+                "\"{}\" matches {} playlists, use one of their ids: {}",
+                query,
+                matches.len(),
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+/// Position, name, artists, album, duration, date added and id of the track; `None` if
+/// the track is no longer available.
+fn track_row(position: usize, item: &PlaylistItem) -> Option<Vec<String>> {
+    let track = item.track.as_ref()?;
+    let artists: Vec<&str> = track
+        .artists
+        .iter()
+        .map(|artist| artist.name.as_str())
+        .collect();
+    let added_at = item.added_at.as_deref().unwrap_or_default();
+    Some(vec![
+        position.to_string(),
+        track.name.clone(),
+        artists.join(", "),
+        track.album.name.clone(),
+        format_duration(track.duration_ms),
+        // only the date of the timestamp
+        added_at.get(..10).unwrap_or(added_at).to_string(),
+        track.id.clone(),
+    ])
+}
+
+/// Quotes the field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub async fn show_playlist(
+    user_access: &UserAccess,
+    query: &str,
+    format: OutputFormat,
+) -> Result<(), MainError> {
+    if format != OutputFormat::Table {
+        status_log::use_stderr();
+    }
+    let playlist_id = resolve_playlist(user_access, query).await?;
+    let playlist = get_playlist(user_access, &playlist_id).await?;
+    let items = get_playlist_items(user_access, &playlist_id, |progress| {
+        status_verbose!("{} of {} tracks loaded", progress.done, progress.total);
+    })
+    .await?;
+    let rows: Vec<Vec<String>> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| track_row(index + 1, item))
+        .collect();
+    match format {
+        OutputFormat::Json => print_json(&json!({
+            "id": playlist.id,
+            "name": playlist.name,
+            "description": playlist.description,
+            "public": playlist.public,
+            "collaborative": playlist.collaborative,
+            "tracks": items,
+        })),
+        OutputFormat::Csv => {
+            println!("position,name,artists,album,duration,added_at,id");
+            for row in rows {
+                let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+                println!("{}", fields.join(","));
+            }
+        }
+        OutputFormat::Table => {
+            println!("{}", tr!("{} ({} tracks)", playlist.name, items.len()));
+            println!();
+            print_table(
+                &[
+                    "#",
+                    t("Name"),
+                    t("Artists"),
+                    t("Album"),
+                    t("Duration"),
+                    t("Added"),
+                    t("Id"),
+                ],
+                &rows,
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn whoami(user_access: &UserAccess, json: bool) {
+    let user = &user_access.user;
+    if json {
+        print_json(user);
+        return;
+    }
+    let unknown = || t("unknown").to_string();
+    println!("{}", tr!("Name: {}", user.display_name));
+    println!("{}", tr!("Id: {}", user.id));
+    println!(
+        "{}",
+        tr!("Country: {}", user.country.clone().unwrap_or_else(unknown))
+    );
+    println!(
+        "{}",
+        tr!(
+            "Subscription: {}",
+            user.product.clone().unwrap_or_else(unknown)
+        )
+    );
+    if user.product.is_none() {
+        status!("Log in again with `spautofy auth` to see the country and subscription.");
+    }
+}
+
+/// Prints the access token, which was refreshed when logging in; fails if it lacks any of
+/// the `required_scopes`.
+pub fn token(
+    user_access: &UserAccess,
+    required_scopes: &[String],
+    json: bool,
+) -> Result<(), MainError> {
+    let access = &user_access.access;
+    let missing: Vec<&str> = required_scopes
+        .iter()
+        .map(String::as_str)
+        .filter(|scope| !access.scopes().any(|granted| granted == *scope))
+        .collect();
+    if !missing.is_empty() {
+        return Err(MainError::NotLoggedIn(tr!(
+            "The access token lacks the scopes {}, it was granted: {}",
+            missing.join(", "),
+            access.scopes().collect::<Vec<_>>().join(", ")
+        )));
+    }
+    if json {
+        print_json(&json!({
+            "access_token": access.access_token(),
+            "expires_in": access.remaining_secs(),
+            "scopes": access.scopes().collect::<Vec<_>>(),
+        }));
+    } else {
+        println!("{}", access.access_token());
+    }
+    Ok(())
+}
+
+/// How many plays of a file were imported, for `import-history --json`.
+#[derive(Debug, Serialize)]
+struct ImportedHistory {
+    file: String,
+    plays: usize,
+    imported: usize,
+    skipped: usize,
+}
+
+/// Imports the plays into the history database, leaving out the ones imported before;
+/// needs no login.
+pub fn import_history(paths: &[String], json: bool) -> Result<(), MainError> {
+    let mut results = Vec::new();
+    for path in expand_paths(paths)? {
+        let file = path.to_string_lossy().into_owned();
+        let imported = read_file(&path)
+            .map_err(|err| io::Error::new(err.kind(), tr!("Error reading {}: {}", file, err)))?;
+        let mut added = 0;
+        storage::record(|storage| {
+            added = storage.add_plays(&imported.plays)?;
+            Ok(())
+        });
+        if is_dry_run() {
+            status!("Would import {} plays from {}.", imported.plays.len(), file);
+        } else if added == imported.plays.len() {
+            status!("Imported {} plays from {}.", added, file);
+        } else {
+            status!(
+                "Imported {} of {} plays from {}, the others were imported before.",
+                added,
+                imported.plays.len(),
+                file
+            );
+        }
+        if imported.skipped > 0 {
+            status_verbose!(
+                "Left out {} entries of {}: short plays, episodes or lines without a track \
+                or date.",
+                imported.skipped,
+                file
+            );
+        }
+        results.push(ImportedHistory {
+            file,
+            plays: imported.plays.len(),
+            imported: added,
+            skipped: imported.skipped,
+        });
+    }
+    if json {
+        print_json(&results);
+    }
+    Ok(())
+}
+
+/// How many tracks are found per song, the more likely ones to choose from when unsure.
+const SONG_CANDIDATES: u32 = 5;
+
+/// The songs of the list in the file, an error if it names none.
+pub fn read_song_list(path: &str) -> io::Result<Vec<Song>> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| io::Error::new(err.kind(), tr!("Error reading {}: {}", path, err)))?;
+    let songs = read_list(&content);
+    if songs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            tr!("{} names no songs.", path),
+        ));
+    }
+    Ok(songs)
+}
+
+/// Searches for every song, choosing the most likely track if it is at least as likely as
+/// `min_confidence`; a song with an ISRC is the track with it, if Spotify has one.
+pub async fn match_songs(
+    user_access: &UserAccess,
+    songs: Vec<Song>,
+    min_confidence: f64,
+) -> Result<Vec<SongMatch>, MainError> {
+    let total = songs.len();
+    status!("Searching for {} songs...", total);
+    let mut matches = Vec::new();
+    for (done, song) in songs.into_iter().enumerate() {
+        let by_isrc = match &song.isrc {
+            Some(isrc) => search_tracks(user_access, &format!("isrc:{}", isrc), 1)
+                .await?
+                .pop(),
+            None => None,
+        };
+        let song_match = match by_isrc {
+            Some(track) => SongMatch::by_isrc(song, track),
+            None => {
+                let tracks = search_tracks(user_access, &song.query, SONG_CANDIDATES).await?;
+                SongMatch::new(song, tracks, min_confidence)
+            }
+        };
+        match song_match.candidates.first() {
+            Some(best) => status_verbose!(
+                "\"{}\": {} by {} ({:.0}%)",
+                song_match.song.text,
+                best.track.name,
+                best.track.artist_names(),
+                best.confidence * 100.0
+            ),
+            None => status_verbose!("\"{}\": nothing found", song_match.song.text),
+        }
+        progress::emit(
+            "song_matched",
+            json!({
+                "done": done + 1,
+                "total": total,
+                "line": song_match.song.line,
+                "confidence": song_match.best_confidence(),
+            }),
+        );
+        matches.push(song_match);
+    }
+    Ok(matches)
+}
+
+/// The playlist for the `--json` output, `None` in a dry run where none was created.
+fn created_json(playlist: &CreatedPlaylist) -> Option<Value> {
+    let id = playlist.id.as_ref()?;
+    Some(json!({ "name": playlist.name, "id": id }))
+}
+
+/// A line of the song list, for `import-songs --json`.
+#[derive(Debug, Serialize)]
+struct ImportedSong<'a> {
+    line: usize,
+    text: &'a str,
+    /// The track added, `None` if the line was left out.
+    uri: Option<&'a str>,
+    name: Option<&'a str>,
+    artists: Option<String>,
+    confidence: Option<f64>,
+}
+
+/// Creates a playlist of the tracks chosen for the songs, in the order of the list; a track
+/// chosen for several lines is only added once.
+pub async fn create_song_playlist(
+    user_access: &UserAccess,
+    name: &str,
+    matches: &[SongMatch],
+    visibility: PlaylistVisibility,
+    json: bool,
+) -> Result<(), MainError> {
+    for song_match in matches
+        .iter()
+        .filter(|song_match| song_match.chosen().is_none())
+    {
+        status!(
+            "Left out line {}: \"{}\"",
+            song_match.song.line,
+            song_match.song.text
+        );
+    }
+    // a track chosen for several lines is only added once
+    let playlist = PlaylistBuilder::new(name)
+        .visibility(visibility)
+        .tracks(
+            matches
+                .iter()
+                .filter_map(|song_match| Some(&song_match.chosen()?.track)),
+        )
+        .dedup_by_key(|track| track.uri.clone())
+        .build(user_access)
+        .await?;
+    if !is_dry_run() {
+        status!(
+            "Created playlist \"{}\" with {} of {} songs.",
+            playlist.name,
+            playlist.track_count,
+            matches.len()
+        );
+    }
+    if json {
+        let songs: Vec<ImportedSong> = matches
+            .iter()
+            .map(|song_match| {
+                let chosen = song_match.chosen();
+                ImportedSong {
+                    line: song_match.song.line,
+                    text: &song_match.song.text,
+                    uri: chosen.map(|chosen| chosen.track.uri.as_str()),
+                    name: chosen.map(|chosen| chosen.track.name.as_str()),
+                    artists: chosen.map(|chosen| chosen.track.artist_names()),
+                    confidence: chosen.map(|chosen| chosen.confidence),
+                }
+            })
+            .collect();
+        print_json(&json!({
+            "playlist": created_json(&playlist),
+            "tracks": playlist.track_count,
+            "songs": songs,
+        }));
+    }
+    Ok(())
+}
+
+/// A music file of the local collection, for `scan-library --json`.
+#[derive(Debug, Serialize)]
+struct LibraryFile<'a> {
+    file: &'a str,
+    /// The track on Spotify, `None` if it was not found surely enough.
+    uri: Option<&'a str>,
+    name: Option<&'a str>,
+    artists: Option<String>,
+    confidence: Option<f64>,
+    /// The most likely track of a file not on Spotify, if any was found.
+    best_guess: Option<&'a str>,
+}
+
+/// Reports which files of the local collection are on Spotify, and creates the playlist of
+/// their tracks if it is named. A file counts as on Spotify if a track was chosen for it.
+pub async fn library_report(
+    user_access: &UserAccess,
+    matches: &[SongMatch],
+    playlist: Option<&str>,
+    visibility: PlaylistVisibility,
+    missing_only: bool,
+    json: bool,
+) -> Result<(), MainError> {
+    let found = matches
+        .iter()
+        .filter(|song_match| song_match.chosen().is_some())
+        .count();
+    let created = match playlist {
+        Some(name) => Some(
+            PlaylistBuilder::new(name)
+                .visibility(visibility)
+                .tracks(
+                    matches
+                        .iter()
+                        .filter_map(|song_match| Some(&song_match.chosen()?.track)),
+                )
+                .dedup_by_key(|track| track.uri.clone())
+                .build(user_access)
+                .await?,
+        ),
+        None => None,
+    };
+    if let Some(playlist) = created.as_ref().filter(|_| !is_dry_run()) {
+        status!(
+            "Created playlist \"{}\" with {} tracks.",
+            playlist.name,
+            playlist.track_count
+        );
+    }
+    if json {
+        let files: Vec<LibraryFile> = matches
+            .iter()
+            .filter(|song_match| !missing_only || song_match.chosen().is_none())
+            .map(|song_match| {
+                let chosen = song_match.chosen();
+                LibraryFile {
+                    file: &song_match.song.text,
+                    uri: chosen.map(|chosen| chosen.track.uri.as_str()),
+                    name: chosen.map(|chosen| chosen.track.name.as_str()),
+                    artists: chosen.map(|chosen| chosen.track.artist_names()),
+                    confidence: chosen.map(|chosen| chosen.confidence),
+                    best_guess: match chosen {
+                        Some(_) => None,
+                        None => song_match
+                            .candidates
+                            .first()
+                            .map(|best| best.track.uri.as_str()),
+                    },
+                }
+            })
+            .collect();
+        print_json(&json!({
+            "files": files,
+            "found": found,
+            "total": matches.len(),
+            "playlist": created.as_ref().and_then(created_json),
+        }));
+    } else {
+        let rows: Vec<Vec<String>> = matches
+            .iter()
+            .filter(|song_match| !missing_only || song_match.chosen().is_none())
+            .map(|song_match| {
+                let (status, candidate) = match song_match.chosen() {
+                    Some(chosen) => (t("On Spotify"), Some(chosen)),
+                    None => (t("Missing"), song_match.candidates.first()),
+                };
+                let (track, confidence) = match candidate {
+                    Some(candidate) => (
+                        tr!(
+                            "{} by {}",
+                            candidate.track.name,
+                            candidate.track.artist_names()
+                        ),
+                        format!("{:.0}%", candidate.confidence * 100.0),
+                    ),
+                    None => (String::new(), String::new()),
+                };
+                vec![
+                    status.to_string(),
+                    song_match.song.text.clone(),
+                    track,
+                    confidence,
+                ]
+            })
+            .collect();
+        print_table(
+            &[t("Status"), t("File"), t("Track"), t("Confidence")],
+            &rows,
+        );
+    }
+    status!("{} of {} files are on Spotify.", found, matches.len());
+    Ok(())
+}
+
+/// Submits the recently played tracks to ListenBrainz, only the ones played after its
+/// latest listen, so running it again submits none twice.
+pub async fn submit_listens(
+    user_access: &UserAccess,
+    config: Option<&ListenbrainzConfig>,
+    json: bool,
+) -> Result<(), MainError> {
+    let Some(config) = config else {
+        return Err(MainError::Config(tr!(
+            "Add the ListenBrainz token as token in integrations.listenbrainz of the config \
+            file."
+        )));
+    };
+    if !can_read_recently_played(user_access) {
+        return Err(MainError::NotLoggedIn(tr!(
+            "Log in again with `spautofy auth` to allow reading the recently played tracks."
+        )));
+    }
+    let user_name = listenbrainz::get_user_name(config).await?;
+    let latest = listenbrainz::get_latest_listen(config, &user_name).await?;
+    let played = get_recently_played(&user_access.access).await?;
+    storage::record(|storage| storage.add_played_tracks(&played).map(|_| ()));
+    let listens: Vec<Listen> = played
+        .iter()
+        .filter_map(|played| {
+            let played_at = DateTime::parse_from_rfc3339(&played.played_at).ok()?;
+            Some(Listen {
+                listened_at: played_at.timestamp(),
+                track: &played.track,
+            })
+        })
+        .filter(|listen| latest.is_none_or(|latest| listen.listened_at > latest))
+        .collect();
+    if is_dry_run() {
+        status!(
+            "Would submit {} listens to ListenBrainz as {}.",
+            listens.len(),
+            user_name
+        );
+    } else if listens.is_empty() {
+        status!("ListenBrainz already has all recently played tracks.");
+    } else {
+        listenbrainz::submit_listens(config, &listens).await?;
+        status!(
+            "Submitted {} listens to ListenBrainz as {}.",
+            listens.len(),
+            user_name
+        );
+    }
+    if json {
+        print_json(&json!({
+            "user_name": user_name,
+            "listens": listens.len(),
+            "dry_run": is_dry_run(),
+        }));
+    }
+    Ok(())
+}
+
+fn is_hex_id(text: &str) -> bool {
+    text.len() == 32 && text.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Reports the mistakes in the config that would only show up later, e.g. while logging in.
+/// Warnings are printed but only errors fail the validation.
+pub async fn validate_config(
+    file_config: &SpautofyConfigFile,
+    check_credentials: bool,
+    json: bool,
+) -> Result<(), MainError> {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let (client_id, client_secret) = file_config.credentials();
+    if !is_hex_id(client_id) {
+        errors.push(tr!(
+            "The client ID should be 32 hexadecimal characters, copy it from your app in the \
+            Spotify developer dashboard."
+        ));
+    }
+    if !is_hex_id(client_secret) {
+        errors.push(tr!(
+            "The client secret should be 32 hexadecimal characters, copy it from your app in \
+            the Spotify developer dashboard."
+        ));
+    }
+    let config = SpautofyConfig::from(file_config.clone());
+    if config.auth.port == 0 {
+        errors.push(tr!(
+            "The port must not be 0, Spotify redirects to a fixed port."
+        ));
+    }
+    if !config.auth.address.is_loopback() {
+        warnings.push(tr!(
+            "Spotify only accepts http redirect URLs on loopback addresses like 127.0.0.1, \
+            logging in with {} will fail.",
+            config.auth.address
+        ));
+    }
+    #[cfg(feature = "tui")]
+    if let Err(err) = Keymap::try_from(&file_config.keybindings) {
+        errors.push(tr!("Error in the keybindings: {}", err));
+    }
+    if let Err(err) = file_config.date_settings() {
+        errors.push(err);
+    }
+    let keys = registry::selectable_keys();
+    for selected in file_config.actions.selected.iter().flatten() {
+        if !keys.contains(&selected.as_str()) {
+            errors.push(tr!(
+                "Unknown action \"{}\" in the selected actions, expected one of: {}",
+                selected,
+                keys.join(", ")
+            ));
+        }
+    }
+    let top_tracks = &file_config.actions.top_tracks;
+    if let Some(limit) = top_tracks.limit {
+        if !(1..=MAX_TOP_TRACKS_LIMIT).contains(&limit) {
+            warnings.push(tr!(
+                "The top tracks limit {} is not between 1 and {}, it will be clamped.",
+                limit,
+                MAX_TOP_TRACKS_LIMIT
+            ));
+        }
+    }
+    if top_tracks.name_template.as_deref() == Some("") {
+        errors.push(tr!(
+            "The top tracks name template is empty, remove it to use the default."
+        ));
+    }
+    if top_tracks.public == Some(true) && top_tracks.collaborative == Some(true) {
+        errors.push(tr!(
+            "The top tracks playlists cannot be public and collaborative at once."
+        ));
+    }
+    if top_tracks.time_ranges.as_ref().is_some_and(Vec::is_empty) {
+        warnings.push(tr!(
+            "The top tracks time ranges are empty, the TUI starts with none selected."
+        ));
+    }
+    for (time_range, id) in &top_tracks.targets {
+        if !looks_like_id(id) {
+            errors.push(tr!(
+                "The top tracks target for {} is no playlist id: \"{}\"",
+                time_range,
+                id
+            ));
+        }
+    }
+    for (pipeline, steps) in &file_config.pipelines {
+        if steps.is_empty() {
+            warnings.push(tr!("Pipeline \"{}\" has no steps.", pipeline));
+        }
+        for (index, step) in steps.iter().enumerate() {
+            if let Err(err) = step.params(&file_config.actions) {
+                errors.push(tr!(
+                    "Error in step {} of pipeline \"{}\": {}",
+                    index + 1,
+                    pipeline,
+                    err
+                ));
+            }
+        }
+    }
+    if let Some(lastfm) = &file_config.integrations.lastfm {
+        if lastfm.api_key.trim().is_empty() || lastfm.user.trim().is_empty() {
+            errors.push(tr!("The Last.fm integration needs an API key and a user."));
+        }
+    }
+    if let Some(listenbrainz) = &file_config.integrations.listenbrainz {
+        if listenbrainz.token.trim().is_empty() {
+            errors.push(tr!("The ListenBrainz integration needs a token."));
+        }
+    }
+    #[cfg(feature = "web-auth")]
+    if let Some(api) = &file_config.api {
+        if api.token.trim().is_empty() {
+            errors.push(tr!("The API needs a token, e.g. in $SPAUTOFY_API_TOKEN."));
+        }
+        for origin in &api.cors_origins {
+            if !is_valid_origin(origin) {
+                errors.push(tr!(
+                    "The CORS origin \"{}\" is neither * nor like http://localhost:5173.",
+                    origin
+                ));
+            }
+        }
+    }
+    for webhook in &file_config.notifications.webhooks {
+        match reqwest::Url::parse(&webhook.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => errors.push(tr!(
+                "The webhook URL \"{}\" is no http or https URL.",
+                webhook.url
+            )),
+        }
+    }
+    for discord in &file_config.notifications.discord {
+        if !discord.webhook_url.starts_with("https://") {
+            errors.push(tr!(
+                "The Discord webhook URL \"{}\" is no https URL.",
+                discord.webhook_url
+            ));
+        }
+    }
+    for slack in &file_config.notifications.slack {
+        if !slack.webhook_url.starts_with("https://") {
+            errors.push(tr!(
+                "The Slack webhook URL \"{}\" is no https URL.",
+                slack.webhook_url
+            ));
+        }
+        for pipeline in &slack.pipelines {
+            let known = file_config.pipelines.contains_key(pipeline)
+                || file_config.rules.contains_key(pipeline)
+                || parse_action(pipeline).is_ok();
+            if !known {
+                errors.push(tr!(
+                    "The Slack notifications name \"{}\", which is no pipeline, rule or \
+                    action.",
+                    pipeline
+                ));
+            }
+        }
+    }
+    if let Some(email) = &file_config.notifications.email {
+        if let Err(err) = email.mailboxes() {
+            errors.push(tr!("Error in the email notifications: {}", err));
+        }
+        if email.username.is_some() && email.password.is_none() {
+            warnings.push(tr!(
+                "The email notifications have a username but no password, set \
+                $SPAUTOFY_SMTP_PASSWORD."
+            ));
+        }
+    }
+    if let Some(ntfy) = &file_config.notifications.ntfy {
+        if ntfy.topic.trim().is_empty() {
+            errors.push(tr!("The ntfy notifications need a topic."));
+        }
+        match reqwest::Url::parse(ntfy.server()) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => errors.push(tr!(
+                "The ntfy server \"{}\" is no http or https URL.",
+                ntfy.server()
+            )),
+        }
+    }
+    if let Some(pushover) = &file_config.notifications.pushover {
+        if pushover.token.trim().is_empty() || pushover.user.trim().is_empty() {
+            errors.push(tr!(
+                "The Pushover notifications need the API token of an application, e.g. in \
+                $SPAUTOFY_PUSHOVER_TOKEN, and a user key."
+            ));
+        }
+    }
+    if let Some(mqtt) = &file_config.notifications.mqtt {
+        if mqtt.host.trim().is_empty() {
+            errors.push(tr!("The MQTT notifications need the host of the broker."));
+        }
+        if mqtt.prefix().is_empty() || mqtt.prefix().contains(['+', '#']) {
+            errors.push(tr!(
+                "The MQTT topic \"{}\" must not be empty or contain '+' or '#'.",
+                mqtt.prefix()
+            ));
+        }
+        if mqtt.username.is_some() && mqtt.password.is_none() {
+            warnings.push(tr!(
+                "The MQTT notifications have a username but no password, set \
+                $SPAUTOFY_MQTT_PASSWORD."
+            ));
+        }
+    }
+    if let Some(healthcheck_url) = &file_config.notifications.healthcheck_url {
+        let url = healthcheck_url.replace("{pipeline}", "pipeline");
+        match reqwest::Url::parse(&url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            _ => errors.push(tr!(
+                "The healthcheck URL \"{}\" is no http or https URL.",
+                healthcheck_url
+            )),
+        }
+    }
+    for (name, rule) in &file_config.rules {
+        if let Err(err) = Rule::parse(name, rule, &file_config.integrations) {
+            errors.push(tr!("Error in rule \"{}\": {}", name, err));
+        }
+        if file_config.pipelines.contains_key(name) {
+            warnings.push(tr!(
+                "Rule \"{}\" has the name of a pipeline, `spautofy run {}` runs the pipeline.",
+                name,
+                name
+            ));
+        }
+        if name.contains(':') || name.contains(',') {
+            errors.push(tr!(
+                "Rule \"{}\" cannot be run, its name must not contain ':' or ','.",
+                name
+            ));
+        }
+    }
+    if config.auth.refresh_token.is_none() {
+        warnings.push(tr!(
+            "Not logged in yet, run `spautofy auth` before using the other commands."
+        ));
+    }
+    if check_credentials
+        && errors.is_empty()
+        && !validate_client_credentials(client_id, client_secret).await?
+    {
+        errors.push(tr!(
+            "Spotify rejected the client ID or client secret, check them in the Spotify \
+            developer dashboard."
+        ));
+    }
+
+    let redirect_url = config.auth.redirect_url();
+    if json {
+        print_json(&json!({
+            "redirect_url": redirect_url,
+            "errors": errors,
+            "warnings": warnings,
+        }));
+    }
+    for warning in &warnings {
+        status!("Warning: {}", warning);
+    }
+    for error in &errors {
+        status_error!("Error: {}", error);
+    }
+    status!(
+        "Add {} as a redirect URL of your app in the Spotify developer dashboard.",
+        redirect_url
+    );
+    if !errors.is_empty() {
+        return Err(MainError::Config(tr!(
+            "The config file has {} errors.",
+            errors.len()
+        )));
+    }
+    status!("The config file is valid.");
+    Ok(())
+}
+
+/// Keeps the playlists with a name or id from `playlists`, and the liked songs if
+/// `liked_songs` is set.
+pub fn select_from_backup(
+    backup: Backup,
+    playlists: &[String],
+    liked_songs: bool,
+) -> Result<Backup, MainError> {
+    let is_selected = |export: &PlaylistExport| {
+        playlists.contains(&export.name) || playlists.contains(&export.id)
+    };
+    if let Some(missing) = playlists.iter().find(|playlist| {
+        !backup
+            .playlists
+            .iter()
+            .any(|export| export.name == **playlist || export.id == **playlist)
+    }) {
+        return Err(MainError::NotFound(tr!(
+            "The backup has no playlist \"{}\".",
+            missing
+        )));
+    }
+    Ok(Backup {
+        playlists: backup.playlists.into_iter().filter(is_selected).collect(),
+        liked_songs: if liked_songs {
+            backup.liked_songs
+        } else {
+            Vec::new()
+        },
+    })
+}
+
+/// Recreates the playlists of the backup as new playlists and likes its liked songs again;
+/// a failing playlist does not stop the others.
+pub async fn restore(
+    user_access: &UserAccess,
+    backup: Backup,
+    json: bool,
+) -> Result<(), MainError> {
+    let mut restored = Vec::new();
+    let mut failed = 0;
+    for export in &backup.playlists {
+        match restore_playlist(user_access, export).await {
+            Ok(playlist) => restored.push(json!({
+                "name": export.name,
+                "id": playlist.id,
+            })),
+            Err(err) => {
+                failed += 1;
+                status_error!("Could not restore playlist \"{}\": {}", export.name, err);
+            }
+        }
+    }
+    // the backup lists the newest first, liking the oldest first keeps that order
+    let track_ids: Vec<&str> = backup
+        .liked_songs
+        .iter()
+        .rev()
+        .map(|saved| saved.track.id.as_str())
+        .collect();
+    if !track_ids.is_empty() {
+        like_tracks(user_access, &track_ids).await?;
+    }
+    if json {
+        print_json(&json!({ "playlists": restored, "liked_songs": track_ids.len() }));
+    }
+    if failed > 0 {
+        return Err(MainError::ActionsFailed(tr!(
+            "{} of {} playlists could not be restored.",
+            failed,
+            backup.playlists.len()
+        )));
+    }
+    Ok(())
+}
+
+/// One side of `spautofy diff`: a backup on disk, or a playlist to fetch from Spotify.
+pub enum DiffSource {
+    Backup(Backup),
+    Playlist(String),
+}
+
+impl DiffSource {
+    /// A backup if `source` is a path that exists, otherwise a playlist as for
+    /// `playlists show`.
+    pub fn open(source: &str) -> io::Result<Self> {
+        if !Path::new(source).exists() {
+            return Ok(DiffSource::Playlist(source.to_string()));
+        }
+        load_backup(source).map(DiffSource::Backup).map_err(|err| {
+            io::Error::new(err.kind(), tr!("Error reading backup {}: {}", source, err))
+        })
+    }
+
+    pub fn is_playlist(&self) -> bool {
+        matches!(self, DiffSource::Playlist(_))
+    }
+}
+
+fn pick_from_backup(backup: Backup, playlist: &str) -> Result<PlaylistExport, MainError> {
+    backup
+        .playlists
+        .into_iter()
+        .find(|export| export.name == playlist || export.id == playlist)
+        .ok_or_else(|| MainError::NotFound(tr!("The backup has no playlist \"{}\".", playlist)))
+}
+
+async fn fetch_snapshot(
+    user_access: Option<&UserAccess>,
+    query: &str,
+) -> Result<PlaylistExport, MainError> {
+    let user_access = user_access.expect("logged in to compare playlists");
+    let playlist_id = resolve_playlist(user_access, query).await?;
+    let export = export_playlist(user_access, &playlist_id, |progress| {
+        status_verbose!("{} of {} tracks loaded", progress.done, progress.total);
+    })
+    .await?;
+    Ok(export)
+}
+
+fn print_track_diff(diff: &TrackDiff, indent: &str) {
+    for track in &diff.removed {
+        println!(
+            "{}- {:>4}  {} - {}",
+            indent, track.position, track.name, track.artists
+        );
+    }
+    for track in &diff.added {
+        println!(
+            "{}+ {:>4}  {} - {}",
+            indent, track.position, track.name, track.artists
+        );
+    }
+    for track in &diff.moved {
+        println!(
+            "{}~ {:>4}  {} - {} ({})",
+            indent,
+            track.to,
+            track.name,
+            track.artists,
+            tr!("was {}", track.from)
+        );
+    }
+}
+
+fn print_backup_diff(diff: &BackupDiff) {
+    for playlist in &diff.removed {
+        println!(
+            "- {}",
+            tr!(
+                "Playlist \"{}\" ({} tracks)",
+                playlist.name,
+                playlist.tracks
+            )
+        );
+    }
+    for playlist in &diff.added {
+        println!(
+            "+ {}",
+            tr!(
+                "Playlist \"{}\" ({} tracks)",
+                playlist.name,
+                playlist.tracks
+            )
+        );
+    }
+    for playlist in &diff.changed {
+        if playlist.old_name == playlist.new_name {
+            println!("~ {}", tr!("Playlist \"{}\":", playlist.new_name));
+        } else {
+            println!(
+                "~ {}",
+                tr!(
+                    "Playlist \"{}\", renamed from \"{}\":",
+                    playlist.new_name,
+                    playlist.old_name
+                )
+            );
+        }
+        print_track_diff(&playlist.tracks, "    ");
+    }
+}
+
+/// Prints the tracks added, removed and moved from `old` to `new`; two backups without
+/// `playlist` are compared playlist by playlist. Differences are an error, so scripts can
+/// check the exit code.
+pub async fn diff(
+    user_access: Option<&UserAccess>,
+    old: DiffSource,
+    new: DiffSource,
+    playlist: Option<&str>,
+    json: bool,
+) -> Result<(), MainError> {
+    let (old, new) = match (old, new) {
+        (DiffSource::Backup(old), DiffSource::Backup(new)) => match playlist {
+            Some(playlist) => (
+                pick_from_backup(old, playlist)?,
+                pick_from_backup(new, playlist)?,
+            ),
+            None => {
+                let diff = diff_backups(&old, &new);
+                if json {
+                    print_json(&diff);
+                } else {
+                    print_backup_diff(&diff);
+                }
+                return differences(diff.count());
+            }
+        },
+        // without `playlist` the backup is searched for the playlist it is compared with
+        (DiffSource::Playlist(old), DiffSource::Backup(new)) => {
+            let old = fetch_snapshot(user_access, &old).await?;
+            let new = pick_from_backup(new, playlist.unwrap_or(&old.id))?;
+            (old, new)
+        }
+        (DiffSource::Backup(old), DiffSource::Playlist(new)) => {
+            let new = fetch_snapshot(user_access, &new).await?;
+            let old = pick_from_backup(old, playlist.unwrap_or(&new.id))?;
+            (old, new)
+        }
+        (DiffSource::Playlist(old), DiffSource::Playlist(new)) => (
+            fetch_snapshot(user_access, &old).await?,
+            fetch_snapshot(user_access, &new).await?,
+        ),
+    };
+    let diff = diff_tracks(&old.tracks, &new.tracks);
+    let count = diff.count();
+    if json {
+        print_json(&SnapshotDiff {
+            old: PlaylistName {
+                id: old.id,
+                name: old.name,
+            },
+            new: PlaylistName {
+                id: new.id,
+                name: new.name,
+            },
+            tracks: diff,
+        });
+    } else {
+        print_track_diff(&diff, "");
+    }
+    differences(count)
+}
+
+fn differences(count: usize) -> Result<(), MainError> {
+    if count == 0 {
+        status!("No differences.");
+        return Ok(());
+    }
+    Err(MainError::Differences(tr!("Found {} differences.", count)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_taken_from_links() {
+        assert_eq!(
+            id_from_link("https://open.spotify.com/playlist/37i9dQZF1DXcBWIGoYBM5M?si=abc"),
+            Some("37i9dQZF1DXcBWIGoYBM5M")
+        );
+        assert_eq!(
+            id_from_link("open.spotify.com/playlist/37i9dQZF1DXcBWIGoYBM5M/"),
+            Some("37i9dQZF1DXcBWIGoYBM5M")
+        );
+        assert_eq!(
+            id_from_link("spotify:playlist:37i9dQZF1DXcBWIGoYBM5M"),
+            Some("37i9dQZF1DXcBWIGoYBM5M")
+        );
+    }
+
+    #[test]
+    fn other_text_is_no_link() {
+        assert_eq!(id_from_link("37i9dQZF1DXcBWIGoYBM5M"), None);
+        assert_eq!(id_from_link("My Playlist"), None);
+        assert_eq!(id_from_link("https://open.spotify.com/playlist/"), None);
+        assert_eq!(id_from_link("spotify:album:4aawyAB9vmqN3uQ7FjRGTy"), None);
+    }
+
+    #[test]
+    fn user_ids_are_taken_from_profile_links() {
+        assert_eq!(
+            user_id_from_link("https://open.spotify.com/user/spotify?si=abc"),
+            Some("spotify")
+        );
+        assert_eq!(user_id_from_link("spotify:user:spotify"), Some("spotify"));
+        assert_eq!(user_id_from_link("spotify"), None);
+        assert_eq!(
+            user_id_from_link("https://open.spotify.com/playlist/37i9dQZF1DXcBWIGoYBM5M"),
+            None
+        );
+    }
+
+    #[test]
+    fn csv_fields_are_only_quoted_if_needed() {
+        assert_eq!(csv_field("Bohemian Rhapsody"), "Bohemian Rhapsody");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("Queen, David Bowie"), "\"Queen, David Bowie\"");
+        assert_eq!(csv_field("12\" Mix"), "\"12\"\" Mix\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+}
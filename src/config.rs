@@ -0,0 +1,475 @@
+use std::env;
+use std::fmt::Display;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::Path;
+use std::str::FromStr;
+#[cfg(feature = "web-auth")]
+use std::sync::{Arc, RwLock};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::actions::pipeline::Pipelines;
+use crate::actions::rules::Rules;
+use crate::actions::ActionsConfig;
+#[cfg(feature = "web-auth")]
+use crate::api::ApiConfig;
+use crate::authorize::AuthConfig;
+use crate::config_format::Examples;
+use crate::config_migration::{unversioned, CONFIG_VERSION};
+use crate::dates::DateSettings;
+use crate::i18n::Locale;
+use crate::integrations::lastfm::LastfmConfig;
+use crate::integrations::listenbrainz::ListenbrainzConfig;
+use crate::integrations::IntegrationsConfig;
+use crate::notifications::NotificationsConfig;
+#[cfg(feature = "tui")]
+use crate::tui::keymap::KeybindingsConfig;
+#[cfg(feature = "tui")]
+use crate::tui::theme::ThemeConfig;
+
+/// Shown with the errors in the config and credentials files.
+pub const CONFIG_EXAMPLES: Examples = &[
+    ("version", "2"),
+    ("address", "\"127.0.0.1\""),
+    ("port", "3000"),
+    ("client_id", "\"<32 hexadecimal characters>\""),
+    ("client_secret", "\"<32 hexadecimal characters>\""),
+    ("preset", "\"light\""),
+    ("highlight", "\"#ffaa00\""),
+    ("border", "\"cyan\""),
+    ("move_up", "[\"Up\", \"k\"]"),
+    ("move_down", "[\"Down\", \"j\"]"),
+    ("select", "[\"Space\"]"),
+    ("confirm", "[\"Enter\"]"),
+    ("quit", "[\"q\"]"),
+    ("locale", "\"de\""),
+    ("date_format", "\"%Y-%m-%d\""),
+    ("date_locale", "\"de_DE\""),
+    ("timezone", "\"Europe/Berlin\""),
+    ("selected", "[\"top_tracks\"]"),
+    ("limit", "50"),
+    ("name_template", "\"Spautofy {range} Top Tracks {date}\""),
+    ("time_ranges", "[\"short\", \"long\"]"),
+    ("public", "true"),
+    ("collaborative", "false"),
+    ("targets", "{\"short\": \"<playlist id>\"}"),
+    ("action", "\"top-tracks:short\""),
+    ("source", "\"liked\""),
+    ("filter", "\"genre:metal & year>=2010\""),
+    ("sort", "\"-year\""),
+    ("target", "\"Modern Metal\""),
+    ("enrich", "true"),
+    ("dedup", "true"),
+    ("api_key", "\"<Last.fm API key>\""),
+    ("user", "\"<Last.fm user name>\""),
+    ("token", "\"<ListenBrainz user token>\""),
+    ("webhooks", "[{\"url\": \"https://example.com/hook\"}]"),
+    ("url", "\"https://example.com/hook\""),
+    ("headers", "{\"Authorization\": \"Bearer <token>\"}"),
+    ("only_failures", "true"),
+    (
+        "discord",
+        "[{\"webhook_url\": \"https://discord.com/api/webhooks/...\"}]",
+    ),
+    ("webhook_url", "\"https://discord.com/api/webhooks/...\""),
+    (
+        "slack",
+        "[{\"webhook_url\": \"https://hooks.slack.com/services/...\"}]",
+    ),
+    ("username", "\"Spautofy\""),
+    ("host", "\"smtp.example.com\""),
+    ("security", "\"starttls\""),
+    ("from", "\"Spautofy <spautofy@example.com>\""),
+    ("to", "[\"me@example.com\"]"),
+    ("ntfy", "{\"topic\": \"<hard to guess topic>\"}"),
+    ("server", "\"https://ntfy.sh\""),
+    ("topic", "\"<hard to guess topic>\""),
+    ("pushover", "{\"user\": \"<Pushover user key>\"}"),
+    ("mqtt", "{\"host\": \"homeassistant.local\"}"),
+    ("tls", "true"),
+    (
+        "healthcheck_url",
+        "\"https://hc-ping.com/<ping key>/{pipeline}\"",
+    ),
+    ("name", "\"Weekly Top Tracks\""),
+];
+
+/// The settings; the credentials are written to their own file, so the config file can
+/// be shared with the dotfiles and writing a new refresh token leaves it as it is.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SpautofyConfigFile {
+    /// See `CONFIG_VERSION`.
+    #[serde(default = "unversioned")]
+    version: u32,
+    address: Option<IpAddr>,
+    port: Option<u16>,
+    /// Taken from the credentials file if there is one.
+    #[serde(default, skip_serializing)]
+    client_id: String,
+    #[serde(default, skip_serializing)]
+    client_secret: String,
+    /// Left out without the TUI, like `api` without the web server, and then ignored.
+    #[cfg(feature = "tui")]
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[cfg(feature = "tui")]
+    #[serde(default)]
+    pub keybindings: KeybindingsConfig,
+    #[serde(default)]
+    pub locale: Locale,
+    /// See `dates`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub actions: ActionsConfig,
+    #[serde(default, skip_serializing_if = "Pipelines::is_empty")]
+    pub pipelines: Pipelines,
+    #[serde(default, skip_serializing_if = "Rules::is_empty")]
+    pub rules: Rules,
+    #[serde(default, skip_serializing_if = "IntegrationsConfig::is_empty")]
+    pub integrations: IntegrationsConfig,
+    #[serde(default, skip_serializing_if = "NotificationsConfig::is_empty")]
+    pub notifications: NotificationsConfig,
+    /// For `spautofy api`.
+    #[cfg(feature = "web-auth")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api: Option<ApiConfig>,
+    #[serde(default, skip_serializing)]
+    refresh_token: Option<String>,
+}
+
+/// What the config file leaves out, kept in `spautofy.credentials` next to it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Credentials {
+    client_id: String,
+    client_secret: String,
+    /// Kept from the last authorization so runs with `--actions` need no browser.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
+}
+
+/// The credentials file belonging to the config file, with the extension `.credentials`.
+pub fn credentials_path(config_path: &str) -> String {
+    Path::new(config_path)
+        .with_extension("credentials")
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SpautofyConfig {
+    #[serde(flatten)]
+    pub auth: AuthConfig,
+    #[cfg(feature = "tui")]
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[cfg(feature = "tui")]
+    #[serde(default)]
+    pub keybindings: KeybindingsConfig,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub actions: ActionsConfig,
+    #[serde(default, skip_serializing_if = "Pipelines::is_empty")]
+    pub pipelines: Pipelines,
+    #[serde(default, skip_serializing_if = "Rules::is_empty")]
+    pub rules: Rules,
+    #[serde(default, skip_serializing_if = "IntegrationsConfig::is_empty")]
+    pub integrations: IntegrationsConfig,
+    #[serde(default, skip_serializing_if = "NotificationsConfig::is_empty")]
+    pub notifications: NotificationsConfig,
+    #[cfg(feature = "web-auth")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api: Option<ApiConfig>,
+}
+
+impl SpautofyConfigFile {
+    #[cfg(feature = "tui")]
+    pub fn new(
+        client_id: String,
+        client_secret: String,
+        address: Option<IpAddr>,
+        port: Option<u16>,
+    ) -> Self {
+        SpautofyConfigFile {
+            version: CONFIG_VERSION,
+            address,
+            port,
+            client_id,
+            client_secret,
+            #[cfg(feature = "tui")]
+            theme: ThemeConfig::default(),
+            #[cfg(feature = "tui")]
+            keybindings: KeybindingsConfig::default(),
+            locale: Locale::default(),
+            date_format: None,
+            date_locale: None,
+            timezone: None,
+            actions: ActionsConfig::default(),
+            pipelines: Pipelines::new(),
+            rules: Rules::new(),
+            integrations: IntegrationsConfig::default(),
+            notifications: NotificationsConfig::default(),
+            #[cfg(feature = "web-auth")]
+            api: None,
+            refresh_token: None,
+        }
+    }
+
+    pub fn date_settings(&self) -> Result<DateSettings, String> {
+        DateSettings::new(
+            self.date_format.as_deref(),
+            self.date_locale.as_deref(),
+            self.locale,
+            self.timezone.as_deref(),
+        )
+    }
+
+    pub fn credentials(&self) -> (&str, &str) {
+        (self.client_id.as_str(), self.client_secret.as_str())
+    }
+
+    /// Whether the client id, secret and refresh token are the same, so the login of one
+    /// holds for the other.
+    pub fn same_login(&self, other: &Self) -> bool {
+        self.client_id == other.client_id
+            && self.client_secret == other.client_secret
+            && self.refresh_token == other.refresh_token
+    }
+
+    /// Whether the web server would listen on the same address and port.
+    #[cfg(feature = "web-auth")]
+    pub fn same_server(&self, other: &Self) -> bool {
+        self.address == other.address && self.port == other.port
+    }
+
+    /// Whether every setting is the same, the credentials included.
+    pub fn same_as(&self, other: &Self) -> bool {
+        self.same_login(other)
+            && serde_json::to_value(self).ok() == serde_json::to_value(other).ok()
+    }
+
+    /// Takes the refresh token Spotify handed out with the last login.
+    pub fn with_refresh_token(self, refresh_token: &str) -> Self {
+        SpautofyConfigFile {
+            refresh_token: Some(refresh_token.to_string()),
+            ..self
+        }
+    }
+
+    /// Replaces the credentials read from the config file itself.
+    pub fn with_credentials(self, credentials: Credentials) -> Self {
+        SpautofyConfigFile {
+            client_id: credentials.client_id,
+            client_secret: credentials.client_secret,
+            refresh_token: credentials.refresh_token,
+            ..self
+        }
+    }
+}
+
+/// Empty variables count as unset.
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+fn env_parsed<T: FromStr>(name: &str) -> Result<Option<T>, String>
+where
+    T::Err: Display,
+{
+    env_var(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|err| format!("{}=\"{}\": {}", name, value, err))
+        })
+        .transpose()
+}
+
+/// For the values written as strings in the config file, like the locale.
+fn env_deserialized<T: DeserializeOwned>(name: &str) -> Result<Option<T>, String> {
+    env_var(name)
+        .map(|value| {
+            serde_json::from_value(Value::String(value.clone()))
+                .map_err(|err| format!("{}=\"{}\": {}", name, value, err))
+        })
+        .transpose()
+}
+
+impl SpautofyConfigFile {
+    /// Overrides the settings with the variables listed in `ENVIRONMENT_HELP`, for setups
+    /// like Docker or NixOS where the config file is shared or read-only.
+    pub fn with_env_overrides(mut self) -> Result<Self, String> {
+        self.address = env_parsed("SPAUTOFY_ADDRESS")?.or(self.address);
+        self.port = env_parsed("SPAUTOFY_PORT")?.or(self.port);
+        if let Some(client_id) = env_var("SPAUTOFY_CLIENT_ID") {
+            self.client_id = client_id;
+        }
+        if let Some(client_secret) = env_var("SPAUTOFY_CLIENT_SECRET") {
+            self.client_secret = client_secret;
+        }
+        self.refresh_token = env_var("SPAUTOFY_REFRESH_TOKEN").or(self.refresh_token);
+        if let Some(locale) = env_deserialized("SPAUTOFY_LOCALE")? {
+            self.locale = locale;
+        }
+        self.date_format = env_var("SPAUTOFY_DATE_FORMAT").or(self.date_format);
+        self.date_locale = env_var("SPAUTOFY_DATE_LOCALE").or(self.date_locale);
+        self.timezone = env_var("SPAUTOFY_TIMEZONE").or(self.timezone);
+        #[cfg(feature = "tui")]
+        if let Some(preset) = env_deserialized("SPAUTOFY_THEME")? {
+            self.theme.preset = preset;
+        }
+        let top_tracks = &mut self.actions.top_tracks;
+        top_tracks.limit = env_parsed("SPAUTOFY_LIMIT")?.or(top_tracks.limit);
+        top_tracks.name_template =
+            env_var("SPAUTOFY_NAME_TEMPLATE").or(top_tracks.name_template.take());
+        if let Some(time_ranges) = env_var("SPAUTOFY_TIME_RANGES") {
+            let parsed = time_ranges
+                .split(',')
+                .map(|time_range| time_range.trim().parse())
+                .collect::<Result<_, String>>()
+                .map_err(|err| format!("SPAUTOFY_TIME_RANGES=\"{}\": {}", time_ranges, err))?;
+            top_tracks.time_ranges = Some(parsed);
+        }
+        top_tracks.public = env_parsed("SPAUTOFY_PUBLIC")?.or(top_tracks.public);
+        top_tracks.collaborative =
+            env_parsed("SPAUTOFY_COLLABORATIVE")?.or(top_tracks.collaborative);
+        let lastfm_api_key = env_var("SPAUTOFY_LASTFM_API_KEY");
+        let lastfm_user = env_var("SPAUTOFY_LASTFM_USER");
+        self.integrations.lastfm = match (self.integrations.lastfm, lastfm_api_key, lastfm_user) {
+            (Some(lastfm), api_key, user) => Some(LastfmConfig {
+                api_key: api_key.unwrap_or(lastfm.api_key),
+                user: user.unwrap_or(lastfm.user),
+            }),
+            (None, Some(api_key), Some(user)) => Some(LastfmConfig { api_key, user }),
+            (None, None, None) => None,
+            (None, _, _) => {
+                return Err("without integrations.lastfm in the config file, set both \
+                    SPAUTOFY_LASTFM_API_KEY and SPAUTOFY_LASTFM_USER"
+                    .to_string())
+            }
+        };
+        if let Some(email) = &mut self.notifications.email {
+            email.password = env_var("SPAUTOFY_SMTP_PASSWORD").or(email.password.take());
+        }
+        if let Some(ntfy) = &mut self.notifications.ntfy {
+            ntfy.token = env_var("SPAUTOFY_NTFY_TOKEN").or(ntfy.token.take());
+        }
+        if let Some(mqtt) = &mut self.notifications.mqtt {
+            mqtt.password = env_var("SPAUTOFY_MQTT_PASSWORD").or(mqtt.password.take());
+        }
+        self.notifications.healthcheck_url =
+            env_var("SPAUTOFY_HEALTHCHECK_URL").or(self.notifications.healthcheck_url.take());
+        if let Some(pushover) = &mut self.notifications.pushover {
+            if let Some(token) = env_var("SPAUTOFY_PUSHOVER_TOKEN") {
+                pushover.token = token;
+            }
+        }
+        if let Some(token) = env_var("SPAUTOFY_LISTENBRAINZ_TOKEN") {
+            self.integrations.listenbrainz = Some(ListenbrainzConfig { token });
+        }
+        #[cfg(feature = "web-auth")]
+        if let Some(token) = env_var("SPAUTOFY_API_TOKEN") {
+            self.api.get_or_insert_with(ApiConfig::default).token = token;
+        }
+        Ok(self)
+    }
+}
+
+impl From<&SpautofyConfigFile> for Credentials {
+    fn from(file_config: &SpautofyConfigFile) -> Self {
+        Credentials {
+            client_id: file_config.client_id.clone(),
+            client_secret: file_config.client_secret.clone(),
+            refresh_token: file_config.refresh_token.clone(),
+        }
+    }
+}
+
+impl From<&SpautofyConfig> for Credentials {
+    fn from(config: &SpautofyConfig) -> Self {
+        let (client_id, client_secret) = config.auth.credentials();
+        Credentials {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            refresh_token: config.auth.refresh_token.clone(),
+        }
+    }
+}
+
+impl From<&SpautofyConfig> for SpautofyConfigFile {
+    fn from(config: &SpautofyConfig) -> Self {
+        let (client_id, client_secret) = config.auth.credentials();
+        SpautofyConfigFile {
+            version: CONFIG_VERSION,
+            address: Some(config.auth.address),
+            port: Some(config.auth.port),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            #[cfg(feature = "tui")]
+            theme: config.theme.clone(),
+            #[cfg(feature = "tui")]
+            keybindings: config.keybindings.clone(),
+            locale: config.locale,
+            date_format: config.date_format.clone(),
+            date_locale: config.date_locale.clone(),
+            timezone: config.timezone.clone(),
+            actions: config.actions.clone(),
+            pipelines: config.pipelines.clone(),
+            rules: config.rules.clone(),
+            integrations: config.integrations.clone(),
+            notifications: config.notifications.clone(),
+            #[cfg(feature = "web-auth")]
+            api: config.api.clone(),
+            refresh_token: config.auth.refresh_token.clone(),
+        }
+    }
+}
+
+impl From<SpautofyConfigFile> for SpautofyConfig {
+    fn from(file_config: SpautofyConfigFile) -> Self {
+        SpautofyConfig {
+            auth: AuthConfig::new(
+                file_config
+                    .address
+                    .unwrap_or(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+                file_config.port.unwrap_or(3000),
+                file_config.client_id,
+                file_config.client_secret,
+                file_config.refresh_token,
+            ),
+            #[cfg(feature = "tui")]
+            theme: file_config.theme,
+            #[cfg(feature = "tui")]
+            keybindings: file_config.keybindings,
+            locale: file_config.locale,
+            date_format: file_config.date_format,
+            date_locale: file_config.date_locale,
+            timezone: file_config.timezone,
+            actions: file_config.actions,
+            pipelines: file_config.pipelines,
+            rules: file_config.rules,
+            integrations: file_config.integrations,
+            notifications: file_config.notifications,
+            #[cfg(feature = "web-auth")]
+            api: file_config.api,
+        }
+    }
+}
+
+/// The config the authorization web server shares between its handlers; every handler
+/// holds the lock only while it reads or writes the fields, never across an `await`.
+#[cfg(feature = "web-auth")]
+pub type SharedConfig = Arc<RwLock<SpautofyConfig>>;
@@ -0,0 +1,197 @@
+//! The config file is read and written in the format its extension names: `.toml` is TOML,
+//! `.yaml` and `.yml` are YAML, anything else, like the default `spautofy.config`, is JSON.
+
+use std::fmt::{self, Display, Formatter};
+#[cfg(feature = "tui")]
+use std::fs;
+#[cfg(feature = "tui")]
+use std::io;
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use serde_path_to_error::{Path as FieldPath, Segment};
+
+use crate::tr;
+
+/// Examples of valid values for the fields of a config file, by the name of the field.
+pub type Examples = &'static [(&'static str, &'static str)];
+
+/// A value in a config file that does not fit its field.
+#[derive(Debug)]
+pub struct FieldError {
+    /// Like `actions.top_tracks.limit`.
+    pub path: String,
+    pub message: String,
+    /// The value in the file, left out for whole sections.
+    pub found: Option<String>,
+    pub example: Option<&'static str>,
+}
+
+impl Display for FieldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)?;
+        match (&self.found, self.example) {
+            (Some(found), Some(example)) => {
+                write!(f, " ({})", tr!("found {}, e.g. {}", found, example))
+            }
+            (Some(found), None) => write!(f, " ({})", tr!("found {}", found)),
+            (None, Some(example)) => write!(f, " ({})", tr!("e.g. {}", example)),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    /// The file is no valid JSON, TOML or YAML at all.
+    Syntax(String),
+    Fields(Vec<FieldError>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            Some(extension)
+                if extension.eq_ignore_ascii_case("yaml")
+                    || extension.eq_ignore_ascii_case("yml") =>
+            {
+                ConfigFormat::Yaml
+            }
+            _ => ConfigFormat::Json,
+        }
+    }
+
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T, String> {
+        match self {
+            ConfigFormat::Json => serde_json::from_str(content).map_err(|err| err.to_string()),
+            ConfigFormat::Toml => toml::from_str(content).map_err(|err| err.to_string()),
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(|err| err.to_string()),
+        }
+    }
+
+    /// Unlike `parse`, reports every value that does not fit its field instead of only the
+    /// first one.
+    pub fn parse_fields<T: DeserializeOwned>(
+        &self,
+        content: &str,
+        examples: Examples,
+    ) -> Result<T, ParseError> {
+        let config = self.parse(content).map_err(ParseError::Syntax)?;
+        from_fields(config, examples).map_err(ParseError::Fields)
+    }
+
+    pub fn serialize<T: Serialize>(&self, value: &T) -> String {
+        match self {
+            ConfigFormat::Json => {
+                serde_json::to_string_pretty(value).expect("Failed to serialize config")
+            }
+            ConfigFormat::Toml => {
+                toml::to_string_pretty(value).expect("Failed to serialize config")
+            }
+            ConfigFormat::Yaml => serde_yaml::to_string(value).expect("Failed to serialize config"),
+        }
+    }
+}
+
+/// Like `ConfigFormat::parse_fields` for a config already read.
+pub fn from_fields<T: DeserializeOwned>(
+    config: Value,
+    examples: Examples,
+) -> Result<T, Vec<FieldError>> {
+    let mut value = config.clone();
+    let mut errors = Vec::new();
+    loop {
+        match serde_path_to_error::deserialize::<_, T>(value.clone()) {
+            Ok(parsed) if errors.is_empty() => return Ok(parsed),
+            Ok(_) => return Err(errors),
+            Err(err) => {
+                errors.push(FieldError {
+                    path: err.path().to_string(),
+                    message: err.inner().to_string(),
+                    found: found_value(&config, err.path()),
+                    example: example(err.path(), examples),
+                });
+                if !remove_field(&mut value, err.path()) {
+                    return Err(errors);
+                }
+            }
+        }
+    }
+}
+
+fn found_value(config: &Value, path: &FieldPath) -> Option<String> {
+    let mut value = config;
+    for segment in path.iter() {
+        value = match segment {
+            Segment::Map { key } => value.get(key)?,
+            Segment::Seq { index } => value.get(index)?,
+            Segment::Enum { .. } | Segment::Unknown => return None,
+        };
+    }
+    match value {
+        Value::Object(_) => None,
+        value => Some(value.to_string()),
+    }
+}
+
+/// Falls back to the enclosing fields for keys named by the user, like a pipeline.
+fn example(path: &FieldPath, examples: Examples) -> Option<&'static str> {
+    path.iter().rev().find_map(|segment| match segment {
+        Segment::Map { key } => examples
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, example)| *example),
+        _ => None,
+    })
+}
+
+/// Removes the field of the error so the next attempt finds the errors after it. A wrong
+/// element removes its whole list, the indices in the other errors would be off otherwise.
+/// Returns `false` if the error is in the config as a whole.
+fn remove_field(config: &mut Value, path: &FieldPath) -> bool {
+    let segments: Vec<&Segment> = path.iter().collect();
+    let Some(last_key) = segments
+        .iter()
+        .rposition(|segment| matches!(segment, Segment::Map { .. }))
+    else {
+        return false;
+    };
+    let mut value = config;
+    for segment in &segments[..last_key] {
+        let next = match segment {
+            Segment::Map { key } => value.get_mut(key),
+            Segment::Seq { index } => value.get_mut(index),
+            Segment::Enum { .. } | Segment::Unknown => None,
+        };
+        match next {
+            Some(next) => value = next,
+            None => return false,
+        }
+    }
+    let Segment::Map { key } = segments[last_key] else {
+        unreachable!()
+    };
+    value
+        .as_object_mut()
+        .and_then(|object| object.remove(key))
+        .is_some()
+}
+
+/// Writes the config in the format of the file extension.
+#[cfg(feature = "tui")]
+pub fn write_config_file<T: Serialize>(path: &str, config: &T) -> io::Result<()> {
+    fs::write(path, ConfigFormat::from_path(path).serialize(config))
+}
@@ -0,0 +1,120 @@
+//! Config files record the version of their layout; older ones are upgraded when they are
+//! read, so an update of Spautofy does not leave them unreadable.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::config::Credentials;
+use crate::config_format::ConfigFormat;
+
+/// 2 moved the credentials to their own file.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Config files from before the versions have none.
+pub fn unversioned() -> u32 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionField {
+    #[serde(default = "unversioned")]
+    version: u32,
+}
+
+/// What an upgrade took out of the config file.
+#[derive(Debug, Default)]
+pub struct Migration {
+    pub credentials: Option<Credentials>,
+}
+
+pub fn config_version(format: ConfigFormat, content: &str) -> Result<u32, String> {
+    format
+        .parse::<VersionField>(content)
+        .map(|field| field.version)
+}
+
+/// 1 → 2: the client ID, secret and refresh token go to the credentials file.
+fn move_credentials(config: &mut Map<String, Value>, migration: &mut Migration) {
+    let credentials: Map<String, Value> = ["client_id", "client_secret", "refresh_token"]
+        .into_iter()
+        .filter_map(|key| config.remove(key).map(|value| (key.to_string(), value)))
+        .collect();
+    migration.credentials = serde_json::from_value(Value::Object(credentials)).ok();
+}
+
+/// Upgrades the config from `version` to `CONFIG_VERSION` one version at a time.
+pub fn migrate(config: &mut Map<String, Value>, version: u32) -> Migration {
+    let mut migration = Migration::default();
+    for from in version..CONFIG_VERSION {
+        match from {
+            1 => move_credentials(config, &mut migration),
+            _ => unreachable!("no migration from config version {}", from),
+        }
+    }
+    config.insert("version".to_string(), Value::from(CONFIG_VERSION));
+    migration
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("not an object: {}", value),
+        }
+    }
+
+    #[test]
+    fn files_without_a_version_are_version_1() {
+        assert_eq!(config_version(ConfigFormat::Json, "{}"), Ok(1));
+        assert_eq!(config_version(ConfigFormat::Yaml, "port: 3000"), Ok(1));
+        assert_eq!(config_version(ConfigFormat::Toml, "version = 2"), Ok(2));
+        assert!(config_version(ConfigFormat::Json, "{\"version\": \"two\"}").is_err());
+    }
+
+    #[test]
+    fn version_1_moves_the_credentials_out() {
+        let mut config = object(json!({
+            "client_id": "id",
+            "client_secret": "secret",
+            "refresh_token": "token",
+            "port": 3000,
+        }));
+        let migration = migrate(&mut config, 1);
+        assert_eq!(
+            Value::Object(config),
+            json!({"port": 3000, "version": CONFIG_VERSION})
+        );
+        let credentials = serde_json::to_value(migration.credentials.unwrap()).unwrap();
+        assert_eq!(
+            credentials,
+            json!({"client_id": "id", "client_secret": "secret", "refresh_token": "token"})
+        );
+    }
+
+    #[test]
+    fn version_1_without_credentials_moves_none() {
+        let mut config = object(json!({"client_id": "id", "port": 3000}));
+        let migration = migrate(&mut config, 1);
+        assert!(migration.credentials.is_none());
+        assert_eq!(
+            Value::Object(config),
+            json!({"port": 3000, "version": CONFIG_VERSION})
+        );
+    }
+
+    #[test]
+    fn the_current_version_stays_as_it_is() {
+        let mut config = object(json!({"version": CONFIG_VERSION, "client_id": "id"}));
+        let migration = migrate(&mut config, CONFIG_VERSION);
+        assert!(migration.credentials.is_none());
+        assert_eq!(
+            Value::Object(config),
+            json!({"version": CONFIG_VERSION, "client_id": "id"})
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Reads the config again when it or the credentials file changes, for the commands running
+//! until stopped: `spautofy mqtt`, `dashboard` and `api` run the pipelines, rules and actions
+//! of the new config without a restart. A config with errors is reported and the old one
+//! kept; new credentials are logged in with before they are taken, so a wrong client secret
+//! shows up at once instead of with the next run.
+
+use std::fs;
+use std::future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::time::timeout;
+
+use crate::config::SpautofyConfigFile;
+#[cfg(feature = "web-auth")]
+use crate::UserAccess;
+use crate::{apply_config, login, parse_config_file, status, status_error, Args};
+
+/// Editors often write a file in several steps; the config is read once they are quiet for
+/// this long.
+const SETTLE: Duration = Duration::from_millis(500);
+
+/// A config read again after its files changed.
+pub struct Reloaded {
+    pub file_config: SpautofyConfigFile,
+    /// The login with the new credentials, if they changed.
+    #[cfg(feature = "web-auth")]
+    pub user_access: Option<UserAccess>,
+}
+
+pub struct ConfigWatcher {
+    args: Args,
+    /// Watches as long as it is kept; `None` if watching failed.
+    _watcher: Option<RecommendedWatcher>,
+    changes: UnboundedReceiver<()>,
+}
+
+impl ConfigWatcher {
+    /// Watches the config and credentials files of the arguments; if that fails, it is
+    /// reported and the config is only read at the start.
+    pub fn new(args: &Args) -> Self {
+        let (sender, changes) = mpsc::unbounded_channel();
+        let files = [args.config_path.clone(), args.credentials_file()];
+        let watcher = watch(&files, sender)
+            .map_err(|err| {
+                status_error!(
+                    "Could not watch the config file {} for changes: {}",
+                    args.config_path,
+                    err
+                )
+            })
+            .ok();
+        ConfigWatcher {
+            args: args.clone(),
+            _watcher: watcher,
+            changes,
+        }
+    }
+
+    /// Waits until one of the files changed and the writes to them are over; forever if
+    /// they are not watched.
+    pub async fn changed(&mut self) {
+        if self.changes.recv().await.is_none() {
+            return future::pending().await;
+        }
+        while let Ok(Some(())) = timeout(SETTLE, self.changes.recv()).await {}
+    }
+
+    /// The config read again if it differs from `current`, else `None`, also if it has
+    /// errors, which are reported; its settings of the whole process, like the locale, are
+    /// taken at once.
+    pub async fn reload(&self, current: &SpautofyConfigFile) -> Option<Reloaded> {
+        let args = &self.args;
+        let file_config = match parse_config_file(
+            &args.config_path,
+            &args.credentials_file(),
+            args.config_section.as_deref(),
+            false,
+        )
+        .await
+        {
+            Ok(file_config) => file_config,
+            Err(err) => {
+                status_error!("Could not reload the config, keeping the old one: {}", err);
+                return None;
+            }
+        };
+        // a login writes the credentials file, mostly with the same refresh token
+        if file_config.same_as(current) {
+            return None;
+        }
+        let (file_config, user_access) = if file_config.same_login(current) {
+            (file_config, None)
+        } else {
+            status!("The credentials changed, logging in again...");
+            match login(args, file_config.clone()).await {
+                Ok(user_access) => (
+                    file_config.with_refresh_token(user_access.access.refresh_token()),
+                    Some(user_access),
+                ),
+                Err(err) => {
+                    status_error!(
+                        "Could not log in with the new credentials, keeping the old config: {}",
+                        err
+                    );
+                    return None;
+                }
+            }
+        };
+        if let Err(err) = apply_config(args, &file_config) {
+            status_error!("Could not reload the config, keeping the old one: {}", err);
+            return None;
+        }
+        status!("Reloaded the config file {}.", args.config_path);
+        // only the dashboard and the api keep a login between their runs
+        #[cfg(not(feature = "web-auth"))]
+        drop(user_access);
+        Some(Reloaded {
+            file_config,
+            #[cfg(feature = "web-auth")]
+            user_access,
+        })
+    }
+}
+
+/// Watches the directories of the files rather than the files themselves, since editors
+/// often replace a file with a new one instead of writing to it.
+fn watch(files: &[String], sender: UnboundedSender<()>) -> notify::Result<RecommendedWatcher> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for file in files {
+        let path = Path::new(file);
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        let directory = match path.parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => directory,
+            _ => Path::new("."),
+        };
+        paths.push(fs::canonicalize(directory)?.join(name));
+    }
+    let watched = paths.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        if !event.kind.is_access() && event.paths.iter().any(|path| watched.contains(path)) {
+            // the receiver is only gone once the command stops
+            let _ = sender.send(());
+        }
+    })?;
+    let mut directories: Vec<&Path> = Vec::new();
+    for directory in paths.iter().filter_map(|path| path.parent()) {
+        if !directories.contains(&directory) {
+            watcher.watch(directory, RecursiveMode::NonRecursive)?;
+            directories.push(directory);
+        }
+    }
+    Ok(watcher)
+}
@@ -0,0 +1,545 @@
+//! A small web dashboard, `spautofy dashboard`, for those who prefer a browser to the TUI:
+//! the playlists, a button for every action, rule and pipeline of the config, and the runs
+//! so far. It is served on the address and port of the config, the same server that waits
+//! for the authorization otherwise, so it is only reachable from the machine by default.
+//!
+//! The runs are the same as those of `spautofy run`: recorded in the history, notified and
+//! counted in the metrics. One runs at a time, shared with `spautofy api`. Both servers hand
+//! out the metrics for Prometheus as `/metrics` and the Atom feed of the playlists as
+//! `/feed.atom`, and take the changes of the config file without a restart.
+
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, RwLock};
+
+use rocket::config::LogLevel;
+use rocket::form::{Form, FromForm};
+use rocket::http::{ContentType, Status};
+use rocket::request::{FlashMessage, FromRequest, Outcome, Request};
+use rocket::response::content::RawHtml;
+use rocket::response::{Flash, Redirect};
+use rocket::{get, post, routes, Build, Config, Rocket, State};
+
+use crate::actions::action_report::ActionReport;
+use crate::actions::playlist_actions::get_current_user_playlists;
+use crate::actions::registry;
+use crate::actions::Task;
+use crate::config::{SpautofyConfig, SpautofyConfigFile};
+use crate::config_reload::ConfigWatcher;
+use crate::feed::{self, escape, summary};
+use crate::history::{self, RunRecord};
+use crate::i18n::t;
+use crate::notifications::Notifier;
+use crate::{
+    commands, login, metrics, parse_run_target, resolve_run_targets, save_run_outputs, status,
+    status_error, systemd, tr, Args, MainError, RunTarget, UserAccess,
+};
+
+/// The newest runs shown on the history page.
+const HISTORY_RUNS: usize = 50;
+
+/// While a run goes on, the dashboard reloads itself this often, in seconds.
+const RELOAD_SECS: u32 = 5;
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 60em; margin: 2em auto; \
+    padding: 0 1em; } nav a { margin-right: 1em; } table { border-collapse: collapse; \
+    width: 100%; } th, td { text-align: left; padding: 0.3em 0.6em; border-bottom: 1px solid \
+    #ddd; } .error { color: #b00; } .success { color: #070; } pre { margin: 0; \
+    white-space: pre-wrap; }";
+
+/// What the pages, or the requests to the API, share: the config, the login and the run
+/// going on.
+pub struct Dashboard {
+    args: Args,
+    /// Replaced whenever the config file changes, see `config_reload`.
+    file_config: RwLock<SpautofyConfigFile>,
+    notifier: RwLock<Notifier>,
+    /// Kept until it expires, so every page does not log in again.
+    user_access: RwLock<Option<UserAccess>>,
+    /// The target of the run going on, if one is.
+    running: Mutex<Option<String>>,
+    /// Why the last run could not start, shown until the next one.
+    last_error: Mutex<Option<String>>,
+}
+
+impl Dashboard {
+    /// Logs in first: a revoked login should show up now, not with the first page.
+    pub async fn new(args: &Args, file_config: SpautofyConfigFile) -> Result<Self, MainError> {
+        let user_access = login(args, file_config.clone()).await?;
+        Ok(Dashboard {
+            args: args.clone(),
+            notifier: RwLock::new(Notifier::new(
+                file_config.notifications.clone(),
+                args.active_profile(),
+            )),
+            file_config: RwLock::new(file_config),
+            user_access: RwLock::new(Some(user_access)),
+            running: Mutex::new(None),
+            last_error: Mutex::new(None),
+        })
+    }
+
+    /// Logs in again without holding the lock, so the other requests are not kept waiting;
+    /// two requests finding the login expired at once may both log in.
+    pub async fn user_access(&self) -> Result<UserAccess, MainError> {
+        if let Some(current) = &*self.user_access.read().unwrap() {
+            if !current.access.is_expired() {
+                return Ok(current.clone());
+            }
+        }
+        let fresh = login(&self.args, self.file_config()).await?;
+        // Spotify may have handed out a new refresh token
+        {
+            let mut file_config = self.file_config.write().unwrap();
+            *file_config = file_config
+                .clone()
+                .with_refresh_token(fresh.access.refresh_token());
+        }
+        *self.user_access.write().unwrap() = Some(fresh.clone());
+        Ok(fresh)
+    }
+
+    pub fn file_config(&self) -> SpautofyConfigFile {
+        self.read_config(SpautofyConfigFile::clone)
+    }
+
+    /// Reads a part of the config without cloning all of it.
+    pub fn read_config<T>(&self, read: impl FnOnce(&SpautofyConfigFile) -> T) -> T {
+        read(&self.file_config.read().unwrap())
+    }
+
+    fn notifier(&self) -> Notifier {
+        self.notifier.read().unwrap().clone()
+    }
+
+    /// Takes the changes of the config until the server stops; only the address and port
+    /// need a restart.
+    async fn follow_config(self: Arc<Self>, mut watcher: ConfigWatcher) {
+        loop {
+            watcher.changed().await;
+            let current = self.file_config();
+            let Some(reloaded) = watcher.reload(&current).await else {
+                continue;
+            };
+            if !reloaded.file_config.same_server(&current) {
+                status!(
+                    "The new address and port of the web server are only taken with a restart."
+                );
+            }
+            *self.notifier.write().unwrap() = Notifier::new(
+                reloaded.file_config.notifications.clone(),
+                self.args.active_profile(),
+            );
+            *self.file_config.write().unwrap() = reloaded.file_config;
+            if let Some(user_access) = reloaded.user_access {
+                *self.user_access.write().unwrap() = Some(user_access);
+            }
+        }
+    }
+
+    /// The pipelines, then the rules, then the actions, by the names `spautofy run` takes.
+    fn targets(&self) -> Vec<(String, &'static str)> {
+        let file_config = self.file_config.read().unwrap();
+        let pipelines = file_config.pipelines.keys().map(|name| (name, "Pipeline"));
+        let rules = file_config.rules.keys().map(|name| (name, "Rule"));
+        pipelines
+            .chain(rules)
+            .map(|(name, kind)| (name.clone(), kind))
+            .chain(registry::cli_names().map(|(name, _)| (name.to_string(), "Action")))
+            .collect()
+    }
+
+    pub fn running(&self) -> Option<String> {
+        self.running.lock().unwrap().clone()
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().unwrap().clone()
+    }
+
+    pub fn history(&self) -> Result<Vec<RunRecord>, MainError> {
+        history::load(&self.args.history_file()).map_err(|err| {
+            MainError::Io(std::io::Error::other(tr!(
+                "Could not read the history: {}",
+                err
+            )))
+        })
+    }
+
+    /// The actions of a pipeline, rule or action, like `spautofy run` would run them.
+    pub fn actions(&self, target: &RunTarget) -> Result<Vec<(String, Task)>, MainError> {
+        resolve_run_targets(
+            &self.args,
+            &self.file_config(),
+            std::slice::from_ref(target),
+            None,
+            None,
+        )
+    }
+
+    /// Runs the target in the background, unless another run goes on; the error says which.
+    pub fn start(self: &Arc<Self>, target: String) -> Result<(), String> {
+        {
+            let mut running = self.running.lock().unwrap();
+            if let Some(running) = &*running {
+                return Err(tr!("{} is still running.", running));
+            }
+            *running = Some(target.clone());
+        }
+        let dashboard = Arc::clone(self);
+        tokio::spawn(async move { dashboard.run(target).await });
+        Ok(())
+    }
+
+    async fn run(&self, target: String) {
+        let result = self.run_target(&target).await;
+        if let Err(err) = &result {
+            status_error!("{}", err);
+        }
+        // the history tells about the failed actions
+        *self.last_error.lock().unwrap() = match result {
+            Ok(()) | Err(MainError::ActionsFailed(_)) => None,
+            Err(err) => Some(err.to_string()),
+        };
+        save_run_outputs(&self.args);
+        *self.running.lock().unwrap() = None;
+    }
+
+    async fn run_target(&self, target: &str) -> Result<(), MainError> {
+        let target = parse_run_target(target).map_err(MainError::Config)?;
+        let actions = self.actions(&target)?;
+        let user_access = match self.user_access().await {
+            Ok(user_access) => user_access,
+            Err(err) => {
+                if !self.args.dry_run {
+                    let pipelines: Vec<String> =
+                        actions.into_iter().map(|(name, _)| name).collect();
+                    self.notifier()
+                        .pipelines_failed(&pipelines, &err.to_string())
+                        .await;
+                }
+                return Err(err);
+            }
+        };
+        commands::run_actions(
+            user_access,
+            actions,
+            &self.args.history_file(),
+            false,
+            self.args.batch_profile.as_deref(),
+            &self.notifier(),
+        )
+        .await
+    }
+}
+
+/// Turns away forms posted by other sites, which a browser may send to the dashboard on
+/// their behalf; browsers name the site a form comes from as its origin.
+pub struct SameOrigin;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SameOrigin {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, ()> {
+        let headers = request.headers();
+        let Some(origin) = headers.get_one("Origin") else {
+            return Outcome::Success(SameOrigin);
+        };
+        let host = origin.split_once("://").map(|(_, host)| host);
+        if host.is_some() && host == headers.get_one("Host") {
+            Outcome::Success(SameOrigin)
+        } else {
+            Outcome::Error((Status::Forbidden, ()))
+        }
+    }
+}
+
+#[derive(FromForm)]
+pub struct RunForm {
+    target: String,
+}
+
+fn page(title: &str, body: &str, reload: bool) -> RawHtml<String> {
+    let refresh = if reload {
+        format!("<meta http-equiv=\"refresh\" content=\"{}\">", RELOAD_SECS)
+    } else {
+        String::new()
+    };
+    RawHtml(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n{}<title>{} - Spautofy</title>\n\
+        <link rel=\"alternate\" type=\"application/atom+xml\" href=\"/feed.atom\">\n\
+        <style>{}</style>\n</head>\n<body>\n<nav><a href=\"/\">{}</a><a href=\"/playlists\">{}</a>\
+        <a href=\"/history\">{}</a></nav>\n<h1>{}</h1>\n{}</body>\n</html>\n",
+        refresh,
+        escape(title),
+        STYLE,
+        escape(t("Dashboard")),
+        escape(t("Playlists")),
+        escape(t("History")),
+        escape(title),
+        body
+    ))
+}
+
+fn error_page(err: MainError) -> RawHtml<String> {
+    let body = format!("<p class=\"error\">{}</p>\n", escape(&err.to_string()));
+    page(t("Error"), &body, false)
+}
+
+fn table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut html = String::from("<table>\n<tr>");
+    for cell in header {
+        html.push_str(&format!("<th>{}</th>", escape(cell)));
+    }
+    html.push_str("</tr>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        for cell in row {
+            html.push_str(&format!("<td>{}</td>", cell));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn link(url: &str, text: &str) -> String {
+    format!("<a href=\"{}\">{}</a>", escape(url), escape(text))
+}
+
+/// The action with the playlist it made, or why it failed.
+fn report_cell(report: &ActionReport) -> String {
+    match &report.result {
+        Ok(playlist) => format!(
+            "{}: {}<pre>{}</pre>",
+            escape(&report.action),
+            link(&playlist.url, &playlist.name),
+            escape(&summary(playlist))
+        ),
+        Err(err) => format!(
+            "{}: <span class=\"error\">{}</span>",
+            escape(&report.action),
+            escape(err)
+        ),
+    }
+}
+
+fn run_rows(records: &[RunRecord]) -> Vec<Vec<String>> {
+    records
+        .iter()
+        .rev()
+        .map(|record| {
+            let reports: Vec<String> = record.reports.iter().map(report_cell).collect();
+            vec![
+                escape(&format!(
+                    "{} ({})",
+                    record.started_at.format("%Y-%m-%d %H:%M"),
+                    record.age()
+                )),
+                reports.join("<br>\n"),
+            ]
+        })
+        .collect()
+}
+
+#[get("/")]
+fn index(dashboard: &State<Arc<Dashboard>>, flash: Option<FlashMessage<'_>>) -> RawHtml<String> {
+    let mut body = String::new();
+    if let Some(flash) = flash {
+        body.push_str(&format!(
+            "<p class=\"{}\">{}</p>\n",
+            escape(flash.kind()),
+            escape(flash.message())
+        ));
+    }
+    let running = dashboard.running();
+    if let Some(target) = &running {
+        body.push_str(&format!(
+            "<p>{}</p>\n",
+            escape(&tr!("Running {}...", target))
+        ));
+    } else if let Some(err) = dashboard.last_error() {
+        body.push_str(&format!(
+            "<p class=\"error\">{}</p>\n",
+            escape(&tr!("The last run failed: {}", err))
+        ));
+    }
+    let disabled = if running.is_some() { " disabled" } else { "" };
+    let rows: Vec<Vec<String>> = dashboard
+        .targets()
+        .into_iter()
+        .map(|(name, kind)| {
+            vec![
+                escape(&name),
+                escape(t(kind)),
+                format!(
+                    "<form method=\"post\" action=\"/run\"><input type=\"hidden\" \
+                    name=\"target\" value=\"{}\"><button{}>{}</button></form>",
+                    escape(&name),
+                    disabled,
+                    escape(t("Run"))
+                ),
+            ]
+        })
+        .collect();
+    body.push_str(&table(&[t("Name"), t("Kind"), ""], &rows));
+    match dashboard.history() {
+        Ok(records) => {
+            if let Some(last) = records.last() {
+                body.push_str(&format!("<h2>{}</h2>\n", escape(t("Last run"))));
+                body.push_str(&table(
+                    &[t("Started"), t("Actions")],
+                    &run_rows(std::slice::from_ref(last)),
+                ));
+            }
+        }
+        Err(err) => body.push_str(&format!(
+            "<p class=\"error\">{}</p>\n",
+            escape(&err.to_string())
+        )),
+    }
+    page(t("Dashboard"), &body, running.is_some())
+}
+
+#[post("/run", data = "<form>")]
+fn run(
+    _origin: SameOrigin,
+    dashboard: &State<Arc<Dashboard>>,
+    form: Form<RunForm>,
+) -> Flash<Redirect> {
+    let target = form.into_inner().target;
+    if !dashboard.targets().iter().any(|(name, _)| *name == target) {
+        return Flash::error(
+            Redirect::to("/"),
+            tr!("There is no pipeline, rule or action \"{}\".", target),
+        );
+    }
+    let message = tr!("Started {}.", target);
+    if let Err(err) = dashboard.start(target.clone()) {
+        return Flash::error(Redirect::to("/"), err);
+    }
+    status!("Running \"{}\" from the dashboard.", target);
+    Flash::success(Redirect::to("/"), message)
+}
+
+#[get("/playlists")]
+async fn playlists(dashboard: &State<Arc<Dashboard>>) -> RawHtml<String> {
+    let user_access = match dashboard.user_access().await {
+        Ok(user_access) => user_access,
+        Err(err) => return error_page(err),
+    };
+    let playlists = match get_current_user_playlists(&user_access, |_| {}).await {
+        Ok(playlists) => playlists,
+        Err(err) => return error_page(err.into()),
+    };
+    let rows: Vec<Vec<String>> = playlists
+        .iter()
+        .map(|playlist| {
+            vec![
+                link(
+                    &format!("https://open.spotify.com/playlist/{}", playlist.id),
+                    &playlist.name,
+                ),
+                playlist.tracks.total.to_string(),
+                escape(playlist.owner_name()),
+                escape(t(playlist.visibility())),
+            ]
+        })
+        .collect();
+    let body = table(
+        &[t("Name"), t("Tracks"), t("Owner"), t("Visibility")],
+        &rows,
+    );
+    page(t("Playlists"), &body, false)
+}
+
+#[get("/history")]
+fn history_page(dashboard: &State<Arc<Dashboard>>) -> RawHtml<String> {
+    let records = match dashboard.history() {
+        Ok(records) => records,
+        Err(err) => return error_page(err),
+    };
+    if records.is_empty() {
+        return page(
+            t("History"),
+            &format!("<p>{}</p>\n", escape(t("No runs yet."))),
+            false,
+        );
+    }
+    let newest = &records[records.len().saturating_sub(HISTORY_RUNS)..];
+    let body = table(&[t("Started"), t("Actions")], &run_rows(newest));
+    page(t("History"), &body, false)
+}
+
+/// The metrics of all runs so far, like `--metrics-path` writes them, for Prometheus to
+/// scrape.
+#[get("/metrics")]
+fn metrics_text(dashboard: &State<Arc<Dashboard>>) -> Result<(ContentType, String), Status> {
+    metrics::text(dashboard.args.active_profile())
+        .map(|text| (ContentType::Plain, text))
+        .map_err(|err| {
+            status_error!("Could not read the metrics: {}", err);
+            Status::InternalServerError
+        })
+}
+
+/// The feed of the playlists of the runs so far, like `--feed-path` writes it.
+#[get("/feed.atom")]
+fn feed_xml(dashboard: &State<Arc<Dashboard>>) -> Result<(ContentType, String), Status> {
+    feed::text(dashboard.args.active_profile())
+        .map(|text| (ContentType::new("application", "atom+xml"), text))
+        .map_err(|err| {
+            status_error!("Could not read the feed: {}", err);
+            Status::InternalServerError
+        })
+}
+
+/// Serves `/metrics`, `/feed.atom` and the routes added by `mount` on the address and port of the config,
+/// until stopped with Ctrl-C or SIGTERM; `announce` tells where once they can be reached.
+pub async fn launch(
+    args: &Args,
+    dashboard: Dashboard,
+    mount: impl FnOnce(Rocket<Build>) -> Rocket<Build>,
+    announce: impl FnOnce(IpAddr, u16),
+) -> Result<(), MainError> {
+    let server = SpautofyConfig::from(dashboard.file_config());
+    let rocket_config = Config {
+        address: server.auth.address,
+        port: server.auth.port,
+        log_level: LogLevel::Critical,
+        ..Config::release_default()
+    };
+    let dashboard = Arc::new(dashboard);
+    let rocket = rocket::custom(&rocket_config)
+        .manage(Arc::clone(&dashboard))
+        .mount("/", routes![metrics_text, feed_xml]);
+    let rocket = mount(rocket).ignite().await?;
+    let following = tokio::spawn(dashboard.follow_config(ConfigWatcher::new(args)));
+    if args.systemd {
+        systemd::notify("READY=1");
+    }
+    announce(rocket_config.address, rocket_config.port);
+    let result = rocket.launch().await;
+    following.abort();
+    result?;
+    status!("Stopped the web server.");
+    Ok(())
+}
+
+/// Serves the dashboard until stopped.
+pub async fn serve(args: &Args, file_config: SpautofyConfigFile) -> Result<(), MainError> {
+    let dashboard = Dashboard::new(args, file_config).await?;
+    launch(
+        args,
+        dashboard,
+        |rocket| rocket.mount("/", routes![index, run, playlists, history_page]),
+        |address, port| {
+            status!(
+                "Serving the dashboard on http://{}:{}/ until stopped.",
+                address,
+                port
+            )
+        },
+    )
+    .await
+}
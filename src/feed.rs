@@ -0,0 +1,148 @@
+//! An Atom feed of the playlists the runs created or updated, `--feed-path <file>`:
+//! written after every command from the runs kept in the database, for a web server or a
+//! synced folder to hand out, so a feed reader tells when a new playlist appears.
+//! `spautofy dashboard` and `spautofy api` serve it as `/feed.atom` too.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::storage::{self, StorageError};
+use crate::{status_error, tr};
+
+/// The newest entries kept in the feed.
+const MAX_ENTRIES: usize = 50;
+
+/// Text for the elements and attributes of the feed, or of the dashboard.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn timestamp(time: &DateTime<FixedOffset>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// The track count and, for an updated top tracks playlist, the tracks that came and went.
+pub fn summary(playlist: &CreatedPlaylist) -> String {
+    let mut lines = vec![if playlist.updated {
+        tr!("Updated with {} tracks", playlist.track_count)
+    } else {
+        tr!("Created with {} tracks", playlist.track_count)
+    }];
+    if let Some(changes) = &playlist.changes {
+        for track in &changes.added {
+            lines.push(tr!("  new: {} by {}", track.name, track.artists));
+        }
+        for track in &changes.removed {
+            lines.push(tr!("  gone: {} by {}", track.name, track.artists));
+        }
+    }
+    lines.join("\n")
+}
+
+fn entry(started_at: &DateTime<FixedOffset>, playlist: &CreatedPlaylist) -> String {
+    // the same playlist shows up again with every update, the time tells them apart
+    let id = format!("{}#{}", playlist.url, started_at.timestamp());
+    let mut entry = format!(
+        "  <entry>\n    <title>{}</title>\n    <link href=\"{}\"/>\n    <id>{}</id>\n    \
+        <updated>{}</updated>\n    <summary>{}</summary>\n",
+        escape(&playlist.name),
+        escape(&playlist.url),
+        escape(&id),
+        timestamp(started_at),
+        escape(&summary(playlist))
+    );
+    if let Some(cover_url) = &playlist.cover_url {
+        entry.push_str(&format!(
+            "    <link rel=\"enclosure\" type=\"image/jpeg\" href=\"{}\"/>\n",
+            escape(cover_url)
+        ));
+    }
+    entry.push_str("  </entry>\n");
+    entry
+}
+
+/// The feed of the newest playlists of the runs, oldest run first; the feed is as new as
+/// its newest entry, so it only changes with a new playlist.
+fn render(runs: &[(DateTime<FixedOffset>, Vec<CreatedPlaylist>)], profile: Option<&str>) -> String {
+    let entries: Vec<(&DateTime<FixedOffset>, &CreatedPlaylist)> = runs
+        .iter()
+        .rev()
+        .flat_map(|(started_at, playlists)| {
+            playlists.iter().map(move |playlist| (started_at, playlist))
+        })
+        .take(MAX_ENTRIES)
+        .collect();
+    let title = match profile {
+        Some(profile) => format!("{} ({})", tr!("Spautofy playlists"), profile),
+        None => tr!("Spautofy playlists"),
+    };
+    let id = match profile {
+        Some(profile) => format!("urn:spautofy:playlists:{}", profile),
+        None => "urn:spautofy:playlists".to_string(),
+    };
+    let updated = entries
+        .first()
+        .map(|(started_at, _)| timestamp(started_at))
+        .unwrap_or_else(|| "1970-01-01T00:00:00Z".to_string());
+    let mut feed = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+        <feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <title>{}</title>\n  <id>{}</id>\n  \
+        <updated>{}</updated>\n  <author><name>Spautofy</name></author>\n",
+        escape(&title),
+        escape(&id),
+        updated
+    );
+    for (started_at, playlist) in entries {
+        feed.push_str(&entry(started_at, playlist));
+    }
+    feed.push_str("</feed>\n");
+    feed
+}
+
+/// The feed of the runs kept in the database, for `/feed.atom` of a server; without a
+/// database it has no entries.
+pub fn text(profile: Option<&str>) -> Result<String, StorageError> {
+    let runs = storage::with_current_storage(|storage| storage.runs())?;
+    let runs: Vec<_> = runs
+        .unwrap_or_default()
+        .into_iter()
+        .map(|run| {
+            let playlists = run
+                .reports
+                .into_iter()
+                .filter_map(|report| report.result.ok())
+                .collect();
+            (run.started_at, playlists)
+        })
+        .collect();
+    Ok(render(&runs, profile))
+}
+
+/// Writes the feed to `path` if given; a failure is only reported, like one of the
+/// history.
+pub fn write(path: Option<&str>, profile: Option<&str>) {
+    let Some(path) = path else {
+        return;
+    };
+    let result = text(profile)
+        .map_err(|err| err.to_string())
+        .and_then(|text| write_file(Path::new(path), &text).map_err(|err| err.to_string()));
+    if let Err(err) = result {
+        status_error!("Could not write the feed to {}: {}", path, err);
+    }
+}
+
+/// Written next to the file and renamed over it, so it is never served half written.
+fn write_file(path: &Path, text: &str) -> io::Result<()> {
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, text)?;
+    fs::rename(&temporary, path)
+}
@@ -0,0 +1,79 @@
+//! Keeps the backups in a git repository, `backup --git <repository>`, so the history of
+//! the library can be browsed and diffed with git: every backup overwrites the files of
+//! the one before and is committed with the time it was made as the message.
+
+use std::path::{Path, PathBuf};
+
+use git2::{Commit, ErrorCode, Oid, Repository, Signature};
+
+/// The author of the commits if git has no `user.name` and `user.email` configured.
+const FALLBACK_NAME: &str = "Spautofy";
+const FALLBACK_EMAIL: &str = "spautofy@localhost";
+
+pub struct GitHistory {
+    repository: Repository,
+}
+
+impl GitHistory {
+    /// Opens the repository at `path`, creating it and the directory if there is none.
+    pub fn open_or_init(path: &str) -> Result<Self, git2::Error> {
+        let repository = match Repository::open(path) {
+            Err(err) if err.code() == ErrorCode::NotFound => Repository::init(path)?,
+            repository => repository?,
+        };
+        if repository.is_bare() {
+            return Err(git2::Error::from_str(&format!(
+                "{} is a bare repository, the backups need a working tree",
+                path
+            )));
+        }
+        Ok(GitHistory { repository })
+    }
+
+    /// The working tree, the backup is written into.
+    pub fn directory(&self) -> &Path {
+        self.repository.workdir().expect("not a bare repository")
+    }
+
+    pub fn path(&self, file: &str) -> PathBuf {
+        self.directory().join(file)
+    }
+
+    /// Commits the files, relative to the repository; `None` if nothing changed since
+    /// the last commit.
+    pub fn commit(&self, files: &[&str], message: &str) -> Result<Option<Oid>, git2::Error> {
+        let mut index = self.repository.index()?;
+        for file in files {
+            index.add_path(Path::new(file))?;
+        }
+        index.write()?;
+        let tree = self.repository.find_tree(index.write_tree()?)?;
+        let parent = match self.repository.head() {
+            Ok(head) => Some(head.peel_to_commit()?),
+            // the first backup
+            Err(err) if err.code() == ErrorCode::UnbornBranch => None,
+            Err(err) => return Err(err),
+        };
+        if parent
+            .as_ref()
+            .is_some_and(|parent| parent.tree_id() == tree.id())
+        {
+            return Ok(None);
+        }
+        let signature = self
+            .repository
+            .signature()
+            .or_else(|_| Signature::now(FALLBACK_NAME, FALLBACK_EMAIL))?;
+        let parents: Vec<&Commit> = parent.iter().collect();
+        self.repository
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .map(Some)
+    }
+}
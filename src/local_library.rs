@@ -0,0 +1,100 @@
+//! Reads a local music collection for `spautofy scan-library`: every music file below the
+//! directory becomes a song to search for on Spotify, by the ISRC, title and artist of its
+//! tags, or by its file name like `01 - Artist - Title.mp3` if it has none.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use lofty::file::TaggedFileExt;
+use lofty::tag::{Accessor, ItemKey};
+
+use crate::song_list::{read_line, Song};
+use crate::{status_verbose, tr};
+
+/// The extensions of the music files, lowercase; videos and playlists are left out.
+const MUSIC_EXTENSIONS: [&str; 14] = [
+    "mp3", "flac", "m4a", "aac", "ogg", "oga", "opus", "wav", "aiff", "aif", "ape", "wv", "mpc",
+    "spx",
+];
+
+fn is_music_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| MUSIC_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+/// The music files below the directory, sorted; symlinked directories are not followed, they
+/// may lead in circles.
+fn music_files(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in fs::read_dir(&directory)? {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            let path = entry.path();
+            if file_type.is_dir() {
+                directories.push(path);
+            } else if is_music_file(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The song of a file, by its tags or else by its name; `text` names the file in the
+/// report.
+fn read_file(number: usize, path: &Path, text: String) -> Option<Song> {
+    match lofty::read_from_path(path) {
+        Ok(file) => {
+            let tag = file.primary_tag().or_else(|| file.first_tag());
+            if let Some(tag) = tag {
+                if let Some(title) = tag.title() {
+                    let isrc = tag.get_string(ItemKey::Isrc).map(String::from);
+                    return Some(Song::tagged(
+                        number,
+                        text,
+                        &title,
+                        tag.artist().as_deref(),
+                        isrc,
+                    ));
+                }
+            }
+        }
+        Err(err) => status_verbose!("Could not read the tags of {}: {}", text, err),
+    }
+    let stem = path.file_stem()?.to_string_lossy();
+    let mut song = read_line(number, &stem)?;
+    song.text = text;
+    Some(song)
+}
+
+/// The songs of the music files below the directory, named by their paths inside it.
+pub fn scan(directory: &str) -> io::Result<Vec<Song>> {
+    let root = Path::new(directory);
+    let files = music_files(root)
+        .map_err(|err| io::Error::new(err.kind(), tr!("Error reading {}: {}", directory, err)))?;
+    let songs: Vec<Song> = files
+        .iter()
+        .enumerate()
+        .filter_map(|(index, path)| {
+            let text = path
+                .strip_prefix(root)
+                .unwrap_or(path)
+                .display()
+                .to_string();
+            read_file(index + 1, path, text)
+        })
+        .collect();
+    if songs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            tr!("{} holds no music files.", directory),
+        ));
+    }
+    status_verbose!("Read {} music files", songs.len());
+    Ok(songs)
+}
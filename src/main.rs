@@ -1,40 +1,813 @@
-use clap::Parser;
-use rocket::{routes, Config};
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::error::ErrorKind;
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use futures::stream::{self, StreamExt};
+#[cfg(feature = "web-auth")]
+use rocket::config::LogLevel;
+#[cfg(feature = "web-auth")]
+use rocket::{routes, Config, Ignite, Rocket};
+use serde_json::Value;
+use std::env;
+use std::ffi::OsStr;
 use std::fs;
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+#[cfg(feature = "web-auth")]
+use std::sync::{Arc, RwLock};
 use thiserror::Error;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::mpsc;
 
-mod actions;
-mod authorize;
-mod endpoints;
-mod models;
-mod user_info;
+#[cfg(feature = "web-auth")]
+mod api;
+#[cfg(feature = "web-auth")]
+mod auth_server;
+mod commands;
+mod config;
+mod config_format;
+mod config_migration;
+mod config_reload;
+#[cfg(feature = "web-auth")]
+mod dashboard;
+mod feed;
+mod git_history;
+mod local_library;
+mod notifications;
+mod profiles;
+mod secret_files;
+mod systemd;
+#[cfg(feature = "tui")]
+mod tui;
 
-use actions::top_track_playlist::{create_top_track_playlist, TimeRange};
-use authorize::{
-    auth, callback, done, get_access_token, index, Access, AuthorizeError, SpautofyConfig,
-    SpautofyConfigFile,
+use spautofy_core::{
+    actions, authorize, dates, endpoints, history, history_import, i18n, integrations, metrics,
+    models, player, progress, schemas, song_list, status_log, storage, user_info, UserAccess,
 };
-use user_info::{get_user_access, User};
+use spautofy_core::{status, status_error, status_verbose, tr};
 
-extern crate rocket;
+use actions::action_report::{ActionReport, RunEvent};
+use actions::export::{load_backup, Backup};
+use actions::playlist_actions::PlaylistVisibility;
+use actions::registry::{self, parse_action};
+use actions::rules::Rule;
+use actions::top_track_playlist::MAX_TOP_TRACKS_LIMIT;
+use actions::{set_dry_run, stages, Task};
+#[cfg(feature = "web-auth")]
+use auth_server::{auth, callback, done, index};
+#[cfg(feature = "web-auth")]
+use authorize::get_access_token;
+#[cfg(feature = "tui")]
+use authorize::refresh_access_token;
+use authorize::{access_from_refresh_token, AuthorizeError};
+use commands::{DiffSource, ExportFormat, OutputFormat};
+#[cfg(feature = "web-auth")]
+use config::SharedConfig;
+use config::{credentials_path, Credentials, SpautofyConfig, SpautofyConfigFile, CONFIG_EXAMPLES};
+#[cfg(feature = "tui")]
+use config_format::write_config_file;
+use config_format::{from_fields, ConfigFormat, ParseError};
+use config_migration::{config_version, migrate, Migration, CONFIG_VERSION};
+use config_reload::ConfigWatcher;
+use dates::set_date_settings;
+#[cfg(feature = "tui")]
+use history::RunRecord;
+use i18n::set_locale;
+use notifications::mqtt::{Listener, MqttConfig};
+use notifications::Notifier;
+use profiles::{find_profiles, profile_path, read_sections, select_profile, DEFAULT_PROFILE};
+use progress::ProgressFormat;
+use schemas::SchemaKind;
+use secret_files::{check_secret_file, copy_secret_file, write_secret_file};
+use song_list::SongMatch;
+use status_log::Level;
+#[cfg(feature = "tui")]
+use tui::{
+    action_selection::{load_selection, save_selection, selection_from_keys, selection_path},
+    auth_screen::wait_for_authorization,
+    config_wizard::run_config_wizard,
+    keymap::{set_keymap, Keymap},
+    match_picker::MatchPicker,
+    now_playing,
+    restore_picker::{RestoreEntry, RestorePicker},
+    run_screen::RunScreen,
+    summary_screen::SummaryScreen,
+    theme::{set_theme, Theme},
+    wizard::{Wizard, WizardOutcome},
+    ScreenOutcome,
+};
+use user_info::get_user_access;
+
+const DEFAULT_CONFIG_FILE: &str = "spautofy.config";
+/// Looked for in this order if there is no `DEFAULT_CONFIG_FILE`.
+const OTHER_CONFIG_FILES: [&str; 3] = ["spautofy.toml", "spautofy.yaml", "spautofy.yml"];
+/// Kept next to the config file unless `--history-path` is given.
+const DEFAULT_HISTORY_FILE: &str = "spautofy.history";
+
+/// Spotify turns away too many requests at once, failing the actions sending them.
+const MAX_CONCURRENT_ACTIONS: usize = 4;
+
+/// The variables `SpautofyConfigFile::with_env_overrides` reads.
+const ENVIRONMENT_HELP: &str = "\
+Environment, overriding the config file; the command line overrides both:
+  SPAUTOFY_CONFIG, SPAUTOFY_CREDENTIALS    Config and credentials file
+  SPAUTOFY_METRICS_PATH                    File the metrics for Prometheus are written to
+  SPAUTOFY_FEED_PATH                       File the Atom feed of the playlists is written to
+  SPAUTOFY_ADDRESS, SPAUTOFY_PORT          Address and port of the login server
+  SPAUTOFY_CLIENT_ID, SPAUTOFY_CLIENT_SECRET, SPAUTOFY_REFRESH_TOKEN
+  SPAUTOFY_LOCALE                          en or de
+  SPAUTOFY_DATE_FORMAT                     Date in playlist names, e.g. %Y-%m-%d
+  SPAUTOFY_DATE_LOCALE                     Language of its month names, e.g. de_DE
+  SPAUTOFY_TIMEZONE                        Timezone of that date, e.g. Europe/Berlin
+  SPAUTOFY_THEME                           dark or light
+  SPAUTOFY_LIMIT                           Number of tracks per top tracks playlist
+  SPAUTOFY_NAME_TEMPLATE                   Name of the top tracks playlists
+  SPAUTOFY_TIME_RANGES                     Time ranges selected in the TUI, e.g. short,long
+  SPAUTOFY_PUBLIC, SPAUTOFY_COLLABORATIVE  true or false
+  SPAUTOFY_LASTFM_API_KEY, SPAUTOFY_LASTFM_USER  For the rules with the source lastfm
+  SPAUTOFY_LISTENBRAINZ_TOKEN              For listenbrainz and its rule source
+  SPAUTOFY_SMTP_PASSWORD                   For the email notifications
+  SPAUTOFY_NTFY_TOKEN                      For a protected ntfy topic
+  SPAUTOFY_PUSHOVER_TOKEN                  API token of the Pushover application
+  SPAUTOFY_MQTT_PASSWORD                   For the MQTT broker
+  SPAUTOFY_HEALTHCHECK_URL                 Pinged after every `run`, e.g. of healthchecks.io
+  SPAUTOFY_API_TOKEN                       Token the requests to `spautofy api` need";
+
+const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  Success
+  1  Other errors, e.g. a file could not be written
+  2  Invalid arguments
+  3  The config file is missing or invalid
+  4  Not logged in, or the authorization failed
+  5  Spotify could not be reached or sent an unexpected response
+  6  Some of the actions failed
+  7  No playlist matches, or more than one does
+  8  `diff` found differences
+With several profiles, the code of the one failing worst, in the order 3, 4, 5, 1, 7, 6, 8.";
 
-#[derive(Debug, Parser)]
-#[command(version, author, about, long_about = None)]
+#[derive(Debug, Clone, Parser)]
+#[command(
+    version,
+    author,
+    about,
+    long_about = None,
+    after_help = format!("{}\n\n{}", ENVIRONMENT_HELP, EXIT_CODES_HELP)
+)]
 struct Args {
-    #[arg(short, long, default_value = "spautofy.config")]
+    /// Config file, TOML if it ends in `.toml`, YAML if it ends in `.yaml` or `.yml` and JSON
+    /// otherwise; by default spautofy.config, .toml, .yaml or .yml in
+    /// $XDG_CONFIG_HOME/spautofy or else in the current directory
+    #[arg(
+        short,
+        long,
+        env = "SPAUTOFY_CONFIG",
+        default_value_t = default_config_path(),
+        global = true
+    )]
     config_path: String,
+    /// File the client ID, secret and refresh token are kept in; by default the config file
+    /// with the extension `.credentials`
+    #[arg(long, env = "SPAUTOFY_CREDENTIALS", global = true)]
+    credentials_path: Option<String>,
+    /// Database the previous runs, their top tracks and playlists and the played tracks are
+    /// recorded in; by default spautofy.history next to the config file
+    #[arg(long, global = true)]
+    history_path: Option<String>,
+    /// Write the counters of all runs to this file after every command, for the textfile
+    /// collector of the Prometheus node exporter, e.g.
+    /// /var/lib/node_exporter/textfile_collector/spautofy.prom
+    #[arg(long, env = "SPAUTOFY_METRICS_PATH", global = true)]
+    metrics_path: Option<String>,
+    /// Write an Atom feed of the playlists created or updated by the last runs to this file
+    /// after every command, for a web server to hand out to feed readers
+    #[arg(long, env = "SPAUTOFY_FEED_PATH", global = true)]
+    feed_path: Option<String>,
+    /// Only read from Spotify and print what would be created, changed or deleted
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Print more details, `-vv` also prints every request to Spotify
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Only print errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Print the results of a command as JSON, and everything else to stderr
+    #[arg(long, global = true)]
+    json: bool,
+    /// Run as a systemd service: no TUI, log lines with journal priorities and
+    /// readiness sent to $NOTIFY_SOCKET
+    #[arg(long, global = true)]
+    systemd: bool,
+    /// Make the created playlists public
+    #[arg(long, global = true, conflicts_with = "collaborative")]
+    public: bool,
+    /// Make the created playlists collaborative, so others can change them
+    #[arg(long, global = true)]
+    collaborative: bool,
+    /// Use the account of this profile, kept in the `profiles.<profile>` section of the
+    /// config file or in e.g. `spautofy.<profile>.config`; `run` accepts several
+    #[arg(long, global = true)]
+    profile: Vec<String>,
+    /// Run for every profile in the config file or with a config file next to it
+    #[arg(long, global = true, conflicts_with = "profile")]
+    all_profiles: bool,
+    /// Set while running one of several profiles.
+    #[arg(skip)]
+    batch_profile: Option<String>,
+    /// The profile within the config file, see `profiles`.
+    #[arg(skip)]
+    config_section: Option<String>,
+    /// Print the JSON Schema of a file Spautofy writes, or of the output of `diff --json`,
+    /// and exit
+    #[arg(long, value_name = "KIND", value_parser = schema_kind_parser())]
+    schema: Option<SchemaKind>,
+    /// How to report the progress; `ndjson` prints one JSON event per line to stdout
+    #[arg(
+        long,
+        global = true,
+        default_value = "text",
+        value_parser = progress_format_parser()
+    )]
+    progress: ProgressFormat,
+    // the TUI starts if no command is given
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+// all commands but `auth` use the refresh token kept from the last login
+#[derive(Debug, Clone, Subcommand)]
+enum Command {
+    /// Log in to Spotify in the browser and keep the refresh token for the other commands
+    Auth,
+    /// Run actions, or pipelines and rules from the config, without the TUI
+    Run {
+        /// The actions to run, e.g. `top-tracks:short,top-tracks:medium`, or the names of
+        /// pipelines or rules from the config
+        #[arg(required = true, value_delimiter = ',', value_parser = RunTargetParser)]
+        actions: Vec<RunTarget>,
+        /// Name of the playlists, overriding the config; `{range}` and `{date}` are replaced
+        /// with the time range and today's date
+        #[arg(long, visible_alias = "name-template")]
+        name: Option<String>,
+        /// Number of tracks per playlist, overriding the config
+        #[arg(
+            long,
+            value_parser = clap::value_parser!(u32).range(1..=MAX_TOP_TRACKS_LIMIT as i64)
+        )]
+        limit: Option<u32>,
+    },
+    /// Print the name, id, country and subscription of the logged in account
+    Whoami,
+    /// Print a fresh access token for your own requests to the Spotify API
+    Token {
+        /// Fail unless the token was granted all of these scopes, e.g. `user-top-read`
+        #[arg(long, value_delimiter = ',')]
+        scopes: Vec<String>,
+    },
+    /// List your playlists or show the tracks of one
+    Playlists {
+        #[command(subcommand)]
+        command: PlaylistsCommand,
+    },
+    /// Write a playlist or the liked songs to a JSON file, or to a CSV file for moving them
+    /// to another service
+    Export {
+        /// Id of the playlist, as listed by `spautofy playlists list`
+        #[arg(required_unless_present = "liked_songs")]
+        playlist_id: Option<String>,
+        /// Export the liked songs instead of a playlist
+        #[arg(long, conflicts_with = "playlist_id")]
+        liked_songs: bool,
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+        /// Defaults to `<playlist id>.json`, or `liked-songs.json`; `.csv` for the csv format
+        /// and `.mapping.json` for the mapping format
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Write the file into this git repository instead, created if needed, and commit
+        /// it with the time of the export as the message
+        #[arg(long, value_name = "REPOSITORY", conflicts_with = "output")]
+        git: Option<String>,
+    },
+    /// Write all your playlists and their tracks to one JSON file
+    Backup {
+        /// Defaults to `spautofy-backup.json`; with `--all` the directory to create the
+        /// backup directory in, by default the current one
+        #[arg(short, long)]
+        output: Option<String>,
+        /// Also back up the liked songs, followed artists and saved albums, into a new
+        /// directory named after the current time
+        #[arg(long)]
+        all: bool,
+        /// Write the backup into this git repository instead, created if needed, over the
+        /// files of the last one, and commit it with the time of the backup as the message
+        #[arg(long, value_name = "REPOSITORY", conflicts_with = "output")]
+        git: Option<String>,
+    },
+    /// Recreate playlists and liked songs from a backup; without a selection they are picked
+    /// in the TUI
+    Restore {
+        /// A directory written by `backup --all`, or a file written by `backup`
+        backup: String,
+        /// Restore the playlist with this name or id, can be given several times
+        #[arg(long = "playlist")]
+        playlists: Vec<String>,
+        /// Like the liked songs of the backup again
+        #[arg(long)]
+        liked_songs: bool,
+        /// Restore everything in the backup
+        #[arg(long, conflicts_with_all = ["playlists", "liked_songs"])]
+        all: bool,
+    },
+    /// Print the tracks added, removed and moved between two playlists or backups; exits
+    /// with 8 if there are any
+    Diff {
+        /// A backup written by `backup` or `backup --all`, or otherwise the id, URL or name
+        /// of a playlist as for `playlists show`
+        old: String,
+        /// Like `old`
+        new: String,
+        /// The playlist to compare in the backups, by name or id; by default the one
+        /// compared with, or all of them if both are backups
+        #[arg(long)]
+        playlist: Option<String>,
+    },
+    /// Print a completion script for the shell, e.g. `spautofy completions bash`
+    Completions { shell: Shell },
+    /// Manage the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Exchange listens with ListenBrainz, using the token in the config
+    Listenbrainz {
+        #[command(subcommand)]
+        command: ListenbrainzCommand,
+    },
+    /// Add the plays of a scrobble CSV export or of the streaming history JSON of the
+    /// Spotify account data to the played tracks in the history database
+    ImportHistory {
+        /// CSV or JSON files, or directories like the unpacked account data holding them
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+    /// Create a playlist of the songs in a text file, one per line like `Artist - Title`;
+    /// the tracks Spotify finds but is unsure about are reviewed in the TUI
+    ImportSongs {
+        /// The text file; numbering, durations, `(Official Video)` and the like are ignored
+        file: String,
+        /// Name of the playlist, by default the name of the file without its extension
+        #[arg(long)]
+        name: Option<String>,
+        /// How sure a track has to be, in percent, to be added without a review
+        #[arg(
+            long,
+            default_value_t = 80,
+            value_parser = clap::value_parser!(u8).range(0..=100)
+        )]
+        min_confidence: u8,
+        /// Leave out the songs below --min-confidence instead of reviewing them
+        #[arg(long)]
+        no_review: bool,
+    },
+    /// Find the music files of a directory on Spotify by their tags or names, and report
+    /// which of them are there
+    ScanLibrary {
+        /// The directory, searched with all the directories inside it
+        directory: String,
+        /// Create a playlist of the tracks found, by default named "My local collection"
+        #[arg(
+            long,
+            value_name = "NAME",
+            num_args = 0..=1,
+            default_missing_value = "My local collection"
+        )]
+        playlist: Option<String>,
+        /// How sure a track has to be, in percent, to count as the file
+        #[arg(
+            long,
+            default_value_t = 80,
+            value_parser = clap::value_parser!(u8).range(0..=100)
+        )]
+        min_confidence: u8,
+        /// With --playlist, leave out the files below --min-confidence instead of
+        /// reviewing them
+        #[arg(long)]
+        no_review: bool,
+        /// Only list the files not on Spotify
+        #[arg(long)]
+        missing: bool,
+    },
+    /// Run what is sent to the `<topic>/run` topic of the MQTT broker in the config until
+    /// stopped, e.g. by Home Assistant; the results go to `<topic>/result`
+    Mqtt,
+    /// Serve a web dashboard on the address and port of the config until stopped: the
+    /// playlists, a button to run every action, rule and pipeline, and the runs so far
+    Dashboard,
+    /// Serve a JSON API on the address and port of the config until stopped, for other
+    /// programs to run what `run` takes: POST /api/run/<target>, GET /api/status and GET
+    /// /api/playlists; the requests need the token of api.token as `Authorization: Bearer
+    /// <token>`. The OpenAPI description is served as /api/openapi.json
+    Api {
+        /// Print the OpenAPI description and exit
+        #[arg(long)]
+        openapi: bool,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum PlaylistsCommand {
+    /// Print your playlists with their ids, track counts, owners and visibility
+    List {
+        /// Only list the playlists whose name matches, like the '/' filter of the TUI
+        #[arg(long)]
+        filter: Option<String>,
+        /// Only list the playlists you own, or the user of `--user` owns
+        #[arg(long)]
+        owned_only: bool,
+        /// List the public playlists of another user instead, by their id or profile link
+        #[arg(long)]
+        user: Option<String>,
+    },
+    /// Print the tracks of a playlist
+    Show {
+        /// Id, URL or name of the playlist; the name only needs to match like the '/'
+        /// filter of the TUI
+        playlist: String,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+        format: OutputFormat,
+    },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum ListenbrainzCommand {
+    /// Submit the recently played tracks as listens, the ones played after the latest
+    /// listen on ListenBrainz
+    Submit,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum ConfigCommand {
+    /// Check the config file for mistakes and print the redirect URL to register
+    Validate {
+        /// Also ask Spotify whether it accepts the client ID and secret
+        #[arg(long)]
+        check_credentials: bool,
+    },
+    /// Ask for the Spotify app settings, log in once to check them and write the config file
+    Init {
+        /// Replace an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+/// The config in the XDG config directory, `~/.config` unless $XDG_CONFIG_HOME is set,
+/// or the one in the current directory if there is none.
+fn default_config_path() -> String {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+    let directories = config_home
+        .map(|config_home| config_home.join("spautofy"))
+        .into_iter()
+        .chain([PathBuf::new()]);
+    directories
+        .flat_map(|directory| {
+            [DEFAULT_CONFIG_FILE]
+                .into_iter()
+                .chain(OTHER_CONFIG_FILES)
+                .map(move |file| directory.join(file))
+        })
+        .find(|path| path.exists())
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|| DEFAULT_CONFIG_FILE.to_string())
+}
+
+impl Args {
+    /// The credentials file given, or the one belonging to the config file.
+    fn credentials_file(&self) -> String {
+        match (&self.credentials_path, &self.config_section) {
+            (Some(path), _) => path.clone(),
+            (None, Some(profile)) => profile_path(&credentials_path(&self.config_path), profile),
+            (None, None) => credentials_path(&self.config_path),
+        }
+    }
+
+    /// The history database given, or the one next to the config file, so it does not
+    /// depend on the directory Spautofy is started in.
+    fn history_file(&self) -> String {
+        match &self.history_path {
+            Some(path) => path.clone(),
+            None => Path::new(&self.config_path)
+                .with_file_name(DEFAULT_HISTORY_FILE)
+                .to_string_lossy()
+                .into_owned(),
+        }
+    }
+
+    /// The profile in use, to tell the notifications about it.
+    fn active_profile(&self) -> Option<&str> {
+        self.batch_profile
+            .as_deref()
+            .or(self.config_section.as_deref())
+            .or(self.profile.first().map(String::as_str))
+    }
+
+    /// The arguments with the config and history paths of the profile; a profile within the
+    /// config file keeps its path.
+    fn for_profile(&self, profile: &str) -> Args {
+        let in_config_file = profile != DEFAULT_PROFILE
+            && read_sections(&self.config_path)
+                .names
+                .iter()
+                .any(|name| name == profile);
+        if in_config_file {
+            return Args {
+                config_section: Some(profile.to_string()),
+                credentials_path: self
+                    .credentials_path
+                    .as_deref()
+                    .map(|path| profile_path(path, profile)),
+                history_path: Some(profile_path(&self.history_file(), profile)),
+                metrics_path: self.metrics_path(profile),
+                feed_path: self.feed_path(profile),
+                ..self.clone()
+            };
+        }
+        Args {
+            config_path: profile_path(&self.config_path, profile),
+            credentials_path: self
+                .credentials_path
+                .as_deref()
+                .map(|path| profile_path(path, profile)),
+            history_path: Some(profile_path(&self.history_file(), profile)),
+            metrics_path: self.metrics_path(profile),
+            feed_path: self.feed_path(profile),
+            ..self.clone()
+        }
+    }
+
+    /// Like the history, every profile gets metrics and a feed of its own, since each writes
+    /// the whole file.
+    fn metrics_path(&self, profile: &str) -> Option<String> {
+        self.metrics_path
+            .as_deref()
+            .map(|path| profile_path(path, profile))
+    }
+
+    fn feed_path(&self, profile: &str) -> Option<String> {
+        self.feed_path
+            .as_deref()
+            .map(|path| profile_path(path, profile))
+    }
+
+    /// Whether the command runs for more than one profile.
+    fn runs_several_profiles(&self) -> bool {
+        self.all_profiles || self.profile.len() > 1
+    }
+
+    /// `None` unless `--public` or `--collaborative` is given, leaving the config defaults.
+    fn visibility(&self) -> Option<PlaylistVisibility> {
+        (self.public || self.collaborative).then_some(PlaylistVisibility {
+            public: self.public,
+            collaborative: self.collaborative,
+        })
+    }
+}
+
+impl Command {
+    /// Whether the command may use the terminal for the setup wizard and the login.
+    fn is_interactive(command: &Option<Command>) -> bool {
+        match command {
+            None
+            | Some(Command::Auth)
+            | Some(Command::Config {
+                command: ConfigCommand::Init { .. },
+            }) => true,
+            // without a selection the picker asks what to restore
+            Some(Command::Restore {
+                playlists,
+                liked_songs,
+                all,
+                ..
+            }) => playlists.is_empty() && !liked_songs && !all,
+            Some(Command::ImportSongs { no_review, .. }) => !no_review,
+            Some(Command::ScanLibrary {
+                playlist,
+                no_review,
+                ..
+            }) => playlist.is_some() && !no_review,
+            _ => false,
+        }
+    }
+}
+
+/// What `spautofy run` was asked to run.
+#[derive(Debug, Clone)]
+enum RunTarget {
+    Action(Task),
+    /// The name of a pipeline or rule, which is only known once the config is loaded.
+    Pipeline(String),
+}
+
+/// Takes anything without a `:` as the name of a pipeline.
+fn parse_run_target(value: &str) -> Result<RunTarget, String> {
+    if !value.contains(':') {
+        return Ok(RunTarget::Pipeline(value.to_string()));
+    }
+    parse_action(value).map(RunTarget::Action)
+}
+
+/// Lists the actions as possible values, so they show up in the help and the completions.
+#[derive(Debug, Clone)]
+struct RunTargetParser;
+
+impl TypedValueParser for RunTargetParser {
+    type Value = RunTarget;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        _arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<RunTarget, clap::Error> {
+        let Some(value) = value.to_str() else {
+            return Err(clap::Error::new(ErrorKind::InvalidUtf8).with_cmd(cmd));
+        };
+        parse_run_target(value).map_err(|err| {
+            clap::Error::raw(ErrorKind::InvalidValue, format!("{}\n", err)).with_cmd(cmd)
+        })
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(registry::cli_names().map(|(name, action)| {
+            PossibleValue::new(name).help(action.description())
+        })))
+    }
+}
+
+/// Parses the names of an enum of the core crate, which knows nothing of clap, and lists
+/// them with their descriptions like `ValueEnum` does.
+fn enum_parser<T: Copy + Send + Sync + 'static>(
+    values: &'static [T],
+    name: fn(T) -> &'static str,
+    description: fn(T) -> &'static str,
+) -> impl TypedValueParser<Value = T> {
+    let possible_values = values
+        .iter()
+        .map(move |value| PossibleValue::new(name(*value)).help(description(*value)));
+    PossibleValuesParser::new(possible_values).map(move |chosen| {
+        *values
+            .iter()
+            .find(|value| name(**value) == chosen)
+            .expect("Only possible values are parsed")
+    })
+}
+
+fn schema_kind_parser() -> impl TypedValueParser<Value = SchemaKind> {
+    enum_parser(&SchemaKind::ALL, SchemaKind::name, SchemaKind::description)
+}
+
+fn progress_format_parser() -> impl TypedValueParser<Value = ProgressFormat> {
+    enum_parser(
+        &ProgressFormat::ALL,
+        ProgressFormat::name,
+        ProgressFormat::description,
+    )
+}
+
+/// The actions to run for the targets: the config defaults, then the pipeline steps, then
+/// the arguments; each with the name of its pipeline for the metrics.
+fn resolve_run_targets(
+    args: &Args,
+    file_config: &SpautofyConfigFile,
+    targets: &[RunTarget],
+    name: Option<&str>,
+    limit: Option<u32>,
+) -> Result<Vec<(String, Task)>, MainError> {
+    let mut actions = Vec::new();
+    for target in targets {
+        match target {
+            RunTarget::Action(task) => {
+                let task = task.clone().with_config(&file_config.actions);
+                actions.push((task.name(), task));
+            }
+            RunTarget::Pipeline(name) if !file_config.pipelines.contains_key(name) => {
+                let Some(rule) = file_config.rules.get(name) else {
+                    return Err(MainError::Config(tr!(
+                        "There is no pipeline or rule \"{}\" in the config file, and it is \
+                        no action.",
+                        name
+                    )));
+                };
+                let rule = Rule::parse(name, rule, &file_config.integrations)
+                    .map_err(|err| MainError::Config(tr!("Error in rule \"{}\": {}", name, err)))?;
+                actions.push((name.clone(), Task::Rule(rule)));
+            }
+            RunTarget::Pipeline(pipeline) => {
+                let steps = &file_config.pipelines[pipeline];
+                for (index, step) in steps.iter().enumerate() {
+                    let params = step.params(&file_config.actions).map_err(|err| {
+                        MainError::Config(tr!(
+                            "Error in step {} of pipeline \"{}\": {}",
+                            index + 1,
+                            pipeline,
+                            err
+                        ))
+                    })?;
+                    actions.push((pipeline.clone(), Task::TopTracks(params)));
+                }
+            }
+        }
+    }
+    // a rule always writes to its target, `--name` is only for the top tracks
+    for (_, action) in &mut actions {
+        match action {
+            Task::TopTracks(params) => {
+                if let Some(visibility) = args.visibility() {
+                    *params = params.clone().with_visibility(visibility);
+                }
+                if let Some(limit) = limit {
+                    params.limit = limit;
+                }
+                if let Some(name) = name {
+                    params.name = Some(name.to_string());
+                }
+            }
+            Task::Rule(rule) => {
+                if let Some(visibility) = args.visibility() {
+                    *rule = rule.clone().with_visibility(visibility);
+                }
+                if let Some(limit) = limit {
+                    rule.limit = Some(limit);
+                }
+            }
+        }
+    }
+    Ok(actions)
 }
 
 #[derive(Debug, Error)]
 enum MainError {
     #[error("Authorization error: {0}")]
     Auth(AuthorizeError),
+    #[cfg(feature = "web-auth")]
     #[error("Rocket error: {0}")]
-    Rocket(rocket::Error),
-    #[error("Unknown error.")]
-    Unknown,
+    Rocket(Box<rocket::Error>),
+    #[error("IO error: {0}")]
+    Io(std::io::Error),
+    /// The messages of the following errors are translated already.
+    #[error("{0}")]
+    Config(String),
+    #[error("{0}")]
+    NotLoggedIn(String),
+    #[error("{0}")]
+    ActionsFailed(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Differences(String),
+}
+
+impl MainError {
+    /// The exit code for this class of errors, see `EXIT_CODES_HELP`.
+    fn exit_code(&self) -> ExitCode {
+        let code = match self {
+            #[cfg(feature = "web-auth")]
+            MainError::Rocket(_) => 1,
+            MainError::Io(_) => 1,
+            MainError::Config(_) => 3,
+            MainError::NotLoggedIn(_) => 4,
+            MainError::Auth(AuthorizeError::RequestError(_)) => 5,
+            MainError::Auth(_) => 4,
+            MainError::ActionsFailed(_) => 6,
+            MainError::NotFound(_) => 7,
+            MainError::Differences(_) => 8,
+        };
+        ExitCode::from(code)
+    }
+
+    /// How much of a run the error kept from happening, for the exit code of several
+    /// profiles: a config or login that does not work stops all of it, `diff` finding
+    /// differences nothing.
+    fn severity(&self) -> u8 {
+        match self {
+            MainError::Config(_) => 6,
+            MainError::NotLoggedIn(_) => 5,
+            MainError::Auth(AuthorizeError::RequestError(_)) => 4,
+            MainError::Auth(_) => 5,
+            #[cfg(feature = "web-auth")]
+            MainError::Rocket(_) => 3,
+            MainError::Io(_) => 3,
+            MainError::NotFound(_) => 2,
+            MainError::ActionsFailed(_) => 1,
+            MainError::Differences(_) => 0,
+        }
+    }
 }
 
 impl From<AuthorizeError> for MainError {
@@ -43,109 +816,1276 @@ impl From<AuthorizeError> for MainError {
     }
 }
 
+#[cfg(feature = "web-auth")]
 impl From<rocket::Error> for MainError {
     fn from(err: rocket::Error) -> Self {
-        MainError::Rocket(err)
+        MainError::Rocket(Box::new(err))
     }
 }
 
-#[derive(Debug)]
-pub struct UserAccess {
-    pub access: Access,
-    pub user: User,
+impl From<std::io::Error> for MainError {
+    fn from(err: std::io::Error) -> Self {
+        MainError::Io(err)
+    }
 }
 
-impl UserAccess {
-    pub fn authorize(&self, request_builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
-        self.access.authorize(request_builder)
-    }
+/// For what this build left out, see the features in `Cargo.toml`.
+#[cfg(not(feature = "tui"))]
+fn missing_feature(feature: &str) -> MainError {
+    MainError::Config(tr!(
+        "This Spautofy was built without the {} feature, which this needs.",
+        feature
+    ))
+}
+
+#[cfg(feature = "tui")]
+async fn create_config_file(
+    filepath_str: &str,
+    credentials_path: &str,
+) -> Result<SpautofyConfigFile, MainError> {
+    let mut terminal = tui::init()?;
+    let file_config = run_config_wizard(&mut terminal).await;
+    tui::restore()?;
+    let Some(file_config) = file_config? else {
+        status_error!("Config file \"{}\" does not exist.", filepath_str);
+        return Err(MainError::Config(tr!(
+            "Run `spautofy config init` to create it with the setup wizard."
+        )));
+    };
+    write_config_file(filepath_str, &file_config)?;
+    status!("Wrote config file \"{}\".", filepath_str);
+    write_secret_file(credentials_path, &Credentials::from(&file_config))?;
+    status!("Wrote credentials file \"{}\".", credentials_path);
+    Ok(file_config)
 }
 
-fn parse_config_file(filepath_str: &str) -> SpautofyConfigFile {
+/// Reads the settings of `profile` if it is one within the config file; only offers the
+/// setup wizard if `interactive` is set.
+async fn parse_config_file(
+    filepath_str: &str,
+    credentials_path: &str,
+    profile: Option<&str>,
+    interactive: bool,
+) -> Result<SpautofyConfigFile, MainError> {
     let path = Path::new(filepath_str);
+    // the setup wizard is part of the TUI
+    let offers_wizard = interactive && cfg!(feature = "tui");
+    if !path.exists() && !offers_wizard {
+        status_error!("Config file \"{}\" does not exist.", filepath_str);
+        return Err(MainError::Config(tr!(
+            "Run `spautofy config init` to create it with the setup wizard."
+        )));
+    }
+    #[cfg(feature = "tui")]
     if !path.exists() {
-        eprintln!("Config file \"{}\" does not exist.", filepath_str);
-        eprintln!(
-        "Please create a config file with the following format:\n\
-        {{\n\
-        \tclient_id = \"<client_id>\",         // required - get this from https://developer.spotify.com/dashboard\n\
-        \tclient_secret = \"<client_secret>\", // required - get this from https://developer.spotify.com/dashboard\n\
-        \taddress = \"<address>\",             // optional - address for the web app (default: \"127.0.0.1\")\n\
-        \tport = <port>,                     // optional - port for the web app (default: 3000)\n\
-        }}"
-        );
-        std::process::exit(1);
+        return create_config_file(filepath_str, credentials_path).await;
     }
-    let config = fs::read_to_string(filepath_str).unwrap_or_else(|err| {
-        eprintln!("Error reading config file {}: {}", filepath_str, err);
-        std::process::exit(1);
-    });
-    serde_json::from_str::<SpautofyConfigFile>(&config).unwrap_or_else(|err| {
-        eprintln!("Error parsing config file {}: {}", filepath_str, err);
-        std::process::exit(1);
-    })
+    let config = fs::read_to_string(filepath_str).map_err(|err| {
+        MainError::Config(tr!("Error reading config file {}: {}", filepath_str, err))
+    })?;
+    let format = ConfigFormat::from_path(filepath_str);
+    let parse_error =
+        |err| MainError::Config(tr!("Error parsing config file {}: {}", filepath_str, err));
+    let version = config_version(format, &config).map_err(parse_error)?;
+    if !(1..=CONFIG_VERSION).contains(&version) {
+        return Err(MainError::Config(tr!(
+            "Config file {} has version {}, this Spautofy only reads versions up to {}.",
+            filepath_str,
+            version,
+            CONFIG_VERSION
+        )));
+    }
+    // an older file is only rewritten on startup, see `upgrade_config_file`
+    let mut config: Value = format.parse(&config).map_err(parse_error)?;
+    let migration = match &mut config {
+        Value::Object(config) => migrate(config, version),
+        _ => Migration::default(),
+    };
+    let config = select_profile(config, profile).map_err(MainError::Config)?;
+    let file_config = from_fields::<SpautofyConfigFile>(config, CONFIG_EXAMPLES)
+        .map_err(|errors| field_errors(filepath_str, ParseError::Fields(errors)))?;
+    // the credentials the upgrade moves out, as long as the file is not rewritten
+    let file_config = match migration.credentials {
+        Some(credentials) => file_config.with_credentials(credentials),
+        None => file_config,
+    };
+    read_credentials(file_config, credentials_path, interactive)?
+        .with_env_overrides()
+        .map_err(|err| MainError::Config(tr!("Error in the environment: {}", err)))
 }
 
-async fn user_authorization(
-    args: &Args,
-    config: Arc<Mutex<SpautofyConfig>>,
-) -> Result<(), rocket::Error> {
-    println!("You need to authenticate with Spotify.");
-    println!("Please visit the following URL in your browser");
+/// Prints every wrong field at once, so they can all be fixed before the next try.
+fn field_errors(filepath_str: &str, err: ParseError) -> MainError {
+    match err {
+        ParseError::Syntax(err) => {
+            MainError::Config(tr!("Error parsing config file {}: {}", filepath_str, err))
+        }
+        ParseError::Fields(errors) => {
+            for error in &errors {
+                status_error!("Error: {}", error);
+            }
+            MainError::Config(tr!("{} has {} errors.", filepath_str, errors.len()))
+        }
+    }
+}
 
+/// Rewrites an older config file in the layout of `CONFIG_VERSION`, keeping the old one
+/// next to it; only on startup, so `config validate` or a reload leave the file alone. A
+/// file that cannot be read is left for `parse_config_file` to report.
+fn upgrade_config_file(filepath_str: &str, credentials_path: &str) -> Result<(), MainError> {
+    let Ok(content) = fs::read_to_string(filepath_str) else {
+        return Ok(());
+    };
+    let format = ConfigFormat::from_path(filepath_str);
+    let version = match config_version(format, &content) {
+        Ok(version) if (1..CONFIG_VERSION).contains(&version) => version,
+        _ => return Ok(()),
+    };
+    let Ok(mut config) = format.parse(&content) else {
+        return Ok(());
+    };
+    let migration = migrate(&mut config, version);
+    // a credentials file written before is newer than the ones of the old config
+    if let Some(credentials) = migration.credentials {
+        if !Path::new(credentials_path).exists() {
+            write_secret_file(credentials_path, &credentials)?;
+            status!("Moved the credentials to \"{}\".", credentials_path);
+        }
+    }
+    let backup_path = format!("{}.v{}", filepath_str, version);
+    // the old config still holds the credentials
+    copy_secret_file(filepath_str, &backup_path)?;
+    fs::write(filepath_str, format.serialize(&config))?;
+    status!(
+        "Upgraded config file \"{}\" to version {}, the old one is kept as \"{}\".",
+        filepath_str,
+        CONFIG_VERSION,
+        backup_path
+    );
+    Ok(())
+}
+
+/// A config file may also contain the credentials itself, the credentials file wins.
+fn read_credentials(
+    file_config: SpautofyConfigFile,
+    filepath_str: &str,
+    interactive: bool,
+) -> Result<SpautofyConfigFile, MainError> {
+    if !Path::new(filepath_str).exists() {
+        return Ok(file_config);
+    }
+    check_secret_file(filepath_str, interactive)?;
+    let content = fs::read_to_string(filepath_str).map_err(|err| {
+        MainError::Config(tr!(
+            "Error reading credentials file {}: {}",
+            filepath_str,
+            err
+        ))
+    })?;
+    let credentials = ConfigFormat::from_path(filepath_str)
+        .parse_fields::<Credentials>(&content, CONFIG_EXAMPLES)
+        .map_err(|err| match err {
+            ParseError::Syntax(err) => MainError::Config(tr!(
+                "Error parsing credentials file {}: {}",
+                filepath_str,
+                err
+            )),
+            err => field_errors(filepath_str, err),
+        })?;
+    Ok(file_config.with_credentials(credentials))
+}
+
+/// The web server Spotify sends the user back to after the authorization, with the URL
+/// to visit; it stops once the authorization is done.
+#[cfg(feature = "web-auth")]
+async fn login_server(
+    args: &Args,
+    config: &SharedConfig,
+) -> Result<(Rocket<Ignite>, String), MainError> {
     let rocket_config = {
-        let unwrapped_config = config.lock().unwrap();
+        let unwrapped_config = config.read().unwrap();
         Config {
-            address: unwrapped_config.address,
-            port: unwrapped_config.port,
+            address: unwrapped_config.auth.address,
+            port: unwrapped_config.auth.port,
+            // the log would be drawn over the TUI, or bury the URL without it
+            log_level: LogLevel::Off,
             ..Config::release_default()
         }
     };
+    let url = format!("http://{}:{}/", rocket_config.address, rocket_config.port);
     let rocket = rocket::custom(&rocket_config)
-        .manage(args.config_path.clone())
+        .manage(args.credentials_file())
         .manage(config.clone())
         .mount("/", routes![index, auth, callback, done])
         .ignite()
         .await?;
-    rocket.launch().await?;
-    println!("Stopped the web server.");
-    Ok(())
+    Ok((rocket, url))
+}
+
+/// Returns `false` if the user cancelled the authorization.
+#[cfg(feature = "tui")]
+async fn user_authorization(
+    terminal: &mut tui::Tui,
+    args: &Args,
+    config: SharedConfig,
+) -> Result<bool, MainError> {
+    let (rocket, url) = login_server(args, &config).await?;
+    let authorized = wait_for_authorization(terminal, &url, rocket).await?;
+    status!("Stopped the web server.");
+    Ok(authorized)
 }
 
+/// Returns `None` if the user cancelled the authorization.
+#[cfg(feature = "tui")]
 async fn authorize(
+    terminal: &mut tui::Tui,
     args: &Args,
     file_config: SpautofyConfigFile,
+) -> Result<Option<(SpautofyConfig, UserAccess)>, MainError> {
+    let config = Arc::new(RwLock::new(SpautofyConfig::from(file_config)));
+    if !user_authorization(terminal, args, config.clone()).await? {
+        return Ok(None);
+    }
+    finish_authorization(args, config).await.map(Some)
+}
+
+/// Trades the code the web server received for the access and keeps the refresh token.
+#[cfg(feature = "web-auth")]
+async fn finish_authorization(
+    args: &Args,
+    config: SharedConfig,
 ) -> Result<(SpautofyConfig, UserAccess), MainError> {
-    let config = Arc::new(Mutex::new(SpautofyConfig::from(file_config)));
-    user_authorization(args, config.clone()).await?;
+    let auth_error = config.write().unwrap().auth.auth_error.take();
+    if let Some(error) = auth_error {
+        return Err(AuthorizeError::Denied(error).into());
+    }
 
-    println!("Getting access token...");
-    let access = get_access_token(config.clone()).await?;
+    status!("Getting access token...");
+    let auth = config.read().unwrap().auth.clone();
+    let access = get_access_token(&auth).await?;
     let user_access = get_user_access(access).await?;
     let lock = Arc::try_unwrap(config).expect("Arc has multiple owners");
-    let config = lock.into_inner().expect("Mutex is already unlocked");
+    let mut config = lock.into_inner().expect("RwLock is poisoned");
+    config.auth.refresh_token = Some(user_access.access.refresh_token().to_string());
+    write_config(args, &config);
+    status!(
+        "Successfully authenticated with Spotify as user {}.",
+        user_access.user.display_name
+    );
     Ok((config, user_access))
 }
 
-#[rocket::main]
-async fn main() -> Result<(), MainError> {
-    let args = Args::parse();
-    let file_config = parse_config_file(args.config_path.as_str());
+/// Only writes the credentials, the settings stay as the user wrote them.
+fn write_config(args: &Args, config: &SpautofyConfig) {
+    let _ = write_secret_file(&args.credentials_file(), &Credentials::from(config));
+}
 
-    let (config, user_access) = authorize(&args, file_config).await?;
-    let _ = std::fs::write(
-        args.config_path.as_str(),
-        serde_json::to_string_pretty(&config).expect("Failed to serialize config"),
+/// Keeps the current access if the user cancels the new authorization.
+#[cfg(feature = "tui")]
+async fn reauthorize(
+    terminal: &mut tui::Tui,
+    args: &Args,
+    config: &mut SpautofyConfig,
+    user_access: &mut UserAccess,
+) -> Result<(), MainError> {
+    let file_config = SpautofyConfigFile::from(&*config);
+    let authorized = retry_on_error!(
+        terminal,
+        "Authorization failed",
+        authorize(terminal, args, file_config.clone()).await
+    );
+    match authorized.flatten() {
+        Some((new_config, new_user_access)) => {
+            *config = new_config;
+            *user_access = new_user_access;
+        }
+        None => status!("Cancelled the authorization, still logged in as before."),
+    }
+    Ok(())
+}
+
+/// Returns `false` if the refresh failed and the user dismissed the error.
+#[cfg(feature = "tui")]
+async fn refresh_if_expired(
+    terminal: &mut tui::Tui,
+    args: &Args,
+    user_access: &mut UserAccess,
+    config: &mut SpautofyConfig,
+) -> Result<bool, MainError> {
+    if !user_access.access.is_expired() {
+        return Ok(true);
+    }
+    status!("Access token expired, refreshing it...");
+    let access = retry_on_error!(
+        terminal,
+        "Could not refresh the access token",
+        refresh_access_token(&config.auth, &user_access.access).await
+    );
+    let Some(access) = access else {
+        return Ok(false);
+    };
+    // Spotify may have handed out a new refresh token
+    config.auth.refresh_token = Some(access.refresh_token().to_string());
+    write_config(args, config);
+    user_access.access = access;
+    Ok(true)
+}
+
+/// Runs the actions on a background task so the TUI keeps drawing while they wait for
+/// Spotify; the ones not depending on each other run together, see `actions::stages`.
+fn spawn_actions(user_access: UserAccess, tasks: Vec<Task>) -> mpsc::UnboundedReceiver<RunEvent> {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        for stage in stages(&tasks) {
+            stream::iter(stage)
+                .for_each_concurrent(MAX_CONCURRENT_ACTIONS, |index| {
+                    run_action(&user_access, index, &tasks[index], &sender)
+                })
+                .await;
+        }
+    });
+    receiver
+}
+
+/// Runs one task of `spawn_actions`; a failure is reported and fails no other task.
+async fn run_action(
+    user_access: &UserAccess,
+    index: usize,
+    task: &Task,
+    sender: &mpsc::UnboundedSender<RunEvent>,
+) {
+    let label = task.label();
+    let _ = sender.send(RunEvent::Started(index, label.clone()));
+    let result = task.action().run(user_access, task).await;
+    // the receiver only goes away if the TUI stopped
+    let _ = sender.send(RunEvent::Finished(
+        index,
+        ActionReport {
+            action: label,
+            result: result.map_err(|err| err.to_string()),
+        },
+    ));
+}
+
+#[cfg(feature = "tui")]
+async fn run_tui(
+    terminal: &mut tui::Tui,
+    args: &Args,
+    config: &mut SpautofyConfig,
+    user_access: &mut UserAccess,
+) -> Result<(), MainError> {
+    let selection_path = selection_path(&args.history_file());
+    let keys = registry::selectable_keys();
+    let default_selection = match &config.actions.selected {
+        Some(selected) => selection_from_keys(&keys, selected),
+        None => load_selection(&selection_path, &keys)
+            .unwrap_or_else(|err| {
+                status!("Could not read the last selection: {}", err);
+                None
+            })
+            // every action is selected on the first start
+            .unwrap_or(vec![true; keys.len()]),
+    };
+    let notifier = Notifier::new(config.notifications.clone(), args.active_profile());
+    let mut wizard = Wizard::new(
+        registry::selectable(),
+        &default_selection,
+        &args.history_file(),
+        &config.actions,
+        args.visibility(),
+    );
+    loop {
+        refresh_if_expired(terminal, args, user_access, config).await?;
+        now_playing::set_access(&user_access.access);
+        let actions = match wizard.run(terminal, user_access).await? {
+            WizardOutcome::Run(actions) => actions,
+            WizardOutcome::Reauthorize => {
+                reauthorize(terminal, args, config, user_access).await?;
+                continue;
+            }
+            WizardOutcome::Quit => return Ok(()),
+        };
+        if !refresh_if_expired(terminal, args, user_access, config).await? {
+            continue;
+        }
+        // a selection set in the config stays as it is
+        if config.actions.selected.is_none() {
+            if let Err(err) = save_selection(&selection_path, &keys, &wizard.selected()) {
+                status_error!("Could not save the selection: {}", err);
+            }
+        }
+        let started_at = dates::now();
+        let run_screen = RunScreen::new(actions.len());
+        let pipelines: Vec<String> = actions.iter().map(Task::name).collect();
+        let receiver = spawn_actions(user_access.clone(), actions);
+        let reports = run_screen.run(terminal, receiver).await?;
+        notifier
+            .run_finished(started_at, args.dry_run, &pipelines, &reports)
+            .await;
+        // a dry run did not create anything worth remembering
+        if !args.dry_run {
+            let record = RunRecord::new(started_at, reports.clone());
+            if let Err(err) = history::append(&args.history_file(), record) {
+                status_error!("Could not save the run to the history: {}", err);
+            }
+            metrics::record_run(&pipelines, &reports);
+            commands::archive_recently_played(user_access).await;
+        }
+        if SummaryScreen::new(reports).run(terminal)? == ScreenOutcome::Quit {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+async fn run(
+    terminal: &mut tui::Tui,
+    args: &Args,
+    file_config: SpautofyConfigFile,
+) -> Result<(), MainError> {
+    let authorized = retry_on_error!(
+        terminal,
+        "Authorization failed",
+        authorize(terminal, args, file_config.clone()).await
     );
-    println!(
+    match authorized.flatten() {
+        Some((mut config, mut user_access)) => {
+            run_tui(terminal, args, &mut config, &mut user_access).await
+        }
+        None => {
+            status!("Cancelled the authorization.");
+            Ok(())
+        }
+    }
+}
+
+/// Logs in without a browser, using the refresh token kept from the last authorization.
+async fn login(args: &Args, file_config: SpautofyConfigFile) -> Result<UserAccess, MainError> {
+    let mut config = SpautofyConfig::from(file_config);
+    let Some(refresh_token) = config.auth.refresh_token.clone() else {
+        return Err(MainError::NotLoggedIn(tr!(
+            "Not logged in yet, run `spautofy auth` first."
+        )));
+    };
+    status!("Getting access token...");
+    let access = match access_from_refresh_token(&config.auth, &refresh_token).await {
+        Err(AuthorizeError::ExpiredUserCode) => {
+            return Err(MainError::NotLoggedIn(tr!(
+                "Spotify did not accept the kept login, it may have been revoked; run \
+                `spautofy auth` again."
+            )));
+        }
+        access => access?,
+    };
+    // Spotify may have handed out a new refresh token
+    config.auth.refresh_token = Some(access.refresh_token().to_string());
+    write_config(args, &config);
+    let user_access = get_user_access(access).await?;
+    status!(
         "Successfully authenticated with Spotify as user {}.",
         user_access.user.display_name
     );
+    Ok(user_access)
+}
+
+/// Only logs in, so the other commands can run unattended afterwards.
+#[cfg(feature = "tui")]
+async fn run_auth(
+    terminal: &mut tui::Tui,
+    args: &Args,
+    file_config: SpautofyConfigFile,
+) -> Result<(), MainError> {
+    let authorized = retry_on_error!(
+        terminal,
+        "Authorization failed",
+        authorize(terminal, args, file_config.clone()).await
+    );
+    if authorized.flatten().is_none() {
+        status!("Cancelled the authorization.");
+    }
+    Ok(())
+}
+
+/// `spautofy auth` without the TUI: prints the URL to visit and waits for the callback,
+/// e.g. on a server reached through `ssh -L 3000:localhost:3000`; Ctrl-C cancels.
+#[cfg(all(feature = "web-auth", not(feature = "tui")))]
+async fn run_plain_auth(args: &Args, file_config: SpautofyConfigFile) -> Result<(), MainError> {
+    let config = Arc::new(RwLock::new(SpautofyConfig::from(file_config)));
+    let (rocket, url) = login_server(args, &config).await?;
+    status!("Please visit the following URL in your browser: {}", url);
+    rocket.launch().await?;
+    status!("Stopped the web server.");
+    let answered = {
+        let config = config.read().unwrap();
+        config.auth.user_auth_code.is_some() || config.auth.auth_error.is_some()
+    };
+    if !answered {
+        status!("Cancelled the authorization.");
+        return Ok(());
+    }
+    finish_authorization(args, config).await?;
+    Ok(())
+}
+
+/// `spautofy auth` in the TUI, or without it if only the web server was built.
+async fn login_with_browser(args: &Args, file_config: SpautofyConfigFile) -> Result<(), MainError> {
+    #[cfg(feature = "tui")]
+    {
+        let mut terminal = tui::init()?;
+        let result = run_auth(&mut terminal, args, file_config).await;
+        tui::restore()?;
+        result
+    }
+    #[cfg(all(feature = "web-auth", not(feature = "tui")))]
+    {
+        run_plain_auth(args, file_config).await
+    }
+    #[cfg(not(feature = "web-auth"))]
+    {
+        let _ = (args, file_config);
+        Err(missing_feature("web-auth"))
+    }
+}
+
+async fn run_command(
+    args: &Args,
+    file_config: SpautofyConfigFile,
+    command: &Command,
+) -> Result<(), MainError> {
+    if let Command::Auth = command {
+        return login_with_browser(args, file_config).await;
+    }
+    // mistakes in the pipelines should show up before logging in
+    let actions = match command {
+        Command::Run {
+            actions,
+            name,
+            limit,
+        } => resolve_run_targets(args, &file_config, actions, name.as_deref(), *limit)?,
+        _ => Vec::new(),
+    };
+    let restore_selection = match command {
+        Command::Restore {
+            backup,
+            playlists,
+            liked_songs,
+            all,
+        } => match select_restore(backup, playlists, *liked_songs, *all).await? {
+            Some(selection) => selection,
+            None => {
+                status!("Cancelled the restore.");
+                return Ok(());
+            }
+        },
+        _ => Backup::default(),
+    };
+    let songs = match command {
+        Command::ImportSongs { file, .. } => commands::read_song_list(file)?,
+        Command::ScanLibrary { directory, .. } => local_library::scan(directory)?,
+        _ => Vec::new(),
+    };
+    // comparing two backups needs no login
+    if let Command::Diff { old, new, playlist } = command {
+        let old = DiffSource::open(old)?;
+        let new = DiffSource::open(new)?;
+        let user_access = if old.is_playlist() || new.is_playlist() {
+            Some(login(args, file_config).await?)
+        } else {
+            None
+        };
+        return commands::diff(
+            user_access.as_ref(),
+            old,
+            new,
+            playlist.as_deref(),
+            args.json,
+        )
+        .await;
+    }
+    if let Command::ImportHistory { files } = command {
+        return commands::import_history(files, args.json);
+    }
+    // logs in again for every command it receives
+    if let Command::Mqtt = command {
+        return run_mqtt(args, file_config).await;
+    }
+    if let Command::Dashboard | Command::Api { .. } = command {
+        #[cfg(feature = "web-auth")]
+        return match command {
+            Command::Dashboard => dashboard::serve(args, file_config).await,
+            _ => api::serve(args, file_config).await,
+        };
+        #[cfg(not(feature = "web-auth"))]
+        return Err(missing_feature("web-auth"));
+    }
+    let listenbrainz = file_config.integrations.listenbrainz.clone();
+    let notifier = Notifier::new(file_config.notifications.clone(), args.active_profile());
+    let user_access = match login(args, file_config).await {
+        Ok(user_access) => user_access,
+        Err(err) => {
+            // unlike the errors of the network, only `spautofy auth` helps
+            if let MainError::NotLoggedIn(_) = err {
+                notifier.login_failed(&err.to_string()).await;
+            }
+            if !args.dry_run {
+                let pipelines: Vec<String> = actions.into_iter().map(|(name, _)| name).collect();
+                notifier
+                    .pipelines_failed(&pipelines, &err.to_string())
+                    .await;
+            }
+            return Err(err);
+        }
+    };
+    if args.systemd {
+        systemd::notify("READY=1");
+    }
+    match command {
+        Command::Auth
+        | Command::Completions { .. }
+        | Command::Config { .. }
+        | Command::Diff { .. }
+        | Command::ImportHistory { .. }
+        | Command::Mqtt
+        | Command::Dashboard
+        | Command::Api { .. } => Ok(()),
+        Command::ImportSongs {
+            file,
+            name,
+            min_confidence,
+            no_review,
+        } => {
+            let min_confidence = f64::from(*min_confidence) / 100.0;
+            let matches = commands::match_songs(&user_access, songs, min_confidence).await?;
+            let Some(matches) = review_matches(matches, min_confidence, *no_review, "Line")? else {
+                status!("Cancelled the import.");
+                return Ok(());
+            };
+            let name = name.clone().unwrap_or_else(|| {
+                Path::new(file)
+                    .file_stem()
+                    .map_or_else(|| file.clone(), |stem| stem.to_string_lossy().into_owned())
+            });
+            commands::create_song_playlist(
+                &user_access,
+                &name,
+                &matches,
+                args.visibility().unwrap_or_default(),
+                args.json,
+            )
+            .await
+        }
+        Command::ScanLibrary {
+            directory: _,
+            playlist,
+            min_confidence,
+            no_review,
+            missing,
+        } => {
+            let min_confidence = f64::from(*min_confidence) / 100.0;
+            let matches = commands::match_songs(&user_access, songs, min_confidence).await?;
+            // only the playlist needs the unsure files decided
+            let matches = if playlist.is_some() {
+                match review_matches(matches, min_confidence, *no_review, "File")? {
+                    Some(matches) => matches,
+                    None => {
+                        status!("Cancelled the scan.");
+                        return Ok(());
+                    }
+                }
+            } else {
+                matches
+            };
+            commands::library_report(
+                &user_access,
+                &matches,
+                playlist.as_deref(),
+                args.visibility().unwrap_or_default(),
+                *missing,
+                args.json,
+            )
+            .await
+        }
+        Command::Run { .. } => {
+            commands::run_actions(
+                user_access,
+                actions,
+                &args.history_file(),
+                args.json,
+                args.batch_profile.as_deref(),
+                &notifier,
+            )
+            .await
+        }
+        Command::Playlists {
+            command:
+                PlaylistsCommand::List {
+                    filter,
+                    owned_only,
+                    user,
+                },
+        } => {
+            commands::list_playlists(
+                &user_access,
+                filter.as_deref(),
+                *owned_only,
+                user.as_deref(),
+                args.json,
+            )
+            .await
+        }
+        Command::Playlists {
+            command: PlaylistsCommand::Show { playlist, format },
+        } => {
+            // `--json` works for all commands, so it wins over the format
+            let format = if args.json {
+                OutputFormat::Json
+            } else {
+                *format
+            };
+            commands::show_playlist(&user_access, playlist, format).await
+        }
+        Command::Restore { .. } => {
+            commands::restore(&user_access, restore_selection, args.json).await
+        }
+        Command::Whoami => {
+            commands::whoami(&user_access, args.json);
+            Ok(())
+        }
+        Command::Token { scopes } => commands::token(&user_access, scopes, args.json),
+        Command::Export {
+            playlist_id,
+            liked_songs: _,
+            format,
+            output,
+            git,
+        } => {
+            commands::export(
+                &user_access,
+                playlist_id.as_deref(),
+                output.clone(),
+                git.as_deref(),
+                *format,
+                args.json,
+            )
+            .await
+        }
+        Command::Backup {
+            output,
+            all: true,
+            git,
+        } => {
+            let parent = output.as_deref().unwrap_or(".");
+            commands::backup_all(&user_access, parent, git.as_deref(), args.json).await
+        }
+        Command::Backup {
+            output,
+            all: false,
+            git,
+        } => {
+            let output = output.as_deref().unwrap_or("spautofy-backup.json");
+            commands::backup(&user_access, output, git.as_deref(), args.json).await
+        }
+        Command::Listenbrainz {
+            command: ListenbrainzCommand::Submit,
+        } => commands::submit_listens(&user_access, listenbrainz.as_ref(), args.json).await,
+    }
+}
+
+/// Runs the commands of the command topic until stopped with Ctrl-C or by systemd; a
+/// command that fails is reported and the next one waited for. A change of the config is
+/// taken between the commands, connecting to the broker again if it changed.
+async fn run_mqtt(args: &Args, mut file_config: SpautofyConfigFile) -> Result<(), MainError> {
+    let Some(mut config) = file_config.notifications.mqtt.clone() else {
+        return Err(MainError::Config(tr!(
+            "Config file {} has no MQTT broker, set notifications.mqtt.",
+            args.config_path
+        )));
+    };
+    // a revoked login should show up now, not with the first command
+    login(args, file_config.clone()).await?;
+    let mut listener = connect_listener(&config).await?;
+    if args.systemd {
+        systemd::notify("READY=1");
+    }
+    status!("Waiting for commands on {}.", config.command_topic());
+    let mut notifier = Notifier::new(file_config.notifications.clone(), args.active_profile());
+    let mut watcher = ConfigWatcher::new(args);
+    let mut terminate = signal(SignalKind::terminate())?;
+    loop {
+        let command = tokio::select! {
+            command = listener.next_command() => command,
+            () = watcher.changed() => {
+                let Some(reloaded) = watcher.reload(&file_config).await else {
+                    continue;
+                };
+                let new_config = reloaded.file_config.notifications.mqtt.clone();
+                match new_config {
+                    Some(new_config) if new_config == config => {}
+                    Some(new_config) => {
+                        // both connected at once would leave the status of the old one
+                        listener.disconnect().await;
+                        match Listener::connect(&new_config).await {
+                            Ok(new_listener) => {
+                                listener = new_listener;
+                                config = new_config;
+                                status!("Waiting for commands on {}.", config.command_topic());
+                            }
+                            Err(err) => {
+                                status_error!(
+                                    "Could not connect to the MQTT broker {}, keeping the old \
+                                    config: {}",
+                                    new_config.host,
+                                    err
+                                );
+                                listener = connect_listener(&config).await?;
+                                continue;
+                            }
+                        }
+                    }
+                    None => {
+                        status_error!(
+                            "Config file {} has no MQTT broker anymore, keeping the old config.",
+                            args.config_path
+                        );
+                        continue;
+                    }
+                }
+                file_config = reloaded.file_config;
+                notifier = Notifier::new(file_config.notifications.clone(), args.active_profile());
+                continue;
+            }
+            _ = tokio::signal::ctrl_c() => None,
+            _ = terminate.recv() => None,
+        };
+        let Some(command) = command else {
+            break;
+        };
+        run_mqtt_command(args, &file_config, &notifier, &listener, command.trim()).await;
+        save_run_outputs(args);
+        if args.systemd {
+            systemd::notify(&format!("STATUS={}", tr!("Waiting for commands")));
+        }
+    }
+    listener.disconnect().await;
+    status!("Disconnected from the MQTT broker.");
+    Ok(())
+}
+
+async fn connect_listener(config: &MqttConfig) -> Result<Listener, MainError> {
+    Listener::connect(config).await.map_err(|err| {
+        MainError::Io(io::Error::other(tr!(
+            "Could not connect to the MQTT broker {}: {}",
+            config.host,
+            err
+        )))
+    })
+}
+
+/// Runs a command like `weekly` or `top-tracks:short,Modern Metal`, like the same targets
+/// given to `spautofy run`.
+async fn run_mqtt_command(
+    args: &Args,
+    file_config: &SpautofyConfigFile,
+    notifier: &Notifier,
+    listener: &Listener,
+    command: &str,
+) {
+    status!("Received command \"{}\".", command);
+    let actions = command
+        .split(',')
+        .map(|target| parse_run_target(target.trim()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(MainError::Config)
+        .and_then(|targets| resolve_run_targets(args, file_config, &targets, None, None));
+    let prepared = match actions {
+        Ok(actions) => match login(args, file_config.clone()).await {
+            Ok(user_access) => Ok((actions, user_access)),
+            Err(err) => {
+                if !args.dry_run {
+                    let pipelines: Vec<String> =
+                        actions.into_iter().map(|(name, _)| name).collect();
+                    notifier
+                        .pipelines_failed(&pipelines, &err.to_string())
+                        .await;
+                }
+                Err(err)
+            }
+        },
+        Err(err) => Err(err),
+    };
+    let (actions, user_access) = match prepared {
+        Ok(prepared) => prepared,
+        Err(err) => {
+            if let MainError::NotLoggedIn(_) = err {
+                notifier.login_failed(&err.to_string()).await;
+            }
+            status_error!("{}", err);
+            listener.command_failed(command, &err.to_string()).await;
+            return;
+        }
+    };
+    let result = commands::run_actions(
+        user_access,
+        actions,
+        &args.history_file(),
+        args.json,
+        args.batch_profile.as_deref(),
+        notifier,
+    )
+    .await;
+    // the notifications told about the failed actions already
+    if let Err(err) = result {
+        status_error!("{}", err);
+    }
+}
+
+/// The parts of the backup to restore, picked in the TUI unless given on the command line;
+/// `None` if the user cancelled.
+async fn select_restore(
+    path: &str,
+    playlists: &[String],
+    liked_songs: bool,
+    all: bool,
+) -> Result<Option<Backup>, MainError> {
+    let backup = load_backup(path)
+        .map_err(|err| io::Error::new(err.kind(), tr!("Error reading backup {}: {}", path, err)))?;
+    if all {
+        return Ok(Some(backup));
+    }
+    if !playlists.is_empty() || liked_songs {
+        return commands::select_from_backup(backup, playlists, liked_songs).map(Some);
+    }
+    pick_restore(backup)
+}
+
+#[cfg(feature = "tui")]
+fn pick_restore(mut backup: Backup) -> Result<Option<Backup>, MainError> {
+    let has_liked_songs = !backup.liked_songs.is_empty();
+    let mut entries = Vec::new();
+    if has_liked_songs {
+        entries.push(RestoreEntry {
+            name: tr!("Liked songs"),
+            tracks: backup.liked_songs.len(),
+        });
+    }
+    entries.extend(backup.playlists.iter().map(|playlist| RestoreEntry {
+        name: playlist.name.clone(),
+        tracks: playlist.tracks.len(),
+    }));
+    let mut terminal = tui::init()?;
+    let picked = RestorePicker::new(entries).run(&mut terminal);
+    tui::restore()?;
+    let Some(selected) = picked? else {
+        return Ok(None);
+    };
+    let mut selected = selected.into_iter();
+    if has_liked_songs && selected.next() != Some(true) {
+        backup.liked_songs.clear();
+    }
+    backup.playlists = backup
+        .playlists
+        .into_iter()
+        .zip(selected)
+        .filter_map(|(playlist, selected)| selected.then_some(playlist))
+        .collect();
+    Ok(Some(backup))
+}
+
+#[cfg(not(feature = "tui"))]
+fn pick_restore(_backup: Backup) -> Result<Option<Backup>, MainError> {
+    Err(missing_feature("tui"))
+}
+
+/// The matches with the tracks of the unsure songs chosen in the TUI, unless `no_review`
+/// leaves them out; `None` if the user cancelled.
+fn review_matches(
+    matches: Vec<SongMatch>,
+    min_confidence: f64,
+    no_review: bool,
+    source: &'static str,
+) -> Result<Option<Vec<SongMatch>>, MainError> {
+    let unsure: Vec<usize> = matches
+        .iter()
+        .enumerate()
+        .filter(|(_, song_match)| song_match.best_confidence() < min_confidence)
+        .map(|(index, _)| index)
+        .collect();
+    if unsure.is_empty() || no_review {
+        return Ok(Some(matches));
+    }
+    pick_matches(matches, unsure, source)
+}
+
+#[cfg(feature = "tui")]
+fn pick_matches(
+    matches: Vec<SongMatch>,
+    unsure: Vec<usize>,
+    source: &'static str,
+) -> Result<Option<Vec<SongMatch>>, MainError> {
+    let mut terminal = tui::init()?;
+    let picked = MatchPicker::new(matches, unsure, source).run(&mut terminal);
+    tui::restore()?;
+    Ok(picked?)
+}
+
+#[cfg(not(feature = "tui"))]
+fn pick_matches(
+    _matches: Vec<SongMatch>,
+    _unsure: Vec<usize>,
+    _source: &'static str,
+) -> Result<Option<Vec<SongMatch>>, MainError> {
+    Err(missing_feature("tui"))
+}
+
+/// Writes a new config with the setup wizard, then logs in once so the credentials and
+/// the redirect URL are known to work.
+#[cfg(feature = "tui")]
+async fn init_config(args: &Args, force: bool) -> Result<(), MainError> {
+    if let Some(profile) = &args.config_section {
+        return Err(MainError::Config(tr!(
+            "Profile {} is a section of config file \"{}\", edit it there or replace the \
+            whole file with --profile {}.",
+            profile,
+            args.config_path,
+            DEFAULT_PROFILE
+        )));
+    }
+    if Path::new(&args.config_path).exists() && !force {
+        return Err(MainError::Config(tr!(
+            "Config file \"{}\" already exists, pass --force to replace it.",
+            args.config_path
+        )));
+    }
+    let file_config = create_config_file(&args.config_path, &args.credentials_file()).await?;
+    let mut terminal = tui::init()?;
+    let result = run_auth(&mut terminal, args, file_config).await;
+    tui::restore()?;
+    result
+}
+
+#[cfg(not(feature = "tui"))]
+async fn init_config(_args: &Args, _force: bool) -> Result<(), MainError> {
+    Err(missing_feature("tui"))
+}
+
+/// Runs the actions for every profile one after the other; a failing profile does not
+/// stop the others.
+async fn run_profiles(args: &Args) -> Result<(), MainError> {
+    let profiles = if args.all_profiles {
+        find_profiles(&args.config_path)?
+    } else {
+        args.profile.clone()
+    };
+    let mut failed = Vec::new();
+    // exits like the worst failure, so a broken config is not taken for a failed action
+    let mut worst: Option<MainError> = None;
+    for profile in &profiles {
+        status!("Running profile {}...", profile);
+        let profile_args = Args {
+            batch_profile: Some(profile.clone()),
+            ..args.for_profile(profile)
+        };
+        if let Err(err) = start_profile(&profile_args).await {
+            status_error!("Profile {} failed: {}", profile, err);
+            failed.push(profile.as_str());
+            if worst
+                .as_ref()
+                .is_none_or(|worst| err.severity() > worst.severity())
+            {
+                worst = Some(err);
+            }
+        }
+    }
+    status!(
+        "{} of {} profiles succeeded.",
+        profiles.len() - failed.len(),
+        profiles.len()
+    );
+    match worst {
+        Some(err) => {
+            status_error!("Profiles failed: {}", failed.join(", "));
+            Err(err)
+        }
+        None => Ok(()),
+    }
+}
+
+async fn start(args: Args) -> Result<(), MainError> {
+    if args.runs_several_profiles() {
+        return run_profiles(&args).await;
+    }
+    let profile = args
+        .profile
+        .first()
+        .cloned()
+        .or_else(|| read_sections(&args.config_path).default);
+    let args = match profile {
+        Some(profile) => args.for_profile(&profile),
+        None => args,
+    };
+    if let Some(Command::Config {
+        command: ConfigCommand::Init { force },
+    }) = &args.command
+    {
+        return init_config(&args, *force).await;
+    }
+    if let Some(Command::Config {
+        command: ConfigCommand::Validate { check_credentials },
+    }) = &args.command
+    {
+        let file_config = parse_config_file(
+            &args.config_path,
+            &args.credentials_file(),
+            args.config_section.as_deref(),
+            false,
+        )
+        .await?;
+        set_locale(file_config.locale);
+        return commands::validate_config(&file_config, *check_credentials, args.json).await;
+    }
+    start_profile(&args).await
+}
 
-    println!("Creating top track playlist");
-    create_top_track_playlist(&user_access, TimeRange::ShortTerm).await?;
-    create_top_track_playlist(&user_access, TimeRange::MediumTerm).await?;
-    create_top_track_playlist(&user_access, TimeRange::LongTerm).await?;
+/// Brings the metrics and the feed up to date with what the command did.
+fn save_run_outputs(args: &Args) {
+    metrics::save(args.metrics_path.as_deref(), args.active_profile());
+    feed::write(args.feed_path.as_deref(), args.active_profile());
+}
 
+/// Takes over the settings of the config that hold for the whole process, like the locale;
+/// none of them if one is wrong.
+fn apply_config(args: &Args, file_config: &SpautofyConfigFile) -> Result<(), MainError> {
+    let date_settings = file_config.date_settings().map_err(|err| {
+        MainError::Config(tr!(
+            "Error in the date format of config file {}: {}",
+            args.config_path,
+            err
+        ))
+    })?;
+    #[cfg(feature = "tui")]
+    let keymap = Keymap::try_from(&file_config.keybindings).map_err(|err| {
+        MainError::Config(tr!(
+            "Error in the keybindings of config file {}: {}",
+            args.config_path,
+            err
+        ))
+    })?;
+    set_locale(file_config.locale);
+    set_date_settings(date_settings);
+    #[cfg(feature = "tui")]
+    {
+        set_theme(Theme::from(&file_config.theme));
+        set_keymap(keymap);
+    }
     Ok(())
 }
+
+async fn start_profile(args: &Args) -> Result<(), MainError> {
+    upgrade_config_file(&args.config_path, &args.credentials_file())?;
+    let file_config = parse_config_file(
+        args.config_path.as_str(),
+        &args.credentials_file(),
+        args.config_section.as_deref(),
+        Command::is_interactive(&args.command),
+    )
+    .await?;
+    apply_config(args, &file_config)?;
+    set_dry_run(args.dry_run);
+    storage::set_path(&args.history_file());
+
+    let result = match &args.command {
+        Some(command) => run_command(args, file_config, command).await,
+        None => run_interactive(args, file_config).await,
+    };
+    save_run_outputs(args);
+    result
+}
+
+#[cfg(feature = "tui")]
+async fn run_interactive(args: &Args, file_config: SpautofyConfigFile) -> Result<(), MainError> {
+    let mut terminal = tui::init()?;
+    let poller = now_playing::spawn_poller();
+    // errors must not skip restoring the terminal
+    let result = run(&mut terminal, args, file_config).await;
+    poller.abort();
+    tui::restore()?;
+    result
+}
+
+#[cfg(not(feature = "tui"))]
+async fn run_interactive(_args: &Args, _file_config: SpautofyConfigFile) -> Result<(), MainError> {
+    Err(missing_feature("tui"))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    status_log::set_level(Level::from_flags(args.verbose, args.quiet));
+    if args.systemd {
+        if Command::is_interactive(&args.command) {
+            Args::command()
+                .error(
+                    ErrorKind::MissingSubcommand,
+                    "--systemd needs a command that runs without the TUI, e.g. `run`",
+                )
+                .exit();
+        }
+        status_log::use_journal_priorities();
+    }
+    if args.progress == ProgressFormat::Ndjson && Command::is_interactive(&args.command) {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--progress=ndjson needs a command that runs without the TUI, e.g. `run`",
+            )
+            .exit();
+    }
+    progress::set_progress_format(args.progress);
+    // `token` prints nothing but the token to stdout, so it can be used in scripts
+    if args.json
+        || args.progress == ProgressFormat::Ndjson
+        || matches!(args.command, Some(Command::Token { .. }))
+    {
+        status_log::use_stderr();
+    }
+    if args.runs_several_profiles() && !matches!(args.command, Some(Command::Run { .. })) {
+        Args::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "only `run` works with several profiles",
+            )
+            .exit();
+    }
+    if let Some(kind) = args.schema {
+        let schema = serde_json::to_string_pretty(&schemas::schema(kind));
+        println!("{}", schema.expect("Failed to serialize the schema"));
+        return ExitCode::SUCCESS;
+    }
+    // for generating clients without a config
+    #[cfg(feature = "web-auth")]
+    if let Some(Command::Api { openapi: true }) = args.command {
+        let description = serde_json::to_string_pretty(&api::openapi());
+        println!(
+            "{}",
+            description.expect("Failed to serialize the OpenAPI description")
+        );
+        return ExitCode::SUCCESS;
+    }
+    // needs no config either
+    if let Some(Command::Completions { shell }) = args.command {
+        clap_complete::generate(shell, &mut Args::command(), "spautofy", &mut io::stdout());
+        return ExitCode::SUCCESS;
+    }
+    let systemd = args.systemd;
+    let result = start(args).await;
+    if systemd {
+        systemd::notify("STOPPING=1");
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            status_error!("{}", err);
+            err.exit_code()
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! Tells other programs how a run went, set up in the `"notifications"` section of the
+//! config file; a notification that cannot be sent is reported but fails nothing.
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use discord::DiscordConfig;
+use email::EmailConfig;
+use healthcheck::checks;
+use mqtt::MqttConfig;
+use ntfy::NtfyConfig;
+use pushover::PushoverConfig;
+use slack::SlackConfig;
+use webhook::WebhookConfig;
+
+use crate::actions::action_report::ActionReport;
+use crate::{dates, status_error, tr};
+
+pub mod discord;
+pub mod email;
+pub mod healthcheck;
+pub mod mqtt;
+pub mod ntfy;
+pub mod pushover;
+pub mod slack;
+pub mod webhook;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct NotificationsConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub discord: Vec<DiscordConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub slack: Vec<SlackConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ntfy: Option<NtfyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pushover: Option<PushoverConfig>,
+    /// Also read by `spautofy mqtt` for the command topic.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mqtt: Option<MqttConfig>,
+    /// Pinged after every `spautofy run`, see `healthcheck`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub healthcheck_url: Option<String>,
+}
+
+impl NotificationsConfig {
+    pub fn is_empty(&self) -> bool {
+        self.webhooks.is_empty()
+            && self.discord.is_empty()
+            && self.slack.is_empty()
+            && self.email.is_none()
+            && self.ntfy.is_none()
+            && self.pushover.is_none()
+            && self.mqtt.is_none()
+            && self.healthcheck_url.is_none()
+    }
+}
+
+/// A finished run, as the notifications describe it.
+pub struct RunSummary<'a> {
+    pub profile: Option<&'a str>,
+    pub started_at: DateTime<FixedOffset>,
+    pub finished_at: DateTime<FixedOffset>,
+    pub dry_run: bool,
+    pub reports: &'a [ActionReport],
+}
+
+impl RunSummary<'_> {
+    pub fn failed(&self) -> usize {
+        self.reports
+            .iter()
+            .filter(|report| report.result.is_err())
+            .count()
+    }
+
+    /// The subject of the email and the title of the pushes.
+    pub fn title(&self) -> String {
+        let failed = self.failed();
+        let title = if failed > 0 {
+            tr!(
+                "Spautofy: {} of {} actions failed",
+                failed,
+                self.reports.len()
+            )
+        } else {
+            tr!(
+                "Spautofy: {} playlists created or updated",
+                self.reports.len()
+            )
+        };
+        with_profile(title, self.profile)
+    }
+}
+
+fn with_profile(title: String, profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("{} ({})", title, profile),
+        None => title,
+    }
+}
+
+/// What became of the playlist of an action, or why it failed.
+fn report_line(report: &ActionReport) -> String {
+    match &report.result {
+        Ok(playlist) if playlist.updated => format!(
+            "{}: {}",
+            playlist.name,
+            tr!("Updated with {} tracks", playlist.track_count)
+        ),
+        Ok(playlist) => format!(
+            "{}: {}",
+            playlist.name,
+            tr!("Created with {} tracks", playlist.track_count)
+        ),
+        Err(err) => tr!("{} failed: {}", report.action, err),
+    }
+}
+
+/// The short notification of ntfy and Pushover, one line per action.
+pub struct Push {
+    pub title: String,
+    pub message: String,
+    /// Failures, which should get through to the user.
+    pub urgent: bool,
+}
+
+impl Push {
+    fn run_finished(summary: &RunSummary) -> Self {
+        let mut lines = Vec::new();
+        if summary.dry_run {
+            lines.push(tr!("This was a dry run, nothing was changed."));
+        }
+        lines.extend(summary.reports.iter().map(report_line));
+        Push {
+            title: summary.title(),
+            message: lines.join("\n"),
+            urgent: summary.failed() > 0,
+        }
+    }
+}
+
+/// Sends the notifications of the config of a profile.
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    config: NotificationsConfig,
+    profile: Option<String>,
+}
+
+impl Notifier {
+    pub fn new(config: NotificationsConfig, profile: Option<&str>) -> Self {
+        Notifier {
+            config,
+            profile: profile.map(str::to_string),
+        }
+    }
+
+    /// `pipelines` names the pipeline of every report, for the notifications limited to
+    /// some.
+    pub async fn run_finished(
+        &self,
+        started_at: DateTime<FixedOffset>,
+        dry_run: bool,
+        pipelines: &[String],
+        reports: &[ActionReport],
+    ) {
+        let summary = RunSummary {
+            profile: self.profile.as_deref(),
+            started_at,
+            finished_at: dates::now(),
+            dry_run,
+            reports,
+        };
+        for webhook in &self.config.webhooks {
+            if webhook.only_failures && summary.failed() == 0 {
+                continue;
+            }
+            if let Err(err) = webhook::send(webhook, &summary).await {
+                status_error!(
+                    "Could not call the webhook {}: {}",
+                    webhook::public_url(&webhook.url),
+                    err
+                );
+            }
+        }
+        for discord in &self.config.discord {
+            if let Err(err) = discord::send(discord, &summary).await {
+                status_error!("Could not post the playlists to Discord: {}", err);
+            }
+        }
+        for slack in &self.config.slack {
+            let reports: Vec<ActionReport> = reports
+                .iter()
+                .zip(pipelines)
+                .filter(|(_, pipeline)| slack.covers(pipeline))
+                .map(|(report, _)| report.clone())
+                .collect();
+            let summary = RunSummary {
+                reports: &reports,
+                ..summary
+            };
+            if slack.only_failures && summary.failed() == 0 {
+                continue;
+            }
+            if let Err(err) = slack::send(slack, &summary).await {
+                status_error!("Could not post the run to Slack: {}", err);
+            }
+        }
+        if let Some(email) = &self.config.email {
+            if !email.only_failures || summary.failed() > 0 {
+                if let Err(err) = email::send(email, &summary).await {
+                    status_error!("Could not mail the summary of the run: {}", err);
+                }
+            }
+        }
+        if let Some(mqtt) = &self.config.mqtt {
+            if let Err(err) = mqtt::send(mqtt, &summary).await {
+                status_error!("Could not publish the run to MQTT: {}", err);
+            }
+        }
+        let push = Push::run_finished(&summary);
+        let ntfy = self
+            .config
+            .ntfy
+            .as_ref()
+            .filter(|ntfy| !ntfy.only_failures || push.urgent);
+        let pushover = self
+            .config
+            .pushover
+            .as_ref()
+            .filter(|pushover| !pushover.only_failures || push.urgent);
+        send_push(ntfy, pushover, &push).await;
+    }
+
+    /// Pings the healthchecks of the pipelines of a `spautofy run`, `pipelines` naming the
+    /// pipeline of every report.
+    pub async fn pipelines_finished(&self, pipelines: &[String], reports: &[ActionReport]) {
+        let Some(url) = &self.config.healthcheck_url else {
+            return;
+        };
+        for (url, indices) in checks(url, pipelines) {
+            let reports: Vec<&ActionReport> =
+                indices.iter().map(|index| &reports[*index]).collect();
+            let failed = reports.iter().any(|report| report.result.is_err());
+            if let Err(err) = healthcheck::ping(&url, failed, healthcheck::body(&reports)).await {
+                status_error!("Could not ping the healthcheck: {}", err);
+            }
+        }
+    }
+
+    /// Pings the healthchecks of the pipelines with a failure, since they could not run,
+    /// e.g. because logging in failed, and posts it to the Slack channels of them.
+    pub async fn pipelines_failed(&self, pipelines: &[String], err: &str) {
+        for slack in &self.config.slack {
+            let covered: Vec<&str> = pipelines
+                .iter()
+                .map(String::as_str)
+                .filter(|pipeline| slack.covers(pipeline))
+                .collect();
+            if covered.is_empty() {
+                continue;
+            }
+            let title = with_profile(
+                tr!("Spautofy could not run {}", covered.join(", ")),
+                self.profile.as_deref(),
+            );
+            if let Err(err) = slack::send_failure(slack, &title, err).await {
+                status_error!("Could not post the run to Slack: {}", err);
+            }
+        }
+        let Some(url) = &self.config.healthcheck_url else {
+            return;
+        };
+        for url in checks(url, pipelines).into_keys() {
+            if let Err(err) = healthcheck::ping(&url, true, err.to_string()).await {
+                status_error!("Could not ping the healthcheck: {}", err);
+            }
+        }
+    }
+
+    /// Logging in without the browser failed, e.g. because the refresh token was revoked,
+    /// and nothing runs until `spautofy auth` is run again; pushed since nobody may be
+    /// watching the timer.
+    pub async fn login_failed(&self, err: &str) {
+        let push = Push {
+            title: with_profile(
+                tr!("Spautofy could not log in to Spotify"),
+                self.profile.as_deref(),
+            ),
+            message: err.to_string(),
+            urgent: true,
+        };
+        send_push(
+            self.config.ntfy.as_ref(),
+            self.config.pushover.as_ref(),
+            &push,
+        )
+        .await;
+    }
+}
+
+async fn send_push(ntfy: Option<&NtfyConfig>, pushover: Option<&PushoverConfig>, push: &Push) {
+    if let Some(ntfy) = ntfy {
+        if let Err(err) = ntfy::send(ntfy, push).await {
+            status_error!("Could not push the notification to ntfy: {}", err);
+        }
+    }
+    if let Some(pushover) = pushover {
+        if let Err(err) = pushover::send(pushover, push).await {
+            status_error!("Could not push the notification with Pushover: {}", err);
+        }
+    }
+}
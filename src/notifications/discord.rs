@@ -0,0 +1,80 @@
+//! Shows the playlists of a run in a Discord channel, one embed each with its name, link,
+//! track count and cover, e.g. for the friends the playlists are made for. Dry runs and
+//! runs without playlists post nothing.
+
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::actions::action_report::CreatedPlaylist;
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::notifications::RunSummary;
+use crate::tr;
+
+/// A hanging Discord should not keep the run from ending.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Discord takes at most this many embeds per message.
+const EMBEDS_PER_MESSAGE: usize = 10;
+
+/// The green of Spotify.
+const EMBED_COLOR: u32 = 0x1db954;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DiscordConfig {
+    /// From the integrations in the settings of the channel,
+    /// `https://discord.com/api/webhooks/...`.
+    pub webhook_url: String,
+    /// Posts under this name instead of the name of the webhook.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+fn embed(playlist: &CreatedPlaylist) -> Value {
+    let description = if playlist.updated {
+        tr!("Updated with {} tracks", playlist.track_count)
+    } else {
+        tr!("Created with {} tracks", playlist.track_count)
+    };
+    let mut embed = json!({
+        "title": playlist.name,
+        "url": playlist.url,
+        "description": description,
+        "color": EMBED_COLOR,
+    });
+    if let Some(cover_url) = &playlist.cover_url {
+        embed["thumbnail"] = json!({ "url": cover_url });
+    }
+    embed
+}
+
+pub async fn send(config: &DiscordConfig, summary: &RunSummary<'_>) -> Result<(), AuthorizeError> {
+    if summary.dry_run {
+        return Ok(());
+    }
+    let embeds: Vec<Value> = summary
+        .reports
+        .iter()
+        .filter_map(|report| report.result.as_ref().ok())
+        .map(embed)
+        .collect();
+    let client = Client::builder().timeout(TIMEOUT).build()?;
+    for embeds in embeds.chunks(EMBEDS_PER_MESSAGE) {
+        let mut message = json!({ "embeds": embeds });
+        if let Some(username) = &config.username {
+            message["username"] = json!(username);
+        }
+        let request = client.post(&config.webhook_url).json(&message).build()?;
+        let resp = execute(&client, request).await?;
+        if !resp.status().is_success() {
+            return Err(AuthorizeError::Service(
+                "Discord",
+                resp.status().to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
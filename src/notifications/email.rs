@@ -0,0 +1,155 @@
+//! Mails a digest of every run over SMTP, so unattended runs are not silent: the playlists
+//! created or updated, the tracks that came and went in the top tracks, and the failures.
+
+use std::time::Duration;
+
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+
+use crate::authorize::AuthorizeError;
+use crate::notifications::RunSummary;
+use crate::tr;
+
+/// A hanging mail server should not keep the run from ending.
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How the connection to the mail server is encrypted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// TLS from the start, port 465 by default.
+    Tls,
+    /// Upgraded to TLS after connecting, port 587 by default.
+    #[default]
+    Starttls,
+    /// Unencrypted, port 25 by default; only for a server on the same machine.
+    None,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct EmailConfig {
+    pub host: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Better given as $SPAUTOFY_SMTP_PASSWORD than written into the config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// E.g. `Spautofy <spautofy@example.com>`.
+    pub from: String,
+    pub to: Vec<String>,
+    /// Only mails the runs with failed actions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only_failures: bool,
+}
+
+impl EmailConfig {
+    /// The errors stay English like the ones of the rules.
+    pub fn mailboxes(&self) -> Result<(Mailbox, Vec<Mailbox>), String> {
+        let parse = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|err| format!("\"{}\" is no email address: {}", address, err))
+        };
+        if self.to.is_empty() {
+            return Err("to needs at least one address".to_string());
+        }
+        let to = self
+            .to
+            .iter()
+            .map(|address| parse(address))
+            .collect::<Result<_, _>>()?;
+        Ok((parse(&self.from)?, to))
+    }
+}
+
+fn digest(summary: &RunSummary) -> String {
+    let mut lines = vec![tr!(
+        "Run from {} to {}.",
+        summary.started_at.format("%Y-%m-%d %H:%M"),
+        summary.finished_at.format("%H:%M")
+    )];
+    if summary.dry_run {
+        lines.push(tr!("This was a dry run, nothing was changed."));
+    }
+    for report in summary.reports {
+        lines.push(String::new());
+        let playlist = match &report.result {
+            Ok(playlist) => playlist,
+            Err(err) => {
+                lines.push(tr!("{} failed: {}", report.action, err));
+                continue;
+            }
+        };
+        lines.push(if playlist.updated {
+            tr!(
+                "Updated \"{}\" with {} tracks: {}",
+                playlist.name,
+                playlist.track_count,
+                playlist.url
+            )
+        } else {
+            tr!(
+                "Created \"{}\" with {} tracks: {}",
+                playlist.name,
+                playlist.track_count,
+                playlist.url
+            )
+        });
+        let Some(changes) = &playlist.changes else {
+            continue;
+        };
+        for track in &changes.added {
+            lines.push(tr!("  new: {} by {}", track.name, track.artists));
+        }
+        for track in &changes.removed {
+            lines.push(tr!("  gone: {} by {}", track.name, track.artists));
+        }
+        if !changes.moved.is_empty() {
+            lines.push(tr!("  {} tracks moved", changes.moved.len()));
+        }
+    }
+    lines.join("\n")
+}
+
+fn smtp_error(err: impl std::fmt::Display) -> AuthorizeError {
+    AuthorizeError::Service("SMTP", err.to_string())
+}
+
+pub async fn send(config: &EmailConfig, summary: &RunSummary<'_>) -> Result<(), AuthorizeError> {
+    let (from, to) = config.mailboxes().map_err(smtp_error)?;
+    let mut builder = Message::builder().from(from).subject(summary.title());
+    for to in to {
+        builder = builder.to(to);
+    }
+    let message = builder
+        .header(ContentType::TEXT_PLAIN)
+        .body(digest(summary))
+        .map_err(smtp_error)?;
+    let mut transport = match config.security {
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host),
+        SmtpSecurity::Starttls => {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+        }
+        SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            &config.host,
+        )),
+    }
+    .map_err(smtp_error)?
+    .timeout(Some(TIMEOUT));
+    if let Some(port) = config.port {
+        transport = transport.port(port);
+    }
+    if let Some(username) = &config.username {
+        let password = config.password.clone().unwrap_or_default();
+        transport = transport.credentials(Credentials::new(username.clone(), password));
+    }
+    transport.build().send(message).await.map_err(smtp_error)?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+//! Pings a check of healthchecks.io, or of a server of one's own, after every `spautofy
+//! run`: the `healthcheck_url` if the run succeeded and the URL with `/fail` appended if an
+//! action failed or the login did. A run that does not happen at all, e.g. since the timer
+//! or cron job is gone, sends no ping, and the check reports it once its period is over.
+//!
+//! With `{pipeline}` in the URL, like `https://hc-ping.com/<ping key>/{pipeline}`, every
+//! pipeline of the run pings a check of its own.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use reqwest::Client;
+
+use crate::actions::action_report::ActionReport;
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::notifications::report_line;
+
+/// A hanging server should not keep the run from ending.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+const PIPELINE_PLACEHOLDER: &str = "{pipeline}";
+
+/// The pipeline as a slug like healthchecks.io makes of the names of the checks, e.g.
+/// `modern-metal` for the rule "Modern Metal".
+fn slug(pipeline: &str) -> String {
+    pipeline
+        .chars()
+        .map(|c| match c {
+            c if c.is_alphanumeric() || c == '_' || c == '-' => c.to_ascii_lowercase(),
+            _ => '-',
+        })
+        .collect()
+}
+
+/// The URLs to ping for the pipelines of a run, with the indices of their reports; the
+/// whole run pings the URL once if it names no pipeline.
+pub fn checks(url: &str, pipelines: &[String]) -> BTreeMap<String, Vec<usize>> {
+    let mut checks: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (index, pipeline) in pipelines.iter().enumerate() {
+        let url = url.replace(PIPELINE_PLACEHOLDER, &slug(pipeline));
+        checks.entry(url).or_default().push(index);
+    }
+    checks
+}
+
+/// Pings the check with the lines of the reports as the body, which the check keeps in
+/// its log; `/fail` tells that something failed.
+pub async fn ping(url: &str, failed: bool, body: String) -> Result<(), AuthorizeError> {
+    let url = if failed {
+        format!("{}/fail", url.trim_end_matches('/'))
+    } else {
+        url.to_string()
+    };
+    let client = Client::builder().timeout(TIMEOUT).build()?;
+    let resp = execute(&client, client.post(url).body(body).build()?).await?;
+    if !resp.status().is_success() {
+        return Err(AuthorizeError::Service(
+            "healthcheck",
+            resp.status().to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The body of the ping for the reports of a check.
+pub fn body(reports: &[&ActionReport]) -> String {
+    let lines: Vec<String> = reports.iter().map(|report| report_line(report)).collect();
+    lines.join("\n")
+}
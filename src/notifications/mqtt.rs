@@ -0,0 +1,250 @@
+//! Publishes every finished run to an MQTT broker, and with `spautofy mqtt` runs what is
+//! sent to the command topic, so Home Assistant automations can start pipelines and react
+//! to their results. The topics start with the configured prefix:
+//! - `<prefix>/run` takes e.g. `weekly` or `top-tracks:short,Modern Metal`, like `spautofy run`
+//! - `<prefix>/result` gets the JSON of the webhooks after every run, retained
+//! - `<prefix>/status` is `online` while `spautofy mqtt` runs and `offline` otherwise
+
+use std::time::Duration;
+
+use rand::distributions::{Alphanumeric, DistString};
+use rumqttc::{
+    AsyncClient, ConnectionError, Event, EventLoop, LastWill, MqttOptions, Outgoing, Packet, QoS,
+    TlsConfiguration, Transport,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+
+use crate::authorize::AuthorizeError;
+use crate::notifications::{webhook, RunSummary};
+use crate::status_error;
+
+/// A hanging broker should not keep the run from ending.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The broker drops the listener if it hears nothing for one and a half times as long.
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// How long the listener waits before connecting again to a broker that went away.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const DEFAULT_PREFIX: &str = "spautofy";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MqttConfig {
+    pub host: String,
+    /// 1883, or 8883 with `tls`, if not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tls: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Better given as $SPAUTOFY_MQTT_PASSWORD than written into the config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// What the topics start with, `spautofy` if not given; several profiles need one each.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic: Option<String>,
+}
+
+impl MqttConfig {
+    pub fn prefix(&self) -> &str {
+        self.topic
+            .as_deref()
+            .unwrap_or(DEFAULT_PREFIX)
+            .trim_end_matches('/')
+    }
+
+    pub fn command_topic(&self) -> String {
+        format!("{}/run", self.prefix())
+    }
+
+    fn result_topic(&self) -> String {
+        format!("{}/result", self.prefix())
+    }
+
+    fn status_topic(&self) -> String {
+        format!("{}/status", self.prefix())
+    }
+
+    /// The broker hands messages of a client id out only once, so every connection gets
+    /// its own.
+    fn options(&self) -> MqttOptions {
+        let client_id = format!(
+            "spautofy-{}",
+            Alphanumeric.sample_string(&mut rand::thread_rng(), 8)
+        );
+        let port = self.port.unwrap_or(if self.tls { 8883 } else { 1883 });
+        let mut options = MqttOptions::new(client_id, &self.host, port);
+        options.set_keep_alive(KEEP_ALIVE);
+        if self.tls {
+            options.set_transport(Transport::Tls(TlsConfiguration::Native));
+        }
+        if let Some(username) = &self.username {
+            options.set_credentials(username, self.password.clone().unwrap_or_default());
+        }
+        options
+    }
+}
+
+fn mqtt_error(err: impl std::fmt::Display) -> AuthorizeError {
+    AuthorizeError::Service("MQTT", err.to_string())
+}
+
+/// Publishes the summary on the result topic, retained so Home Assistant knows the last
+/// run after a restart.
+pub async fn send(config: &MqttConfig, summary: &RunSummary<'_>) -> Result<(), AuthorizeError> {
+    let (client, mut eventloop) = AsyncClient::new(config.options(), 10);
+    let payload = webhook::payload(summary).to_string();
+    client
+        .publish(config.result_topic(), QoS::AtLeastOnce, true, payload)
+        .await
+        .map_err(mqtt_error)?;
+    let delivered = async {
+        loop {
+            match eventloop.poll().await? {
+                Event::Incoming(Packet::PubAck(_)) => {
+                    let _ = client.disconnect().await;
+                }
+                Event::Outgoing(Outgoing::Disconnect) => return Ok::<_, ConnectionError>(()),
+                _ => {}
+            }
+        }
+    };
+    match timeout(TIMEOUT, delivered).await {
+        Ok(result) => result.map_err(mqtt_error),
+        Err(_) => Err(mqtt_error("the broker did not answer")),
+    }
+}
+
+/// The connection of `spautofy mqtt`, receiving the commands on a task of its own so the
+/// broker keeps hearing from it while pipelines run.
+pub struct Listener {
+    client: AsyncClient,
+    config: MqttConfig,
+    commands: UnboundedReceiver<String>,
+    task: JoinHandle<()>,
+}
+
+impl Listener {
+    /// Waits until the broker accepted the connection, so wrong settings are reported
+    /// right away; later the connection is made again whenever it drops.
+    pub async fn connect(config: &MqttConfig) -> Result<Self, AuthorizeError> {
+        let mut options = config.options();
+        options.set_last_will(LastWill::new(
+            config.status_topic(),
+            "offline",
+            QoS::AtLeastOnce,
+            true,
+        ));
+        let (client, mut eventloop) = AsyncClient::new(options, 10);
+        let connected = async {
+            loop {
+                if let Event::Incoming(Packet::ConnAck(_)) = eventloop.poll().await? {
+                    return Ok::<_, ConnectionError>(());
+                }
+            }
+        };
+        match timeout(TIMEOUT, connected).await {
+            Ok(result) => result.map_err(mqtt_error)?,
+            Err(_) => return Err(mqtt_error("the broker did not answer")),
+        }
+        announce(&client, config).await.map_err(mqtt_error)?;
+        let (sender, commands) = mpsc::unbounded_channel();
+        let task = tokio::spawn(receive_commands(
+            client.clone(),
+            config.clone(),
+            eventloop,
+            sender,
+        ));
+        Ok(Listener {
+            client,
+            config: config.clone(),
+            commands,
+            task,
+        })
+    }
+
+    /// The payload of the next message on the command topic.
+    pub async fn next_command(&mut self) -> Option<String> {
+        self.commands.recv().await
+    }
+
+    /// Tells the result topic that a command could not be run, e.g. since it named nothing
+    /// to run; not retained, the last run stays the result.
+    pub async fn command_failed(&self, command: &str, err: &str) {
+        let payload = json!({ "event": "command_failed", "command": command, "error": err });
+        let published = self
+            .client
+            .publish(
+                self.config.result_topic(),
+                QoS::AtLeastOnce,
+                false,
+                payload.to_string(),
+            )
+            .await;
+        if let Err(err) = published {
+            status_error!("Could not publish to MQTT: {}", err);
+        }
+    }
+
+    /// Marks Spautofy offline before disconnecting, the last will only covers going away.
+    pub async fn disconnect(self) {
+        let _ = self
+            .client
+            .publish(
+                self.config.status_topic(),
+                QoS::AtLeastOnce,
+                true,
+                "offline",
+            )
+            .await;
+        let _ = self.client.disconnect().await;
+        let _ = timeout(TIMEOUT, self.task).await;
+    }
+}
+
+/// Subscribes to the command topic and marks Spautofy online, again after every reconnect
+/// since the session is not kept.
+async fn announce(client: &AsyncClient, config: &MqttConfig) -> Result<(), rumqttc::ClientError> {
+    client
+        .subscribe(config.command_topic(), QoS::AtLeastOnce)
+        .await?;
+    client
+        .publish(config.status_topic(), QoS::AtLeastOnce, true, "online")
+        .await
+}
+
+async fn receive_commands(
+    client: AsyncClient,
+    config: MqttConfig,
+    mut eventloop: EventLoop,
+    sender: mpsc::UnboundedSender<String>,
+) {
+    let command_topic = config.command_topic();
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::Publish(publish))) if publish.topic == command_topic => {
+                let command = String::from_utf8_lossy(&publish.payload).into_owned();
+                if sender.send(command).is_err() {
+                    return;
+                }
+            }
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                if let Err(err) = announce(&client, &config).await {
+                    status_error!("Could not subscribe to {}: {}", command_topic, err);
+                }
+            }
+            Ok(Event::Outgoing(Outgoing::Disconnect)) => return,
+            Ok(_) => {}
+            Err(err) => {
+                status_error!("Lost the connection to the MQTT broker: {}", err);
+                sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Pushes a short notification to a topic of ntfy (https://ntfy.sh or a server of one's
+//! own), which the ntfy app shows on the phone.
+
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::notifications::Push;
+
+/// A hanging ntfy server should not keep the run from ending.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+const DEFAULT_SERVER: &str = "https://ntfy.sh";
+
+/// The priorities of ntfy go from 1 to 5, 3 is the default.
+const PRIORITY: u8 = 3;
+const URGENT_PRIORITY: u8 = 4;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NtfyConfig {
+    /// https://ntfy.sh if not given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// Anyone knowing the topic of a public server can read it, so better not guessable.
+    pub topic: String,
+    /// The access token for a protected topic, better given as $SPAUTOFY_NTFY_TOKEN.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Only pushes the runs with failed actions; a failed login is pushed anyway.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only_failures: bool,
+}
+
+impl NtfyConfig {
+    pub fn server(&self) -> &str {
+        self.server
+            .as_deref()
+            .unwrap_or(DEFAULT_SERVER)
+            .trim_end_matches('/')
+    }
+}
+
+/// Published as JSON, since the title would have to be ASCII as a header.
+pub async fn send(config: &NtfyConfig, push: &Push) -> Result<(), AuthorizeError> {
+    let message = json!({
+        "topic": config.topic,
+        "title": push.title,
+        "message": push.message,
+        "priority": if push.urgent { URGENT_PRIORITY } else { PRIORITY },
+        "tags": [if push.urgent { "warning" } else { "musical_note" }],
+    });
+    let client = Client::builder().timeout(TIMEOUT).build()?;
+    let mut request_builder = client.post(config.server()).json(&message);
+    if let Some(token) = &config.token {
+        request_builder = request_builder.bearer_auth(token);
+    }
+    let resp = execute(&client, request_builder.build()?).await?;
+    if !resp.status().is_success() {
+        return Err(AuthorizeError::Service("ntfy", resp.status().to_string()));
+    }
+    Ok(())
+}
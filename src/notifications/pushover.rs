@@ -0,0 +1,74 @@
+//! Pushes a short notification with Pushover, to the devices of the user key.
+
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::notifications::Push;
+
+const API_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// A hanging Pushover should not keep the run from ending.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pushover turns away longer messages.
+const MAX_MESSAGE_CHARS: usize = 1024;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PushoverConfig {
+    /// The API token of the application created for Spautofy, better given as
+    /// $SPAUTOFY_PUSHOVER_TOKEN.
+    #[serde(default)]
+    pub token: String,
+    /// The user or group key the notifications go to.
+    pub user: String,
+    /// Only pushes the runs with failed actions; a failed login is pushed anyway.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only_failures: bool,
+}
+
+/// Pushover answers errors with the messages in `errors`.
+#[derive(Debug, Deserialize)]
+struct PushoverError {
+    errors: Vec<String>,
+}
+
+fn truncated(message: &str) -> String {
+    if message.chars().count() <= MAX_MESSAGE_CHARS {
+        return message.to_string();
+    }
+    let mut message: String = message.chars().take(MAX_MESSAGE_CHARS - 1).collect();
+    message.push('…');
+    message
+}
+
+pub async fn send(config: &PushoverConfig, push: &Push) -> Result<(), AuthorizeError> {
+    let message = truncated(&push.message);
+    // priority 1 sounds even in the quiet hours of the user
+    let priority = if push.urgent { "1" } else { "0" };
+    let client = Client::builder().timeout(TIMEOUT).build()?;
+    let request = client
+        .post(API_URL)
+        .form(&[
+            ("token", config.token.as_str()),
+            ("user", config.user.as_str()),
+            ("title", push.title.as_str()),
+            ("message", message.as_str()),
+            ("priority", priority),
+        ])
+        .build()?;
+    let resp = execute(&client, request).await?;
+    let status = resp.status();
+    if !status.is_success() {
+        let message = resp
+            .json::<PushoverError>()
+            .await
+            .map(|error| error.errors.join(", "))
+            .unwrap_or_else(|_| status.to_string());
+        return Err(AuthorizeError::Service("Pushover", message));
+    }
+    Ok(())
+}
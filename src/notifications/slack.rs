@@ -0,0 +1,151 @@
+//! Posts how a run went to a Slack channel through an incoming webhook: the playlists with
+//! their links and covers, and what failed. Every webhook can be limited to some pipelines,
+//! so the noisy ones stay out of the channel. Dry runs post nothing.
+
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::actions::action_report::ActionReport;
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::notifications::{report_line, RunSummary};
+use crate::tr;
+
+/// A hanging Slack should not keep the run from ending.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Slack takes at most 50 blocks per message, one is the header.
+const SECTIONS_PER_MESSAGE: usize = 49;
+
+/// Slack cuts off longer headers.
+const MAX_HEADER_CHARS: usize = 150;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SlackConfig {
+    /// From the incoming webhooks of the Slack app, `https://hooks.slack.com/services/...`.
+    pub webhook_url: String,
+    /// Only posts the runs of these pipelines, rules or actions, by the names `spautofy run`
+    /// takes; all of them if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pipelines: Vec<String>,
+    /// Only posts the runs with failed actions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only_failures: bool,
+}
+
+impl SlackConfig {
+    pub fn covers(&self, pipeline: &str) -> bool {
+        self.pipelines.is_empty() || self.pipelines.iter().any(|name| name == pipeline)
+    }
+}
+
+/// Slack reads `&`, `<` and `>` as the start of entities and links.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn header(title: &str) -> Value {
+    let text: String = title.chars().take(MAX_HEADER_CHARS).collect();
+    json!({
+        "type": "header",
+        "text": { "type": "plain_text", "text": text },
+    })
+}
+
+fn section(report: &ActionReport) -> Value {
+    let playlist = match &report.result {
+        Ok(playlist) => playlist,
+        Err(_) => {
+            return json!({
+                "type": "section",
+                "text": {
+                    "type": "mrkdwn",
+                    "text": format!(":warning: {}", escape(&report_line(report))),
+                },
+            })
+        }
+    };
+    let description = if playlist.updated {
+        tr!("Updated with {} tracks", playlist.track_count)
+    } else {
+        tr!("Created with {} tracks", playlist.track_count)
+    };
+    let mut section = json!({
+        "type": "section",
+        "text": {
+            "type": "mrkdwn",
+            "text": format!(
+                "*<{}|{}>*\n{}",
+                playlist.url,
+                escape(&playlist.name),
+                escape(&description)
+            ),
+        },
+    });
+    if let Some(cover_url) = &playlist.cover_url {
+        section["accessory"] = json!({
+            "type": "image",
+            "image_url": cover_url,
+            "alt_text": playlist.name,
+        });
+    }
+    section
+}
+
+async fn post(
+    client: &Client,
+    config: &SlackConfig,
+    message: &Value,
+) -> Result<(), AuthorizeError> {
+    let request = client.post(&config.webhook_url).json(message).build()?;
+    let resp = execute(client, request).await?;
+    if !resp.status().is_success() {
+        return Err(AuthorizeError::Service("Slack", resp.status().to_string()));
+    }
+    Ok(())
+}
+
+/// Posts the reports of the summary, the ones of the pipelines of the webhook.
+pub async fn send(config: &SlackConfig, summary: &RunSummary<'_>) -> Result<(), AuthorizeError> {
+    if summary.dry_run || summary.reports.is_empty() {
+        return Ok(());
+    }
+    let title = summary.title();
+    let client = Client::builder().timeout(TIMEOUT).build()?;
+    for (index, reports) in summary.reports.chunks(SECTIONS_PER_MESSAGE).enumerate() {
+        let mut blocks = Vec::with_capacity(reports.len() + 1);
+        if index == 0 {
+            blocks.push(header(&title));
+        }
+        blocks.extend(reports.iter().map(section));
+        // shown in the notifications, which do not show blocks
+        let message = json!({ "text": title, "blocks": blocks });
+        post(&client, config, &message).await?;
+    }
+    Ok(())
+}
+
+/// Posts that the pipelines could not run at all, e.g. because logging in failed.
+pub async fn send_failure(
+    config: &SlackConfig,
+    title: &str,
+    err: &str,
+) -> Result<(), AuthorizeError> {
+    let message = json!({
+        "text": title,
+        "blocks": [
+            header(title),
+            {
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": format!(":warning: {}", escape(err)) },
+            },
+        ],
+    });
+    let client = Client::builder().timeout(TIMEOUT).build()?;
+    post(&client, config, &message).await
+}
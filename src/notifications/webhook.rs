@@ -0,0 +1,93 @@
+//! Posts every finished run as JSON, e.g.
+//! `{"event": "run_finished", "profile": null, "started_at": "...", "finished_at": "...",
+//! "dry_run": false, "succeeded": 1, "failed": 1, "actions": ["short top tracks playlist",
+//! "rule Modern Metal"], "playlists": [{"action": "short top tracks playlist", "name": "...",
+//! "url": "...", "track_count": 50, "cover_url": "...", "updated": true}], "errors": [{"action": "rule Modern Metal", "error":
+//! "..."}]}`.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::authorize::AuthorizeError;
+use crate::endpoints::execute;
+use crate::notifications::RunSummary;
+
+/// A hanging automation should not keep the run from ending.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Sent with the request, e.g. `{"Authorization": "Bearer <token>"}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Only called for runs with failed actions.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub only_failures: bool,
+}
+
+/// Only the origin, the path may hold a secret.
+pub fn public_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(url) => url.origin().ascii_serialization(),
+        Err(_) => url.to_string(),
+    }
+}
+
+pub fn payload(summary: &RunSummary) -> Value {
+    let playlists: Vec<Value> = summary
+        .reports
+        .iter()
+        .filter_map(|report| {
+            let playlist = report.result.as_ref().ok()?;
+            Some(json!({
+                "action": report.action,
+                "name": playlist.name,
+                "url": playlist.url,
+                "track_count": playlist.track_count,
+                "cover_url": playlist.cover_url,
+                "updated": playlist.updated,
+            }))
+        })
+        .collect();
+    let errors: Vec<Value> = summary
+        .reports
+        .iter()
+        .filter_map(|report| {
+            let error = report.result.as_ref().err()?;
+            Some(json!({ "action": report.action, "error": error }))
+        })
+        .collect();
+    json!({
+        "event": "run_finished",
+        "profile": summary.profile,
+        "started_at": summary.started_at.to_rfc3339(),
+        "finished_at": summary.finished_at.to_rfc3339(),
+        "dry_run": summary.dry_run,
+        "succeeded": playlists.len(),
+        "failed": errors.len(),
+        "actions": summary.reports.iter().map(|report| &report.action).collect::<Vec<_>>(),
+        "playlists": playlists,
+        "errors": errors,
+    })
+}
+
+pub async fn send(config: &WebhookConfig, summary: &RunSummary<'_>) -> Result<(), AuthorizeError> {
+    let client = Client::builder().timeout(TIMEOUT).build()?;
+    let mut request_builder = client.post(&config.url).json(&payload(summary));
+    for (name, value) in &config.headers {
+        request_builder = request_builder.header(name, value);
+    }
+    let resp = execute(&client, request_builder.build()?).await?;
+    if !resp.status().is_success() {
+        return Err(AuthorizeError::Service(
+            "Webhook",
+            resp.status().to_string(),
+        ));
+    }
+    Ok(())
+}
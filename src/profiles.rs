@@ -0,0 +1,136 @@
+//! A profile is one more account, with its own config and history file next to the
+//! default ones: `--profile work` uses `spautofy.work.config` and `spautofy.work.history`.
+//!
+//! The accounts can also share one config file, where `profiles.work` holds the settings
+//! of `work` that differ from the ones at the top of the file and `default_profile` names
+//! the profile used without `--profile`. Such a profile still has its own credentials and
+//! history files.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::config_format::ConfigFormat;
+use crate::tr;
+
+/// The profile using the config and history paths as they are given.
+pub const DEFAULT_PROFILE: &str = "default";
+
+const PROFILES_KEY: &str = "profiles";
+const DEFAULT_PROFILE_KEY: &str = "default_profile";
+
+/// The profiles within a config file.
+#[derive(Debug, Default)]
+pub struct ProfileSections {
+    pub default: Option<String>,
+    pub names: Vec<String>,
+}
+
+/// Empty if the config file cannot be read, the errors show once it is parsed.
+pub fn read_sections(config_path: &str) -> ProfileSections {
+    let Ok(content) = fs::read_to_string(config_path) else {
+        return ProfileSections::default();
+    };
+    let Ok(config) = ConfigFormat::from_path(config_path).parse::<Value>(&content) else {
+        return ProfileSections::default();
+    };
+    ProfileSections {
+        default: config
+            .get(DEFAULT_PROFILE_KEY)
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        names: config
+            .get(PROFILES_KEY)
+            .and_then(Value::as_object)
+            .map(|profiles| profiles.keys().cloned().collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Takes the profiles out of the config and lays the settings of `profile` over the
+/// others; sections like `actions` are merged field by field.
+pub fn select_profile(config: Value, profile: Option<&str>) -> Result<Value, String> {
+    let Value::Object(mut config) = config else {
+        return Ok(config);
+    };
+    config.remove(DEFAULT_PROFILE_KEY);
+    let profiles = config.remove(PROFILES_KEY);
+    let mut config = Value::Object(config);
+    if let Some(profile) = profile {
+        let settings = profiles
+            .and_then(|mut profiles| profiles.get_mut(profile).map(Value::take))
+            .ok_or_else(|| tr!("There is no profile \"{}\" in the config file.", profile))?;
+        merge(&mut config, settings);
+    }
+    Ok(config)
+}
+
+fn merge(config: &mut Value, settings: Value) {
+    match (config, settings) {
+        (Value::Object(config), Value::Object(settings)) => {
+            for (key, value) in settings {
+                match config.get_mut(&key) {
+                    Some(current) => merge(current, value),
+                    None => {
+                        config.insert(key, value);
+                    }
+                }
+            }
+        }
+        (config, settings) => *config = settings,
+    }
+}
+
+/// The path of the file for the profile, with its name inserted before the extension.
+pub fn profile_path(path: &str, profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        return path.to_string();
+    }
+    let path = Path::new(path);
+    let file_name = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) => format!(
+            "{}.{}.{}",
+            stem.to_string_lossy(),
+            profile,
+            extension.to_string_lossy()
+        ),
+        _ => format!("{}.{}", path.to_string_lossy(), profile),
+    };
+    path.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The profiles within the config file or with a config file next to it, sorted by name;
+/// the default profile comes first if its config file exists.
+pub fn find_profiles(config_path: &str) -> io::Result<Vec<String>> {
+    let path = Path::new(config_path);
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let (Some(stem), Some(extension)) = (path.file_stem(), path.extension()) else {
+        return Ok(Vec::new());
+    };
+    let prefix = format!("{}.", stem.to_string_lossy());
+    let suffix = format!(".{}", extension.to_string_lossy());
+    let mut profiles: Vec<String> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let profile = file_name.strip_prefix(&prefix)?.strip_suffix(&suffix)?;
+            let valid = !profile.is_empty() && !profile.contains('.');
+            valid.then(|| profile.to_string())
+        })
+        .chain(read_sections(config_path).names)
+        .filter(|profile| profile != DEFAULT_PROFILE)
+        .collect();
+    profiles.sort();
+    profiles.dedup();
+    if path.exists() {
+        profiles.insert(0, DEFAULT_PROFILE.to_string());
+    }
+    Ok(profiles)
+}
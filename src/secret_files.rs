@@ -0,0 +1,78 @@
+//! The credentials file holds the client secret and the refresh token, so it is written
+//! readable by its owner only, and a credentials file others can read is reported.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+
+use serde::Serialize;
+
+use crate::config_format::ConfigFormat;
+use crate::{status, tr};
+
+#[cfg(unix)]
+fn restrict(path: &str) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn restrict(_path: &str) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn readable_by_others(path: &str) -> io::Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+    Ok(fs::metadata(path)?.permissions().mode() & 0o077 != 0)
+}
+
+#[cfg(not(unix))]
+fn readable_by_others(_path: &str) -> io::Result<bool> {
+    Ok(false)
+}
+
+/// Like `write_config_file`, but with mode 0600 on Unix, also for a file that exists.
+pub fn write_secret_file<T: Serialize>(path: &str, value: &T) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    restrict(path)?;
+    file.write_all(ConfigFormat::from_path(path).serialize(value).as_bytes())
+}
+
+/// Copies a file with secrets, the copy only readable by its owner.
+pub fn copy_secret_file(from: &str, to: &str) -> io::Result<()> {
+    fs::copy(from, to)?;
+    restrict(to)
+}
+
+/// Warns if others can read the file; asks to fix it if `interactive` is set and there is
+/// someone at the terminal to answer.
+pub fn check_secret_file(path: &str, interactive: bool) -> io::Result<()> {
+    if !readable_by_others(path)? {
+        return Ok(());
+    }
+    status!(
+        "Warning: Other users can read \"{}\", which holds your client secret and refresh \
+        token.",
+        path
+    );
+    if !interactive || !io::stdin().is_terminal() {
+        status!("Restrict it to your user with `chmod 600 {}`.", path);
+        return Ok(());
+    }
+    eprint!("{} ", tr!("Restrict it to your user now? [Y/n]"));
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    if matches!(answer.trim(), "" | "y" | "Y" | "j" | "J") {
+        restrict(path)?;
+        status!("Restricted \"{}\" to your user.", path);
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::io::{self, Stdout};
+use std::ops::{Deref, DerefMut};
+use std::panic;
+use std::sync::{Mutex, Once, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::Duration;
+
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::{Margin, Rect};
+use ratatui::widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ratatui::{Frame, Terminal};
+
+use crate::status_log::{self, Level};
+
+pub mod action_selection;
+pub mod auth_screen;
+pub mod config_wizard;
+pub mod confirm_dialog;
+pub mod confirm_screen;
+#[cfg(feature = "album-art")]
+pub mod cover_art;
+pub mod details_panel;
+pub mod error_popup;
+pub mod help_overlay;
+pub mod history_screen;
+pub mod keymap;
+pub mod list_filter;
+pub mod log_pane;
+pub mod match_picker;
+pub mod now_playing;
+pub mod parameters;
+pub mod playlist_browser;
+pub mod progress_gauge;
+pub mod restore_picker;
+pub mod run_screen;
+pub mod search_panel;
+pub mod status_bar;
+pub mod summary_screen;
+pub mod theme;
+pub mod track_preview;
+pub mod wizard;
+
+pub type Tui = Terminal<CrosstermBackend<Stdout>>;
+
+/// The thread drawing the TUI while it owns the terminal.
+static SESSION_THREAD: Mutex<Option<ThreadId>> = Mutex::new(None);
+
+/// How often screens are redrawn while waiting for input, e.g. to move the now-playing progress.
+const REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Frames of the spinner shown while waiting for background work.
+pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// How often animated screens like the spinner are redrawn.
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenOutcome {
+    Confirm,
+    Back,
+    Quit,
+    BrowsePlaylists,
+    Search,
+    History,
+    /// Log in to Spotify again, possibly with another account.
+    Reauthorize,
+    /// Preview the tracks of the action at this index.
+    Preview(usize),
+    /// Pick the playlist the action at this index updates.
+    PickPlaylist(usize),
+}
+
+/// Draws a vertical scrollbar on the right border of `area` if the content does not fit.
+pub fn render_scrollbar(frame: &mut Frame, area: Rect, content_length: usize, position: usize) {
+    let visible = area.height.saturating_sub(2) as usize;
+    if content_length <= visible {
+        return;
+    }
+    let mut state = ScrollbarState::new(content_length).position(position);
+    frame.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut state,
+    );
+}
+
+/// Waits for the next event; returns `None` when the screen should just be redrawn.
+pub fn read_event() -> io::Result<Option<Event>> {
+    if event::poll(REDRAW_INTERVAL)? {
+        event::read().map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Number of rows that fit into a bordered list rendered in `area`.
+pub fn page_size(area: Rect) -> usize {
+    (area.height.saturating_sub(2) as usize).max(1)
+}
+
+/// The terminal while the TUI owns it; dropping it gives the terminal back, so an error
+/// or a panic leaving a screen early does not leave it in raw mode.
+pub struct Session {
+    terminal: Tui,
+}
+
+impl Deref for Session {
+    type Target = Tui;
+
+    fn deref(&self) -> &Tui {
+        &self.terminal
+    }
+}
+
+impl DerefMut for Session {
+    fn deref_mut(&mut self) -> &mut Tui {
+        &mut self.terminal
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = restore();
+    }
+}
+
+pub fn init() -> io::Result<Session> {
+    install_panic_hook();
+    *SESSION_THREAD
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = Some(thread::current().id());
+    status_log::start_capturing();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    // the terminal has to be queried before any events are read
+    #[cfg(feature = "album-art")]
+    cover_art::detect_graphics_protocol();
+    Ok(Session {
+        terminal: Terminal::new(CrosstermBackend::new(io::stdout()))?,
+    })
+}
+
+/// Gives the terminal back and prints the messages kept meanwhile; does nothing if the TUI
+/// does not own the terminal.
+pub fn restore() -> io::Result<()> {
+    let session = SESSION_THREAD
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    if session.is_none() {
+        return Ok(());
+    }
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    for (level, message) in status_log::stop_capturing() {
+        status_log::push_at(level, message);
+    }
+    Ok(())
+}
+
+/// Gives the terminal back before a panic of the TUI is printed. The TUI goes on after a
+/// panic of a background task, like an action, which shows in its log pane instead.
+fn install_panic_hook() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let session = *SESSION_THREAD
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            match session {
+                Some(thread_id) if thread_id != thread::current().id() => {
+                    status_log::push_at(Level::Error, info.to_string());
+                }
+                Some(_) => {
+                    let _ = restore();
+                    previous(info);
+                }
+                None => previous(info),
+            }
+        }));
+    });
+}
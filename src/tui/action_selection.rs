@@ -0,0 +1,302 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::text::Line;
+use ratatui::widgets::{List, ListItem, ListState};
+use ratatui::Frame;
+
+use crate::actions::parameters::ActionParameters;
+use crate::i18n::t;
+use crate::tui::details_panel::render_details;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::list_filter::ListFilter;
+use crate::tui::log_pane::LogPane;
+use crate::tui::now_playing::render_footer;
+use crate::tui::parameters::action_details;
+use crate::tui::status_bar::AuthStatus;
+use crate::tui::theme;
+use crate::tui::{page_size, read_event, render_scrollbar, ScreenOutcome, Tui};
+
+const KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("PgUp, PgDn, Home, End", "Move the cursor by pages"),
+    ("{select}", "Toggle the highlighted action"),
+    ("a", "Select all visible actions"),
+    ("n", "Deselect all visible actions"),
+    ("i", "Invert the selection of the visible actions"),
+    (
+        "Shift+↑/K, Shift+↓/J",
+        "Move the highlighted action in the run order",
+    ),
+    ("/", "Filter the actions, Esc clears the filter"),
+    ("{confirm}", "Continue with the selected actions"),
+    ("p", "Browse and delete your playlists"),
+    ("s", "Search tracks and artists"),
+    ("h", "Browse the previous runs"),
+    ("r", "Log in to Spotify again"),
+    ("{quit}, Esc", "Quit"),
+];
+
+/// The file the selection of the last run is kept in, next to the history file:
+/// `spautofy.history` keeps it in `spautofy.selection`.
+pub fn selection_path(history_path: &str) -> String {
+    Path::new(history_path)
+        .with_extension("selection")
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Which of the actions named by `keys` are in `selected`.
+pub fn selection_from_keys(keys: &[&str], selected: &[String]) -> Vec<bool> {
+    keys.iter()
+        .map(|key| selected.iter().any(|selected| selected == key))
+        .collect()
+}
+
+/// The selection of the last run; `None` if nothing was selected yet.
+pub fn load_selection(filepath: &str, keys: &[&str]) -> io::Result<Option<Vec<bool>>> {
+    if !Path::new(filepath).exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(filepath)?;
+    let selected: Vec<String> = serde_json::from_str(&content)
+        .map_err(|err| io::Error::new(ErrorKind::InvalidData, err))?;
+    Ok(Some(selection_from_keys(keys, &selected)))
+}
+
+pub fn save_selection(filepath: &str, keys: &[&str], selected: &[bool]) -> io::Result<()> {
+    let selected: Vec<&str> = keys
+        .iter()
+        .zip(selected)
+        .filter_map(|(key, selected)| selected.then_some(*key))
+        .collect();
+    let content = serde_json::to_string_pretty(&selected).map_err(io::Error::other)?;
+    fs::write(filepath, content)
+}
+
+#[derive(Debug)]
+pub struct ActionSelection {
+    pub selected: Vec<bool>,
+    /// Indices of all items in the order they are run in.
+    pub order: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub struct ActionSelectionList {
+    items: Vec<&'static str>,
+    selected: Vec<bool>,
+    /// Indices of the items in display and run order.
+    order: Vec<usize>,
+    state: ListState,
+    page_size: usize,
+    log: LogPane,
+    show_help: bool,
+    filter: ListFilter,
+    /// Indices of the items matching the filter, in run order; the cursor moves over these.
+    visible: Vec<usize>,
+}
+
+impl ActionSelectionList {
+    pub fn new(items: &[&'static str], default_selection: &[bool]) -> Self {
+        let mut selected = default_selection.to_vec();
+        selected.resize(items.len(), false);
+        ActionSelectionList {
+            visible: (0..items.len()).collect(),
+            items: items.to_vec(),
+            selected,
+            order: (0..items.len()).collect(),
+            state: ListState::default().with_selected(Some(0)),
+            page_size: 1,
+            log: LogPane::default(),
+            show_help: false,
+            filter: ListFilter::default(),
+        }
+    }
+
+    /// `details` holds the parameters of every action, in the order of the items.
+    pub fn run(
+        &mut self,
+        terminal: &mut Tui,
+        status: &AuthStatus,
+        details: &[ActionParameters],
+    ) -> io::Result<ScreenOutcome> {
+        loop {
+            terminal.draw(|frame| self.render(frame, status, details))?;
+            if let Some(outcome) = self.handle_events()? {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    pub fn selection(&self) -> ActionSelection {
+        ActionSelection {
+            selected: self.selected.clone(),
+            order: self.order.clone(),
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame, status: &AuthStatus, details: &[ActionParameters]) {
+        let area = render_footer(frame);
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|index| {
+                let checkbox = if self.selected[*index] { "[x]" } else { "[ ]" };
+                ListItem::new(format!("{} {}", checkbox, t(self.items[*index])))
+            })
+            .collect();
+        let mut block = theme::block()
+            .title(t(" Spautofy - Select Actions "))
+            .title_bottom(Line::from(expand(
+                " TOGGLE WITH '{select}' | REORDER WITH 'SHIFT+↑'/'↓' | CONFIRM WITH '{confirm}' ",
+                true,
+            )))
+            .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+        if let Some(filter) = self.filter.title() {
+            block = block.title(filter.right_aligned());
+        }
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme::highlight_style());
+        let [status_area, main_area, log_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(8),
+        ])
+        .areas(area);
+        let [list_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(main_area);
+        status.render(frame, status_area);
+        let highlighted = self.highlighted().and_then(|index| details.get(index));
+        render_details(
+            frame,
+            details_area,
+            highlighted.map(action_details).unwrap_or_default(),
+        );
+        self.page_size = page_size(list_area);
+        frame.render_stateful_widget(list, list_area, &mut self.state);
+        render_scrollbar(
+            frame,
+            list_area,
+            self.visible.len(),
+            self.state.selected().unwrap_or(0),
+        );
+        self.log.render(frame, log_area);
+        if self.show_help {
+            render_help(frame, KEYBINDINGS);
+        }
+    }
+
+    /// Index of the highlighted item in `items`.
+    fn highlighted(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|position| self.visible.get(position))
+            .copied()
+    }
+
+    fn apply_filter(&mut self) {
+        let positions = self
+            .filter
+            .visible(self.order.iter().map(|index| t(self.items[*index])));
+        self.visible = positions
+            .into_iter()
+            .map(|position| self.order[position])
+            .collect();
+        let selected = if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.state.select(selected);
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.visible.len().saturating_sub(1);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    /// Swaps the highlighted item with its visible neighbour in the run order.
+    fn move_item(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let Some(next) = current
+            .checked_add_signed(delta)
+            .filter(|next| *next < self.visible.len())
+        else {
+            return;
+        };
+        let (a, b) = (self.visible[current], self.visible[next]);
+        let position = |item| self.order.iter().position(|index| *index == item).unwrap();
+        let (position_a, position_b) = (position(a), position(b));
+        self.order.swap(position_a, position_b);
+        self.visible.swap(current, next);
+        self.state.select(Some(next));
+    }
+
+    fn handle_events(&mut self) -> io::Result<Option<ScreenOutcome>> {
+        let Some(Event::Key(key)) = read_event()? else {
+            return Ok(None);
+        };
+        if key.kind != KeyEventKind::Press {
+            return Ok(None);
+        }
+        if self.show_help {
+            self.show_help = false;
+            return Ok(None);
+        }
+        if self.filter.handle_key(key.code) {
+            self.apply_filter();
+            return Ok(None);
+        }
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        match key.code {
+            KeyCode::Up if shift => self.move_item(-1),
+            KeyCode::Down if shift => self.move_item(1),
+            KeyCode::Char('K') => self.move_item(-1),
+            KeyCode::Char('J') => self.move_item(1),
+            code if is_help_key(code) => self.show_help = true,
+            code if is_bound(code, KeyAction::MoveUp) => self.move_cursor(-1),
+            code if is_bound(code, KeyAction::MoveDown) => self.move_cursor(1),
+            code if is_bound(code, KeyAction::Select) => {
+                if let Some(index) = self.highlighted() {
+                    self.selected[index] = !self.selected[index];
+                }
+            }
+            code if is_bound(code, KeyAction::Confirm) => return Ok(Some(ScreenOutcome::Confirm)),
+            code if is_bound(code, KeyAction::Quit) => return Ok(Some(ScreenOutcome::Quit)),
+            KeyCode::PageUp => self.move_cursor(-(self.page_size as isize)),
+            KeyCode::PageDown => self.move_cursor(self.page_size as isize),
+            KeyCode::Home => self.move_cursor(isize::MIN),
+            KeyCode::End => self.move_cursor(isize::MAX),
+            KeyCode::Char('a') => self.visible.iter().for_each(|i| self.selected[*i] = true),
+            KeyCode::Char('n') => self.visible.iter().for_each(|i| self.selected[*i] = false),
+            KeyCode::Char('i') => self
+                .visible
+                .iter()
+                .for_each(|i| self.selected[*i] = !self.selected[*i]),
+            KeyCode::Char('/') => self.filter.start(),
+            KeyCode::Esc if self.filter.is_active() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Char('p') => return Ok(Some(ScreenOutcome::BrowsePlaylists)),
+            KeyCode::Char('s') => return Ok(Some(ScreenOutcome::Search)),
+            KeyCode::Char('h') => return Ok(Some(ScreenOutcome::History)),
+            KeyCode::Char('r') => return Ok(Some(ScreenOutcome::Reauthorize)),
+            KeyCode::Esc => return Ok(Some(ScreenOutcome::Quit)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,87 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
+use rocket::{Ignite, Rocket};
+
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::theme;
+use crate::tui::{Tui, FRAME_INTERVAL, SPINNER};
+use crate::MainError;
+
+fn draw(terminal: &mut Tui, url: &str, started: Instant, frame_index: usize) -> io::Result<()> {
+    let elapsed = started.elapsed().as_secs();
+    let lines = vec![
+        Line::from(tr!(
+            "{} Waiting for you to authorize Spautofy with Spotify...",
+            SPINNER[frame_index % SPINNER.len()]
+        )),
+        Line::from(""),
+        Line::from(t("Please visit the following URL in your browser:")),
+        Line::from(Span::styled(
+            url.to_string(),
+            Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            tr!(
+                "Waiting for {}",
+                format!("{}:{:02}", elapsed / 60, elapsed % 60)
+            ),
+            theme::dimmed_style(),
+        )),
+    ];
+    terminal.draw(|frame| {
+        let block = theme::block()
+            .title(t(" Spautofy - Authorization "))
+            .title_bottom(Line::from(expand(" CANCEL WITH '{quit}' OR 'ESC' ", true)));
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, frame.area());
+    })?;
+    Ok(())
+}
+
+/// Whether one of the pending key presses cancels the authorization.
+fn cancel_pressed() -> io::Result<bool> {
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press
+                && (key.code == KeyCode::Esc || is_bound(key.code, KeyAction::Quit))
+            {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Runs the web server until the user authorized the app; returns `false` if they cancelled.
+pub async fn wait_for_authorization(
+    terminal: &mut Tui,
+    url: &str,
+    rocket: Rocket<Ignite>,
+) -> Result<bool, MainError> {
+    let shutdown = rocket.shutdown();
+    let server = tokio::spawn(rocket.launch());
+    let started = Instant::now();
+    let mut frame_index = 0;
+    let mut cancelled = false;
+    while !server.is_finished() {
+        draw(terminal, url, started, frame_index)?;
+        if !cancelled && cancel_pressed()? {
+            cancelled = true;
+            shutdown.clone().notify();
+        }
+        frame_index += 1;
+        tokio::time::sleep(FRAME_INTERVAL).await;
+    }
+    server.await.map_err(io::Error::other)??;
+    Ok(!cancelled)
+}
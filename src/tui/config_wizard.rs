@@ -0,0 +1,94 @@
+use std::net::IpAddr;
+
+use crate::actions::parameters::{ActionParameters, Parameter, ParameterValue};
+use crate::authorize::validate_client_credentials;
+use crate::config::SpautofyConfigFile;
+use crate::tr;
+use crate::tui::parameters::ParameterScreen;
+use crate::tui::{ScreenOutcome, Tui};
+use crate::MainError;
+
+fn text_parameter(key: &'static str, label: &'static str, placeholder: &str) -> Parameter {
+    Parameter {
+        key,
+        label,
+        value: ParameterValue::Text {
+            value: String::new(),
+            placeholder: tr!(placeholder),
+        },
+    }
+}
+
+fn wizard_parameters() -> ActionParameters {
+    ActionParameters {
+        title: tr!("Spotify app settings (see https://developer.spotify.com/dashboard)"),
+        description: tr!(
+            "The credentials of your Spotify app and where its redirect URL points to."
+        ),
+        parameters: vec![
+            text_parameter("client_id", "Client ID", "required"),
+            text_parameter("client_secret", "Client secret", "required"),
+            text_parameter("address", "Web app address", "127.0.0.1"),
+            Parameter {
+                key: "port",
+                label: "Web app port",
+                value: ParameterValue::Number {
+                    value: 3000,
+                    min: 1,
+                    max: u16::MAX as u32,
+                },
+            },
+        ],
+    }
+}
+
+fn parse_wizard_parameters(parameters: &ActionParameters) -> Result<SpautofyConfigFile, String> {
+    let client_id = parameters.text("client_id").unwrap_or_default();
+    let client_secret = parameters.text("client_secret").unwrap_or_default();
+    if client_id.is_empty() || client_secret.is_empty() {
+        return Err(tr!("Client ID and client secret are required."));
+    }
+    let address = match parameters.text("address").unwrap_or_default() {
+        "" => None,
+        address => Some(
+            address
+                .parse::<IpAddr>()
+                .map_err(|_| tr!("\"{}\" is not a valid IP address.", address))?,
+        ),
+    };
+    let port = parameters.number("port").map(|port| port as u16);
+    Ok(SpautofyConfigFile::new(
+        client_id.to_string(),
+        client_secret.to_string(),
+        address,
+        port,
+    ))
+}
+
+/// Asks for the app settings until Spotify accepts the credentials; `None` if cancelled.
+pub async fn run_config_wizard(
+    terminal: &mut Tui,
+) -> Result<Option<SpautofyConfigFile>, MainError> {
+    let mut parameters = vec![wizard_parameters()];
+    let mut notice = None;
+    loop {
+        let mut screen = ParameterScreen::new(parameters).with_title("Spautofy - First-Run Setup");
+        if let Some(notice) = notice.take() {
+            screen = screen.with_notice(notice);
+        }
+        if screen.run(terminal)? != ScreenOutcome::Confirm {
+            return Ok(None);
+        }
+        parameters = screen.into_actions();
+        match parse_wizard_parameters(&parameters[0]) {
+            Ok(config) => {
+                let (client_id, client_secret) = config.credentials();
+                if validate_client_credentials(client_id, client_secret).await? {
+                    return Ok(Some(config));
+                }
+                notice = Some(tr!("Spotify rejected the client ID or client secret."));
+            }
+            Err(err) => notice = Some(err),
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use ratatui::crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::text::Line;
+use ratatui::widgets::{Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::theme;
+
+/// Modal dialog listing exactly what a destructive action is going to remove.
+#[derive(Debug)]
+pub struct ConfirmDialog {
+    title: String,
+    items: Vec<String>,
+    scroll: usize,
+}
+
+impl ConfirmDialog {
+    pub fn new(title: &str, items: Vec<String>) -> Self {
+        ConfirmDialog {
+            title: format!(" {} ", t(title)),
+            items,
+            scroll: 0,
+        }
+    }
+
+    /// Returns `Some(true)` if the action was confirmed and `Some(false)` if it was aborted.
+    pub fn handle_key(&mut self, code: KeyCode) -> Option<bool> {
+        match code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => return Some(true),
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => return Some(false),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.scroll = (self.scroll + 1).min(self.items.len().saturating_sub(1))
+            }
+            _ => {}
+        }
+        None
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let mut lines = vec![
+            Line::from(tr!(
+                "The following {} items will be removed:",
+                self.items.len()
+            )),
+            Line::from(""),
+        ];
+        lines.extend(
+            self.items[self.scroll..]
+                .iter()
+                .map(|item| Line::from(format!("  - {}", item))),
+        );
+
+        // message, blank line and borders take up four lines
+        let height = (self.items.len() as u16 + 4).min(frame.area().height.saturating_sub(2));
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(frame.area());
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Percentage(60),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let block = theme::block()
+            .border_style(theme::error_style())
+            .title(self.title.as_str())
+            .title_bottom(Line::from(t(" CONFIRM WITH 'Y' | ABORT WITH 'ESC' ")));
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
@@ -0,0 +1,98 @@
+use std::io;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
+
+use crate::actions::parameters::ActionParameters;
+use crate::i18n::t;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::now_playing::render_footer;
+use crate::tui::parameters::display_value;
+use crate::tui::theme;
+use crate::tui::{read_event, ScreenOutcome, Tui};
+
+const KEYBINDINGS: Keybindings = &[
+    ("{confirm}, {select}", "Run the actions"),
+    ("Esc, Backspace", "Go back"),
+    ("{quit}", "Quit"),
+];
+
+#[derive(Debug)]
+pub struct ConfirmScreen<'a> {
+    actions: &'a [ActionParameters],
+}
+
+impl<'a> ConfirmScreen<'a> {
+    pub fn new(actions: &'a [ActionParameters]) -> Self {
+        ConfirmScreen { actions }
+    }
+
+    pub fn run(&self, terminal: &mut Tui) -> io::Result<ScreenOutcome> {
+        let mut show_help = false;
+        loop {
+            terminal.draw(|frame| {
+                let area = render_footer(frame);
+                let block = theme::block()
+                    .title(t(" Spautofy - Confirm "))
+                    .title_bottom(Line::from(expand(
+                        " RUN WITH '{confirm}' | BACK WITH 'ESC' ",
+                        true,
+                    )))
+                    .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+                let paragraph = Paragraph::new(self.lines())
+                    .block(block)
+                    .wrap(Wrap { trim: false });
+                frame.render_widget(paragraph, area);
+                if show_help {
+                    render_help(frame, KEYBINDINGS);
+                }
+            })?;
+            let Some(Event::Key(key)) = read_event()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if show_help {
+                show_help = false;
+                continue;
+            }
+            match key.code {
+                code if is_help_key(code) => show_help = true,
+                code if is_bound(code, KeyAction::Confirm) || is_bound(code, KeyAction::Select) => {
+                    return Ok(ScreenOutcome::Confirm)
+                }
+                code if is_bound(code, KeyAction::Quit) => return Ok(ScreenOutcome::Quit),
+                KeyCode::Esc | KeyCode::Backspace => return Ok(ScreenOutcome::Back),
+                _ => {}
+            }
+        }
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        if self.actions.is_empty() {
+            return vec![Line::from(t("No actions selected, nothing will be run."))];
+        }
+        let mut lines = vec![
+            Line::from(t("The following actions will be run:")),
+            Line::from(""),
+        ];
+        for action in self.actions {
+            lines.push(Line::from(Span::styled(
+                action.title.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+            for parameter in &action.parameters {
+                lines.push(Line::from(vec![
+                    Span::raw(format!("  {}: ", t(parameter.label))),
+                    display_value(&parameter.value),
+                ]));
+            }
+            lines.push(Line::from(""));
+        }
+        lines
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+use ratatui::layout::Rect;
+use ratatui::Frame;
+use ratatui_image::picker::{Picker, ProtocolType};
+use ratatui_image::protocol::StatefulProtocol;
+use ratatui_image::StatefulImage;
+
+use crate::models::image::Image;
+use crate::progress::Progress;
+use crate::status;
+use crate::tui::progress_gauge::draw_progress;
+use crate::tui::Tui;
+
+static PICKER: OnceLock<Option<Picker>> = OnceLock::new();
+
+/// Looks for kitty, iTerm2 or sixel graphics support; covers are not shown without any of them.
+pub fn detect_graphics_protocol() {
+    PICKER.get_or_init(|| {
+        Picker::from_query_stdio()
+            .ok()
+            .filter(|picker| picker.protocol_type() != ProtocolType::Halfblocks)
+    });
+}
+
+fn picker() -> Option<&'static Picker> {
+    PICKER.get().and_then(Option::as_ref)
+}
+
+/// Picks the smallest image, the covers are only shown in a small panel.
+fn smallest(images: &[Image]) -> Option<&Image> {
+    images
+        .iter()
+        .min_by_key(|image| image.width.unwrap_or(u32::MAX))
+}
+
+async fn fetch_image(url: &str) -> Option<image::DynamicImage> {
+    let bytes = reqwest::get(url).await.ok()?.bytes().await.ok()?;
+    image::load_from_memory(&bytes).ok()
+}
+
+/// Cover images by the id of the album or playlist they belong to.
+#[derive(Default)]
+pub struct CoverArt {
+    covers: HashMap<String, StatefulProtocol>,
+}
+
+impl fmt::Debug for CoverArt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoverArt")
+            .field("covers", &self.covers.len())
+            .finish()
+    }
+}
+
+impl CoverArt {
+    /// Downloads the covers of the given (id, images) pairs if the terminal can show them.
+    pub async fn load<'a>(
+        terminal: &mut Tui,
+        sources: impl IntoIterator<Item = (&'a str, &'a [Image])>,
+    ) -> CoverArt {
+        let mut cover_art = CoverArt::default();
+        let Some(picker) = picker() else {
+            return cover_art;
+        };
+        let mut sources: Vec<(&str, &[Image])> = sources.into_iter().collect();
+        sources.sort_by_key(|(id, _)| *id);
+        sources.dedup_by_key(|(id, _)| *id);
+
+        let title = "Loading covers";
+        let total = sources.len();
+        for (done, (id, images)) in sources.into_iter().enumerate() {
+            let _ = draw_progress(terminal, title, Progress { done, total });
+            let Some(image) = smallest(images) else {
+                continue;
+            };
+            match fetch_image(&image.url).await {
+                Some(image) => {
+                    cover_art
+                        .covers
+                        .insert(id.to_string(), picker.new_resize_protocol(image));
+                }
+                None => status!("Could not load the cover from {}", image.url),
+            }
+        }
+        cover_art
+    }
+
+    pub fn has_cover(&self, id: &str) -> bool {
+        self.covers.contains_key(id)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, id: &str) {
+        if let Some(cover) = self.covers.get_mut(id) {
+            frame.render_stateful_widget(StatefulImage::default(), area, cover);
+        }
+    }
+}
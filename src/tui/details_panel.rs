@@ -0,0 +1,15 @@
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::i18n::t;
+use crate::tui::theme;
+
+/// Explains the highlighted item of the list next to it.
+pub fn render_details(frame: &mut Frame, area: Rect, lines: Vec<Line>) {
+    let paragraph = Paragraph::new(lines)
+        .block(theme::block().title(t(" Details ")))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,101 @@
+use std::io;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::text::Line;
+use ratatui::widgets::{Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::i18n::t;
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::theme;
+use crate::tui::{read_event, Tui};
+use crate::MainError;
+
+#[derive(Debug)]
+pub struct ErrorPopup {
+    title: String,
+    message: String,
+}
+
+impl ErrorPopup {
+    pub fn new(title: &str, message: String) -> Self {
+        ErrorPopup {
+            title: format!(" {} ", t(title)),
+            message,
+        }
+    }
+
+    /// Returns whether the user wants to retry.
+    pub fn run(&self, terminal: &mut Tui) -> io::Result<bool> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            let Some(Event::Key(key)) = read_event()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('r') => return Ok(true),
+                code if is_bound(code, KeyAction::Select) => return Ok(true),
+                KeyCode::Esc => return Ok(false),
+                code if is_bound(code, KeyAction::Quit) => return Ok(false),
+                _ => {}
+            }
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(8),
+            Constraint::Fill(1),
+        ])
+        .areas(frame.area());
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Percentage(60),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let block = theme::block()
+            .border_style(theme::error_style())
+            .title(self.title.as_str())
+            .title_bottom(Line::from(expand(
+                " RETRY WITH 'R' OR '{select}' | DISMISS WITH 'ESC' ",
+                true,
+            )));
+        let paragraph = Paragraph::new(self.message.as_str())
+            .block(block)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+    }
+}
+
+/// Shows `err` in a popup; returns whether the user wants to retry. Terminal errors are passed on.
+pub fn offer_retry(terminal: &mut Tui, title: &str, err: MainError) -> Result<bool, MainError> {
+    if let MainError::Io(err) = err {
+        return Err(MainError::Io(err));
+    }
+    Ok(ErrorPopup::new(title, err.to_string()).run(terminal)?)
+}
+
+/// Evaluates the fallible operation until it succeeds, offering a retry in an error popup.
+/// Results in `None` if the user dismissed the error.
+#[macro_export]
+macro_rules! retry_on_error {
+    ( $terminal: expr, $title: expr, $operation: expr ) => {
+        loop {
+            match $operation {
+                Ok(value) => break Some(value),
+                Err(err) => {
+                    if !$crate::tui::error_popup::offer_retry($terminal, $title, err.into())? {
+                        break None;
+                    }
+                }
+            }
+        }
+    };
+}
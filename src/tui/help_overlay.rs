@@ -0,0 +1,65 @@
+use ratatui::crossterm::event::KeyCode;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::i18n::t;
+use crate::tui::keymap::expand;
+use crate::tui::theme;
+
+/// Pairs of (keys, description) shown in the help overlay of a screen.
+pub type Keybindings = &'static [(&'static str, &'static str)];
+
+pub const HELP_HINT: &str = " HELP WITH '?' ";
+
+pub fn is_help_key(code: KeyCode) -> bool {
+    matches!(code, KeyCode::Char('?') | KeyCode::F(1))
+}
+
+/// Placeholders like `{up}` in the keys are replaced with the configured keybindings.
+pub fn render_help(frame: &mut Frame, keybindings: Keybindings) {
+    let keybindings: Vec<(String, &str)> = keybindings
+        .iter()
+        .map(|(keys, description)| (expand(keys, false), t(description)))
+        .collect();
+    let key_width = keybindings
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or_default();
+    let mut lines: Vec<Line> = keybindings
+        .iter()
+        .map(|(keys, description)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{:width$}  ", keys, width = key_width),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(*description),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(t("Press any key to close this help.")));
+
+    let height = lines.len() as u16 + 2;
+    let [_, area, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Fill(1),
+        Constraint::Percentage(60),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(theme::block().title(t(" Keybindings "))),
+        area,
+    );
+}
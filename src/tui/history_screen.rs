@@ -0,0 +1,229 @@
+use std::io;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, ListState};
+use ratatui::Frame;
+
+use crate::history::RunRecord;
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::details_panel::render_details;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::list_filter::ListFilter;
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{page_size, read_event, render_scrollbar, Tui};
+
+const KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("PgUp, PgDn, Home, End", "Move the cursor by pages"),
+    (
+        "/",
+        "Filter by date or playlist name, Esc clears the filter",
+    ),
+    ("{quit}, Esc", "Go back"),
+];
+
+/// Browses the previous runs and the playlists they created, newest first.
+#[derive(Debug)]
+pub struct HistoryScreen {
+    records: Vec<RunRecord>,
+    state: ListState,
+    page_size: usize,
+    show_help: bool,
+    filter: ListFilter,
+    /// Indices of the runs matching the filter; the cursor moves over these.
+    visible: Vec<usize>,
+}
+
+impl HistoryScreen {
+    /// `records` are expected oldest first, as they are stored.
+    pub fn new(mut records: Vec<RunRecord>) -> Self {
+        records.reverse();
+        let selected = if records.is_empty() { None } else { Some(0) };
+        HistoryScreen {
+            visible: (0..records.len()).collect(),
+            records,
+            state: ListState::default().with_selected(selected),
+            page_size: 1,
+            show_help: false,
+            filter: ListFilter::default(),
+        }
+    }
+
+    pub fn run(mut self, terminal: &mut Tui) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            if self.handle_events()? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let area = render_footer(frame);
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|index| {
+                let record = &self.records[*index];
+                let created = record
+                    .reports
+                    .iter()
+                    .filter(|report| report.result.is_ok())
+                    .count();
+                ListItem::new(tr!(
+                    "{} ({}) - {} of {} actions succeeded",
+                    record.started_at.format("%Y-%m-%d %H:%M"),
+                    record.age(),
+                    created,
+                    record.reports.len()
+                ))
+            })
+            .collect();
+        let mut block = theme::block()
+            .title(t(" Spautofy - Run History "))
+            .title_bottom(Line::from(expand(" BACK WITH '{quit}' ", true)))
+            .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+        if let Some(filter) = self.filter.title() {
+            block = block.title(filter.right_aligned());
+        }
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme::highlight_style());
+        let [list_area, details_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(area);
+        self.page_size = page_size(list_area);
+        frame.render_stateful_widget(list, list_area, &mut self.state);
+        render_details(frame, details_area, self.details());
+        render_scrollbar(
+            frame,
+            list_area,
+            self.visible.len(),
+            self.state.selected().unwrap_or(0),
+        );
+        if self.show_help {
+            render_help(frame, KEYBINDINGS);
+        }
+    }
+
+    fn details(&self) -> Vec<Line<'static>> {
+        let Some(record) = self.highlighted().map(|index| &self.records[index]) else {
+            return vec![Line::from(t("No runs recorded yet."))];
+        };
+        let mut lines = vec![
+            Line::from(Span::styled(
+                tr!("Run of {}", record.started_at.format("%Y-%m-%d %H:%M:%S")),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+        for report in &record.reports {
+            lines.push(Line::from(Span::styled(
+                report.action.clone(),
+                Style::default().add_modifier(Modifier::UNDERLINED),
+            )));
+            match &report.result {
+                Ok(playlist) => {
+                    lines.push(Line::from(tr!(
+                        "{} ({} tracks)",
+                        playlist.name,
+                        playlist.track_count
+                    )));
+                    lines.push(Line::from(playlist.url.clone()));
+                }
+                Err(err) => lines.push(Line::from(Span::styled(
+                    tr!("failed: {}", err),
+                    theme::error_style(),
+                ))),
+            }
+            lines.push(Line::from(""));
+        }
+        lines
+    }
+
+    /// Index of the highlighted run in `records`.
+    fn highlighted(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|position| self.visible.get(position))
+            .copied()
+    }
+
+    fn apply_filter(&mut self) {
+        let entries: Vec<String> = self
+            .records
+            .iter()
+            .map(|record| {
+                let names: Vec<&str> = record
+                    .reports
+                    .iter()
+                    .filter_map(|report| report.result.as_ref().ok())
+                    .map(|playlist| playlist.name.as_str())
+                    .collect();
+                format!(
+                    "{} {}",
+                    record.started_at.format("%Y-%m-%d"),
+                    names.join(" ")
+                )
+            })
+            .collect();
+        self.visible = self.filter.visible(entries.iter().map(String::as_str));
+        let selected = if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.state.select(selected);
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.visible.len().saturating_sub(1);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    /// Returns whether the screen should be closed.
+    fn handle_events(&mut self) -> io::Result<bool> {
+        let Some(Event::Key(key)) = read_event()? else {
+            return Ok(false);
+        };
+        if key.kind != KeyEventKind::Press {
+            return Ok(false);
+        }
+        if self.show_help {
+            self.show_help = false;
+            return Ok(false);
+        }
+        if self.filter.handle_key(key.code) {
+            self.apply_filter();
+            return Ok(false);
+        }
+        match key.code {
+            code if is_help_key(code) => self.show_help = true,
+            code if is_bound(code, KeyAction::MoveUp) => self.move_cursor(-1),
+            code if is_bound(code, KeyAction::MoveDown) => self.move_cursor(1),
+            code if is_bound(code, KeyAction::Quit) => return Ok(true),
+            KeyCode::PageUp => self.move_cursor(-(self.page_size as isize)),
+            KeyCode::PageDown => self.move_cursor(self.page_size as isize),
+            KeyCode::Home => self.move_cursor(isize::MIN),
+            KeyCode::End => self.move_cursor(isize::MAX),
+            KeyCode::Char('/') => self.filter.start(),
+            KeyCode::Esc if self.filter.is_active() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Esc => return Ok(true),
+            _ => {}
+        }
+        Ok(false)
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::OnceLock;
+
+use ratatui::crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::t;
+use crate::tr;
+
+static KEYMAP: OnceLock<Keymap> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    MoveUp,
+    MoveDown,
+    Select,
+    Confirm,
+    Quit,
+}
+
+/// The `keybindings` section of the config file; unset actions keep their default keys.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct KeybindingsConfig {
+    pub move_up: Option<Vec<String>>,
+    pub move_down: Option<Vec<String>>,
+    pub select: Option<Vec<String>>,
+    pub confirm: Option<Vec<String>>,
+    pub quit: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    pub move_up: Vec<KeyCode>,
+    pub move_down: Vec<KeyCode>,
+    pub select: Vec<KeyCode>,
+    pub confirm: Vec<KeyCode>,
+    pub quit: Vec<KeyCode>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            move_up: vec![KeyCode::Up, KeyCode::Char('k')],
+            move_down: vec![KeyCode::Down, KeyCode::Char('j')],
+            select: vec![KeyCode::Enter],
+            confirm: vec![KeyCode::Char(' ')],
+            quit: vec![KeyCode::Char('q')],
+        }
+    }
+}
+
+fn parse_key(name: &str) -> Result<KeyCode, String> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(KeyCode::Char(c));
+    }
+    let code = match name.to_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "space" => KeyCode::Char(' '),
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(n @ 1..=12) => KeyCode::F(n),
+            _ => return Err(tr!("unknown key \"{}\"", name)),
+        },
+    };
+    Ok(code)
+}
+
+fn parse_keys(names: &Option<Vec<String>>, default: Vec<KeyCode>) -> Result<Vec<KeyCode>, String> {
+    match names {
+        Some(names) if names.is_empty() => Err(tr!("every action needs at least one key")),
+        Some(names) => names.iter().map(|name| parse_key(name)).collect(),
+        None => Ok(default),
+    }
+}
+
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        code => code.to_string(),
+    }
+}
+
+impl TryFrom<&KeybindingsConfig> for Keymap {
+    type Error = String;
+
+    fn try_from(config: &KeybindingsConfig) -> Result<Self, Self::Error> {
+        let default = Keymap::default();
+        Ok(Keymap {
+            move_up: parse_keys(&config.move_up, default.move_up)?,
+            move_down: parse_keys(&config.move_down, default.move_down)?,
+            select: parse_keys(&config.select, default.select)?,
+            confirm: parse_keys(&config.confirm, default.confirm)?,
+            quit: parse_keys(&config.quit, default.quit)?,
+        })
+    }
+}
+
+impl Keymap {
+    fn keys(&self, action: KeyAction) -> &[KeyCode] {
+        match action {
+            KeyAction::MoveUp => &self.move_up,
+            KeyAction::MoveDown => &self.move_down,
+            KeyAction::Select => &self.select,
+            KeyAction::Confirm => &self.confirm,
+            KeyAction::Quit => &self.quit,
+        }
+    }
+}
+
+/// Sets the keymap used by all screens; only the first call has an effect.
+pub fn set_keymap(keymap: Keymap) {
+    let _ = KEYMAP.set(keymap);
+}
+
+fn current() -> &'static Keymap {
+    KEYMAP.get_or_init(Keymap::default)
+}
+
+pub fn is_bound(code: KeyCode, action: KeyAction) -> bool {
+    current().keys(action).contains(&code)
+}
+
+/// Translates the template and replaces `{up}`, `{down}`, `{select}`, `{confirm}` and `{quit}`
+/// with the bound keys. Hints only name the first key, in upper case.
+pub fn expand(template: &str, hint: bool) -> String {
+    let keymap = current();
+    let mut expanded = t(template).to_string();
+    for (placeholder, action) in [
+        ("{up}", KeyAction::MoveUp),
+        ("{down}", KeyAction::MoveDown),
+        ("{select}", KeyAction::Select),
+        ("{confirm}", KeyAction::Confirm),
+        ("{quit}", KeyAction::Quit),
+    ] {
+        let keys = keymap.keys(action);
+        let names = if hint {
+            key_name(keys[0]).to_uppercase()
+        } else {
+            keys.iter()
+                .map(|code| key_name(*code))
+                .collect::<Vec<_>>()
+                .join("/")
+        };
+        expanded = expanded.replace(placeholder, &names);
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_characters_are_their_key() {
+        assert_eq!(parse_key("k"), Ok(KeyCode::Char('k')));
+        assert_eq!(parse_key("K"), Ok(KeyCode::Char('K')));
+        assert_eq!(parse_key("ü"), Ok(KeyCode::Char('ü')));
+    }
+
+    #[test]
+    fn names_ignore_the_case() {
+        assert_eq!(parse_key("Enter"), Ok(KeyCode::Enter));
+        assert_eq!(parse_key("SPACE"), Ok(KeyCode::Char(' ')));
+        assert_eq!(parse_key("pageDown"), Ok(KeyCode::PageDown));
+    }
+
+    #[test]
+    fn function_keys_go_up_to_12() {
+        assert_eq!(parse_key("F1"), Ok(KeyCode::F(1)));
+        assert_eq!(parse_key("f12"), Ok(KeyCode::F(12)));
+        assert!(parse_key("f0").is_err());
+        assert!(parse_key("f13").is_err());
+    }
+
+    #[test]
+    fn unknown_names_are_errors() {
+        assert!(parse_key("").is_err());
+        assert!(parse_key("ctrl").is_err());
+    }
+
+    #[test]
+    fn an_action_needs_a_key() {
+        let default = vec![KeyCode::Enter];
+        assert_eq!(parse_keys(&None, default.clone()), Ok(default.clone()));
+        assert!(parse_keys(&Some(Vec::new()), default.clone()).is_err());
+        let keys = Some(vec!["x".to_string(), "esc".to_string()]);
+        assert_eq!(
+            parse_keys(&keys, default),
+            Ok(vec![KeyCode::Char('x'), KeyCode::Esc])
+        );
+    }
+}
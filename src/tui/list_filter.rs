@@ -0,0 +1,62 @@
+use ratatui::crossterm::event::KeyCode;
+use ratatui::text::Line;
+
+use crate::song_list::fuzzy_matches;
+
+/// The '/' filter mode of a list, narrowing the visible entries while typing.
+#[derive(Debug, Default)]
+pub struct ListFilter {
+    query: String,
+    editing: bool,
+}
+
+impl ListFilter {
+    pub fn start(&mut self) {
+        self.editing = true;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.editing || !self.query.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.editing = false;
+    }
+
+    /// Returns whether the key was used for editing the filter.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        if !self.editing {
+            return false;
+        }
+        match code {
+            KeyCode::Esc => self.clear(),
+            KeyCode::Enter => self.editing = false,
+            KeyCode::Backspace => {
+                self.query.pop();
+            }
+            KeyCode::Char(c) => self.query.push(c),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Indices of the entries matching the filter, in their original order.
+    pub fn visible<'a>(&self, entries: impl IntoIterator<Item = &'a str>) -> Vec<usize> {
+        entries
+            .into_iter()
+            .enumerate()
+            .filter(|(_, entry)| fuzzy_matches(&self.query, entry))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The filter line shown at the top of the list.
+    pub fn title(&self) -> Option<Line<'static>> {
+        if !self.is_active() {
+            return None;
+        }
+        let cursor = if self.editing { "_" } else { "" };
+        Some(Line::from(format!(" /{}{} ", self.query, cursor)))
+    }
+}
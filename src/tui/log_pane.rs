@@ -0,0 +1,43 @@
+use ratatui::crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+
+use crate::status_log;
+use crate::tr;
+use crate::tui::theme;
+
+#[derive(Debug, Default)]
+pub struct LogPane {
+    /// Number of lines scrolled up from the newest message.
+    scroll: usize,
+}
+
+impl LogPane {
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let messages = status_log::messages();
+        let visible = area.height.saturating_sub(2) as usize;
+        let end = messages.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(visible);
+        let title = if self.scroll > 0 {
+            tr!(" Log (scrolled up {} lines) ", self.scroll)
+        } else {
+            tr!(" Log ")
+        };
+        let paragraph = Paragraph::new(messages[start..end].join("\n"))
+            .block(theme::block().title(title))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, area);
+    }
+
+    /// Returns whether the key was used for scrolling.
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        let total = status_log::messages().len();
+        match code {
+            KeyCode::PageUp => self.scroll = (self.scroll + 5).min(total.saturating_sub(1)),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(5),
+            _ => return false,
+        }
+        true
+    }
+}
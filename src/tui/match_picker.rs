@@ -0,0 +1,227 @@
+use std::io;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::layout::Constraint;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Row, Table, TableState};
+use ratatui::Frame;
+
+use crate::i18n::t;
+use crate::song_list::SongMatch;
+use crate::tr;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{page_size, read_event, render_scrollbar, Tui};
+
+const KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("PgUp, PgDn, Home, End", "Move the cursor by pages"),
+    ("Left, Right", "Show the other tracks found for the song"),
+    ("{select}", "Add or leave out the highlighted song"),
+    ("{confirm}", "Create the playlist"),
+    ("{quit}, Esc", "Cancel"),
+];
+
+/// Lets the user choose the tracks of the songs `spautofy import-songs` or `scan-library`
+/// is unsure about; they start left out unless a track was sure enough after all.
+#[derive(Debug)]
+pub struct MatchPicker {
+    matches: Vec<SongMatch>,
+    /// The header of the songs as written, like "Line" or "File".
+    source: &'static str,
+    /// Indices of the matches to review; the cursor moves over these.
+    unsure: Vec<usize>,
+    /// The track shown for every match, also while it is left out.
+    shown: Vec<usize>,
+    state: TableState,
+    page_size: usize,
+    show_help: bool,
+}
+
+enum PickerEvent {
+    Confirm,
+    Cancel,
+}
+
+impl MatchPicker {
+    pub fn new(matches: Vec<SongMatch>, unsure: Vec<usize>, source: &'static str) -> Self {
+        let selected = if unsure.is_empty() { None } else { Some(0) };
+        MatchPicker {
+            shown: matches
+                .iter()
+                .map(|song_match| song_match.choice.unwrap_or(0))
+                .collect(),
+            matches,
+            source,
+            unsure,
+            state: TableState::default().with_selected(selected),
+            page_size: 1,
+            show_help: false,
+        }
+    }
+
+    /// Returns the matches with the tracks chosen, or `None` if the user cancelled.
+    pub fn run(mut self, terminal: &mut Tui) -> io::Result<Option<Vec<SongMatch>>> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            match self.handle_events()? {
+                Some(PickerEvent::Confirm) => return Ok(Some(self.matches)),
+                Some(PickerEvent::Cancel) => return Ok(None),
+                None => {}
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let area = render_footer(frame);
+        let rows = self.unsure.iter().map(|index| {
+            let song_match = &self.matches[*index];
+            let shown = self.shown[*index];
+            let chosen = song_match.choice.is_some();
+            let (track, confidence) = match song_match.candidates.get(shown) {
+                Some(candidate) => (
+                    format!(
+                        "{} ({}/{})",
+                        tr!(
+                            "{} by {}",
+                            candidate.track.name,
+                            candidate.track.artist_names()
+                        ),
+                        shown + 1,
+                        song_match.candidates.len()
+                    ),
+                    format!("{:.0}%", candidate.confidence * 100.0),
+                ),
+                None => (t("Nothing found").to_string(), String::new()),
+            };
+            let row = Row::new(vec![
+                if chosen { "[x]" } else { "[ ]" }.to_string(),
+                song_match.song.text.clone(),
+                track,
+                confidence,
+            ]);
+            if chosen {
+                row
+            } else {
+                row.style(theme::dimmed_style())
+            }
+        });
+        let header = Row::new(vec!["", t(self.source), t("Track"), t("Confidence")])
+            .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+        let chosen = self
+            .unsure
+            .iter()
+            .filter(|index| self.matches[**index].choice.is_some())
+            .count();
+        let block = theme::block()
+            .title(t(" Spautofy - Review Matches "))
+            .title_bottom(Line::from(format!(
+                "{}{}",
+                tr!(" {} OF {} UNSURE ADDED", chosen, self.unsure.len()),
+                expand(
+                    " | OTHER TRACK WITH LEFT/RIGHT | ADD WITH '{select}' | CREATE WITH \
+                    '{confirm}' | CANCEL WITH '{quit}' ",
+                    true
+                )
+            )))
+            .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .row_highlight_style(theme::highlight_style());
+        self.page_size = page_size(area).saturating_sub(1).max(1);
+        frame.render_stateful_widget(table, area, &mut self.state);
+        render_scrollbar(
+            frame,
+            area,
+            self.unsure.len() + 1,
+            self.state.selected().unwrap_or(0),
+        );
+        if self.show_help {
+            render_help(frame, KEYBINDINGS);
+        }
+    }
+
+    /// Index of the highlighted match in `matches`.
+    fn highlighted(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|position| self.unsure.get(position))
+            .copied()
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.unsure.len().saturating_sub(1);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    /// Shows another track for the highlighted song and chooses it.
+    fn show_other(&mut self, delta: isize) {
+        let Some(index) = self.highlighted() else {
+            return;
+        };
+        let Some(last) = self.matches[index].candidates.len().checked_sub(1) else {
+            return;
+        };
+        let shown = self.shown[index].saturating_add_signed(delta).min(last);
+        self.shown[index] = shown;
+        self.matches[index].choice = Some(shown);
+    }
+
+    fn toggle(&mut self) {
+        let Some(index) = self.highlighted() else {
+            return;
+        };
+        let song_match = &mut self.matches[index];
+        song_match.choice = match song_match.choice {
+            Some(_) => None,
+            None if song_match.candidates.is_empty() => None,
+            None => Some(self.shown[index]),
+        };
+    }
+
+    fn handle_events(&mut self) -> io::Result<Option<PickerEvent>> {
+        let Some(Event::Key(key)) = read_event()? else {
+            return Ok(None);
+        };
+        if key.kind != KeyEventKind::Press {
+            return Ok(None);
+        }
+        if self.show_help {
+            self.show_help = false;
+            return Ok(None);
+        }
+        match key.code {
+            code if is_help_key(code) => self.show_help = true,
+            code if is_bound(code, KeyAction::MoveUp) => self.move_cursor(-1),
+            code if is_bound(code, KeyAction::MoveDown) => self.move_cursor(1),
+            code if is_bound(code, KeyAction::Select) => self.toggle(),
+            code if is_bound(code, KeyAction::Confirm) => return Ok(Some(PickerEvent::Confirm)),
+            code if is_bound(code, KeyAction::Quit) => return Ok(Some(PickerEvent::Cancel)),
+            KeyCode::Left => self.show_other(-1),
+            KeyCode::Right => self.show_other(1),
+            KeyCode::PageUp => self.move_cursor(-(self.page_size as isize)),
+            KeyCode::PageDown => self.move_cursor(self.page_size as isize),
+            KeyCode::Home => self.move_cursor(isize::MIN),
+            KeyCode::End => self.move_cursor(isize::MAX),
+            KeyCode::Esc => return Ok(Some(PickerEvent::Cancel)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
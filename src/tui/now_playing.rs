@@ -0,0 +1,89 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::text::Span;
+use ratatui::widgets::LineGauge;
+use ratatui::Frame;
+use tokio::task::JoinHandle;
+
+use crate::authorize::Access;
+use crate::models::track::format_duration;
+use crate::player::{get_currently_playing, CurrentlyPlaying};
+use crate::tui::theme;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+struct NowPlaying {
+    access: Option<Access>,
+    /// The last response and when it was received.
+    current: Option<(CurrentlyPlaying, Instant)>,
+}
+
+static NOW_PLAYING: Mutex<NowPlaying> = Mutex::new(NowPlaying {
+    access: None,
+    current: None,
+});
+
+/// Sets the access used for polling, call it again after every refresh.
+pub fn set_access(access: &Access) {
+    NOW_PLAYING.lock().unwrap().access = Some(access.clone());
+}
+
+/// Polls the currently playing track until the returned task is aborted.
+pub fn spawn_poller() -> JoinHandle<()> {
+    tokio::spawn(async {
+        loop {
+            let access = NOW_PLAYING.lock().unwrap().access.clone();
+            if let Some(access) = access {
+                // errors are not worth interrupting the user for, the footer just disappears
+                let current = get_currently_playing(&access).await.ok().flatten();
+                NOW_PLAYING.lock().unwrap().current =
+                    current.map(|current| (current, Instant::now()));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    })
+}
+
+/// Renders the now-playing footer if something is playing; returns the area left for the screen.
+pub fn render_footer(frame: &mut Frame) -> Rect {
+    let now_playing = NOW_PLAYING.lock().unwrap();
+    let Some((current, received_at)) = &now_playing.current else {
+        return frame.area();
+    };
+    let Some(track) = &current.item else {
+        return frame.area();
+    };
+    let mut progress_ms = current.progress_ms.unwrap_or_default();
+    if current.is_playing {
+        progress_ms += received_at.elapsed().as_millis() as u64;
+    }
+    let progress_ms = progress_ms.min(track.duration_ms);
+
+    let artists: Vec<&str> = track.artists.iter().map(|a| a.name.as_str()).collect();
+    let symbol = if current.is_playing { "▶" } else { "⏸" };
+    let label = format!(
+        " {} {} - {}  {} / {} ",
+        symbol,
+        track.name,
+        artists.join(", "),
+        format_duration(progress_ms),
+        format_duration(track.duration_ms)
+    );
+    let ratio = if track.duration_ms == 0 {
+        0.0
+    } else {
+        progress_ms as f64 / track.duration_ms as f64
+    };
+    let gauge = LineGauge::default()
+        .label(Span::raw(label))
+        .filled_style(theme::success_style())
+        .unfilled_style(theme::dimmed_style())
+        .ratio(ratio);
+    let [screen_area, footer_area] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+    frame.render_widget(gauge, footer_area);
+    screen_area
+}
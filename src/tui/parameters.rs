@@ -0,0 +1,293 @@
+use std::io;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, ListState};
+use ratatui::Frame;
+
+use crate::actions::parameters::{ActionParameters, ParameterValue};
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{read_event, ScreenOutcome, Tui};
+
+const KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move between parameters"),
+    (
+        "←/h, →/l",
+        "Change the highlighted value, move between options",
+    ),
+    (
+        "{select}",
+        "Edit text and numbers, pick playlists, toggle options, change other values",
+    ),
+    ("Enter, Esc (editing)", "Finish editing"),
+    ("v", "Preview the tracks of the highlighted action"),
+    ("{confirm}", "Continue"),
+    ("Esc, Backspace", "Go back"),
+    ("{quit}", "Quit"),
+];
+
+/// How the value is shown in the lists of parameters.
+pub fn display_value(value: &ParameterValue) -> Span<'static> {
+    match value {
+        ParameterValue::Text { value, placeholder } if value.is_empty() => {
+            Span::styled(placeholder.clone(), theme::dimmed_style())
+        }
+        ParameterValue::Text { value, .. } => Span::raw(value.clone()),
+        ParameterValue::Number { value, .. } => Span::raw(value.to_string()),
+        ParameterValue::Toggle(value) => Span::raw(t(if *value { "yes" } else { "no" })),
+        ParameterValue::Choice { options, index } => Span::raw(format!("< {} >", options[*index])),
+        ParameterValue::MultiChoice {
+            options, selected, ..
+        } => {
+            let chosen: Vec<&str> = options
+                .iter()
+                .zip(selected)
+                .filter_map(|(option, selected)| selected.then_some(option.as_str()))
+                .collect();
+            if chosen.is_empty() {
+                Span::styled(t("none"), theme::dimmed_style())
+            } else {
+                Span::raw(chosen.join(", "))
+            }
+        }
+        ParameterValue::Playlist(Some(target)) => Span::raw(target.name.clone()),
+        ParameterValue::Playlist(None) => {
+            Span::styled(t("create a new playlist"), theme::dimmed_style())
+        }
+    }
+}
+
+/// Like `display_value`, but shows every option of a multi-choice with the cursor underlined.
+fn display_highlighted(value: &ParameterValue) -> Vec<Span<'static>> {
+    let ParameterValue::MultiChoice {
+        options,
+        selected,
+        cursor,
+    } = value
+    else {
+        return vec![display_value(value)];
+    };
+    options
+        .iter()
+        .zip(selected)
+        .enumerate()
+        .flat_map(|(index, (option, selected))| {
+            let checkbox = if *selected { "[x]" } else { "[ ]" };
+            let label = Span::raw(format!("{} {}", checkbox, option));
+            let label = if index == *cursor {
+                label.style(Style::default().add_modifier(Modifier::UNDERLINED))
+            } else {
+                label
+            };
+            [label, Span::raw("  ")]
+        })
+        .collect()
+}
+
+/// Description and current parameter values of the action, shown in the details panel.
+pub fn action_details(action: &ActionParameters) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(Span::styled(
+            action.title.clone(),
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(action.description.clone()),
+        Line::from(""),
+    ];
+    lines.extend(action.parameters.iter().map(|parameter| {
+        Line::from(vec![
+            Span::raw(format!("{}: ", t(parameter.label))),
+            display_value(&parameter.value),
+        ])
+    }));
+    lines
+}
+
+#[derive(Debug)]
+pub struct ParameterScreen {
+    title: String,
+    notice: Option<String>,
+    actions: Vec<ActionParameters>,
+    /// (action index, parameter index) of every editable row, in display order.
+    rows: Vec<(usize, usize)>,
+    cursor: usize,
+    editing: bool,
+    show_help: bool,
+}
+
+impl ParameterScreen {
+    pub fn new(actions: Vec<ActionParameters>) -> Self {
+        let rows = actions
+            .iter()
+            .enumerate()
+            .flat_map(|(a, action)| (0..action.parameters.len()).map(move |p| (a, p)))
+            .collect();
+        ParameterScreen {
+            title: tr!(" Spautofy - Action Parameters "),
+            notice: None,
+            actions,
+            rows,
+            cursor: 0,
+            editing: false,
+            show_help: false,
+        }
+    }
+
+    pub fn with_title(mut self, title: &str) -> Self {
+        self.title = format!(" {} ", t(title));
+        self
+    }
+
+    /// Shows a message (e.g. a validation error) above the parameters.
+    pub fn with_notice(mut self, notice: String) -> Self {
+        self.notice = Some(notice);
+        self
+    }
+
+    pub fn run(&mut self, terminal: &mut Tui) -> io::Result<ScreenOutcome> {
+        if self.rows.is_empty() {
+            return Ok(ScreenOutcome::Confirm);
+        }
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            if let Some(outcome) = self.handle_events()? {
+                return Ok(outcome);
+            }
+        }
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn with_cursor(mut self, cursor: usize) -> Self {
+        self.cursor = cursor.min(self.rows.len().saturating_sub(1));
+        self
+    }
+
+    pub fn into_actions(self) -> Vec<ActionParameters> {
+        self.actions
+    }
+
+    fn current(&mut self) -> &mut ParameterValue {
+        let (action, parameter) = self.rows[self.cursor];
+        &mut self.actions[action].parameters[parameter].value
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let area = render_footer(frame);
+        let mut items = Vec::new();
+        if let Some(notice) = &self.notice {
+            items.push(ListItem::new(Line::from(Span::styled(
+                notice.clone(),
+                theme::error_style(),
+            ))));
+        }
+        let mut highlighted = None;
+        for (a, action) in self.actions.iter().enumerate() {
+            items.push(ListItem::new(Line::from(Span::styled(
+                action.title.clone(),
+                Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            ))));
+            for (p, parameter) in action.parameters.iter().enumerate() {
+                if self.rows[self.cursor] == (a, p) {
+                    highlighted = Some(items.len());
+                }
+                let mut spans = vec![Span::raw(format!("  {}: ", t(parameter.label)))];
+                if self.rows[self.cursor] == (a, p) {
+                    spans.extend(display_highlighted(&parameter.value));
+                } else {
+                    spans.push(display_value(&parameter.value));
+                }
+                if self.editing && self.rows[self.cursor] == (a, p) {
+                    spans.push(Span::raw("_"));
+                }
+                items.push(ListItem::new(Line::from(spans)));
+            }
+        }
+        let hint = if self.editing {
+            " TYPE TO EDIT | FINISH WITH 'ENTER' "
+        } else {
+            " CHANGE WITH '←'/'→' OR '{select}' | NEXT WITH '{confirm}' | BACK WITH 'ESC' "
+        };
+        let hint = expand(hint, true);
+        let block = theme::block()
+            .title(self.title.as_str())
+            .title_bottom(Line::from(hint))
+            .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(theme::highlight_style());
+        let mut state = ListState::default().with_selected(highlighted);
+        frame.render_stateful_widget(list, area, &mut state);
+        if self.show_help {
+            render_help(frame, KEYBINDINGS);
+        }
+    }
+
+    fn handle_events(&mut self) -> io::Result<Option<ScreenOutcome>> {
+        let Some(Event::Key(key)) = read_event()? else {
+            return Ok(None);
+        };
+        if key.kind != KeyEventKind::Press {
+            return Ok(None);
+        }
+        if self.editing {
+            match key.code {
+                KeyCode::Enter | KeyCode::Esc => {
+                    self.current().finish_editing();
+                    self.editing = false;
+                }
+                KeyCode::Backspace => self.current().pop_char(),
+                KeyCode::Char(c) => self.current().push_char(c),
+                _ => {}
+            }
+            return Ok(None);
+        }
+        if self.show_help {
+            self.show_help = false;
+            return Ok(None);
+        }
+        match key.code {
+            code if is_help_key(code) => self.show_help = true,
+            code if is_bound(code, KeyAction::MoveUp) => {
+                self.cursor = self.cursor.saturating_sub(1)
+            }
+            code if is_bound(code, KeyAction::MoveDown) => {
+                self.cursor = (self.cursor + 1).min(self.rows.len() - 1)
+            }
+            code if is_bound(code, KeyAction::Select) => {
+                if let ParameterValue::Playlist(_) = self.current() {
+                    let (action, _) = self.rows[self.cursor];
+                    return Ok(Some(ScreenOutcome::PickPlaylist(action)));
+                } else if let ParameterValue::MultiChoice {
+                    selected, cursor, ..
+                } = self.current()
+                {
+                    selected[*cursor] = !selected[*cursor];
+                } else if self.current().is_editable_text() {
+                    self.editing = true;
+                } else {
+                    self.current().step(true);
+                }
+            }
+            code if is_bound(code, KeyAction::Confirm) => return Ok(Some(ScreenOutcome::Confirm)),
+            code if is_bound(code, KeyAction::Quit) => return Ok(Some(ScreenOutcome::Quit)),
+            KeyCode::Left | KeyCode::Char('h') => self.current().step(false),
+            KeyCode::Right | KeyCode::Char('l') => self.current().step(true),
+            KeyCode::Char('v') => {
+                let (action, _) = self.rows[self.cursor];
+                return Ok(Some(ScreenOutcome::Preview(action)));
+            }
+            KeyCode::Esc | KeyCode::Backspace => return Ok(Some(ScreenOutcome::Back)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,361 @@
+use std::io;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Row, Table, TableState};
+use ratatui::Frame;
+
+use crate::i18n::t;
+use crate::models::playlist::SimplifiedPlaylist;
+use crate::tr;
+use crate::tui::confirm_dialog::ConfirmDialog;
+use crate::tui::details_panel::render_details;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::list_filter::ListFilter;
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{page_size, read_event, render_scrollbar, Tui};
+
+const KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("PgUp, PgDn, Home, End", "Move the cursor by pages"),
+    ("{select}", "Pick the highlighted playlist"),
+    ("/", "Filter the playlists, Esc clears the filter"),
+    ("{quit}, Esc", "Go back"),
+];
+
+const TARGET_KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("PgUp, PgDn, Home, End", "Move the cursor by pages"),
+    ("{select}", "Pick the highlighted playlist"),
+    (
+        "o",
+        "Show all playlists or only the ones created by Spautofy",
+    ),
+    ("/", "Filter the playlists, Esc clears the filter"),
+    ("{quit}, Esc", "Go back"),
+];
+
+const DELETION_KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("PgUp, PgDn, Home, End", "Move the cursor by pages"),
+    ("x", "Mark the highlighted playlist for deletion"),
+    ("d", "Delete the marked or the highlighted playlists"),
+    ("/", "Filter the playlists, Esc clears the filter"),
+    ("{quit}, Esc", "Go back"),
+];
+
+#[derive(Debug)]
+pub enum BrowserOutcome {
+    Picked(Box<SimplifiedPlaylist>),
+    /// The user confirmed deleting these playlists.
+    Delete(Vec<SimplifiedPlaylist>),
+    Back,
+}
+
+enum BrowserEvent {
+    Picked(usize),
+    Delete,
+    Back,
+}
+
+#[derive(Debug)]
+pub struct PlaylistBrowser {
+    title: String,
+    playlists: Vec<SimplifiedPlaylist>,
+    state: TableState,
+    page_size: usize,
+    show_help: bool,
+    /// Set if playlists can be marked and deleted instead of picked.
+    marked: Option<Vec<bool>>,
+    confirm: Option<ConfirmDialog>,
+    /// Set if the list can be narrowed to the playlists Spautofy created for the user.
+    spautofy_owned: Option<Vec<bool>>,
+    show_all: bool,
+    filter: ListFilter,
+    /// Indices of the playlists matching the filter; the cursor moves over these.
+    visible: Vec<usize>,
+}
+
+impl PlaylistBrowser {
+    pub fn new(title: &str, playlists: Vec<SimplifiedPlaylist>) -> Self {
+        let selected = if playlists.is_empty() { None } else { Some(0) };
+        PlaylistBrowser {
+            title: format!(" {} ", t(title)),
+            visible: (0..playlists.len()).collect(),
+            playlists,
+            state: TableState::default().with_selected(selected),
+            page_size: 1,
+            show_help: false,
+            marked: None,
+            confirm: None,
+            spautofy_owned: None,
+            show_all: false,
+            filter: ListFilter::default(),
+        }
+    }
+
+    pub fn with_deletion(mut self) -> Self {
+        self.marked = Some(vec![false; self.playlists.len()]);
+        self
+    }
+
+    /// Shows only the playlists Spautofy created for the user until 'o' is pressed.
+    pub fn with_spautofy_filter(mut self, user_id: &str) -> Self {
+        self.spautofy_owned = Some(
+            self.playlists
+                .iter()
+                .map(|playlist| playlist.is_spautofy_owned(user_id))
+                .collect(),
+        );
+        self.apply_filter();
+        self
+    }
+
+    pub fn run(mut self, terminal: &mut Tui) -> io::Result<BrowserOutcome> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            match self.handle_events()? {
+                Some(BrowserEvent::Picked(index)) => {
+                    return Ok(BrowserOutcome::Picked(Box::new(
+                        self.playlists.swap_remove(index),
+                    )))
+                }
+                Some(BrowserEvent::Delete) => {
+                    let indices = self.to_delete();
+                    let mut playlists: Vec<Option<SimplifiedPlaylist>> =
+                        self.playlists.into_iter().map(Some).collect();
+                    let deleted = indices
+                        .into_iter()
+                        .filter_map(|index| playlists[index].take())
+                        .collect();
+                    return Ok(BrowserOutcome::Delete(deleted));
+                }
+                Some(BrowserEvent::Back) => return Ok(BrowserOutcome::Back),
+                None => {}
+            }
+        }
+    }
+
+    /// The marked playlists, or the highlighted one if none are marked.
+    fn to_delete(&self) -> Vec<usize> {
+        let marked: Vec<usize> = self
+            .marked
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(index, marked)| marked.then_some(index))
+            .collect();
+        if marked.is_empty() {
+            self.highlighted().into_iter().collect()
+        } else {
+            marked
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let area = render_footer(frame);
+        let rows = self.visible.iter().map(|index| {
+            let playlist = &self.playlists[*index];
+            let owner = playlist
+                .owner
+                .display_name
+                .clone()
+                .unwrap_or_else(|| playlist.owner.id.clone());
+            let name = match &self.marked {
+                Some(marked) if marked[*index] => format!("[x] {}", playlist.name),
+                Some(_) => format!("[ ] {}", playlist.name),
+                None => playlist.name.clone(),
+            };
+            Row::new(vec![name, playlist.tracks.total.to_string(), owner])
+        });
+        let header = Row::new(vec![t("Name"), t("Tracks"), t("Owner")])
+            .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+        let hint = if self.marked.is_some() {
+            " MARK WITH 'X' | DELETE WITH 'D' | BACK WITH '{quit}' "
+        } else if self.spautofy_owned.is_some() && self.show_all {
+            " PICK WITH '{select}' | ONLY SPAUTOFY PLAYLISTS WITH 'O' | BACK WITH '{quit}' "
+        } else if self.spautofy_owned.is_some() {
+            " PICK WITH '{select}' | ALL PLAYLISTS WITH 'O' | BACK WITH '{quit}' "
+        } else {
+            " PICK WITH '{select}' | BACK WITH '{quit}' "
+        };
+        let hint = expand(hint, true);
+        let mut block = theme::block()
+            .title(self.title.as_str())
+            .title_bottom(Line::from(hint))
+            .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+        if let Some(filter) = self.filter.title() {
+            block = block.title(filter.right_aligned());
+        }
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(8),
+                Constraint::Length(24),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .row_highlight_style(theme::highlight_style());
+        let [area, details_area] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(area);
+        // the header row takes up one more line
+        self.page_size = page_size(area).saturating_sub(1).max(1);
+        frame.render_stateful_widget(table, area, &mut self.state);
+        render_details(frame, details_area, self.details());
+        render_scrollbar(
+            frame,
+            area,
+            self.visible.len() + 1,
+            self.state.selected().unwrap_or(0),
+        );
+        if let Some(confirm) = &self.confirm {
+            confirm.render(frame);
+        }
+        if self.show_help {
+            let keybindings = if self.marked.is_some() {
+                DELETION_KEYBINDINGS
+            } else if self.spautofy_owned.is_some() {
+                TARGET_KEYBINDINGS
+            } else {
+                KEYBINDINGS
+            };
+            render_help(frame, keybindings);
+        }
+    }
+
+    fn details(&self) -> Vec<Line<'static>> {
+        let Some(playlist) = self.highlighted().map(|index| &self.playlists[index]) else {
+            return Vec::new();
+        };
+        let owner = playlist.owner_name();
+        let visibility = t(playlist.visibility());
+        let mut lines = vec![
+            Line::from(Span::styled(
+                playlist.name.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(tr!("by {}", owner)),
+            Line::from(""),
+            Line::from(tr!("Tracks: {}", playlist.tracks.total)),
+            Line::from(tr!("Visibility: {}", visibility)),
+            Line::from(format!(
+                "URL: https://open.spotify.com/playlist/{}",
+                playlist.id
+            )),
+        ];
+        if let Some(description) = playlist.description.as_ref().filter(|d| !d.is_empty()) {
+            lines.push(Line::from(""));
+            lines.push(Line::from(description.clone()));
+        }
+        lines
+    }
+
+    /// Index of the highlighted playlist in `playlists`.
+    fn highlighted(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|position| self.visible.get(position))
+            .copied()
+    }
+
+    fn apply_filter(&mut self) {
+        self.visible = self
+            .filter
+            .visible(self.playlists.iter().map(|playlist| playlist.name.as_str()));
+        if let (Some(owned), false) = (&self.spautofy_owned, self.show_all) {
+            self.visible.retain(|index| owned[*index]);
+        }
+        let selected = if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.state.select(selected);
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.visible.len().saturating_sub(1);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    fn handle_events(&mut self) -> io::Result<Option<BrowserEvent>> {
+        let Some(Event::Key(key)) = read_event()? else {
+            return Ok(None);
+        };
+        if key.kind != KeyEventKind::Press {
+            return Ok(None);
+        }
+        if let Some(confirm) = &mut self.confirm {
+            match confirm.handle_key(key.code) {
+                Some(true) => return Ok(Some(BrowserEvent::Delete)),
+                Some(false) => self.confirm = None,
+                None => {}
+            }
+            return Ok(None);
+        }
+        if self.show_help {
+            self.show_help = false;
+            return Ok(None);
+        }
+        if self.filter.handle_key(key.code) {
+            self.apply_filter();
+            return Ok(None);
+        }
+        match key.code {
+            code if is_help_key(code) => self.show_help = true,
+            code if is_bound(code, KeyAction::MoveUp) => self.move_cursor(-1),
+            code if is_bound(code, KeyAction::MoveDown) => self.move_cursor(1),
+            code if is_bound(code, KeyAction::Select) && self.marked.is_none() => {
+                if let Some(index) = self.highlighted() {
+                    return Ok(Some(BrowserEvent::Picked(index)));
+                }
+            }
+            code if is_bound(code, KeyAction::Quit) => return Ok(Some(BrowserEvent::Back)),
+            KeyCode::PageUp => self.move_cursor(-(self.page_size as isize)),
+            KeyCode::PageDown => self.move_cursor(self.page_size as isize),
+            KeyCode::Home => self.move_cursor(isize::MIN),
+            KeyCode::End => self.move_cursor(isize::MAX),
+            KeyCode::Char('x') => {
+                if let (Some(index), Some(marked)) = (self.highlighted(), &mut self.marked) {
+                    marked[index] = !marked[index];
+                }
+            }
+            KeyCode::Char('d') if self.marked.is_some() => {
+                let items: Vec<String> = self
+                    .to_delete()
+                    .into_iter()
+                    .map(|index| {
+                        let playlist = &self.playlists[index];
+                        tr!("{} ({} tracks)", playlist.name, playlist.tracks.total)
+                    })
+                    .collect();
+                if !items.is_empty() {
+                    self.confirm = Some(ConfirmDialog::new("Delete Playlists", items));
+                }
+            }
+            KeyCode::Char('o') if self.spautofy_owned.is_some() => {
+                self.show_all = !self.show_all;
+                self.apply_filter();
+            }
+            KeyCode::Char('/') => self.filter.start(),
+            KeyCode::Esc if self.filter.is_active() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Esc => return Ok(Some(BrowserEvent::Back)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,27 @@
+use std::io;
+
+use ratatui::layout::{Constraint, Layout};
+use ratatui::widgets::Gauge;
+
+use crate::i18n::t;
+use crate::progress::Progress;
+use crate::tui::theme;
+use crate::tui::Tui;
+
+pub fn draw_progress(terminal: &mut Tui, title: &str, progress: Progress) -> io::Result<()> {
+    terminal.draw(|frame| {
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Fill(1),
+        ])
+        .areas(frame.area());
+        let gauge = Gauge::default()
+            .block(theme::block().title(format!(" {} ", t(title))))
+            .gauge_style(theme::success_style())
+            .ratio(progress.ratio())
+            .label(format!("{} / {}", progress.done, progress.total));
+        frame.render_widget(gauge, area);
+    })?;
+    Ok(())
+}
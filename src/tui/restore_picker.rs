@@ -0,0 +1,214 @@
+use std::io;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::layout::Constraint;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Row, Table, TableState};
+use ratatui::Frame;
+
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::list_filter::ListFilter;
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{page_size, read_event, render_scrollbar, Tui};
+
+const KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("PgUp, PgDn, Home, End", "Move the cursor by pages"),
+    ("{select}", "Restore or skip the highlighted entry"),
+    ("a", "Select all visible entries"),
+    ("n", "Deselect all visible entries"),
+    ("/", "Filter the entries, Esc clears the filter"),
+    ("{confirm}", "Restore the selected entries"),
+    ("{quit}, Esc", "Cancel"),
+];
+
+/// Something `spautofy restore` can write back, with its number of tracks.
+#[derive(Debug)]
+pub struct RestoreEntry {
+    pub name: String,
+    pub tracks: usize,
+}
+
+/// Lets the user pick what to restore from a backup; everything starts selected.
+#[derive(Debug)]
+pub struct RestorePicker {
+    entries: Vec<RestoreEntry>,
+    selected: Vec<bool>,
+    state: TableState,
+    page_size: usize,
+    show_help: bool,
+    filter: ListFilter,
+    /// Indices of the entries matching the filter; the cursor moves over these.
+    visible: Vec<usize>,
+}
+
+enum PickerEvent {
+    Confirm,
+    Cancel,
+}
+
+impl RestorePicker {
+    pub fn new(entries: Vec<RestoreEntry>) -> Self {
+        let selected = if entries.is_empty() { None } else { Some(0) };
+        RestorePicker {
+            selected: vec![true; entries.len()],
+            visible: (0..entries.len()).collect(),
+            entries,
+            state: TableState::default().with_selected(selected),
+            page_size: 1,
+            show_help: false,
+            filter: ListFilter::default(),
+        }
+    }
+
+    /// Returns which entries to restore, or `None` if the user cancelled.
+    pub fn run(mut self, terminal: &mut Tui) -> io::Result<Option<Vec<bool>>> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            match self.handle_events()? {
+                Some(PickerEvent::Confirm) => return Ok(Some(self.selected)),
+                Some(PickerEvent::Cancel) => return Ok(None),
+                None => {}
+            }
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let area = render_footer(frame);
+        let rows = self.visible.iter().map(|index| {
+            let entry = &self.entries[*index];
+            let row = Row::new(vec![
+                if self.selected[*index] { "[x]" } else { "[ ]" }.to_string(),
+                entry.name.clone(),
+                entry.tracks.to_string(),
+            ]);
+            if self.selected[*index] {
+                row
+            } else {
+                row.style(theme::dimmed_style())
+            }
+        });
+        let header = Row::new(vec!["", t("Name"), t("Tracks")])
+            .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+        let selected = self.selected.iter().filter(|selected| **selected).count();
+        let mut block = theme::block()
+            .title(t(" Spautofy - Restore Backup "))
+            .title_bottom(Line::from(format!(
+                "{}{}",
+                tr!(" {} OF {} SELECTED", selected, self.entries.len()),
+                expand(
+                    " | TOGGLE WITH '{select}' | RESTORE WITH '{confirm}' | CANCEL WITH '{quit}' ",
+                    true
+                )
+            )))
+            .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+        if let Some(filter) = self.filter.title() {
+            block = block.title(filter.right_aligned());
+        }
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Fill(1),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .row_highlight_style(theme::highlight_style());
+        self.page_size = page_size(area).saturating_sub(1).max(1);
+        frame.render_stateful_widget(table, area, &mut self.state);
+        render_scrollbar(
+            frame,
+            area,
+            self.visible.len() + 1,
+            self.state.selected().unwrap_or(0),
+        );
+        if self.show_help {
+            render_help(frame, KEYBINDINGS);
+        }
+    }
+
+    /// Index of the highlighted entry in `entries`.
+    fn highlighted(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|position| self.visible.get(position))
+            .copied()
+    }
+
+    fn apply_filter(&mut self) {
+        self.visible = self
+            .filter
+            .visible(self.entries.iter().map(|entry| entry.name.as_str()));
+        let selected = if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.state.select(selected);
+    }
+
+    fn select_visible(&mut self, selected: bool) {
+        for index in &self.visible {
+            self.selected[*index] = selected;
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.visible.len().saturating_sub(1);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    fn handle_events(&mut self) -> io::Result<Option<PickerEvent>> {
+        let Some(Event::Key(key)) = read_event()? else {
+            return Ok(None);
+        };
+        if key.kind != KeyEventKind::Press {
+            return Ok(None);
+        }
+        if self.show_help {
+            self.show_help = false;
+            return Ok(None);
+        }
+        if self.filter.handle_key(key.code) {
+            self.apply_filter();
+            return Ok(None);
+        }
+        match key.code {
+            code if is_help_key(code) => self.show_help = true,
+            code if is_bound(code, KeyAction::MoveUp) => self.move_cursor(-1),
+            code if is_bound(code, KeyAction::MoveDown) => self.move_cursor(1),
+            code if is_bound(code, KeyAction::Select) => {
+                if let Some(index) = self.highlighted() {
+                    self.selected[index] = !self.selected[index];
+                }
+            }
+            code if is_bound(code, KeyAction::Confirm) => return Ok(Some(PickerEvent::Confirm)),
+            code if is_bound(code, KeyAction::Quit) => return Ok(Some(PickerEvent::Cancel)),
+            KeyCode::PageUp => self.move_cursor(-(self.page_size as isize)),
+            KeyCode::PageDown => self.move_cursor(self.page_size as isize),
+            KeyCode::Home => self.move_cursor(isize::MIN),
+            KeyCode::End => self.move_cursor(isize::MAX),
+            KeyCode::Char('a') => self.select_visible(true),
+            KeyCode::Char('n') => self.select_visible(false),
+            KeyCode::Char('/') => self.filter.start(),
+            KeyCode::Esc if self.filter.is_active() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Esc => return Ok(Some(PickerEvent::Cancel)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
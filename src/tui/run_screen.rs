@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::time::Duration;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::LineGauge;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::actions::action_report::{in_run_order, ActionReport, RunEvent};
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::log_pane::LogPane;
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{Tui, FRAME_INTERVAL, SPINNER};
+
+/// Shows the progress of the actions while they run on a background task.
+#[derive(Debug)]
+pub struct RunScreen {
+    log: LogPane,
+    total: usize,
+    /// The labels of the actions running, by their indices.
+    running: BTreeMap<usize, String>,
+    finished: Vec<(usize, ActionReport)>,
+    frame_index: usize,
+}
+
+impl RunScreen {
+    pub fn new(total: usize) -> Self {
+        RunScreen {
+            log: LogPane::default(),
+            total,
+            running: BTreeMap::new(),
+            finished: Vec::new(),
+            frame_index: 0,
+        }
+    }
+
+    /// Returns the reports once the sender of `events` is done.
+    pub async fn run(
+        mut self,
+        terminal: &mut Tui,
+        mut events: UnboundedReceiver<RunEvent>,
+    ) -> io::Result<Vec<ActionReport>> {
+        loop {
+            self.draw(terminal)?;
+            self.handle_events()?;
+            tokio::select! {
+                event = events.recv() => match event {
+                    Some(RunEvent::Started(index, action)) => {
+                        self.running.insert(index, action);
+                    }
+                    Some(RunEvent::Finished(index, report)) => {
+                        self.running.remove(&index);
+                        self.finished.push((index, report));
+                    }
+                    None => return Ok(in_run_order(self.finished)),
+                },
+                _ = tokio::time::sleep(FRAME_INTERVAL) => self.frame_index += 1,
+            }
+        }
+    }
+
+    /// Scrolls the log; the actions cannot be interrupted.
+    fn handle_events(&mut self) -> io::Result<()> {
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.log.handle_key(key.code);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, terminal: &mut Tui) -> io::Result<()> {
+        let done = self.finished.len();
+        let label = if self.running.is_empty() {
+            tr!("{} of {} done", done, self.total)
+        } else {
+            let running: Vec<&str> = self.running.values().map(String::as_str).collect();
+            tr!(
+                "{} {} ({} of {} done)",
+                SPINNER[self.frame_index % SPINNER.len()],
+                running.join(", "),
+                done,
+                self.total
+            )
+        };
+        let ratio = if self.total == 0 {
+            1.0
+        } else {
+            done as f64 / self.total as f64
+        };
+        terminal.draw(|frame| {
+            let area = render_footer(frame);
+            let block = theme::block()
+                .title(t(" Spautofy - Running Actions "))
+                .title_bottom(Line::from(
+                    " RUNNING ACTIONS... | SCROLL THE LOG WITH 'PGUP'/'PGDN' ",
+                ));
+            let inner = block.inner(area);
+            frame.render_widget(block, area);
+            let [gauge_area, log_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+            let gauge = LineGauge::default()
+                .label(Span::raw(label))
+                .filled_style(theme::success_style())
+                .unfilled_style(theme::dimmed_style())
+                .ratio(ratio);
+            frame.render_widget(gauge, gauge_area);
+            self.log.render(frame, log_area);
+        })?;
+        Ok(())
+    }
+}
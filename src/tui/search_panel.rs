@@ -0,0 +1,310 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::text::Line;
+use ratatui::widgets::{List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::actions::recommendation_playlist::MAX_SEEDS;
+use crate::actions::search::search_tracks_and_artists;
+use crate::authorize::AuthorizeError;
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::help_overlay::{render_help, Keybindings};
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{read_event, Tui};
+use crate::{MainError, UserAccess};
+
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(400);
+const SEARCH_LIMIT: u32 = 20;
+
+const KEYBINDINGS: Keybindings = &[
+    ("Typing", "Edit the search query"),
+    ("↑, ↓", "Move the cursor"),
+    ("Tab", "Switch between tracks and artists"),
+    ("Enter", "Pick or unpick the highlighted result"),
+    ("Ctrl+R", "Create a recommendations playlist from the picks"),
+    ("Ctrl+P", "Add the picked tracks to a playlist"),
+    ("Esc", "Go back"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultKind {
+    Tracks,
+    Artists,
+}
+
+#[derive(Debug, Clone)]
+struct SearchResult {
+    id: String,
+    uri: String,
+    label: String,
+}
+
+#[derive(Debug)]
+pub enum SearchOutcome {
+    Recommend {
+        seed_track_ids: Vec<String>,
+        seed_artist_ids: Vec<String>,
+    },
+    AddToPlaylist {
+        track_uris: Vec<String>,
+    },
+    Back,
+}
+
+#[derive(Debug)]
+pub struct SearchPanel {
+    query: String,
+    last_edit: Option<Instant>,
+    kind: ResultKind,
+    tracks: Vec<SearchResult>,
+    artists: Vec<SearchResult>,
+    picked_tracks: Vec<SearchResult>,
+    picked_artists: Vec<SearchResult>,
+    state: ListState,
+    show_help: bool,
+}
+
+impl SearchPanel {
+    pub fn new() -> Self {
+        SearchPanel {
+            query: String::new(),
+            last_edit: None,
+            kind: ResultKind::Tracks,
+            tracks: Vec::new(),
+            artists: Vec::new(),
+            picked_tracks: Vec::new(),
+            picked_artists: Vec::new(),
+            state: ListState::default(),
+            show_help: false,
+        }
+    }
+
+    pub async fn run(
+        mut self,
+        terminal: &mut Tui,
+        user_access: &UserAccess,
+    ) -> Result<SearchOutcome, MainError> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            if event::poll(Duration::from_millis(50))? {
+                if let Some(outcome) = self.handle_events()? {
+                    return Ok(outcome);
+                }
+            } else if self
+                .last_edit
+                .is_some_and(|last_edit| last_edit.elapsed() >= SEARCH_DEBOUNCE)
+            {
+                self.last_edit = None;
+                self.search(user_access).await?;
+            }
+        }
+    }
+
+    async fn search(&mut self, user_access: &UserAccess) -> Result<(), AuthorizeError> {
+        let query = self.query.trim();
+        if query.is_empty() {
+            self.tracks.clear();
+            self.artists.clear();
+        } else {
+            let results = search_tracks_and_artists(user_access, query, SEARCH_LIMIT).await?;
+            self.tracks = results
+                .tracks
+                .items
+                .into_iter()
+                .map(|track| {
+                    let artists: Vec<&str> =
+                        track.artists.iter().map(|a| a.name.as_str()).collect();
+                    SearchResult {
+                        label: format!("{} - {}", track.name, artists.join(", ")),
+                        id: track.id,
+                        uri: track.uri,
+                    }
+                })
+                .collect();
+            self.artists = results
+                .artists
+                .items
+                .into_iter()
+                .map(|artist| SearchResult {
+                    uri: format!("spotify:artist:{}", artist.id),
+                    id: artist.id,
+                    label: artist.name,
+                })
+                .collect();
+        }
+        let selected = if self.results().is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.state.select(selected);
+        Ok(())
+    }
+
+    fn results(&self) -> &[SearchResult] {
+        match self.kind {
+            ResultKind::Tracks => &self.tracks,
+            ResultKind::Artists => &self.artists,
+        }
+    }
+
+    fn picked(&self) -> &[SearchResult] {
+        match self.kind {
+            ResultKind::Tracks => &self.picked_tracks,
+            ResultKind::Artists => &self.picked_artists,
+        }
+    }
+
+    fn is_picked(&self, result: &SearchResult) -> bool {
+        self.picked().iter().any(|picked| picked.id == result.id)
+    }
+
+    fn toggle_pick(&mut self) {
+        let Some(result) = self.state.selected().and_then(|i| self.results().get(i)) else {
+            return;
+        };
+        let result = result.clone();
+        let picked = match self.kind {
+            ResultKind::Tracks => &mut self.picked_tracks,
+            ResultKind::Artists => &mut self.picked_artists,
+        };
+        if let Some(index) = picked.iter().position(|p| p.id == result.id) {
+            picked.remove(index);
+        } else {
+            picked.push(result);
+        }
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let area = render_footer(frame);
+        let [input_area, results_area, picked_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(3),
+        ])
+        .areas(area);
+
+        let input = Paragraph::new(format!("{}_", self.query))
+            .block(theme::block().title(t(" Spautofy - Search ")));
+        frame.render_widget(input, input_area);
+
+        let items: Vec<ListItem> = self
+            .results()
+            .iter()
+            .map(|result| {
+                let checkbox = if self.is_picked(result) { "[x]" } else { "[ ]" };
+                ListItem::new(format!("{} {}", checkbox, result.label))
+            })
+            .collect();
+        let title = match self.kind {
+            ResultKind::Tracks => t(" Tracks (switch with 'TAB') "),
+            ResultKind::Artists => t(" Artists (switch with 'TAB') "),
+        };
+        let list = List::new(items)
+            .block(theme::block().title(title))
+            .highlight_style(theme::highlight_style());
+        frame.render_stateful_widget(list, results_area, &mut self.state);
+
+        let picked = Paragraph::new(tr!(
+            "{} tracks, {} artists picked",
+            self.picked_tracks.len(),
+            self.picked_artists.len()
+        ))
+        .block(
+            theme::block()
+                .title_bottom(Line::from(t(" PICK WITH 'ENTER' | BACK WITH 'ESC' ")))
+                .title_bottom(Line::from(t(" HELP WITH 'F1' ")).right_aligned()),
+        );
+        frame.render_widget(picked, picked_area);
+        if self.show_help {
+            render_help(frame, KEYBINDINGS);
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.results().len().saturating_sub(1);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    fn handle_events(&mut self) -> io::Result<Option<SearchOutcome>> {
+        let Some(Event::Key(key)) = read_event()? else {
+            return Ok(None);
+        };
+        if key.kind != KeyEventKind::Press {
+            return Ok(None);
+        }
+        if self.show_help {
+            self.show_help = false;
+            return Ok(None);
+        }
+        if key.code == KeyCode::F(1) {
+            self.show_help = true;
+            return Ok(None);
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('r') => {
+                    let seeds = self.picked_tracks.len() + self.picked_artists.len();
+                    if seeds > 0 && seeds <= MAX_SEEDS {
+                        return Ok(Some(SearchOutcome::Recommend {
+                            seed_track_ids: self
+                                .picked_tracks
+                                .iter()
+                                .map(|t| t.id.clone())
+                                .collect(),
+                            seed_artist_ids: self
+                                .picked_artists
+                                .iter()
+                                .map(|a| a.id.clone())
+                                .collect(),
+                        }));
+                    }
+                }
+                KeyCode::Char('p') if !self.picked_tracks.is_empty() => {
+                    return Ok(Some(SearchOutcome::AddToPlaylist {
+                        track_uris: self.picked_tracks.iter().map(|t| t.uri.clone()).collect(),
+                    }));
+                }
+                _ => {}
+            }
+            return Ok(None);
+        }
+        match key.code {
+            KeyCode::Up => self.move_cursor(-1),
+            KeyCode::Down => self.move_cursor(1),
+            KeyCode::Tab => {
+                self.kind = match self.kind {
+                    ResultKind::Tracks => ResultKind::Artists,
+                    ResultKind::Artists => ResultKind::Tracks,
+                };
+                let selected = if self.results().is_empty() {
+                    None
+                } else {
+                    Some(0)
+                };
+                self.state.select(selected);
+            }
+            KeyCode::Enter => self.toggle_pick(),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.last_edit = Some(Instant::now());
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.last_edit = Some(Instant::now());
+            }
+            KeyCode::Esc => return Ok(Some(SearchOutcome::Back)),
+            _ => {}
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,52 @@
+use std::time::{Duration, Instant};
+
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+use crate::actions::is_dry_run;
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::theme;
+use crate::UserAccess;
+
+/// Who is logged in and until when the access token is valid.
+#[derive(Debug, Clone)]
+pub struct AuthStatus {
+    user: String,
+    expires_at: Instant,
+}
+
+impl From<&UserAccess> for AuthStatus {
+    fn from(user_access: &UserAccess) -> Self {
+        AuthStatus {
+            user: user_access.user.display_name.clone(),
+            expires_at: Instant::now() + Duration::from_secs(user_access.access.remaining_secs()),
+        }
+    }
+}
+
+impl AuthStatus {
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let remaining = self.expires_at.saturating_duration_since(Instant::now());
+        let expiry = if remaining.is_zero() {
+            Span::styled(t("token expired"), theme::error_style())
+        } else {
+            Span::styled(
+                tr!("token expires in {} min", remaining.as_secs().div_ceil(60)),
+                theme::dimmed_style(),
+            )
+        };
+        let mut spans = vec![
+            Span::raw(tr!(" Logged in as {} | ", self.user)),
+            expiry,
+            Span::styled(t(" | RE-AUTHORIZE WITH 'R' "), theme::dimmed_style()),
+        ];
+        if is_dry_run() {
+            spans.push(Span::styled(t("| DRY RUN "), theme::error_style()));
+        }
+        let line = Line::from(spans);
+        frame.render_widget(Paragraph::new(line), area);
+    }
+}
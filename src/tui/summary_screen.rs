@@ -0,0 +1,165 @@
+use std::io::{self, Write};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+
+use crate::actions::action_report::ActionReport;
+use crate::i18n::t;
+use crate::tr;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::log_pane::LogPane;
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{read_event, ScreenOutcome, Tui};
+
+const KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("c", "Copy the highlighted playlist URL"),
+    ("PgUp, PgDn", "Scroll the log"),
+    ("{select}, Esc", "Back to the action selection"),
+    ("{quit}", "Quit"),
+];
+
+#[derive(Debug)]
+pub struct SummaryScreen {
+    reports: Vec<ActionReport>,
+    state: TableState,
+    notice: Option<String>,
+    log: LogPane,
+    show_help: bool,
+}
+
+impl SummaryScreen {
+    pub fn new(reports: Vec<ActionReport>) -> Self {
+        let selected = if reports.is_empty() { None } else { Some(0) };
+        SummaryScreen {
+            reports,
+            state: TableState::default().with_selected(selected),
+            notice: None,
+            log: LogPane::default(),
+            show_help: false,
+        }
+    }
+
+    pub fn run(mut self, terminal: &mut Tui) -> io::Result<ScreenOutcome> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            let Some(Event::Key(key)) = read_event()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press || self.log.handle_key(key.code) {
+                continue;
+            }
+            if self.show_help {
+                self.show_help = false;
+                continue;
+            }
+            match key.code {
+                code if is_help_key(code) => self.show_help = true,
+                code if is_bound(code, KeyAction::MoveUp) => self.move_cursor(-1),
+                code if is_bound(code, KeyAction::MoveDown) => self.move_cursor(1),
+                code if is_bound(code, KeyAction::Select) => return Ok(ScreenOutcome::Back),
+                code if is_bound(code, KeyAction::Quit) => return Ok(ScreenOutcome::Quit),
+                KeyCode::Char('c') => self.copy_url()?,
+                KeyCode::Esc => return Ok(ScreenOutcome::Back),
+                _ => {}
+            }
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.reports.len().saturating_sub(1);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    /// Copies the highlighted playlist URL using the OSC 52 terminal clipboard sequence.
+    fn copy_url(&mut self) -> io::Result<()> {
+        let Some(report) = self.state.selected().map(|i| &self.reports[i]) else {
+            return Ok(());
+        };
+        let Ok(playlist) = &report.result else {
+            self.notice = Some(tr!("This action did not create a playlist."));
+            return Ok(());
+        };
+        let mut stdout = io::stdout();
+        write!(stdout, "\x1b]52;c;{}\x07", STANDARD.encode(&playlist.url))?;
+        stdout.flush()?;
+        self.notice = Some(tr!("Copied {} to the clipboard.", playlist.url));
+        Ok(())
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let area = render_footer(frame);
+        let rows = self.reports.iter().map(|report| match &report.result {
+            Ok(playlist) => Row::new(vec![
+                report.action.clone(),
+                tr!("done"),
+                playlist.name.clone(),
+                playlist.track_count.to_string(),
+                playlist.url.clone(),
+            ])
+            .style(theme::success_style()),
+            Err(err) => Row::new(vec![
+                report.action.clone(),
+                tr!("failed"),
+                err.clone(),
+                String::new(),
+                String::new(),
+            ])
+            .style(theme::error_style()),
+        });
+        let header = Row::new(vec![
+            t("Action"),
+            t("Status"),
+            t("Playlist"),
+            t("Tracks"),
+            "URL",
+        ])
+        .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+        let block = theme::block()
+            .title(t(" Spautofy - Results "))
+            .title_bottom(Line::from(expand(
+                " COPY URL WITH 'C' | BACK WITH '{select}' | QUIT WITH '{quit}' ",
+                true,
+            )))
+            .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(8),
+                Constraint::Fill(1),
+                Constraint::Length(7),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .row_highlight_style(theme::highlight_style());
+        let [table_area, notice_area, log_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(10),
+        ])
+        .areas(area);
+        frame.render_stateful_widget(table, table_area, &mut self.state);
+        self.log.render(frame, log_area);
+        if let Some(notice) = &self.notice {
+            frame.render_widget(Paragraph::new(notice.as_str()), notice_area);
+        }
+        if self.show_help {
+            render_help(frame, KEYBINDINGS);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::OnceLock;
+
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders};
+use serde::{Deserialize, Serialize};
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreset {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// The `theme` section of the config file; unset colors fall back to the preset.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub preset: ThemePreset,
+    pub highlight: Option<Color>,
+    pub border: Option<Color>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub highlight: Color,
+    pub border: Color,
+    pub dimmed: Color,
+    pub success: Color,
+    pub error: Color,
+}
+
+impl From<ThemePreset> for Theme {
+    fn from(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Theme {
+                highlight: Color::DarkGray,
+                border: Color::Reset,
+                dimmed: Color::DarkGray,
+                success: Color::Green,
+                error: Color::Red,
+            },
+            ThemePreset::Light => Theme {
+                highlight: Color::LightBlue,
+                border: Color::Black,
+                dimmed: Color::Gray,
+                success: Color::Green,
+                error: Color::Red,
+            },
+        }
+    }
+}
+
+impl From<&ThemeConfig> for Theme {
+    fn from(config: &ThemeConfig) -> Self {
+        let preset = Theme::from(config.preset);
+        Theme {
+            highlight: config.highlight.unwrap_or(preset.highlight),
+            border: config.border.unwrap_or(preset.border),
+            ..preset
+        }
+    }
+}
+
+/// Sets the theme used by all screens; only the first call has an effect.
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+pub fn current() -> Theme {
+    *THEME.get_or_init(|| Theme::from(ThemePreset::default()))
+}
+
+pub fn block() -> Block<'static> {
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(current().border))
+}
+
+pub fn highlight_style() -> Style {
+    Style::default()
+        .bg(current().highlight)
+        .add_modifier(Modifier::BOLD)
+}
+
+pub fn dimmed_style() -> Style {
+    Style::default().fg(current().dimmed)
+}
+
+pub fn success_style() -> Style {
+    Style::default().fg(current().success)
+}
+
+pub fn error_style() -> Style {
+    Style::default().fg(current().error)
+}
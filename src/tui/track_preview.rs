@@ -0,0 +1,264 @@
+use std::io;
+
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+#[cfg(feature = "album-art")]
+use ratatui::layout::Layout;
+use ratatui::layout::{Constraint, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Row, Table, TableState};
+use ratatui::Frame;
+
+use crate::i18n::t;
+use crate::models::track::Track;
+use crate::tr;
+#[cfg(feature = "album-art")]
+use crate::tui::cover_art::CoverArt;
+use crate::tui::help_overlay::{is_help_key, render_help, Keybindings, HELP_HINT};
+use crate::tui::keymap::{expand, is_bound, KeyAction};
+use crate::tui::list_filter::ListFilter;
+use crate::tui::now_playing::render_footer;
+use crate::tui::theme;
+use crate::tui::{page_size, read_event, render_scrollbar, Tui};
+
+/// Terminal cells are about twice as high as wide, so the cover takes half as many rows.
+#[cfg(feature = "album-art")]
+const COVER_WIDTH: u16 = 28;
+
+const KEYBINDINGS: Keybindings = &[
+    ("{up}, {down}", "Move the cursor"),
+    ("PgUp, PgDn, Home, End", "Move the cursor by pages"),
+    (
+        "{select}, {confirm}",
+        "Include or leave out the highlighted track",
+    ),
+    (
+        "/",
+        "Filter by track, artist or album, Esc clears the filter",
+    ),
+    ("{quit}, Esc", "Go back to the parameters"),
+];
+
+#[derive(Debug)]
+pub struct TrackPreview {
+    title: String,
+    tracks: Vec<Track>,
+    excluded: Vec<bool>,
+    state: TableState,
+    page_size: usize,
+    show_help: bool,
+    filter: ListFilter,
+    /// Indices of the tracks matching the filter; the cursor moves over these.
+    visible: Vec<usize>,
+    #[cfg(feature = "album-art")]
+    covers: CoverArt,
+}
+
+impl TrackPreview {
+    pub fn new(title: &str, tracks: Vec<Track>, excluded_track_ids: &[String]) -> Self {
+        let excluded = tracks
+            .iter()
+            .map(|track| excluded_track_ids.contains(&track.id))
+            .collect();
+        let selected = if tracks.is_empty() { None } else { Some(0) };
+        TrackPreview {
+            title: format!(" {} ", t(title)),
+            visible: (0..tracks.len()).collect(),
+            tracks,
+            excluded,
+            state: TableState::default().with_selected(selected),
+            page_size: 1,
+            show_help: false,
+            filter: ListFilter::default(),
+            #[cfg(feature = "album-art")]
+            covers: CoverArt::default(),
+        }
+    }
+
+    #[cfg(feature = "album-art")]
+    pub fn with_covers(mut self, covers: CoverArt) -> Self {
+        self.covers = covers;
+        self
+    }
+
+    /// Returns the ids of the tracks the user left out.
+    pub fn run(mut self, terminal: &mut Tui) -> io::Result<Vec<String>> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            if self.handle_events()? {
+                break;
+            }
+        }
+        Ok(self
+            .tracks
+            .into_iter()
+            .zip(self.excluded)
+            .filter(|(_, excluded)| *excluded)
+            .map(|(track, _)| track.id)
+            .collect())
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let area = render_footer(frame);
+        let area = self.render_cover(frame, area);
+        let rows = self.visible.iter().map(|index| {
+            let track = &self.tracks[*index];
+            let excluded = self.excluded[*index];
+            let artists: Vec<&str> = track.artists.iter().map(|a| a.name.as_str()).collect();
+            let row = Row::new(vec![
+                if excluded { "[ ]" } else { "[x]" }.to_string(),
+                (index + 1).to_string(),
+                track.name.clone(),
+                artists.join(", "),
+                track.album.name.clone(),
+            ]);
+            if excluded {
+                row.style(theme::dimmed_style())
+            } else {
+                row
+            }
+        });
+        let header = Row::new(vec!["", "#", t("Track"), t("Artists"), t("Album")])
+            .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED));
+        let included = self.excluded.iter().filter(|excluded| !**excluded).count();
+        let mut block = theme::block()
+            .title(self.title.as_str())
+            .title_bottom(Line::from(format!(
+                "{}{}",
+                tr!(" {} OF {} TRACKS INCLUDED", included, self.tracks.len()),
+                expand(" | TOGGLE WITH '{select}' | BACK WITH '{quit}' ", true)
+            )))
+            .title_bottom(Line::from(t(HELP_HINT)).right_aligned());
+        if let Some(filter) = self.filter.title() {
+            block = block.title(filter.right_aligned());
+        }
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Fill(2),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .row_highlight_style(theme::highlight_style());
+        self.page_size = page_size(area).saturating_sub(1).max(1);
+        frame.render_stateful_widget(table, area, &mut self.state);
+        render_scrollbar(
+            frame,
+            area,
+            self.visible.len() + 1,
+            self.state.selected().unwrap_or(0),
+        );
+        if self.show_help {
+            render_help(frame, KEYBINDINGS);
+        }
+    }
+
+    /// Shows the album cover of the highlighted track; returns the area left for the table.
+    #[cfg(feature = "album-art")]
+    fn render_cover(&mut self, frame: &mut Frame, area: Rect) -> Rect {
+        let Some(album_id) = self
+            .highlighted()
+            .map(|index| self.tracks[index].album.id.as_str())
+            .filter(|album_id| self.covers.has_cover(album_id))
+        else {
+            return area;
+        };
+        let [table_area, cover_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Length(COVER_WIDTH)]).areas(area);
+        let [cover_area, _] =
+            Layout::vertical([Constraint::Length(COVER_WIDTH / 2), Constraint::Fill(1)])
+                .areas(cover_area);
+        let block = theme::block().title(t(" Album "));
+        let inner = block.inner(cover_area);
+        frame.render_widget(block, cover_area);
+        let album_id = album_id.to_string();
+        self.covers.render(frame, inner, &album_id);
+        table_area
+    }
+
+    #[cfg(not(feature = "album-art"))]
+    fn render_cover(&mut self, _frame: &mut Frame, area: Rect) -> Rect {
+        area
+    }
+
+    /// Index of the highlighted track in `tracks`.
+    fn highlighted(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|position| self.visible.get(position))
+            .copied()
+    }
+
+    fn apply_filter(&mut self) {
+        let entries: Vec<String> = self
+            .tracks
+            .iter()
+            .map(|track| {
+                let artists: Vec<&str> = track.artists.iter().map(|a| a.name.as_str()).collect();
+                format!("{} {} {}", track.name, artists.join(" "), track.album.name)
+            })
+            .collect();
+        self.visible = self.filter.visible(entries.iter().map(String::as_str));
+        let selected = if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        self.state.select(selected);
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let Some(current) = self.state.selected() else {
+            return;
+        };
+        let last = self.visible.len().saturating_sub(1);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.state.select(Some(next));
+    }
+
+    /// Returns whether the preview should be closed.
+    fn handle_events(&mut self) -> io::Result<bool> {
+        let Some(Event::Key(key)) = read_event()? else {
+            return Ok(false);
+        };
+        if key.kind != KeyEventKind::Press {
+            return Ok(false);
+        }
+        if self.show_help {
+            self.show_help = false;
+            return Ok(false);
+        }
+        if self.filter.handle_key(key.code) {
+            self.apply_filter();
+            return Ok(false);
+        }
+        match key.code {
+            code if is_help_key(code) => self.show_help = true,
+            code if is_bound(code, KeyAction::MoveUp) => self.move_cursor(-1),
+            code if is_bound(code, KeyAction::MoveDown) => self.move_cursor(1),
+            code if is_bound(code, KeyAction::Select) || is_bound(code, KeyAction::Confirm) => {
+                if let Some(index) = self.highlighted() {
+                    self.excluded[index] = !self.excluded[index];
+                }
+            }
+            code if is_bound(code, KeyAction::Quit) => return Ok(true),
+            KeyCode::PageUp => self.move_cursor(-(self.page_size as isize)),
+            KeyCode::PageDown => self.move_cursor(self.page_size as isize),
+            KeyCode::Home => self.move_cursor(isize::MIN),
+            KeyCode::End => self.move_cursor(isize::MAX),
+            KeyCode::Char('/') => self.filter.start(),
+            KeyCode::Esc if self.filter.is_active() => {
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Esc => return Ok(true),
+            _ => {}
+        }
+        Ok(false)
+    }
+}
@@ -0,0 +1,393 @@
+use crate::actions::parameters::ActionParameters;
+use crate::actions::playlist_actions::{
+    add_to_playlist, get_current_user_playlists, unfollow_playlist, PlaylistTarget,
+    PlaylistVisibility,
+};
+use crate::actions::recommendation_playlist::create_recommendation_playlist;
+use crate::actions::registry::Action;
+use crate::actions::top_track_playlist::{get_top_tracks, TimeRange, TopTrackPlaylistParams};
+use crate::actions::{is_dry_run, ActionsConfig, Task};
+use crate::models::playlist::SimplifiedPlaylist;
+use crate::progress::Progress;
+use crate::tui::action_selection::ActionSelectionList;
+use crate::tui::confirm_screen::ConfirmScreen;
+#[cfg(feature = "album-art")]
+use crate::tui::cover_art::CoverArt;
+use crate::tui::history_screen::HistoryScreen;
+use crate::tui::parameters::ParameterScreen;
+use crate::tui::playlist_browser::{BrowserOutcome, PlaylistBrowser};
+use crate::tui::progress_gauge::draw_progress;
+use crate::tui::search_panel::{SearchOutcome, SearchPanel};
+use crate::tui::status_bar::AuthStatus;
+use crate::tui::track_preview::TrackPreview;
+use crate::tui::{ScreenOutcome, Tui};
+use crate::{history, retry_on_error, status, tr, MainError, UserAccess};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WizardStep {
+    Select,
+    Configure,
+    Confirm,
+}
+
+pub async fn load_playlists(
+    terminal: &mut Tui,
+    user_access: &UserAccess,
+) -> Result<Vec<SimplifiedPlaylist>, MainError> {
+    let title = "Loading playlists";
+    draw_progress(terminal, title, Progress::default())?;
+    let playlists = get_current_user_playlists(user_access, |progress| {
+        let _ = draw_progress(terminal, title, progress);
+    })
+    .await?;
+    Ok(playlists)
+}
+
+/// Shows the user's playlists until they go back, deleting the ones they confirmed.
+async fn browse_playlists(terminal: &mut Tui, user_access: &UserAccess) -> Result<(), MainError> {
+    loop {
+        let playlists = retry_on_error!(
+            terminal,
+            "Could not load your playlists",
+            load_playlists(terminal, user_access).await
+        );
+        let Some(playlists) = playlists else {
+            return Ok(());
+        };
+        let outcome = PlaylistBrowser::new("Spautofy - My Playlists", playlists)
+            .with_deletion()
+            .run(terminal)?;
+        let BrowserOutcome::Delete(playlists) = outcome else {
+            return Ok(());
+        };
+        for playlist in playlists {
+            if is_dry_run() {
+                status!("Would delete playlist \"{}\".", playlist.name);
+                continue;
+            }
+            let deleted = retry_on_error!(
+                terminal,
+                "Could not delete the playlist",
+                unfollow_playlist(user_access, &playlist.id).await
+            );
+            if deleted.is_some() {
+                status!("Deleted playlist \"{}\".", playlist.name);
+            }
+        }
+    }
+}
+
+/// Lets the user pick the playlist an action updates, newest Spautofy playlists first.
+async fn pick_target_playlist(
+    terminal: &mut Tui,
+    user_access: &UserAccess,
+) -> Result<Option<PlaylistTarget>, MainError> {
+    // Spotify lists the playlists most recently added to the library first
+    let playlists = load_playlists(terminal, user_access).await?;
+    let outcome = PlaylistBrowser::new("Spautofy - Pick Playlist To Update", playlists)
+        .with_spautofy_filter(&user_access.user.id)
+        .run(terminal)?;
+    let BrowserOutcome::Picked(playlist) = outcome else {
+        return Ok(None);
+    };
+    Ok(Some(PlaylistTarget {
+        id: playlist.id,
+        name: playlist.name,
+    }))
+}
+
+/// Lets the user leave out individual tracks; returns the new list of excluded track ids.
+async fn preview_tracks(
+    terminal: &mut Tui,
+    user_access: &UserAccess,
+    params: &TopTrackPlaylistParams,
+) -> Result<Vec<String>, MainError> {
+    draw_progress(terminal, "Loading tracks", Progress::default())?;
+    let tracks = get_top_tracks(user_access, params.time_range, params.limit).await?;
+    let title = tr!("Spautofy - Preview {} Top Tracks", params.time_range);
+    #[cfg(feature = "album-art")]
+    let covers = CoverArt::load(
+        terminal,
+        tracks
+            .iter()
+            .map(|track| (track.album.id.as_str(), track.album.images.as_slice())),
+    )
+    .await;
+    let preview = TrackPreview::new(&title, tracks, &params.excluded_track_ids);
+    #[cfg(feature = "album-art")]
+    let preview = preview.with_covers(covers);
+    let excluded = preview.run(terminal)?;
+    Ok(excluded)
+}
+
+async fn search(
+    terminal: &mut Tui,
+    user_access: &UserAccess,
+    visibility: PlaylistVisibility,
+) -> Result<(), MainError> {
+    match SearchPanel::new().run(terminal, user_access).await? {
+        SearchOutcome::Recommend {
+            seed_track_ids,
+            seed_artist_ids,
+        } => {
+            let playlist = create_recommendation_playlist(
+                user_access,
+                &seed_track_ids,
+                &seed_artist_ids,
+                50,
+                visibility,
+            )
+            .await?;
+            if !is_dry_run() {
+                status!(
+                    "Created playlist \"{}\" from your recommendations.",
+                    playlist.name
+                );
+            }
+        }
+        SearchOutcome::AddToPlaylist { track_uris } => {
+            let playlists = load_playlists(terminal, user_access).await?;
+            let outcome =
+                PlaylistBrowser::new("Spautofy - Add To Playlist", playlists).run(terminal)?;
+            if let BrowserOutcome::Picked(target) = outcome {
+                let track_uris: Vec<&str> = track_uris.iter().map(String::as_str).collect();
+                if is_dry_run() {
+                    status!(
+                        "Would add {} tracks to playlist \"{}\".",
+                        track_uris.len(),
+                        target.name
+                    );
+                    return Ok(());
+                }
+                add_to_playlist(user_access, &target.id, &track_uris).await?;
+                status!(
+                    "Added {} tracks to playlist \"{}\".",
+                    track_uris.len(),
+                    target.name
+                );
+            }
+        }
+        SearchOutcome::Back => {}
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum WizardOutcome {
+    Run(Vec<Task>),
+    Reauthorize,
+    Quit,
+}
+
+/// Walks through action selection, parameter entry and confirmation.
+/// Keeps the selection and parameters between runs.
+#[derive(Debug)]
+pub struct Wizard {
+    /// The actions of the registry listed for selection, in the order of their items.
+    actions: Vec<&'static dyn Action>,
+    selection_list: ActionSelectionList,
+    // parameters of every action, kept while navigating back and forth
+    parameters: Vec<ActionParameters>,
+    /// Tracks left out in the preview, per action and time range.
+    excluded: Vec<[Vec<String>; TimeRange::ALL.len()]>,
+    configure_cursor: usize,
+    history_path: String,
+    /// Set with `--public`/`--collaborative`, used for the recommendation playlists.
+    visibility: PlaylistVisibility,
+    actions_config: ActionsConfig,
+}
+
+fn time_range_index(time_range: TimeRange) -> usize {
+    TimeRange::ALL
+        .iter()
+        .position(|t| *t == time_range)
+        .unwrap_or_default()
+}
+
+impl Wizard {
+    pub fn new(
+        actions: Vec<&'static dyn Action>,
+        default_selection: &[bool],
+        history_path: &str,
+        actions_config: &ActionsConfig,
+        visibility: Option<PlaylistVisibility>,
+    ) -> Self {
+        let names: Vec<&'static str> = actions.iter().map(|action| action.name()).collect();
+        let parameters: Vec<ActionParameters> = actions
+            .iter()
+            .map(|action| action.params(actions_config, visibility))
+            .collect();
+        let top_tracks_config = &actions_config.top_tracks;
+        Wizard {
+            actions,
+            selection_list: ActionSelectionList::new(&names, default_selection),
+            excluded: vec![Default::default(); parameters.len()],
+            parameters,
+            configure_cursor: 0,
+            history_path: history_path.to_string(),
+            visibility: visibility.unwrap_or(PlaylistVisibility {
+                public: top_tracks_config.public.unwrap_or_default(),
+                collaborative: top_tracks_config.collaborative.unwrap_or_default(),
+            }),
+            actions_config: actions_config.clone(),
+        }
+    }
+
+    pub async fn run(
+        &mut self,
+        terminal: &mut Tui,
+        user_access: &UserAccess,
+    ) -> Result<WizardOutcome, MainError> {
+        let status = AuthStatus::from(user_access);
+        let mut step = WizardStep::Select;
+        let mut notice = None;
+        loop {
+            let selected = self.selected_indices();
+            let outcome = match step {
+                WizardStep::Select => {
+                    match self
+                        .selection_list
+                        .run(terminal, &status, &self.parameters)?
+                    {
+                        ScreenOutcome::Reauthorize => return Ok(WizardOutcome::Reauthorize),
+                        ScreenOutcome::BrowsePlaylists => {
+                            browse_playlists(terminal, user_access).await?;
+                            continue;
+                        }
+                        ScreenOutcome::History => {
+                            let records = history::load(&self.history_path).unwrap_or_else(|err| {
+                                status!("Could not read the run history: {}", err);
+                                Vec::new()
+                            });
+                            HistoryScreen::new(records).run(terminal)?;
+                            continue;
+                        }
+                        ScreenOutcome::Search => {
+                            retry_on_error!(
+                                terminal,
+                                "Search failed",
+                                search(terminal, user_access, self.visibility).await
+                            );
+                            continue;
+                        }
+                        outcome => outcome,
+                    }
+                }
+                WizardStep::Configure => {
+                    let mut screen = ParameterScreen::new(
+                        selected
+                            .iter()
+                            .map(|i| self.parameters[*i].clone())
+                            .collect(),
+                    )
+                    .with_cursor(self.configure_cursor);
+                    if let Some(notice) = notice.take() {
+                        screen = screen.with_notice(notice);
+                    }
+                    let outcome = screen.run(terminal)?;
+                    self.configure_cursor = screen.cursor();
+                    for (index, edited) in selected.iter().zip(screen.into_actions()) {
+                        self.parameters[*index] = edited;
+                    }
+                    if let ScreenOutcome::Preview(action) = outcome {
+                        let actions = match self.actions_for(selected[action]) {
+                            Ok(actions) => actions,
+                            Err(err) => {
+                                notice = Some(err);
+                                continue;
+                            }
+                        };
+                        for task in actions {
+                            // only the top tracks can be previewed
+                            let Task::TopTracks(params) = task else {
+                                continue;
+                            };
+                            let excluded = retry_on_error!(
+                                terminal,
+                                "Could not load the tracks",
+                                preview_tracks(terminal, user_access, &params).await
+                            );
+                            let Some(excluded) = excluded else {
+                                break;
+                            };
+                            self.excluded[selected[action]][time_range_index(params.time_range)] =
+                                excluded;
+                        }
+                        continue;
+                    }
+                    if let ScreenOutcome::PickPlaylist(action) = outcome {
+                        let target = retry_on_error!(
+                            terminal,
+                            "Could not load your playlists",
+                            pick_target_playlist(terminal, user_access).await
+                        );
+                        if let Some(target) = target.flatten() {
+                            self.parameters[selected[action]].set_playlist("target", target);
+                        }
+                        continue;
+                    }
+                    if outcome == ScreenOutcome::Confirm {
+                        let invalid = selected
+                            .iter()
+                            .find_map(|index| self.actions_for(*index).err());
+                        if invalid.is_some() {
+                            notice = invalid;
+                            continue;
+                        }
+                    }
+                    outcome
+                }
+                WizardStep::Confirm => {
+                    let chosen: Vec<ActionParameters> = selected
+                        .iter()
+                        .map(|i| self.parameters[*i].clone())
+                        .collect();
+                    ConfirmScreen::new(&chosen).run(terminal)?
+                }
+            };
+            step = match (step, outcome) {
+                (_, ScreenOutcome::Quit) => return Ok(WizardOutcome::Quit),
+                (WizardStep::Select, ScreenOutcome::Back) => return Ok(WizardOutcome::Quit),
+                (WizardStep::Select, _) => WizardStep::Configure,
+                (WizardStep::Configure, ScreenOutcome::Back) => WizardStep::Select,
+                (WizardStep::Configure, _) => WizardStep::Confirm,
+                (WizardStep::Confirm, ScreenOutcome::Back) => WizardStep::Configure,
+                (WizardStep::Confirm, _) => break,
+            };
+        }
+
+        let mut actions = Vec::new();
+        for index in self.selected_indices() {
+            // the parameters were validated before confirming
+            actions.extend(self.actions_for(index).unwrap_or_default());
+        }
+        Ok(WizardOutcome::Run(actions))
+    }
+
+    /// Which actions are selected, in the order of the action names.
+    pub fn selected(&self) -> Vec<bool> {
+        self.selection_list.selection().selected
+    }
+
+    /// Indices of the selected actions, in run order.
+    fn selected_indices(&self) -> Vec<usize> {
+        let selection = self.selection_list.selection();
+        selection
+            .order
+            .into_iter()
+            .filter(|index| selection.selected[*index])
+            .collect()
+    }
+
+    /// The tasks of the action at `index`, or why its parameters are invalid.
+    fn actions_for(&self, index: usize) -> Result<Vec<Task>, String> {
+        let mut tasks = self.actions[index].tasks(&self.parameters[index], &self.actions_config)?;
+        for task in &mut tasks {
+            if let Task::TopTracks(params) = task {
+                params.excluded_track_ids =
+                    self.excluded[index][time_range_index(params.time_range)].clone();
+            }
+        }
+        Ok(tasks)
+    }
+}